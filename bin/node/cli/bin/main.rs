@@ -18,7 +18,7 @@
 
 #![warn(missing_docs)]
 
-fn main() -> sc_cli::Result<()> {
+fn main() {
 	let version = sc_cli::VersionInfo {
 		name: "Substrate Node",
 		commit: env!("VERGEN_SHA_SHORT"),
@@ -30,5 +30,8 @@ fn main() -> sc_cli::Result<()> {
 		copyright_start_year: 2017,
 	};
 
-	node_cli::run(std::env::args(), version)
+	if let Err(e) = node_cli::run(std::env::args(), version) {
+		eprintln!("Error: {}", e);
+		std::process::exit(e.exit_code() as i32);
+	}
 }
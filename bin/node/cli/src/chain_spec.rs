@@ -31,6 +31,7 @@ use hex_literal::hex;
 use sc_telemetry::TelemetryEndpoints;
 use grandpa_primitives::{AuthorityId as GrandpaId};
 use sp_consensus_babe::{AuthorityId as BabeId};
+use sp_consensus_aura::sr25519::{AuthorityId as AuraId};
 use pallet_im_online::sr25519::{AuthorityId as ImOnlineId};
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_runtime::{Perbill, traits::{Verify, IdentifyAccount}};
@@ -53,13 +54,26 @@ pub struct Extensions {
 	pub fork_blocks: sc_client::ForkBlocks<Block>,
 	/// Known bad block hashes.
 	pub bad_blocks: sc_client::BadBlocks<Block>,
+	/// The compiled-in runtime of the chain, `node` if unset.
+	pub runtime: Option<RuntimeName>,
 }
 
+/// Name of a runtime compiled into the node, see [`Runtime`](crate::Runtime).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RuntimeName(pub String);
+
 /// Specialized `ChainSpec`.
 pub type ChainSpec = sc_service::GenericChainSpec<
 	GenesisConfig,
 	Extensions,
 >;
+
+/// `ChainSpec` of the chains running the template runtime.
+pub type TemplateChainSpec = sc_service::GenericChainSpec<
+	node_template_runtime::GenesisConfig,
+	Extensions,
+>;
 /// Flaming Fir testnet generator
 pub fn flaming_fir_config() -> Result<ChainSpec, String> {
 	ChainSpec::from_json_bytes(&include_bytes!("../res/flaming-fir.json")[..])
@@ -199,123 +213,219 @@ pub fn get_authority_keys_from_seed(seed: &str) -> (
 	)
 }
 
+/// Stash, controller and session keys of an initial authority.
+pub type AuthorityKeys = (AccountId, AccountId, GrandpaId, BabeId, ImOnlineId, AuthorityDiscoveryId);
+
+/// The accounts endowed by default: the well-known development accounts and their stashes.
+pub fn development_accounts() -> Vec<AccountId> {
+	vec![
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		get_account_id_from_seed::<sr25519::Public>("Bob"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie"),
+		get_account_id_from_seed::<sr25519::Public>("Dave"),
+		get_account_id_from_seed::<sr25519::Public>("Eve"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie"),
+		get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
+		get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+	]
+}
+
+/// Builder of a `GenesisConfig`, for the presets and the tests.
+///
+/// ```ignore
+/// let genesis = GenesisBuilder::new()
+/// 	.with_authorities(vec![get_authority_keys_from_seed("Alice")])
+/// 	.with_sudo(get_account_id_from_seed::<sr25519::Public>("Alice"))
+/// 	.with_staking(1_000 * DOLLARS, 10)
+/// 	.build();
+/// ```
+pub struct GenesisBuilder {
+	authorities: Vec<AuthorityKeys>,
+	endowed_accounts: Vec<AccountId>,
+	root_key: Option<AccountId>,
+	stash: Balance,
+	validator_count: Option<u32>,
+	enable_println: bool,
+}
+
+impl GenesisBuilder {
+	/// Amount given to each endowed account.
+	pub const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
+	/// Amount bonded by each initial authority, unless set with `with_staking`.
+	pub const STASH: Balance = 100 * DOLLARS;
+
+	/// A genesis without authorities nor sudo key, endowing the `development_accounts`.
+	pub fn new() -> Self {
+		GenesisBuilder {
+			authorities: Vec::new(),
+			endowed_accounts: development_accounts(),
+			root_key: None,
+			stash: Self::STASH,
+			validator_count: None,
+			enable_println: false,
+		}
+	}
+
+	/// Endow `accounts` instead of the `development_accounts`.
+	///
+	/// The first half of them are members of the council, the technical committee and the
+	/// society.
+	pub fn with_endowed_accounts(mut self, accounts: Vec<AccountId>) -> Self {
+		self.endowed_accounts = accounts;
+		self
+	}
+
+	/// Start with `authorities` as validators, their stash being endowed with their bond.
+	pub fn with_authorities(mut self, authorities: Vec<AuthorityKeys>) -> Self {
+		self.authorities = authorities;
+		self
+	}
+
+	/// Give the sudo key to `root_key`.
+	pub fn with_sudo(mut self, root_key: AccountId) -> Self {
+		self.root_key = Some(root_key);
+		self
+	}
+
+	/// Bond `bond` for each authority, electing up to `validator_count` validators.
+	///
+	/// By default, each authority bonds `STASH` and twice as many validators as authorities are
+	/// elected.
+	pub fn with_staking(mut self, bond: Balance, validator_count: u32) -> Self {
+		self.stash = bond;
+		self.validator_count = Some(validator_count);
+		self
+	}
+
+	/// Enable `println` in contracts, only for development chains.
+	pub fn with_println(mut self, enable_println: bool) -> Self {
+		self.enable_println = enable_println;
+		self
+	}
+
+	/// The `GenesisConfig` built.
+	pub fn build(self) -> GenesisConfig {
+		let GenesisBuilder {
+			authorities: initial_authorities,
+			endowed_accounts,
+			root_key,
+			stash,
+			validator_count,
+			enable_println,
+		} = self;
+		let num_endowed_accounts = endowed_accounts.len();
+		let validator_count = validator_count.unwrap_or(initial_authorities.len() as u32 * 2);
+
+		GenesisConfig {
+			frame_system: Some(SystemConfig {
+				code: WASM_BINARY.to_vec(),
+				changes_trie_config: Default::default(),
+			}),
+			pallet_balances: Some(BalancesConfig {
+				balances: endowed_accounts.iter().cloned()
+					.map(|k| (k, Self::ENDOWMENT))
+					.chain(initial_authorities.iter().map(|x| (x.0.clone(), stash)))
+					.collect(),
+			}),
+			pallet_indices: Some(IndicesConfig {
+				indices: vec![],
+			}),
+			pallet_session: Some(SessionConfig {
+				keys: initial_authorities.iter().map(|x| {
+					(x.0.clone(), x.0.clone(), session_keys(x.2.clone(), x.3.clone(), x.4.clone(), x.5.clone()))
+				}).collect::<Vec<_>>(),
+			}),
+			pallet_staking: Some(StakingConfig {
+				validator_count,
+				minimum_validator_count: initial_authorities.len() as u32,
+				stakers: initial_authorities.iter().map(|x| {
+					(x.0.clone(), x.1.clone(), stash, StakerStatus::Validator)
+				}).collect(),
+				invulnerables: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+				slash_reward_fraction: Perbill::from_percent(10),
+				.. Default::default()
+			}),
+			pallet_democracy: Some(DemocracyConfig::default()),
+			pallet_collective_Instance1: Some(CouncilConfig {
+				members: endowed_accounts.iter()
+							.take((num_endowed_accounts + 1) / 2)
+							.cloned()
+							.collect(),
+				phantom: Default::default(),
+			}),
+			pallet_collective_Instance2: Some(TechnicalCommitteeConfig {
+				members: endowed_accounts.iter()
+							.take((num_endowed_accounts + 1) / 2)
+							.cloned()
+							.collect(),
+				phantom: Default::default(),
+			}),
+			pallet_contracts: Some(ContractsConfig {
+				current_schedule: pallet_contracts::Schedule {
+					enable_println, // this should only be enabled on development chains
+					..Default::default()
+				},
+				gas_price: 1 * MILLICENTS,
+			}),
+			pallet_sudo: root_key.map(|key| SudoConfig { key }),
+			pallet_babe: Some(BabeConfig {
+				authorities: vec![],
+			}),
+			pallet_im_online: Some(ImOnlineConfig {
+				keys: vec![],
+			}),
+			pallet_authority_discovery: Some(AuthorityDiscoveryConfig {
+				keys: vec![],
+			}),
+			pallet_grandpa: Some(GrandpaConfig {
+				authorities: vec![],
+			}),
+			pallet_membership_Instance1: Some(Default::default()),
+			pallet_treasury: Some(Default::default()),
+			pallet_society: Some(SocietyConfig {
+				members: endowed_accounts.iter()
+							.take((num_endowed_accounts + 1) / 2)
+							.cloned()
+							.collect(),
+				pot: 0,
+				max_members: 999,
+			}),
+			pallet_vesting: Some(Default::default()),
+		}
+	}
+}
+
+impl Default for GenesisBuilder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 /// Helper function to create GenesisConfig for testing
 pub fn testnet_genesis(
-	initial_authorities: Vec<(AccountId, AccountId, GrandpaId, BabeId, ImOnlineId, AuthorityDiscoveryId)>,
+	initial_authorities: Vec<AuthorityKeys>,
 	root_key: AccountId,
 	endowed_accounts: Option<Vec<AccountId>>,
 	enable_println: bool,
 ) -> GenesisConfig {
-	let endowed_accounts: Vec<AccountId> = endowed_accounts.unwrap_or_else(|| {
-		vec![
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			get_account_id_from_seed::<sr25519::Public>("Bob"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie"),
-			get_account_id_from_seed::<sr25519::Public>("Dave"),
-			get_account_id_from_seed::<sr25519::Public>("Eve"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-		]
-	});
-	let num_endowed_accounts = endowed_accounts.len();
-
-	const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
-	const STASH: Balance = 100 * DOLLARS;
-
-	GenesisConfig {
-		frame_system: Some(SystemConfig {
-			code: WASM_BINARY.to_vec(),
-			changes_trie_config: Default::default(),
-		}),
-		pallet_balances: Some(BalancesConfig {
-			balances: endowed_accounts.iter().cloned()
-				.map(|k| (k, ENDOWMENT))
-				.chain(initial_authorities.iter().map(|x| (x.0.clone(), STASH)))
-				.collect(),
-		}),
-		pallet_indices: Some(IndicesConfig {
-			indices: vec![],
-		}),
-		pallet_session: Some(SessionConfig {
-			keys: initial_authorities.iter().map(|x| {
-				(x.0.clone(), x.0.clone(), session_keys(x.2.clone(), x.3.clone(), x.4.clone(), x.5.clone()))
-			}).collect::<Vec<_>>(),
-		}),
-		pallet_staking: Some(StakingConfig {
-			validator_count: initial_authorities.len() as u32 * 2,
-			minimum_validator_count: initial_authorities.len() as u32,
-			stakers: initial_authorities.iter().map(|x| {
-				(x.0.clone(), x.1.clone(), STASH, StakerStatus::Validator)
-			}).collect(),
-			invulnerables: initial_authorities.iter().map(|x| x.0.clone()).collect(),
-			slash_reward_fraction: Perbill::from_percent(10),
-			.. Default::default()
-		}),
-		pallet_democracy: Some(DemocracyConfig::default()),
-		pallet_collective_Instance1: Some(CouncilConfig {
-			members: endowed_accounts.iter()
-						.take((num_endowed_accounts + 1) / 2)
-						.cloned()
-						.collect(),
-			phantom: Default::default(),
-		}),
-		pallet_collective_Instance2: Some(TechnicalCommitteeConfig {
-			members: endowed_accounts.iter()
-						.take((num_endowed_accounts + 1) / 2)
-						.cloned()
-						.collect(),
-			phantom: Default::default(),
-		}),
-		pallet_contracts: Some(ContractsConfig {
-			current_schedule: pallet_contracts::Schedule {
-				enable_println, // this should only be enabled on development chains
-				..Default::default()
-			},
-			gas_price: 1 * MILLICENTS,
-		}),
-		pallet_sudo: Some(SudoConfig {
-			key: root_key,
-		}),
-		pallet_babe: Some(BabeConfig {
-			authorities: vec![],
-		}),
-		pallet_im_online: Some(ImOnlineConfig {
-			keys: vec![],
-		}),
-		pallet_authority_discovery: Some(AuthorityDiscoveryConfig {
-			keys: vec![],
-		}),
-		pallet_grandpa: Some(GrandpaConfig {
-			authorities: vec![],
-		}),
-		pallet_membership_Instance1: Some(Default::default()),
-		pallet_treasury: Some(Default::default()),
-		pallet_society: Some(SocietyConfig {
-			members: endowed_accounts.iter()
-						.take((num_endowed_accounts + 1) / 2)
-						.cloned()
-						.collect(),
-			pot: 0,
-			max_members: 999,
-		}),
-		pallet_vesting: Some(Default::default()),
-	}
+	GenesisBuilder::new()
+		.with_authorities(initial_authorities)
+		.with_sudo(root_key)
+		.with_endowed_accounts(endowed_accounts.unwrap_or_else(development_accounts))
+		.with_println(enable_println)
+		.build()
 }
 
 fn development_config_genesis() -> GenesisConfig {
-	testnet_genesis(
-		vec![
-			get_authority_keys_from_seed("Alice"),
-		],
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		None,
-		true,
-	)
+	GenesisBuilder::new()
+		.with_authorities(vec![get_authority_keys_from_seed("Alice")])
+		.with_sudo(get_account_id_from_seed::<sr25519::Public>("Alice"))
+		.with_println(true)
+		.build()
 }
 
 /// Development config (single validator Alice)
@@ -333,15 +443,13 @@ pub fn development_config() -> ChainSpec {
 }
 
 fn local_testnet_genesis() -> GenesisConfig {
-	testnet_genesis(
-		vec![
+	GenesisBuilder::new()
+		.with_authorities(vec![
 			get_authority_keys_from_seed("Alice"),
 			get_authority_keys_from_seed("Bob"),
-		],
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		None,
-		false,
-	)
+		])
+		.with_sudo(get_account_id_from_seed::<sr25519::Public>("Alice"))
+		.build()
 }
 
 /// Local testnet config (multivalidator Alice + Bob)
@@ -358,6 +466,48 @@ pub fn local_testnet_config() -> ChainSpec {
 	)
 }
 
+fn template_development_config_genesis() -> node_template_runtime::GenesisConfig {
+	use node_template_runtime::{
+		AuraConfig, BalancesConfig, GrandpaConfig, SudoConfig, SystemConfig, WASM_BINARY,
+	};
+
+	node_template_runtime::GenesisConfig {
+		system: Some(SystemConfig {
+			code: WASM_BINARY.to_vec(),
+			changes_trie_config: Default::default(),
+		}),
+		balances: Some(BalancesConfig {
+			balances: development_accounts().into_iter().map(|k| (k, 1 << 60)).collect(),
+		}),
+		aura: Some(AuraConfig {
+			authorities: vec![get_from_seed::<AuraId>("Alice")],
+		}),
+		grandpa: Some(GrandpaConfig {
+			authorities: vec![(get_from_seed::<GrandpaId>("Alice"), 1)],
+		}),
+		sudo: Some(SudoConfig {
+			key: get_account_id_from_seed::<sr25519::Public>("Alice"),
+		}),
+	}
+}
+
+/// Development config of the template runtime (single Aura authority Alice)
+pub fn template_development_config() -> TemplateChainSpec {
+	TemplateChainSpec::from_genesis(
+		"Template Development",
+		"template_dev",
+		template_development_config_genesis,
+		vec![],
+		None,
+		None,
+		None,
+		Extensions {
+			runtime: Some(RuntimeName(crate::Runtime::Template.name().into())),
+			..Default::default()
+		},
+	)
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
 	use super::*;
@@ -366,14 +516,10 @@ pub(crate) mod tests {
 	use sp_runtime::BuildStorage;
 
 	fn local_testnet_genesis_instant_single() -> GenesisConfig {
-		testnet_genesis(
-			vec![
-				get_authority_keys_from_seed("Alice"),
-			],
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			None,
-			false,
-		)
+		GenesisBuilder::new()
+			.with_authorities(vec![get_authority_keys_from_seed("Alice")])
+			.with_sudo(get_account_id_from_seed::<sr25519::Public>("Alice"))
+			.build()
 	}
 
 	/// Local testnet config (single validator - Alice)
@@ -428,4 +574,62 @@ pub(crate) mod tests {
 	fn test_staging_test_net_chain_spec() {
 		staging_testnet_config().build_storage().unwrap();
 	}
+
+	#[test]
+	fn runtime_is_selected_by_the_spec() {
+		use crate::Runtime;
+
+		let mut spec = development_config();
+		assert_eq!(Runtime::of_spec(&spec).unwrap(), Runtime::Node);
+
+		spec.patch(&serde_json::json!({ "runtime": "node" })).unwrap();
+		assert_eq!(Runtime::of_spec(&spec).unwrap(), Runtime::Node);
+
+		spec.patch(&serde_json::json!({ "runtime": "elm" })).unwrap();
+		assert!(Runtime::of_spec(&spec).unwrap_err().contains("`elm`"));
+	}
+
+	#[test]
+	fn test_create_template_development_chain_spec() {
+		let spec = template_development_config();
+		assert_eq!(crate::Runtime::of_spec(&spec).unwrap(), crate::Runtime::Template);
+		spec.build_storage().unwrap();
+	}
+
+	#[test]
+	fn template_spec_files_are_loaded_with_their_genesis() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("template.json");
+		std::fs::write(&path, template_development_config().as_json(false).unwrap()).unwrap();
+
+		let spec = crate::load_spec(path.to_str().unwrap()).unwrap();
+		assert_eq!(crate::Runtime::of_spec(&*spec).unwrap(), crate::Runtime::Template);
+		spec.as_storage_builder().build_storage().unwrap();
+	}
+
+	#[test]
+	fn genesis_builder_composes_genesis() {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		let genesis = GenesisBuilder::new()
+			.with_endowed_accounts(vec![alice.clone()])
+			.with_authorities(vec![
+				get_authority_keys_from_seed("Alice"),
+				get_authority_keys_from_seed("Bob"),
+			])
+			.with_staking(1_000 * DOLLARS, 10)
+			.build();
+
+		let balances = genesis.pallet_balances.as_ref().unwrap();
+		assert_eq!(balances.balances.len(), 3);
+		assert_eq!(balances.balances[0], (alice, GenesisBuilder::ENDOWMENT));
+		assert!(balances.balances[1..].iter().all(|(_, balance)| *balance == 1_000 * DOLLARS));
+
+		let staking = genesis.pallet_staking.as_ref().unwrap();
+		assert_eq!(staking.validator_count, 10);
+		assert_eq!(staking.minimum_validator_count, 2);
+		assert!(staking.stakers.iter().all(|staker| staker.2 == 1_000 * DOLLARS));
+
+		assert!(genesis.pallet_sudo.is_none());
+		genesis.build_storage().unwrap();
+	}
 }
@@ -23,6 +23,9 @@ pub struct Cli {
 	/// Possible subcommand with parameters.
 	#[structopt(subcommand)]
 	pub subcommand: Option<Subcommand>,
+	/// List the chain specs built into the node and exit.
+	#[structopt(long = "list-chains")]
+	pub list_chains: bool,
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub run: RunCmd,
@@ -55,6 +58,80 @@ pub enum Subcommand {
 		about = "Benchmark runtime pallets."
 	)]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// The custom package-spec subcommand for publishing chain specs with releases.
+	#[structopt(
+		name = "package-spec",
+		about = "Write the raw chain spec and a checksum manifest for a release. \
+		The runtime of the chain spec must match the runtime of this build."
+	)]
+	PackageSpec(sc_cli::PackageSpecCmd),
+
+	/// The custom check-spec subcommand for validating a chain spec.
+	#[structopt(
+		name = "check-spec",
+		about = "Validate a chain spec against the runtime genesis configuration."
+	)]
+	CheckSpec(sc_cli::CheckSpecCmd),
+
+	/// The key management subcommands.
+	#[structopt(
+		name = "key",
+		about = "Generate, inspect and insert keys into the keystore of the node."
+	)]
+	Key(sc_cli::KeySubcommand),
+
+	/// The sign subcommand.
+	#[structopt(
+		name = "sign",
+		about = "Sign a message with a secret phrase or URI."
+	)]
+	Sign(sc_cli::SignCmd),
+
+	/// The verify subcommand.
+	#[structopt(
+		name = "verify",
+		about = "Verify the signature of a message by a public key."
+	)]
+	Verify(sc_cli::VerifyCmd),
+
+	/// The completions subcommand.
+	#[structopt(
+		name = "completions",
+		about = "Generate the completions of the command line for a shell."
+	)]
+	Completions(sc_cli::CompletionsCmd),
+
+	/// The custom offence-report subcommand for writing slashing post-mortems.
+	#[structopt(
+		name = "offence-report",
+		about = "Print the offences of an era together with the resulting slashes and \
+		reporter rewards."
+	)]
+	OffenceReport(OffenceReportCmd),
+
+	/// The custom dry-run subcommand for debugging extrinsics.
+	#[structopt(
+		name = "dry-run",
+		about = "Apply an extrinsic on top of a block of the local database without \
+		broadcasting it, and print the dispatch outcome and events."
+	)]
+	DryRun(DryRunCmd),
+
+	/// The custom rotate-keys subcommand for setting up validators.
+	#[structopt(
+		name = "rotate-keys",
+		about = "Generate new session keys in the keystore of a running node and print them \
+		for the `session.setKeys` extrinsic."
+	)]
+	RotateKeys(RotateKeysCmd),
+
+	/// The custom stress subcommand for profiling the runtime at scale.
+	#[structopt(
+		name = "stress",
+		about = "Run the native runtime under synthetic load and report its performance."
+	)]
+	Stress(StressCmd),
 }
 
 /// The `factory` command used to generate transactions.
@@ -77,3 +154,101 @@ pub struct FactoryCmd {
 	#[structopt(flatten)]
 	pub import_params: ImportParams,
 }
+
+/// The `offence-report` command used to write the post-mortem of the offences of an era.
+#[derive(Debug, StructOpt, Clone)]
+pub struct OffenceReportCmd {
+	/// Era to report on.
+	#[structopt(value_name = "ERA")]
+	pub era: u32,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub node_params: NodeHandleParams,
+}
+
+/// The `dry-run` command used to apply an extrinsic without broadcasting it.
+#[derive(Debug, StructOpt, Clone)]
+pub struct DryRunCmd {
+	/// The SCALE encoded extrinsic, hex encoded.
+	#[structopt(long = "call", value_name = "HEX")]
+	pub call: String,
+
+	/// Number or hash of the block to apply the extrinsic on top of, the best block by default.
+	#[structopt(long = "at", value_name = "BLOCK")]
+	pub at: Option<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+/// The `rotate-keys` command used to generate the session keys of a validator.
+#[derive(Debug, StructOpt, Clone)]
+pub struct RotateKeysCmd {
+	/// RPC endpoint of the node, either `http://` or `ws://`.
+	///
+	/// The node must allow the unsafe RPC methods on this endpoint.
+	#[structopt(long = "url", value_name = "URL", default_value = "http://localhost:9933")]
+	pub url: String,
+}
+
+/// Parameters of the subcommands reading the chain through a `NodeHandle`.
+#[derive(Debug, StructOpt, Clone)]
+pub struct NodeHandleParams {
+	/// Query a running node instead of the local database.
+	///
+	/// Both `http://` and `ws://` endpoints are supported.
+	#[structopt(long = "url", value_name = "URL")]
+	pub url: Option<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+/// The `stress` command used to profile the runtime under synthetic load.
+#[derive(Debug, StructOpt, Clone)]
+pub enum StressCmd {
+	/// Run staking and slashing through many eras with synthetic offences, reporting timing
+	/// and storage growth per era.
+	#[structopt(name = "eras")]
+	Eras(StressErasCmd),
+}
+
+/// The `stress eras` command.
+#[derive(Debug, StructOpt, Clone)]
+pub struct StressErasCmd {
+	/// Number of eras to run.
+	#[structopt(long = "count", default_value = "100")]
+	pub count: u32,
+
+	/// Number of validators at genesis, all of them candidates for election.
+	#[structopt(long = "validators", default_value = "200")]
+	pub validators: u32,
+
+	/// Number of nominators at genesis, each nominating random validators.
+	#[structopt(long = "nominators", default_value = "10000")]
+	pub nominators: u32,
+
+	/// Probability of each validator being offline during a session, which is then reported
+	/// by the im-online module.
+	#[structopt(long = "offence-rate", default_value = "0.02")]
+	pub offence_rate: f64,
+
+	/// Seed of the random choices, runs with the same parameters and seed are identical.
+	#[structopt(long = "seed", default_value = "0")]
+	pub seed: u64,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
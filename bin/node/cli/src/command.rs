@@ -14,10 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use sc_cli::VersionInfo;
+use sc_cli::{VersionInfo, CustomCommands, CustomCommandContext, ParsedCommand};
 use sc_service::{Roles as ServiceRoles};
 use node_transaction_factory::RuntimeAdapter;
-use crate::{Cli, service, ChainSpec, load_spec, Subcommand, factory_impl::FactoryState};
+use crate::{
+	Cli, service, template_service, Runtime, load_spec, chain_spec_presets, Subcommand, StressCmd,
+	factory_impl::FactoryState,
+};
 
 /// Parse command line arguments into service configuration.
 pub fn run<I, T>(args: I, version: VersionInfo) -> sc_cli::Result<()>
@@ -25,25 +28,59 @@ where
 	I: Iterator<Item = T>,
 	T: Into<std::ffi::OsString> + Clone,
 {
-	let args: Vec<_> = args.collect();
-	let opt = sc_cli::from_iter::<Cli, _>(args.clone(), &version);
+	run_with_custom_commands(args, version, &CustomCommands::new())
+}
 
+/// Parse command line arguments into service configuration, also accepting the subcommands
+/// registered in `custom_commands`.
+pub fn run_with_custom_commands<I, T>(
+	args: I,
+	version: VersionInfo,
+	custom_commands: &CustomCommands,
+) -> sc_cli::Result<()>
+where
+	I: Iterator<Item = T>,
+	T: Into<std::ffi::OsString> + Clone,
+{
 	let mut config = sc_service::Configuration::from_version(&version);
 
+	let opt = match custom_commands.from_iter::<Cli, _>(args, &version) {
+		ParsedCommand::Cli(opt) => opt,
+		ParsedCommand::Custom(command, matches) => {
+			let mut context = CustomCommandContext::new(&matches, config, &load_spec, &version);
+			context.init(command)?;
+			return command.run(&matches, &mut context)
+		},
+	};
+
+	if opt.list_chains {
+		print!("{}", chain_spec_presets().list());
+		return Ok(())
+	}
+
 	match opt.subcommand {
 		None => {
 			opt.run.init(&version)?;
 			opt.run.update_config(&mut config, load_spec, &version)?;
-			opt.run.run(
-				config,
-				service::new_light,
-				service::new_full,
-				&version,
-			)
+			match Runtime::of_spec(config.expect_chain_spec())? {
+				Runtime::Node => opt.run.run(
+					config,
+					service::new_light,
+					service::new_full,
+					&version,
+				),
+				Runtime::Template => opt.run.run(
+					config,
+					template_service::new_light,
+					template_service::new_full,
+					&version,
+				),
+			}
 		},
 		Some(Subcommand::Inspect(cmd)) => {
 			cmd.init(&version)?;
 			cmd.update_config(&mut config, load_spec, &version)?;
+			ensure_node_runtime(&config, "inspect")?;
 
 			let client = sc_service::new_full_client::<
 				node_runtime::Block, node_runtime::RuntimeApi, node_executor::Executor,
@@ -55,9 +92,65 @@ where
 		Some(Subcommand::Benchmark(cmd)) => {
 			cmd.init(&version)?;
 			cmd.update_config(&mut config, load_spec, &version)?;
+			ensure_node_runtime(&config, "benchmark")?;
 
 			cmd.run::<node_runtime::Block, node_executor::Executor>(config)
 		},
+		Some(Subcommand::PackageSpec(cmd)) => {
+			cmd.init(&version)?;
+			cmd.update_config(&mut config, load_spec, &version)?;
+
+			let code = match Runtime::of_spec(config.expect_chain_spec())? {
+				Runtime::Node => node_runtime::WASM_BINARY,
+				Runtime::Template => node_template_runtime::WASM_BINARY,
+			};
+			cmd.run(config, Some(code))
+		},
+		Some(Subcommand::CheckSpec(cmd)) => {
+			cmd.init(&version)?;
+
+			let reference = crate::chain_spec::development_config();
+			cmd.run(load_spec, Some(&reference as &dyn sc_service::ChainSpec))
+		},
+		Some(Subcommand::Key(cmd)) => {
+			cmd.update_config(&mut config, load_spec, &version)?;
+
+			cmd.run(config)
+		},
+		Some(Subcommand::Sign(cmd)) => cmd.run(),
+		Some(Subcommand::Verify(cmd)) => cmd.run(),
+		Some(Subcommand::Completions(cmd)) => {
+			cmd.run(custom_commands.app::<Cli>(&version), &version)
+		},
+		Some(Subcommand::OffenceReport(cmd)) => {
+			cmd.node_params.init(&version)?;
+			let mut node = cmd.node_params.open(&mut config, &version)?;
+
+			cmd.run(&mut node)
+		},
+		Some(Subcommand::DryRun(cmd)) => {
+			cmd.shared_params.init(&version)?;
+			cmd.shared_params.update_config(&mut config, load_spec, &version)?;
+			cmd.import_params.update_config(
+				&mut config,
+				ServiceRoles::FULL,
+				cmd.shared_params.dev,
+			)?;
+			config.use_in_memory_keystore()?;
+			ensure_node_runtime(&config, "dry-run")?;
+
+			let client = sc_service::new_full_client::<
+				node_runtime::Block, node_runtime::RuntimeApi, node_executor::Executor,
+			>(&config)?;
+
+			cmd.run(&client)
+		},
+		Some(Subcommand::RotateKeys(cmd)) => cmd.run(),
+		Some(Subcommand::Stress(StressCmd::Eras(cmd))) => {
+			cmd.shared_params.init(&version)?;
+
+			cmd.run()
+		},
 		Some(Subcommand::Factory(cli_args)) => {
 			cli_args.shared_params.init(&version)?;
 			cli_args.shared_params.update_config(&mut config, load_spec, &version)?;
@@ -68,9 +161,10 @@ where
 			)?;
 
 			config.use_in_memory_keystore()?;
+			ensure_node_runtime(&config, "factory")?;
 
-			match ChainSpec::from(config.expect_chain_spec().id()) {
-				Some(ref c) if c == &ChainSpec::Development || c == &ChainSpec::LocalTestnet => {},
+			match config.expect_chain_spec().id() {
+				"dev" | "local" => {},
 				_ => return Err(
 					"Factory is only supported for development and local testnet.".into()
 				),
@@ -106,10 +200,28 @@ where
 		Some(Subcommand::Base(subcommand)) => {
 			subcommand.init(&version)?;
 			subcommand.update_config(&mut config, load_spec, &version)?;
-			subcommand.run(
-				config,
-				|config: sc_service::Configuration| Ok(new_full_start!(config).0),
-			)
+			match Runtime::of_spec(config.expect_chain_spec())? {
+				Runtime::Node => subcommand.run(
+					config,
+					|config: sc_service::Configuration| Ok(new_full_start!(config).0),
+				),
+				Runtime::Template => subcommand.run(
+					config,
+					|config: sc_service::Configuration| Ok(new_template_full_start!(config).0),
+				),
+			}
 		},
 	}
 }
+
+/// Fails unless the chain of `config` runs the node runtime, the only one `subcommand` supports.
+fn ensure_node_runtime(config: &sc_service::Configuration, subcommand: &str) -> sc_cli::Result<()> {
+	match Runtime::of_spec(config.expect_chain_spec())? {
+		Runtime::Node => Ok(()),
+		runtime => Err(format!(
+			"`{}` only supports chains running the node runtime, not `{}`.",
+			subcommand,
+			runtime.name(),
+		).into()),
+	}
+}
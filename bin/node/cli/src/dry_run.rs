@@ -0,0 +1,127 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `dry-run` subcommand.
+//!
+//! The extrinsic is applied through the block builder runtime API as the first extrinsic of
+//! a new block on top of the requested one. The changes are discarded, except for the events
+//! deposited by the extrinsic which are read back from them.
+
+use std::fmt::Write;
+use codec::Decode;
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder;
+use sp_core::ExecutionContext;
+use sp_runtime::{generic::BlockId, traits::Header as HeaderT};
+use frame_system::{EventRecord, Phase};
+use node_primitives::Hash;
+use node_runtime::{Event, Header, UncheckedExtrinsic};
+
+use crate::{DryRunCmd, node_handle::LocalClient, offence_report::value_key};
+
+impl DryRunCmd {
+	/// Apply `self.call` on top of `self.at` in the database of `client`.
+	pub fn run(self, client: &LocalClient) -> sc_cli::Result<()> {
+		let extrinsic = sp_core::bytes::from_hex(&self.call)
+			.map_err(|e| format!("Invalid hex extrinsic: {}", e))?;
+		let extrinsic = UncheckedExtrinsic::decode(&mut &extrinsic[..])
+			.map_err(|e| format!("Error decoding extrinsic: {}", e))?;
+		let at = match &self.at {
+			Some(at) => parse_block(client, at)?,
+			None => client.chain_info().best_hash,
+		};
+
+		print!("{}", dry_run(client, at, extrinsic)?);
+		Ok(())
+	}
+}
+
+/// The hash of the block given by number or by hash.
+fn parse_block(client: &LocalClient, block: &str) -> sc_cli::Result<Hash> {
+	if block.starts_with("0x") {
+		let hash = sp_core::bytes::from_hex(block)
+			.map_err(|e| format!("Invalid block hash {}: {}", block, e))?;
+		if hash.len() != Hash::len_bytes() {
+			return Err(format!("Invalid block hash {}", block).into())
+		}
+		return Ok(Hash::from_slice(&hash))
+	}
+
+	let number = block.parse()
+		.map_err(|e| format!("Invalid block number {}: {}", block, e))?;
+	client.block_hash(number)?
+		.ok_or_else(|| format!("Block #{} is not known", number).into())
+}
+
+/// Apply `extrinsic` on top of the block `at` and describe the outcome.
+pub fn dry_run(
+	client: &LocalClient,
+	at: Hash,
+	extrinsic: UncheckedExtrinsic,
+) -> sc_cli::Result<String> {
+	let block_id = BlockId::Hash(at);
+	let parent = client.header(&block_id)?
+		.ok_or_else(|| format!("Block {:?} is not known", at))?;
+	let header = Header::new(
+		parent.number + 1,
+		Default::default(),
+		Default::default(),
+		at,
+		Default::default(),
+	);
+
+	let api = client.runtime_api();
+	api.initialize_block_with_context(&block_id, ExecutionContext::BlockConstruction, &header)?;
+	let outcome = api.apply_extrinsic_with_context(
+		&block_id,
+		ExecutionContext::BlockConstruction,
+		extrinsic,
+	)?;
+
+	// the events are cleared when initializing the block, the ones found in the changes are
+	// therefore deposited by this block.
+	let state = client.state_at(&block_id)?;
+	let changes = api.into_storage_changes(&state, None, at)?;
+	let events_key = value_key(b"System", b"Events").0;
+	let events = changes.main_storage_changes.into_iter()
+		.find(|(key, _)| *key == events_key)
+		.and_then(|(_, value)| value)
+		.map(|events| Vec::<EventRecord<Event, Hash>>::decode(&mut &events[..]))
+		.transpose()
+		.map_err(|e| format!("Error decoding events: {}", e))?
+		.unwrap_or_default();
+
+	let mut out = String::new();
+	let _ = writeln!(out, "Dry run on top of block #{} ({:?})", parent.number, at);
+	let _ = match outcome {
+		Ok(Ok(())) => writeln!(out, "Dispatch succeeded"),
+		Ok(Err(e)) => writeln!(out, "Dispatch failed: {:?}", e),
+		Err(e) => writeln!(out, "Invalid transaction: {:?}", e),
+	};
+
+	let _ = writeln!(out, "\nEvents:");
+	let mut deposited = events.into_iter()
+		.filter(|record| record.phase == Phase::ApplyExtrinsic(0))
+		.peekable();
+	if deposited.peek().is_none() {
+		let _ = writeln!(out, "  none");
+	}
+	for record in deposited {
+		let _ = writeln!(out, "  {:?}", record.event);
+	}
+
+	Ok(out)
+}
@@ -32,6 +32,9 @@ pub mod chain_spec;
 
 #[macro_use]
 mod service;
+#[cfg(feature = "cli")]
+#[macro_use]
+mod template_service;
 #[cfg(feature = "browser")]
 mod browser;
 #[cfg(feature = "cli")]
@@ -40,6 +43,16 @@ mod cli;
 mod factory_impl;
 #[cfg(feature = "cli")]
 mod command;
+#[cfg(feature = "cli")]
+mod dry_run;
+#[cfg(feature = "cli")]
+mod node_handle;
+#[cfg(feature = "cli")]
+mod offence_report;
+#[cfg(feature = "cli")]
+mod rotate_keys;
+#[cfg(feature = "cli")]
+mod stress;
 
 #[cfg(feature = "browser")]
 pub use browser::*;
@@ -48,44 +61,100 @@ pub use cli::*;
 #[cfg(feature = "cli")]
 pub use command::*;
 
-/// The chain specification option.
-#[derive(Clone, Debug, PartialEq)]
-pub enum ChainSpec {
-	/// Whatever the current runtime is, with just Alice as an auth.
-	Development,
-	/// Whatever the current runtime is, with simple Alice/Bob auths.
-	LocalTestnet,
-	/// The Flaming Fir testnet.
-	FlamingFir,
-	/// Whatever the current runtime is with the "global testnet" defaults.
-	StagingTestnet,
+/// The chain specs built into the node, selected with `--chain <id>`.
+///
+/// Nodes building on this one register their own chain specs on top of these.
+pub fn chain_spec_presets() -> sc_chain_spec::ChainSpecPresets {
+	fn boxed(spec: chain_spec::ChainSpec) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		Ok(Box::new(spec))
+	}
+
+	sc_chain_spec::ChainSpecPresets::new()
+		.register(
+			&["dev"],
+			"Whatever the current runtime is, with just Alice as an auth.",
+			|| boxed(chain_spec::development_config()),
+		)
+		.register(
+			&["local"],
+			"Whatever the current runtime is, with simple Alice/Bob auths.",
+			|| boxed(chain_spec::local_testnet_config()),
+		)
+		.register(
+			&["", "fir", "flaming-fir"],
+			"The Flaming Fir testnet.",
+			|| boxed(chain_spec::flaming_fir_config()?),
+		)
+		.register(
+			&["staging"],
+			"Whatever the current runtime is with the \"global testnet\" defaults.",
+			|| boxed(chain_spec::staging_testnet_config()),
+		)
+		.register(
+			&["template-dev"],
+			"The Aura-based template runtime, with just Alice as an auth.",
+			|| Ok(Box::new(chain_spec::template_development_config())),
+		)
 }
 
-/// Get a chain config from a spec setting.
-impl ChainSpec {
-	pub(crate) fn load(self) -> Result<chain_spec::ChainSpec, String> {
-		Ok(match self {
-			ChainSpec::FlamingFir => chain_spec::flaming_fir_config()?,
-			ChainSpec::Development => chain_spec::development_config(),
-			ChainSpec::LocalTestnet => chain_spec::local_testnet_config(),
-			ChainSpec::StagingTestnet => chain_spec::staging_testnet_config(),
-		})
-	}
+/// The runtimes compiled into the node, selected by the `runtime` field of the chain spec.
+///
+/// Each runtime comes with its own executor and service, a single node binary can serve the
+/// networks of all of them. The runtime also decides the block authoring engine of the chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Runtime {
+	/// The `node-runtime` of this repository, used by the chain specs not selecting any.
+	///
+	/// Blocks are authored with BABE.
+	Node,
+	/// The `node-template-runtime`, whose blocks are authored with Aura.
+	Template,
+}
 
-	pub(crate) fn from(s: &str) -> Option<Self> {
-		match s {
-			"dev" => Some(ChainSpec::Development),
-			"local" => Some(ChainSpec::LocalTestnet),
-			"" | "fir" | "flaming-fir" => Some(ChainSpec::FlamingFir),
-			"staging" => Some(ChainSpec::StagingTestnet),
-			_ => None,
+impl Runtime {
+	/// All the compiled-in runtimes.
+	pub const ALL: &'static [Runtime] = &[Runtime::Node, Runtime::Template];
+
+	/// The name of the runtime in chain specs.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Runtime::Node => "node",
+			Runtime::Template => "template",
 		}
 	}
+
+	/// The runtime selected by `spec`.
+	pub fn of_spec(spec: &dyn sc_service::ChainSpec) -> Result<Self, String> {
+		let name = match spec.extension::<Option<chain_spec::RuntimeName>>() {
+			Some(Some(name)) => &name.0,
+			_ => return Ok(Runtime::Node),
+		};
+		Runtime::ALL.iter()
+			.find(|runtime| runtime.name() == name)
+			.cloned()
+			.ok_or_else(|| format!(
+				"Chain spec {} uses the runtime `{}`, this node only has: {}",
+				spec.id(),
+				name,
+				Runtime::ALL.iter().map(|r| r.name()).collect::<Vec<_>>().join(", "),
+			))
+	}
 }
 
 fn load_spec(id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
-	Ok(match ChainSpec::from(id) {
-		Some(spec) => Box::new(spec.load()?),
-		None => Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(id))?),
-	})
+	let spec: Box<dyn sc_service::ChainSpec> = match chain_spec_presets().load(id) {
+		Some(spec) => spec?,
+		None => {
+			// the genesis of a spec file is only read when building the storage, reload the file
+			// with the genesis of the runtime it selects.
+			let path = std::path::PathBuf::from(id);
+			let spec = chain_spec::ChainSpec::from_json_file(path.clone())?;
+			match Runtime::of_spec(&spec)? {
+				Runtime::Node => Box::new(spec),
+				Runtime::Template => Box::new(chain_spec::TemplateChainSpec::from_json_file(path)?),
+			}
+		},
+	};
+	Runtime::of_spec(&*spec)?;
+	Ok(spec)
 }
@@ -0,0 +1,64 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chain access for the subcommands of the node.
+//!
+//! The subcommands read the chain of the node runtime through a [`NodeHandle`], either from a
+//! running node over RPC, when `--url` is given, or from the local database.
+
+use std::sync::Arc;
+use sc_cli::VersionInfo;
+use sc_service::{Configuration, Roles as ServiceRoles};
+use node_runtime::{Block, RuntimeApi};
+use node_executor::Executor;
+
+use crate::{load_spec, NodeHandleParams};
+
+/// Client of the local database.
+pub type LocalClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
+
+/// Read access to the chain of the node runtime.
+pub type NodeHandle = sc_cli::NodeHandle<Block, RuntimeApi, Executor>;
+
+/// A running node of the node runtime, queried over RPC.
+pub type RemoteNode = sc_cli::RemoteNode<Block>;
+
+impl NodeHandleParams {
+	/// Initialize logging as requested by the shared parameters.
+	pub fn init(&self, version: &VersionInfo) -> sc_cli::Result<()> {
+		self.shared_params.init(version)
+	}
+
+	/// Open the handle, connecting to `--url` if given and to the local database otherwise.
+	///
+	/// `config` is only updated when the local database is opened.
+	pub fn open(
+		&self,
+		config: &mut Configuration,
+		version: &VersionInfo,
+	) -> sc_cli::Result<NodeHandle> {
+		if let Some(url) = &self.url {
+			return NodeHandle::connect(url)
+		}
+
+		self.shared_params.update_config(config, load_spec, version)?;
+		self.import_params.update_config(config, ServiceRoles::FULL, self.shared_params.dev)?;
+		config.use_in_memory_keystore()?;
+
+		let client = sc_service::new_full_client::<Block, RuntimeApi, Executor>(config)?;
+		Ok(NodeHandle::Local(Arc::new(client)))
+	}
+}
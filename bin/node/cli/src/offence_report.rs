@@ -0,0 +1,247 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `offence-report` subcommand.
+//!
+//! The report is assembled from the staking slashing storage of the era and from the
+//! `Offence` and `Slash` events deposited in the blocks of the era.
+
+use std::fmt::Write;
+use codec::Decode;
+use log::warn;
+use sp_core::{
+	hashing::{twox_128, twox_64},
+	hexdisplay::HexDisplay,
+	storage::StorageKey,
+};
+use sp_runtime::Perbill;
+use sp_staking::{offence::Kind, slashing::UnappliedSlash};
+use frame_system::{EventRecord, Phase};
+use pallet_staking::{ActiveEraInfo, EraIndex};
+use node_primitives::{AccountId, Balance, BlockNumber, Hash, Moment};
+use node_runtime::Event;
+
+use crate::{OffenceReportCmd, node_handle::NodeHandle};
+
+impl OffenceReportCmd {
+	/// Write the report of `self.era` read through `node`.
+	pub fn run(self, node: &mut NodeHandle) -> sc_cli::Result<()> {
+		print!("{}", report(node, self.era)?);
+		Ok(())
+	}
+}
+
+/// An `Offence` event of the offences module.
+struct OffenceEvent {
+	block: BlockNumber,
+	phase: Phase,
+	kind: Kind,
+	time_slot: Vec<u8>,
+}
+
+/// A `Slash` event of the staking module.
+struct SlashEvent {
+	block: BlockNumber,
+	validator: AccountId,
+	amount: Balance,
+}
+
+/// Assemble the post-mortem report of `era`.
+pub fn report(node: &mut NodeHandle, era: EraIndex) -> sc_cli::Result<String> {
+	let best = node.best_number()?;
+	let start = first_block_of_era(node, era, best)?
+		.ok_or_else(|| format!("Era {} has not started yet at block #{}", era, best))?;
+	let end = match first_block_of_era(node, era.saturating_add(1), best)? {
+		Some(next) => next - 1,
+		None => best,
+	};
+	let end_hash = node.expect_block_hash(end)?;
+	let best_hash = node.expect_block_hash(best)?;
+
+	let mut offences = Vec::new();
+	let mut slashes = Vec::new();
+	for number in start..=end {
+		let hash = node.expect_block_hash(number)?;
+		let events = match node.storage(hash, value_key(b"System", b"Events"))? {
+			Some(encoded) => encoded,
+			None => continue,
+		};
+		let events = match Vec::<EventRecord<Event, Hash>>::decode(&mut &events[..]) {
+			Ok(events) => events,
+			Err(_) => {
+				warn!("Skipping events of block #{} which do not decode with this runtime", number);
+				continue
+			}
+		};
+		for record in events {
+			match record.event {
+				Event::pallet_offences(pallet_offences::Event::Offence(kind, time_slot)) =>
+					offences.push(OffenceEvent { block: number, phase: record.phase, kind, time_slot }),
+				Event::pallet_staking(pallet_staking::RawEvent::Slash(validator, amount)) =>
+					slashes.push(SlashEvent { block: number, validator, amount }),
+				_ => {},
+			}
+		}
+	}
+
+	// Slashes are recorded under the era of the offence and may be reported after its end,
+	// hence the latest state is used. Unapplied slashes are recorded under the era of the
+	// report and applied in a later era, hence the state at the end of the era is used.
+	let validator_slashes: Vec<(AccountId, (Perbill, Balance))> =
+		double_map_entries(node, best_hash, b"ValidatorSlashInEra", era)?;
+	let nominator_slashes: Vec<(AccountId, Balance)> =
+		double_map_entries(node, best_hash, b"NominatorSlashInEra", era)?;
+	let unapplied: Vec<UnappliedSlash<AccountId, Balance>> =
+		node.decoded_storage(end_hash, map_key(b"Staking", b"UnappliedSlashes", era))?
+			.unwrap_or_default();
+
+	let mut out = String::new();
+	let _ = writeln!(out, "Offence report for era {} (blocks #{} to #{})", era, start, end);
+	if end == best {
+		let _ = writeln!(out, "The era is still active, the report may be incomplete.");
+	}
+
+	let _ = writeln!(out, "\nOffences reported during the era:");
+	if offences.is_empty() {
+		let _ = writeln!(out, "  none");
+	}
+	for offence in &offences {
+		let _ = writeln!(
+			out,
+			"  {} kind `{}`, time slot 0x{}",
+			evidence(offence.block, &offence.phase),
+			String::from_utf8_lossy(&offence.kind),
+			HexDisplay::from(&offence.time_slot),
+		);
+	}
+
+	let _ = writeln!(out, "\nSlashes of offences committed during the era:");
+	if validator_slashes.is_empty() && nominator_slashes.is_empty() {
+		let _ = writeln!(out, "  none");
+	}
+	for (validator, (severity, amount)) in &validator_slashes {
+		let _ = writeln!(out, "  validator {}: severity {:?}, slashed {}", validator, severity, amount);
+	}
+	for (nominator, amount) in &nominator_slashes {
+		let _ = writeln!(out, "  nominator {}: slashed {}", nominator, amount);
+	}
+
+	let _ = writeln!(out, "\nSlashes deferred for a later era, with reporter rewards:");
+	if unapplied.is_empty() {
+		let _ = writeln!(out, "  none");
+	}
+	for slash in &unapplied {
+		let _ = writeln!(out, "  validator {}: own {}", slash.validator, slash.own);
+		for (nominator, amount) in &slash.others {
+			let _ = writeln!(out, "    nominator {}: {}", nominator, amount);
+		}
+		if slash.reporters.is_empty() {
+			let _ = writeln!(out, "    no reporters, nothing paid out");
+		} else {
+			let share = slash.payout / slash.reporters.len() as Balance;
+			for reporter in &slash.reporters {
+				let _ = writeln!(out, "    reporter {}: rewarded up to {}", reporter, share);
+			}
+		}
+	}
+
+	let _ = writeln!(out, "\nSlashes applied during the era:");
+	if slashes.is_empty() {
+		let _ = writeln!(out, "  none");
+	}
+	for slash in &slashes {
+		let _ = writeln!(out, "  #{} validator {}: {}", slash.block, slash.validator, slash.amount);
+	}
+
+	Ok(out)
+}
+
+/// Reference to where an event was deposited, as `#block` or `#block-extrinsic`.
+fn evidence(block: BlockNumber, phase: &Phase) -> String {
+	match phase {
+		Phase::ApplyExtrinsic(index) => format!("#{}-{}", block, index),
+		_ => format!("#{}", block),
+	}
+}
+
+/// Find the first block, up to `best`, at which `era` or a later era is active.
+fn first_block_of_era(
+	node: &mut NodeHandle,
+	era: EraIndex,
+	best: BlockNumber,
+) -> sc_cli::Result<Option<BlockNumber>> {
+	if active_era_at(node, best)?.map_or(true, |active| active < era) {
+		return Ok(None)
+	}
+
+	// the active era never decreases, so bisect on it.
+	let (mut low, mut high) = (0, best);
+	while low < high {
+		let middle = low + (high - low) / 2;
+		if active_era_at(node, middle)?.map_or(false, |active| active >= era) {
+			high = middle;
+		} else {
+			low = middle + 1;
+		}
+	}
+	Ok(Some(low))
+}
+
+fn active_era_at(node: &mut NodeHandle, number: BlockNumber) -> sc_cli::Result<Option<EraIndex>> {
+	let hash = node.expect_block_hash(number)?;
+	let info: Option<ActiveEraInfo<Moment>> =
+		node.decoded_storage(hash, value_key(b"Staking", b"ActiveEra"))?;
+	Ok(info.map(|info| info.index))
+}
+
+/// All the entries of the `Staking` double map `item` under the first key `era`.
+///
+/// Both keys of the maps are hashed with `twox_64_concat`, the account can therefore be read
+/// back from the storage key.
+fn double_map_entries<T: Decode>(
+	node: &mut NodeHandle,
+	at: Hash,
+	item: &[u8],
+	era: EraIndex,
+) -> sc_cli::Result<Vec<(AccountId, T)>> {
+	let prefix = map_key(b"Staking", item, era);
+	let mut entries = Vec::new();
+	for key in node.storage_keys(at, prefix.clone())? {
+		let mut account = key.0.get(prefix.0.len() + 8..).unwrap_or_default();
+		let account = AccountId::decode(&mut account)
+			.map_err(|e| format!("Error decoding storage key: {}", e))?;
+		if let Some(value) = node.decoded_storage(at, key)? {
+			entries.push((account, value));
+		}
+	}
+	Ok(entries)
+}
+
+/// Key of the storage value `item` of `module`.
+pub(crate) fn value_key(module: &[u8], item: &[u8]) -> StorageKey {
+	let mut key = twox_128(module).to_vec();
+	key.extend_from_slice(&twox_128(item));
+	StorageKey(key)
+}
+
+/// Key of `era` in the `twox_64_concat` map `item`, also the prefix of the double maps.
+fn map_key(module: &[u8], item: &[u8], era: EraIndex) -> StorageKey {
+	let mut key = value_key(module, item).0;
+	let era = codec::Encode::encode(&era);
+	key.extend_from_slice(&twox_64(&era));
+	key.extend_from_slice(&era);
+	StorageKey(key)
+}
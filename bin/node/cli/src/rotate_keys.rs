@@ -0,0 +1,86 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `rotate-keys` subcommand.
+//!
+//! New session keys are generated in the keystore of a running node through the
+//! `author_rotateKeys` RPC. Their public keys are then printed along with the
+//! `session.setKeys` call registering them, to be signed by the controller account.
+
+use std::fmt::Write;
+use codec::{Decode, Encode};
+use sp_core::hexdisplay::HexDisplay;
+use node_runtime::{Call, SessionKeys};
+
+use crate::{RotateKeysCmd, node_handle::RemoteNode};
+
+impl RotateKeysCmd {
+	/// Rotate the session keys of the node at `self.url`.
+	pub fn run(self) -> sc_cli::Result<()> {
+		let keys = RemoteNode::connect(&self.url)?.rotate_keys()?;
+		print!("{}", describe_keys(&keys)?);
+		Ok(())
+	}
+}
+
+/// Describe the SCALE encoded session `keys` and the call registering them.
+pub fn describe_keys(keys: &[u8]) -> sc_cli::Result<String> {
+	let session_keys = SessionKeys::decode(&mut &keys[..])
+		.map_err(|e| format!("Error decoding session keys: {}", e))?;
+	let call = Call::Session(pallet_session::Call::set_keys(session_keys.clone(), Vec::new()));
+
+	let mut out = String::new();
+	let _ = writeln!(out, "Session keys: 0x{}", HexDisplay::from(&keys));
+	let _ = writeln!(out, "  grandpa: 0x{}", HexDisplay::from(&session_keys.grandpa.encode()));
+	let _ = writeln!(out, "  babe: 0x{}", HexDisplay::from(&session_keys.babe.encode()));
+	let _ = writeln!(out, "  im_online: 0x{}", HexDisplay::from(&session_keys.im_online.encode()));
+	let _ = writeln!(
+		out,
+		"  authority_discovery: 0x{}",
+		HexDisplay::from(&session_keys.authority_discovery.encode()),
+	);
+	let _ = writeln!(
+		out,
+		"Submit from the controller account: session.setKeys(0x{}, 0x)",
+		HexDisplay::from(&keys),
+	);
+	let _ = writeln!(out, "Encoded call: 0x{}", HexDisplay::from(&call.encode()));
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn describes_session_keys() {
+		let keys = (0u8..128).collect::<Vec<_>>();
+		let out = describe_keys(&keys).unwrap();
+
+		assert!(out.contains(&format!("Session keys: 0x{}", HexDisplay::from(&keys))));
+		assert!(out.contains(&format!("  grandpa: 0x{}", HexDisplay::from(&keys[..32].to_vec()))));
+		assert!(out.contains(&format!(
+			"  authority_discovery: 0x{}",
+			HexDisplay::from(&keys[96..].to_vec()),
+		)));
+		assert!(out.contains("Encoded call: 0x"));
+	}
+
+	#[test]
+	fn rejects_truncated_keys() {
+		assert!(describe_keys(&[0u8; 64]).is_err());
+	}
+}
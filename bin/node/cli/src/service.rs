@@ -96,6 +96,7 @@ macro_rules! new_full_start {
 					pool: builder.pool(),
 					select_chain: builder.select_chain().cloned()
 						.expect("SelectChain is present for full services or set up failed; qed."),
+					subscriptions: builder.rpc_subscriptions(),
 					babe: node_rpc::BabeDeps {
 						keystore: builder.keystore(),
 						babe_config: sc_consensus_babe::BabeLink::config(babe_link).clone(),
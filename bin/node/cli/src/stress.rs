@@ -0,0 +1,204 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `stress eras` subcommand.
+//!
+//! The native runtime is run in test externalities without producing blocks. At every session
+//! the validators are noted as block authors, except those drawn as offline, and the session
+//! is rotated. The offline validators are then reported by the im-online module, so that the
+//! offences go through the offences and staking modules like on a live chain.
+
+use std::time::{Duration, Instant};
+use codec::Encode;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use sp_core::{blake2_256, storage::Storage};
+use sp_runtime::BuildStorage;
+use frame_support::traits::Get;
+use frame_system::InitKind;
+use pallet_authorship::EventHandler;
+use pallet_staking::StakerStatus;
+use node_primitives::{AccountId, Balance, BlockNumber};
+use node_runtime::{
+	Event, EpochDuration, Runtime, Session, Staking, System, Timestamp,
+	constants::{currency::DOLLARS, time::MILLISECS_PER_BLOCK},
+};
+
+use crate::{StressErasCmd, chain_spec};
+
+/// Free balance of each nominator at genesis.
+const NOMINATOR_ENDOWMENT: Balance = 1_000 * DOLLARS;
+
+/// Bond of each nominator at genesis.
+const NOMINATOR_BOND: Balance = 500 * DOLLARS;
+
+/// What happened during a single era.
+struct EraStats {
+	index: u32,
+	duration: Duration,
+	offline: u32,
+	offences: u32,
+	slashes: u32,
+	keys: usize,
+	bytes: usize,
+}
+
+impl StressErasCmd {
+	/// Run the eras and print the statistics of each of them.
+	pub fn run(self) -> sc_cli::Result<()> {
+		if !(0.0..=1.0).contains(&self.offence_rate) {
+			return Err(format!("Offence rate must be between 0 and 1, got {}", self.offence_rate).into())
+		}
+		if self.validators == 0 {
+			return Err("At least one validator is required".into())
+		}
+
+		let mut rng = StdRng::seed_from_u64(self.seed);
+		let started = Instant::now();
+		let mut ext = sp_io::TestExternalities::new(self.genesis(&mut rng)?);
+		let (keys, mut bytes) = ext.execute_with(storage_size);
+		println!(
+			"Genesis of {} validators and {} nominators: {} keys, {} bytes, built in {} ms",
+			self.validators, self.nominators, keys, bytes, started.elapsed().as_millis(),
+		);
+
+		println!(
+			"{:>6} {:>10} {:>8} {:>9} {:>8} {:>10} {:>12} {:>10}",
+			"era", "time (ms)", "offline", "offences", "slashes", "keys", "bytes", "growth",
+		);
+		let mut block = 0;
+		let mut total = Duration::default();
+		for _ in 0..self.count {
+			let stats = ext.execute_with(|| run_era(&mut block, self.offence_rate, &mut rng));
+			println!(
+				"{:>6} {:>10} {:>8} {:>9} {:>8} {:>10} {:>12} {:>10}",
+				stats.index,
+				stats.duration.as_millis(),
+				stats.offline,
+				stats.offences,
+				stats.slashes,
+				stats.keys,
+				stats.bytes,
+				stats.bytes as i64 - bytes as i64,
+			);
+			total += stats.duration;
+			bytes = stats.bytes;
+		}
+
+		if self.count > 0 {
+			println!(
+				"{} eras in {} ms, {} ms per era on average",
+				self.count, total.as_millis(), total.as_millis() / self.count as u128,
+			);
+		}
+		Ok(())
+	}
+
+	/// Genesis storage with all the validators and nominators bonded.
+	///
+	/// Each nominator nominates `MaxNominations` random validators.
+	fn genesis(&self, rng: &mut StdRng) -> sc_cli::Result<Storage> {
+		let authorities: Vec<_> = (0..self.validators)
+			.map(|i| chain_spec::get_authority_keys_from_seed(&format!("Stress{}", i)))
+			.collect();
+		let validators: Vec<AccountId> = authorities.iter().map(|keys| keys.0.clone()).collect();
+		let root = authorities[0].1.clone();
+		let mut genesis = chain_spec::testnet_genesis(
+			authorities,
+			root.clone(),
+			Some(vec![root]),
+			false,
+		);
+
+		let nominators: Vec<(AccountId, AccountId)> = (0..self.nominators)
+			.map(|i| (account(b"stash", i), account(b"controller", i)))
+			.collect();
+		if let Some(balances) = genesis.pallet_balances.as_mut() {
+			balances.balances.extend(
+				nominators.iter().map(|(stash, _)| (stash.clone(), NOMINATOR_ENDOWMENT))
+			);
+		}
+		if let Some(staking) = genesis.pallet_staking.as_mut() {
+			staking.validator_count = self.validators;
+			staking.minimum_validator_count = 1;
+			staking.invulnerables = Vec::new();
+			let max_nominations = <Runtime as pallet_staking::Trait>::MaxNominations::get() as usize;
+			staking.stakers.extend(nominators.into_iter().map(|(stash, controller)| {
+				let targets = validators.choose_multiple(rng, max_nominations).cloned().collect();
+				(stash, controller, NOMINATOR_BOND, StakerStatus::Nominator(targets))
+			}));
+		}
+
+		Ok(genesis.build_storage()?)
+	}
+}
+
+/// Deterministic account of a synthetic nominator.
+fn account(kind: &[u8], index: u32) -> AccountId {
+	AccountId::from(blake2_256(&(kind, index).encode()))
+}
+
+/// Rotate sessions until the next era is active.
+fn run_era(block: &mut BlockNumber, offence_rate: f64, rng: &mut StdRng) -> EraStats {
+	let started = Instant::now();
+	let active_era = || Staking::active_era().map_or(0, |era| era.index);
+	let index = active_era() + 1;
+	let (mut offline, mut offences, mut slashes) = (0, 0, 0);
+
+	while active_era() < index {
+		*block += EpochDuration::get() as BlockNumber;
+		System::initialize(
+			block,
+			&Default::default(),
+			&Default::default(),
+			&Default::default(),
+			InitKind::Full,
+		);
+		Timestamp::set_timestamp(*block as u64 * MILLISECS_PER_BLOCK);
+
+		for validator in Session::validators() {
+			if rng.gen_bool(offence_rate) {
+				offline += 1;
+			} else {
+				<Runtime as pallet_authorship::Trait>::EventHandler::note_author(validator);
+			}
+		}
+		Session::rotate_session();
+
+		for record in System::events() {
+			match record.event {
+				Event::pallet_offences(_) => offences += 1,
+				Event::pallet_staking(pallet_staking::RawEvent::Slash(..)) => slashes += 1,
+				_ => {},
+			}
+		}
+	}
+
+	let duration = started.elapsed();
+	let (keys, bytes) = storage_size();
+	EraStats { index, duration, offline, offences, slashes, keys, bytes }
+}
+
+/// Number of keys and total size of the keys and values in storage.
+fn storage_size() -> (usize, usize) {
+	let (mut keys, mut bytes) = (0, 0);
+	let mut key = Vec::new();
+	while let Some(next) = sp_io::storage::next_key(&key) {
+		bytes += next.len() + sp_io::storage::get(&next).map_or(0, |value| value.len());
+		keys += 1;
+		key = next;
+	}
+	(keys, bytes)
+}
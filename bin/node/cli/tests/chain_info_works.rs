@@ -0,0 +1,56 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(unix)]
+
+use assert_cmd::cargo::cargo_bin;
+use std::process::Command;
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn chain_info_works() {
+	let base_path = tempdir().expect("could not create a temp dir");
+
+	common::run_dev_node_for_a_while(base_path.path());
+
+	let output = Command::new(cargo_bin("substrate"))
+		.args(&["chain-info", "--dev", "--pruning", "archive", "-d"])
+		.arg(base_path.path())
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+
+	let output = String::from_utf8(output.stdout).unwrap();
+	assert!(output.contains("Genesis hash: 0x"));
+	assert!(!output.contains("Best block: #0 "));
+	assert!(output.contains("  header: "));
+}
+
+#[test]
+fn chain_info_does_not_create_a_database() {
+	let base_path = tempdir().expect("could not create a temp dir");
+	let missing = base_path.path().join("missing");
+
+	let status = Command::new(cargo_bin("substrate"))
+		.args(&["chain-info", "--dev", "-d"])
+		.arg(&missing)
+		.status()
+		.unwrap();
+	assert!(!status.success());
+	assert!(!missing.join("chains").join("dev").join("db").exists());
+}
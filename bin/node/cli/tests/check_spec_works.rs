@@ -0,0 +1,52 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use assert_cmd::cargo::cargo_bin;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn check_spec_works() {
+	let base_path = tempdir().expect("could not create a temp dir");
+	let spec_path = base_path.path().join("spec.json");
+
+	let output = Command::new(cargo_bin("substrate"))
+		.args(&["build-spec", "--dev", "-d"])
+		.arg(base_path.path())
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+	std::fs::write(&spec_path, &output.stdout).unwrap();
+
+	let status = Command::new(cargo_bin("substrate"))
+		.arg("check-spec")
+		.arg(&spec_path)
+		.status()
+		.unwrap();
+	assert!(status.success());
+
+	let mut spec: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	spec["genesis"]["runtime"]["unknownModule"] = serde_json::json!({});
+	std::fs::write(&spec_path, spec.to_string()).unwrap();
+
+	let output = Command::new(cargo_bin("substrate"))
+		.arg("check-spec")
+		.arg(&spec_path)
+		.output()
+		.unwrap();
+	assert!(!output.status.success());
+	assert!(String::from_utf8_lossy(&output.stdout).contains("unknown module `unknownModule`"));
+}
@@ -0,0 +1,45 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(unix)]
+
+use assert_cmd::cargo::cargo_bin;
+use codec::Encode;
+use sp_core::hexdisplay::HexDisplay;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn dry_run_works() {
+	let base_path = tempdir().expect("could not create a temp dir");
+
+	// nothing validates an unsigned remark, it is rejected before being dispatched.
+	let remark = node_runtime::UncheckedExtrinsic::new_unsigned(
+		node_runtime::Call::System(frame_system::Call::remark(b"dry run".to_vec())),
+	);
+	let output = Command::new(cargo_bin("substrate"))
+		.args(&["dry-run", "--dev", "--at", "0", "--call"])
+		.arg(format!("0x{}", HexDisplay::from(&remark.encode())))
+		.arg("-d")
+		.arg(base_path.path())
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+
+	let output = String::from_utf8(output.stdout).unwrap();
+	assert!(output.starts_with("Dry run on top of block #0"));
+	assert!(output.contains("Invalid transaction"));
+}
@@ -50,6 +50,22 @@ fn import_export_and_revert_work() {
 		.unwrap();
 	assert!(status.success());
 
+	let status = Command::new(cargo_bin("substrate"))
+		.args(&["export-blocks", "--dev", "--pruning", "archive", "--binary", "true", "--to", "5", "-d"])
+		.arg(base_path.path())
+		.arg(&exported_blocks)
+		.status()
+		.unwrap();
+	assert!(status.success());
+
+	let status = Command::new(cargo_bin("substrate"))
+		.args(&["import-blocks", "--dev", "--pruning", "archive", "--binary", "true", "-d"])
+		.arg(base_path.path())
+		.arg(&exported_blocks)
+		.status()
+		.unwrap();
+	assert!(status.success());
+
 	let status = Command::new(cargo_bin("substrate"))
 		.args(&["revert", "--dev", "--pruning", "archive", "-d"])
 		.arg(base_path.path())
@@ -0,0 +1,31 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use assert_cmd::cargo::cargo_bin;
+use std::process::Command;
+
+#[test]
+fn list_chains_works() {
+	let output = Command::new(cargo_bin("substrate"))
+		.arg("--list-chains")
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(stdout.lines().any(|line| line.starts_with("dev: ")));
+	assert!(stdout.lines().any(|line| line.starts_with("fir, flaming-fir, (default): ")));
+}
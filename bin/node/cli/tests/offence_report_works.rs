@@ -0,0 +1,60 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(unix)]
+
+use assert_cmd::cargo::cargo_bin;
+use std::process::Command;
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn offence_report_works() {
+	let base_path = tempdir().expect("could not create a temp dir");
+
+	common::run_dev_node_for_a_while(base_path.path());
+
+	let report = |era: &str| {
+		Command::new(cargo_bin("substrate"))
+			.args(&["offence-report", "--dev", "--pruning", "archive", "-d"])
+			.arg(base_path.path())
+			.arg(era)
+			.output()
+			.unwrap()
+	};
+
+	// the first era is still active and nobody misbehaved on the dev chain.
+	let output = report("0");
+	assert!(output.status.success());
+
+	let output = String::from_utf8(output.stdout).unwrap();
+	assert!(output.starts_with("Offence report for era 0 (blocks #"));
+	assert!(output.contains("The era is still active, the report may be incomplete."));
+	for section in &[
+		"Offences reported during the era:",
+		"Slashes of offences committed during the era:",
+		"Slashes deferred for a later era, with reporter rewards:",
+		"Slashes applied during the era:",
+	] {
+		assert!(output.contains(&format!("\n{}\n  none\n", section)), "{}", output);
+	}
+
+	// the storage of an era which has not started yet is not read at all.
+	let output = report("1000");
+	assert!(!output.status.success());
+	assert!(String::from_utf8(output.stderr).unwrap().contains("Era 1000 has not started yet"));
+}
@@ -0,0 +1,61 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use assert_cmd::cargo::cargo_bin;
+use std::process::Command;
+use sp_core::{hashing::blake2_256, hexdisplay::HexDisplay};
+use tempfile::tempdir;
+
+#[test]
+fn package_spec_works() {
+	let base_path = tempdir().expect("could not create a temp dir");
+	let spec_path = base_path.path().join("dev.json");
+
+	// the dev chain spec has no bootnodes.
+	let status = Command::new(cargo_bin("substrate"))
+		.args(&["package-spec", "--dev", "--out"])
+		.arg(&spec_path)
+		.status()
+		.unwrap();
+	assert!(!status.success());
+	assert!(!spec_path.exists());
+
+	let status = Command::new(cargo_bin("substrate"))
+		.args(&["package-spec", "--dev", "--allow-no-bootnodes", "--out"])
+		.arg(&spec_path)
+		.status()
+		.unwrap();
+	assert!(status.success());
+
+	let spec_json = std::fs::read(&spec_path).unwrap();
+	let spec: serde_json::Value = serde_json::from_slice(&spec_json).unwrap();
+	let code = spec["genesis"]["raw"]["top"]["0x3a636f6465"].as_str()
+		.expect("the raw spec contains the runtime code");
+	let code = sp_core::bytes::from_hex(code).unwrap();
+
+	let manifest_path = base_path.path().join("dev.manifest.json");
+	let manifest: serde_json::Value =
+		serde_json::from_slice(&std::fs::read(&manifest_path).unwrap()).unwrap();
+	assert_eq!(manifest["id"], "dev");
+	assert_eq!(manifest["spec"], "dev.json");
+	assert_eq!(
+		manifest["specHash"],
+		format!("0x{}", HexDisplay::from(&blake2_256(&spec_json))),
+	);
+	assert_eq!(manifest["codeHash"], format!("0x{}", HexDisplay::from(&blake2_256(&code))));
+	assert_eq!(manifest["codeSize"], code.len());
+	assert_eq!(manifest["bootNodes"], serde_json::json!([]));
+}
@@ -0,0 +1,40 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use assert_cmd::cargo::cargo_bin;
+use std::process::Command;
+
+#[test]
+fn stress_eras_works() {
+	let output = Command::new(cargo_bin("substrate"))
+		.args(&["stress", "eras", "--count", "3", "--validators", "8", "--nominators", "20"])
+		.args(&["--offence-rate", "0.5"])
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(stdout.contains("3 eras in"));
+}
+
+#[test]
+fn stress_eras_rejects_invalid_offence_rate() {
+	let status = Command::new(cargo_bin("substrate"))
+		.args(&["stress", "eras", "--count", "1", "--offence-rate", "1.5"])
+		.status()
+		.unwrap();
+	assert!(!status.success());
+}
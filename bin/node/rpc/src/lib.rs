@@ -31,7 +31,7 @@
 
 use std::{sync::Arc, fmt};
 
-use node_primitives::{Block, BlockNumber, AccountId, Index, Balance};
+use node_primitives::{Block, BlockNumber, AccountId, Index, Balance, Hash};
 use node_runtime::UncheckedExtrinsic;
 use sp_api::ProvideRuntimeApi;
 use sp_transaction_pool::TransactionPool;
@@ -73,35 +73,41 @@ pub struct FullDeps<C, P, SC> {
 	pub pool: Arc<P>,
 	/// The SelectChain Strategy
 	pub select_chain: SC,
+	/// Manager of the subscriptions of the RPC extensions.
+	pub subscriptions: sc_rpc::Subscriptions,
 	/// BABE specific dependencies.
 	pub babe: BabeDeps,
 }
 
 /// Instantiate all Full RPC extensions.
-pub fn create_full<C, P, M, SC>(
+pub fn create_full<C, P, SC>(
 	deps: FullDeps<C, P, SC>,
-) -> jsonrpc_core::IoHandler<M> where
+) -> jsonrpc_core::IoHandler<sc_rpc::Metadata> where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error=BlockChainError> + 'static,
+	C: sc_client::BlockchainEvents<Block>,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
+	C::Api: substrate_frame_rpc_system::EventsRuntimeApi<Block, Hash>,
 	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance, UncheckedExtrinsic>,
+	C::Api: pallet_staking_rpc::StakingRuntimeApi<Block, AccountId, Balance>,
 	C::Api: BabeApi<Block>,
 	<C::Api as sp_api::ApiErrorExt>::Error: fmt::Debug,
 	P: TransactionPool + 'static,
-	M: jsonrpc_core::Metadata + Default,
 	SC: SelectChain<Block> +'static,
 {
-	use substrate_frame_rpc_system::{FullSystem, SystemApi};
+	use substrate_frame_rpc_system::{FullSystem, SystemApi, FullEvents, EventsApi};
 	use pallet_contracts_rpc::{Contracts, ContractsApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use pallet_staking_rpc::{Staking, StakingApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
 		pool,
 		select_chain,
+		subscriptions,
 		babe
 	} = deps;
 	let BabeDeps {
@@ -113,6 +119,9 @@ pub fn create_full<C, P, M, SC>(
 	io.extend_with(
 		SystemApi::to_delegate(FullSystem::new(client.clone(), pool))
 	);
+	io.extend_with(
+		EventsApi::to_delegate(FullEvents::new(client.clone(), subscriptions))
+	);
 	// Making synchronous calls in light client freezes the browser currently,
 	// more context: https://github.com/paritytech/substrate/pull/3480
 	// These RPCs should use an asynchronous caller instead.
@@ -122,6 +131,9 @@ pub fn create_full<C, P, M, SC>(
 	io.extend_with(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
+	io.extend_with(
+		StakingApi::to_delegate(Staking::new(client.clone()))
+	);
 	io.extend_with(
 		sc_consensus_babe_rpc::BabeApi::to_delegate(
 			BabeRPCHandler::new(client, shared_epoch_changes, keystore, babe_config, select_chain)
@@ -156,3 +168,62 @@ pub fn create_light<C, P, M, F>(
 
 	io
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use jsonrpc_core::futures::Stream;
+	use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+	use node_testing::client::{
+		ClientBlockImportExt, TestClientBuilder, TestClientBuilderExt,
+		sp_consensus::BlockOrigin,
+	};
+	use sc_block_builder::BlockBuilderProvider;
+	use sp_block_builder::BlockBuilder;
+	use sp_inherents::InherentData;
+	use sp_runtime::generic::BlockId;
+	use substrate_frame_rpc_system::{EventFilter, EventsApi, FullEvents};
+
+	#[test]
+	fn should_notify_about_events_of_imported_blocks() {
+		let mut core = tokio::runtime::Runtime::new().unwrap();
+		let remote = core.executor();
+		let (subscriber, id, transport) = Subscriber::new_test("test");
+
+		let block_hash = {
+			let mut client = Arc::new(TestClientBuilder::new().build());
+			let api = FullEvents::new(client.clone(), sc_rpc::Subscriptions::new(Arc::new(remote)));
+
+			// only the `System` events, the successful inherents of the block.
+			let filter = EventFilter { events: vec![(0, None)], topics: vec![] };
+			api.subscribe_events(Default::default(), subscriber, Some(filter));
+			assert_eq!(core.block_on(id), Ok(Ok(SubscriptionId::Number(1))));
+
+			let timestamp = node_runtime::MinimumPeriod::get();
+			let mut inherent_data = InherentData::new();
+			inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp).unwrap();
+			inherent_data.put_data(sp_finality_tracker::INHERENT_IDENTIFIER, &0u32).unwrap();
+			let inherents = client.runtime_api()
+				.inherent_extrinsics(&BlockId::number(0), inherent_data)
+				.unwrap();
+
+			let mut builder = client.new_block(Default::default()).unwrap();
+			for inherent in inherents {
+				builder.push(inherent).unwrap();
+			}
+			let block = builder.build().unwrap().block;
+			client.import(BlockOrigin::Own, block).unwrap();
+			client.chain_info().best_hash
+		};
+
+		// the events of the imported block are sent to the transport.
+		let (notification, next) = core.block_on(transport.into_future()).unwrap();
+		let notification = notification.expect("the block has `System` events");
+		assert!(notification.contains(&format!("\"block\":\"{:?}\"", block_hash)));
+		assert!(notification.contains("\"events\":[\"0x"));
+		// no more notifications on this channel
+		assert_eq!(core.block_on(next.into_future()).unwrap().0, None);
+	}
+}
+
@@ -21,6 +21,7 @@
 #![recursion_limit="256"]
 
 use sp_std::prelude::*;
+use codec::Encode;
 use frame_support::{
 	construct_runtime, parameter_types, debug,
 	weights::Weight,
@@ -82,7 +83,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 238,
+	spec_version: 261,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
 };
@@ -279,6 +280,7 @@ parameter_types! {
 	pub const SlashDeferDuration: pallet_staking::EraIndex = 24 * 7; // 1/4 the bonding duration.
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxNominations: u32 = 16;
 }
 
 impl pallet_staking::Trait for Runtime {
@@ -297,6 +299,7 @@ impl pallet_staking::Trait for Runtime {
 	type SessionInterface = Self;
 	type RewardCurve = RewardCurve;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type MaxNominations = MaxNominations;
 }
 
 parameter_types! {
@@ -472,6 +475,10 @@ pub type SubmitTransaction = TransactionSubmitter<ImOnlineId, Runtime, Unchecked
 
 parameter_types! {
 	pub const SessionDuration: BlockNumber = EPOCH_DURATION_IN_SLOTS as _;
+	// unresponsiveness slashes are calibrated for the sessions of this chain.
+	pub const ReferenceSessionLength: u64 = EPOCH_DURATION_IN_SLOTS;
+	pub const UnresponsivenessQuorum: u32 = 3;
+	pub const MaxOfflineSessions: sp_staking::SessionIndex = 6;
 }
 
 impl pallet_im_online::Trait for Runtime {
@@ -480,6 +487,10 @@ impl pallet_im_online::Trait for Runtime {
 	type Call = Call;
 	type SubmitTransaction = SubmitTransaction;
 	type SessionDuration = SessionDuration;
+	type ReferenceSessionLength = ReferenceSessionLength;
+	type UnresponsivenessQuorum = UnresponsivenessQuorum;
+	type MaxOfflineSessions = MaxOfflineSessions;
+	type ChillOffline = Staking;
 	type ReportUnresponsiveness = Offences;
 }
 
@@ -635,7 +646,7 @@ construct_runtime!(
 		Indices: pallet_indices::{Module, Call, Storage, Config<T>, Event<T>},
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
-		Staking: pallet_staking::{Module, Call, Config<T>, Storage, Event<T>},
+		Staking: pallet_staking::{Module, Call, Config<T>, Storage, Event<T>, ValidateUnsigned},
 		Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
 		Democracy: pallet_democracy::{Module, Call, Storage, Config, Event<T>},
 		Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
@@ -782,6 +793,16 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl frame_system_rpc_runtime_api::EventsApi<Block, Hash> for Runtime {
+		fn events(filter: frame_system_rpc_runtime_api::EventFilter<Hash>) -> Vec<Vec<u8>> {
+			System::events()
+				.into_iter()
+				.filter(|record| filter.matches(&record.event.encode(), &record.topics))
+				.map(|record| record.encode())
+				.collect()
+		}
+	}
+
 	impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber>
 		for Runtime
 	{
@@ -792,14 +813,16 @@ impl_runtime_apis! {
 			gas_limit: u64,
 			input_data: Vec<u8>,
 		) -> ContractExecResult {
-			let exec_result =
-				Contracts::bare_call(origin, dest.into(), value, gas_limit, input_data);
+			let pallet_contracts::DryRunResult { exec_result, gas_consumed, debug_message } =
+				Contracts::dry_run_call(origin, dest.into(), value, gas_limit, input_data);
 			match exec_result {
 				Ok(v) => ContractExecResult::Success {
 					status: v.status,
 					data: v.data,
+					gas_consumed,
+					debug_message,
 				},
-				Err(_) => ContractExecResult::Error,
+				Err(_) => ContractExecResult::Error { gas_consumed, debug_message },
 			}
 		}
 
@@ -827,6 +850,37 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_staking_rpc_runtime_api::StakingApi<Block, AccountId, Balance> for Runtime {
+		fn validators() -> Vec<AccountId> {
+			Session::validators()
+		}
+
+		fn exposure_of(who: AccountId) -> pallet_staking::Exposure<AccountId, Balance> {
+			Staking::active_era()
+				.map(|active_era| Staking::eras_stakers(active_era.index, &who))
+				.unwrap_or_default()
+		}
+
+		fn ledger(controller: AccountId) -> Option<pallet_staking::StakingLedger<AccountId, Balance>> {
+			Staking::ledger(&controller)
+		}
+
+		fn slashable_balance(who: AccountId) -> Balance {
+			Staking::slashable_balance_of(&who)
+		}
+
+		fn unlocking(controller: AccountId) -> Vec<pallet_staking::UnlockChunk<Balance>> {
+			Staking::ledger(&controller).map(|ledger| ledger.unlocking).unwrap_or_default()
+		}
+
+		fn pending_slashes() -> Vec<(
+			pallet_staking::EraIndex,
+			Vec<pallet_staking::UnappliedSlash<AccountId, Balance>>,
+		)> {
+			Staking::pending_slashes()
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			SessionKeys::generate(seed)
@@ -34,7 +34,9 @@ pub use light::*;
 pub use notifications::*;
 pub use proof_provider::*;
 
-pub use sp_state_machine::{StorageProof, ExecutionStrategy, CloneableSpawn};
+pub use sp_state_machine::{
+	StorageProof, ExecutionStrategy, CloneableSpawn, StateChunk, state_chunk_proof_check,
+};
 
 /// Utility methods for the client.
 pub mod utils {
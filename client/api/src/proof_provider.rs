@@ -68,4 +68,16 @@ pub trait ProofProvider<Block: BlockT> {
 		storage_key: Option<&StorageKey>,
 		key: &StorageKey,
 	) -> sp_blockchain::Result<ChangesProof<Block::Header>>;
+
+	/// Prove a chunk of the state of a block, which is served to peers downloading the state
+	/// instead of executing the blocks.
+	///
+	/// The chunk is read by `sp_state_machine::read_state_chunk` from `start`, until the size of
+	/// its top and child trie pairs reaches `size_limit`.
+	fn state_chunk_proof(
+		&self,
+		id: &BlockId<Block>,
+		start: &[Vec<u8>],
+		size_limit: usize,
+	) -> sp_blockchain::Result<StorageProof>;
 }
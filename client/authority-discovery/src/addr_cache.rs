@@ -97,7 +97,8 @@ where
 			.collect()
 	}
 
-	pub fn retain_ids(&mut self, ids: &Vec<Id>) {
+	/// Removes the addresses of the authorities not in `ids`. Returns whether any were removed.
+	pub fn retain_ids(&mut self, ids: &Vec<Id>) -> bool {
 		let to_remove = self
 			.cache
 			.iter()
@@ -106,9 +107,11 @@ where
 			.cloned()
 			.collect::<Vec<Id>>();
 
-		for key in to_remove {
-			self.cache.remove(&key);
+		for key in &to_remove {
+			self.cache.remove(key);
 		}
+
+		!to_remove.is_empty()
 	}
 }
 
@@ -321,6 +321,12 @@ where
 				.get_value(&hash_authority_id(authority_id.as_ref())?);
 		}
 
+		// Stop prioritizing connections to nodes that left the authority set, even if no new
+		// record is ever found on the Dht.
+		if self.addr_cache.retain_ids(&authorities) {
+			self.update_peer_set_priority_group()?;
+		}
+
 		Ok(())
 	}
 
@@ -423,3 +423,43 @@ fn handle_dht_events_with_value_found_should_call_set_priority_group() {
 
 	let _ = block_on(poll_fn(f));
 }
+
+#[test]
+fn request_addresses_of_others_removes_former_authorities_from_priority_group() {
+	let _ = ::env_logger::try_init();
+	let (_dht_event_tx, dht_event_rx) = channel(1000);
+
+	let current_key_pair = AuthorityPair::from_seed_slice(&[1; 32]).unwrap();
+	let former_key_pair = AuthorityPair::from_seed_slice(&[2; 32]).unwrap();
+
+	let test_api = Arc::new(TestApi {
+		authorities: vec![current_key_pair.public()],
+	});
+
+	let network: Arc<TestNetwork> = Arc::new(Default::default());
+	let key_store = KeyStore::new();
+
+	let mut authority_discovery = AuthorityDiscovery::new(
+		test_api,
+		network.clone(),
+		vec![],
+		key_store,
+		dht_event_rx.boxed(),
+		None,
+	);
+
+	let former_address: Multiaddr = "/ip6/2001:db8::".parse().unwrap();
+	authority_discovery.addr_cache.insert(former_key_pair.public(), vec![former_address]);
+
+	authority_discovery.request_addresses_of_others().unwrap();
+
+	// Expect the address of the former authority to be removed from the priority group.
+	assert_eq!(
+		network.set_priority_group_call.lock().unwrap().clone(),
+		vec![("authorities".to_string(), HashSet::new())],
+	);
+
+	// Without any further change to the authority set, the priority group is left alone.
+	authority_discovery.request_addresses_of_others().unwrap();
+	assert_eq!(network.set_priority_group_call.lock().unwrap().len(), 1);
+}
@@ -17,7 +17,7 @@
 //! Substrate chain configurations.
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -34,6 +34,7 @@ enum GenesisSource<G> {
 	File(PathBuf),
 	Binary(Cow<'static, [u8]>),
 	Factory(Arc<dyn Fn() -> G + Send + Sync>),
+	Storage(Storage),
 }
 
 impl<G> Clone for GenesisSource<G> {
@@ -42,6 +43,7 @@ impl<G> Clone for GenesisSource<G> {
 			GenesisSource::File(ref path) => GenesisSource::File(path.clone()),
 			GenesisSource::Binary(ref d) => GenesisSource::Binary(d.clone()),
 			GenesisSource::Factory(ref f) => GenesisSource::Factory(f.clone()),
+			GenesisSource::Storage(ref s) => GenesisSource::Storage(s.clone()),
 		}
 	}
 }
@@ -67,6 +69,7 @@ impl<G: RuntimeGenesis> GenesisSource<G> {
 				Ok(genesis.genesis)
 			},
 			GenesisSource::Factory(f) => Ok(Genesis::Runtime(f())),
+			GenesisSource::Storage(storage) => Ok(Genesis::Raw(RawGenesis::from(storage.clone()))),
 		}
 	}
 }
@@ -102,7 +105,8 @@ impl<G: RuntimeGenesis, E> BuildStorage for ChainSpec<G, E> {
 	}
 }
 
-type GenesisStorage = HashMap<StorageKey, StorageData>;
+/// Sorted, for the raw spec to be reproducible.
+type GenesisStorage = BTreeMap<StorageKey, StorageData>;
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -119,7 +123,32 @@ struct ChildRawStorage {
 /// Storage content for genesis block.
 struct RawGenesis {
 	top: GenesisStorage,
-	children: HashMap<StorageKey, ChildRawStorage>,
+	children: BTreeMap<StorageKey, ChildRawStorage>,
+}
+
+impl From<Storage> for RawGenesis {
+	fn from(storage: Storage) -> Self {
+		let top = storage.top.into_iter()
+			.map(|(k, v)| (StorageKey(k), StorageData(v)))
+			.collect();
+		let children = storage.children.into_iter()
+			.map(|(sk, child)| {
+				let info = child.child_info.as_ref();
+				let (info, ci_type) = info.info();
+				(
+					StorageKey(sk),
+					ChildRawStorage {
+						data: child.data.into_iter()
+							.map(|(k, v)| (StorageKey(k), StorageData(v)))
+							.collect(),
+						child_info: info.to_vec(),
+						child_type: ci_type,
+					},
+			)})
+			.collect();
+
+		RawGenesis { top, children }
+	}
 }
 
 #[derive(Serialize, Deserialize)]
@@ -152,6 +181,38 @@ struct ClientSpec<E> {
 /// Arbitrary properties defined in chain spec as a JSON object
 pub type Properties = json::map::Map<String, json::Value>;
 
+/// The well-known `properties` of a chain, describing its token and its addresses.
+///
+/// They are read from and written to the `properties` of the spec, next to any other property.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainProperties {
+	/// Symbol of the token of the chain, e.g. `DOT`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub token_symbol: Option<String>,
+	/// Number of decimals of the token amounts displayed to users.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub token_decimals: Option<u32>,
+	/// SS58 address format of the chain.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ss58_format: Option<u8>,
+}
+
+impl ChainProperties {
+	/// The well-known properties among `properties`.
+	pub fn from_properties(properties: &Properties) -> Result<Self, String> {
+		json::from_value(json::Value::Object(properties.clone()))
+			.map_err(|e| format!("Invalid chain properties: {}", e))
+	}
+
+	/// Add the properties which are set to `properties`, replacing the existing ones.
+	pub fn merge_into(&self, properties: &mut Properties) {
+		if let json::Value::Object(set) = json::to_value(self).expect("Serialization can't fail") {
+			properties.extend(set);
+		}
+	}
+}
+
 /// A type denoting empty extensions.
 ///
 /// We use `Option` here since `()` is not flattenable by serde.
@@ -205,11 +266,41 @@ impl<G, E> ChainSpec<G, E> {
 		self.client_spec.properties.as_ref().unwrap_or(&json::map::Map::new()).clone()
 	}
 
+	/// Replace the genesis of the spec by the raw `storage`.
+	pub fn set_storage(&mut self, storage: Storage) {
+		self.genesis = GenesisSource::Storage(storage);
+	}
+
 	/// Add a bootnode to the list.
 	pub fn add_boot_node(&mut self, addr: Multiaddr) {
 		self.client_spec.boot_nodes.push(addr.to_string())
 	}
 
+	/// Replace the bootnodes of the spec by `boot_nodes`.
+	pub fn with_boot_nodes(mut self, boot_nodes: Vec<String>) -> Self {
+		self.client_spec.boot_nodes = boot_nodes;
+		self
+	}
+
+	/// The well-known properties of the chain.
+	pub fn chain_properties(&self) -> Result<ChainProperties, String> {
+		ChainProperties::from_properties(&self.properties())
+	}
+
+	/// Set the well-known properties of the chain, keeping the others.
+	pub fn with_chain_properties(mut self, chain_properties: ChainProperties) -> Self {
+		let mut properties = self.properties();
+		chain_properties.merge_into(&mut properties);
+		self.client_spec.properties = Some(properties);
+		self
+	}
+
+	/// Replace the default telemetry endpoints of the chain.
+	pub fn with_telemetry_endpoints(mut self, endpoints: Option<TelemetryEndpoints>) -> Self {
+		self.client_spec.telemetry_endpoints = endpoints;
+		self
+	}
+
 	/// Returns a reference to defined chain spec extensions.
 	pub fn extensions(&self) -> &E {
 		&self.client_spec.extensions
@@ -281,29 +372,7 @@ impl<G: RuntimeGenesis, E: serde::Serialize + Clone> ChainSpec<G, E> {
 
 		};
 		let genesis = match (raw, self.genesis.resolve()?) {
-			(true, Genesis::Runtime(g)) => {
-				let storage = g.build_storage()?;
-				let top = storage.top.into_iter()
-					.map(|(k, v)| (StorageKey(k), StorageData(v)))
-					.collect();
-				let children = storage.children.into_iter()
-					.map(|(sk, child)| {
-						let info = child.child_info.as_ref();
-						let (info, ci_type) = info.info();
-						(
-							StorageKey(sk),
-							ChildRawStorage {
-								data: child.data.into_iter()
-									.map(|(k, v)| (StorageKey(k), StorageData(v)))
-									.collect(),
-								child_info: info.to_vec(),
-								child_type: ci_type,
-							},
-					)})
-					.collect();
-
-				Genesis::Raw(RawGenesis { top, children })
-			},
+			(true, Genesis::Runtime(g)) => Genesis::Raw(RawGenesis::from(g.build_storage()?)),
 			(_, genesis) => genesis,
 		};
 		let container = Container {
@@ -315,10 +384,57 @@ impl<G: RuntimeGenesis, E: serde::Serialize + Clone> ChainSpec<G, E> {
 	}
 }
 
+impl<G, E> ChainSpec<G, E> where
+	G: RuntimeGenesis,
+	E: serde::Serialize + serde::de::DeserializeOwned + Clone,
+{
+	/// Overlay `patch` on the JSON of the spec, following JSON merge patch (RFC 7386).
+	///
+	/// Objects are merged recursively, `null` removes a field and any other value replaces it.
+	/// This allows e.g. changing the balances or the code of a spec without copying it whole.
+	pub fn patch(&mut self, patch: &json::Value) -> Result<(), String> {
+		let mut spec: json::Value = json::from_str(&self.as_json(false)?)
+			.map_err(|e| format!("Error parsing spec json: {}", e))?;
+		merge_patch(&mut spec, patch);
+		let spec = json::to_vec(&spec)
+			.map_err(|e| format!("Error generating spec json: {}", e))?;
+		let patched = Self::from_json_bytes(spec)
+			.map_err(|e| format!("Invalid patched spec: {}", e))?;
+		// Fail now rather than when the genesis is built.
+		patched.genesis.resolve()
+			.map_err(|e| format!("Invalid patched spec: {}", e))?;
+		*self = patched;
+		Ok(())
+	}
+}
+
+/// Apply the JSON merge `patch` to `target`.
+fn merge_patch(target: &mut json::Value, patch: &json::Value) {
+	let patch = match patch {
+		json::Value::Object(patch) => patch,
+		_ => {
+			*target = patch.clone();
+			return
+		},
+	};
+	if !target.is_object() {
+		*target = json::Value::Object(Default::default());
+	}
+	if let json::Value::Object(target) = target {
+		for (key, value) in patch {
+			if value.is_null() {
+				target.remove(key);
+			} else {
+				merge_patch(target.entry(key.clone()).or_insert(json::Value::Null), value);
+			}
+		}
+	}
+}
+
 impl<G, E> crate::ChainSpec for ChainSpec<G, E>
 where
-	G: RuntimeGenesis,
-	E: GetExtension + serde::Serialize + Clone + Send,
+	G: RuntimeGenesis + 'static,
+	E: GetExtension + serde::Serialize + serde::de::DeserializeOwned + Clone + Send + 'static,
 {
 	fn boot_nodes(&self) -> &[String] {
 		ChainSpec::boot_nodes(self)
@@ -344,6 +460,10 @@ where
 		ChainSpec::properties(self)
 	}
 
+	fn chain_properties(&self) -> Result<ChainProperties, String> {
+		ChainSpec::chain_properties(self)
+	}
+
 	fn add_boot_node(&mut self, addr: Multiaddr) {
 		ChainSpec::add_boot_node(self, addr)
 	}
@@ -359,6 +479,18 @@ where
 	fn as_storage_builder(&self) -> &dyn BuildStorage {
 		self
 	}
+
+	fn patch(&mut self, patch: &json::Value) -> Result<(), String> {
+		ChainSpec::patch(self, patch)
+	}
+
+	fn set_storage(&mut self, storage: Storage) {
+		ChainSpec::set_storage(self, storage)
+	}
+
+	fn cloned_box(&self) -> Box<dyn crate::ChainSpec> {
+		Box::new(self.clone())
+	}
 }
 
 #[cfg(test)]
@@ -394,6 +526,128 @@ mod tests {
 		assert_eq!(spec1.as_json(false), spec2.as_json(false));
 	}
 
+	#[test]
+	fn should_read_and_set_chain_properties() {
+		let spec = TestSpec::from_json_bytes(Cow::Owned(
+			include_bytes!("../res/chain_spec.json").to_vec()
+		)).unwrap();
+		assert_eq!(spec.chain_properties().unwrap(), ChainProperties {
+			token_symbol: Some("FIR".into()),
+			token_decimals: Some(15),
+			ss58_format: None,
+		});
+
+		let spec = spec.with_chain_properties(ChainProperties {
+			token_symbol: Some("TST".into()),
+			token_decimals: None,
+			ss58_format: Some(42),
+		});
+		let properties = spec.properties();
+		assert_eq!(properties["tokenSymbol"], "TST");
+		assert_eq!(properties["tokenDecimals"], 15);
+		assert_eq!(properties["ss58Format"], 42);
+
+		let mut properties = Properties::new();
+		properties.insert("tokenDecimals".into(), "fifteen".into());
+		assert!(ChainProperties::from_properties(&properties).is_err());
+	}
+
+	#[test]
+	fn should_replace_boot_nodes() {
+		let spec = TestSpec::from_json_bytes(Cow::Owned(
+			include_bytes!("../res/chain_spec.json").to_vec()
+		)).unwrap();
+		let boot_nodes = vec![
+			"/ip4/10.0.0.1/tcp/30333/p2p/QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".to_string(),
+		];
+
+		let spec = spec.with_boot_nodes(boot_nodes.clone());
+		assert_eq!(spec.boot_nodes(), &boot_nodes[..]);
+	}
+
+	#[test]
+	fn should_patch_spec() {
+		let mut spec = TestSpec::from_json_bytes(Cow::Owned(
+			include_bytes!("../res/chain_spec.json").to_vec()
+		)).unwrap();
+		let name = spec.name().to_string();
+
+		spec.patch(&json::json!({
+			"id": "patched",
+			"bootNodes": [],
+			"telemetryEndpoints": null,
+		})).unwrap();
+
+		assert_eq!(spec.id(), "patched");
+		assert_eq!(spec.name(), name);
+		assert!(spec.boot_nodes().is_empty());
+		assert!(spec.telemetry_endpoints().is_none());
+		assert_eq!(spec.build_storage().unwrap().top, TestSpec::from_json_bytes(Cow::Owned(
+			include_bytes!("../res/chain_spec.json").to_vec()
+		)).unwrap().build_storage().unwrap().top);
+
+		assert!(spec.patch(&json::json!({ "genesis": { "runtime": 1 } })).is_err());
+		assert_eq!(spec.id(), "patched");
+		spec.build_storage().unwrap();
+	}
+
+	#[test]
+	fn merge_patch_follows_rfc_7386() {
+		let mut target = json::json!({ "a": "b", "c": { "d": "e", "f": "g" } });
+		merge_patch(&mut target, &json::json!({ "a": "z", "c": { "f": null } }));
+		assert_eq!(target, json::json!({ "a": "z", "c": { "d": "e" } }));
+
+		let mut target = json::json!({ "a": [1, 2] });
+		merge_patch(&mut target, &json::json!({ "a": [3], "b": { "c": null } }));
+		assert_eq!(target, json::json!({ "a": [3], "b": {} }));
+	}
+
+	#[test]
+	fn raw_spec_is_reproducible() {
+		let spec = |keys: Vec<u32>| {
+			let genesis = Genesis(keys.iter().map(|k| (format!("key{}", k), k.to_string())).collect());
+			TestSpec::from_genesis(
+				"test",
+				"test-id",
+				move || Genesis(genesis.0.clone()),
+				Vec::new(),
+				None,
+				None,
+				None,
+				None,
+			).as_json(true).unwrap()
+		};
+
+		let raw = spec((0..100).collect());
+		assert_eq!(raw, spec((0..100).rev().collect()));
+
+		let json: json::Value = json::from_str(&raw).unwrap();
+		let top = json["genesis"]["raw"]["top"].as_object().unwrap();
+		let keys = raw.match_indices("\"0x6b6579").map(|(i, _)| i).collect::<Vec<_>>();
+		assert_eq!(keys.len(), top.len());
+		let mut sorted = top.keys().cloned().collect::<Vec<_>>();
+		sorted.sort();
+		let in_output = keys.iter()
+			.map(|i| raw[i + 1..].split('"').next().unwrap().to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(in_output, sorted);
+	}
+
+	#[test]
+	fn should_replace_genesis_by_raw_storage() {
+		let mut spec = TestSpec::from_json_bytes(Cow::Owned(
+			include_bytes!("../res/chain_spec.json").to_vec()
+		)).unwrap();
+
+		let mut storage = Storage::default();
+		storage.top.insert(b"snapshot".to_vec(), b"value".to_vec());
+		spec.set_storage(storage.clone());
+
+		assert_eq!(spec.build_storage().unwrap().top, storage.top);
+		let json: json::Value = json::from_str(&spec.as_json(false).unwrap()).unwrap();
+		assert_eq!(json["genesis"]["raw"]["top"]["0x736e617073686f74"], "0x76616c7565");
+	}
+
 	#[derive(Debug, Serialize, Deserialize)]
 	#[serde(rename_all = "camelCase")]
 	struct Extension1 {
@@ -410,4 +664,25 @@ mod tests {
 
 		assert_eq!(spec.extensions().my_property, "Test Extension");
 	}
+
+	// Make the proc macro work for tests.
+	use crate as sc_chain_spec;
+
+	#[derive(Debug, Clone, Serialize, Deserialize, sc_chain_spec::ChainSpecExtension)]
+	#[serde(rename_all = "camelCase")]
+	struct Extension2 {
+		my_property: String,
+	}
+
+	#[test]
+	fn extensions_are_typed_through_the_chain_spec_trait() {
+		let spec: Box<dyn sc_chain_spec::ChainSpec> = Box::new(
+			ChainSpec::<Genesis, Extension2>::from_json_bytes(Cow::Owned(
+				include_bytes!("../res/chain_spec2.json").to_vec()
+			)).unwrap()
+		);
+
+		assert_eq!(spec.extension::<String>().map(|s| s.as_str()), Some("Test Extension"));
+		assert!(spec.extension::<u64>().is_none());
+	}
 }
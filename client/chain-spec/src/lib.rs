@@ -110,13 +110,16 @@
 
 mod chain_spec;
 mod extension;
+mod presets;
 
-pub use chain_spec::{ChainSpec as GenericChainSpec, Properties, NoExtension};
+pub use chain_spec::{ChainSpec as GenericChainSpec, ChainProperties, Properties, NoExtension};
+pub use presets::{ChainSpecPreset, ChainSpecPresets};
 pub use extension::{Group, Fork, Forks, Extension, GetExtension, get_extension};
 pub use sc_chain_spec_derive::{ChainSpecExtension, ChainSpecGroup};
 
 use serde::{Serialize, de::DeserializeOwned};
 use sp_runtime::BuildStorage;
+use sp_core::storage::Storage;
 use sc_network::Multiaddr;
 use sc_telemetry::TelemetryEndpoints;
 
@@ -140,6 +143,8 @@ pub trait ChainSpec: BuildStorage + Send {
 	///
 	/// Returns an empty JSON object if 'properties' not defined in config
 	fn properties(&self) -> Properties;
+	/// The well-known properties of the chain: its token and its address format.
+	fn chain_properties(&self) -> Result<ChainProperties, String>;
 	/// Returns a reference to defined chain spec extensions.
 	fn extensions(&self) -> &dyn GetExtension;
 	/// Add a bootnode to the list.
@@ -148,4 +153,20 @@ pub trait ChainSpec: BuildStorage + Send {
 	fn as_json(&self, raw: bool) -> Result<String, String>;
 	/// Return StorageBuilder for this spec.
 	fn as_storage_builder(&self) -> &dyn BuildStorage;
+	/// Overlay the JSON merge `patch` on the spec, see [`GenericChainSpec::patch`].
+	fn patch(&mut self, patch: &serde_json::Value) -> Result<(), String>;
+	/// Replace the genesis of the spec by the raw `storage`, e.g. a snapshot of a live chain.
+	fn set_storage(&mut self, storage: Storage);
+	/// Clone the spec into a new box.
+	fn cloned_box(&self) -> Box<dyn ChainSpec>;
+}
+
+impl<'a> dyn ChainSpec + 'a {
+	/// The extension of type `T`, if the spec defines one.
+	///
+	/// Typed access to the custom fields of a chain, e.g. `ForkBlocks`, for the code only
+	/// holding a `dyn ChainSpec`.
+	pub fn extension<T: 'static>(&self) -> Option<&T> {
+		get_extension::<T>(self.extensions())
+	}
 }
@@ -0,0 +1,151 @@
+// Copyright 2019-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Registry of the chain specs built into a node.
+
+use crate::ChainSpec;
+
+/// Function building a chain spec.
+type Loader = Box<dyn Fn() -> Result<Box<dyn ChainSpec>, String>>;
+
+/// A chain spec built into a node, selected with `--chain <id>`.
+pub struct ChainSpecPreset {
+	ids: Vec<&'static str>,
+	description: &'static str,
+	load: Loader,
+}
+
+impl ChainSpecPreset {
+	/// The identifiers of the preset, the empty identifier is the default chain.
+	pub fn ids(&self) -> &[&'static str] {
+		&self.ids
+	}
+
+	/// A description of the chain.
+	pub fn description(&self) -> &'static str {
+		self.description
+	}
+
+	/// Build the chain spec.
+	pub fn load(&self) -> Result<Box<dyn ChainSpec>, String> {
+		(self.load)()
+	}
+}
+
+/// The chain specs built into a node.
+///
+/// Downstream nodes extend the presets of the node they build on by registering their own.
+#[derive(Default)]
+pub struct ChainSpecPresets {
+	presets: Vec<ChainSpecPreset>,
+}
+
+impl ChainSpecPresets {
+	/// An empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register the chain spec built by `load` under `ids`.
+	///
+	/// Panics if one of the identifiers is already registered.
+	pub fn register<F>(mut self, ids: &[&'static str], description: &'static str, load: F) -> Self
+	where
+		F: Fn() -> Result<Box<dyn ChainSpec>, String> + 'static,
+	{
+		for id in ids {
+			assert!(self.get(id).is_none(), "The chain spec preset `{}` is registered twice", id);
+		}
+		self.presets.push(ChainSpecPreset { ids: ids.to_vec(), description, load: Box::new(load) });
+		self
+	}
+
+	/// The preset registered under `id`, if any.
+	pub fn get(&self, id: &str) -> Option<&ChainSpecPreset> {
+		self.presets.iter().find(|preset| preset.ids.contains(&id))
+	}
+
+	/// Build the chain spec registered under `id`, `None` if no preset has this identifier.
+	pub fn load(&self, id: &str) -> Option<Result<Box<dyn ChainSpec>, String>> {
+		self.get(id).map(|preset| preset.load())
+	}
+
+	/// The registered presets, in the order of registration.
+	pub fn iter(&self) -> impl Iterator<Item = &ChainSpecPreset> {
+		self.presets.iter()
+	}
+
+	/// One line per preset, listing its identifiers and its description.
+	pub fn list(&self) -> String {
+		self.presets.iter().map(|preset| {
+			let mut ids = preset.ids.iter().filter(|id| !id.is_empty()).cloned().collect::<Vec<_>>();
+			if preset.ids.contains(&"") {
+				ids.push("(default)");
+			}
+			format!("{}: {}\n", ids.join(", "), preset.description)
+		}).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::GenericChainSpec;
+
+	fn spec(id: &'static str) -> impl Fn() -> Result<Box<dyn ChainSpec>, String> {
+		move || Ok(Box::new(GenericChainSpec::<Genesis>::from_genesis(
+			id,
+			id,
+			|| Genesis,
+			Vec::new(),
+			None,
+			None,
+			None,
+			None,
+		)))
+	}
+
+	#[derive(serde::Serialize, serde::Deserialize)]
+	struct Genesis;
+
+	impl sp_runtime::BuildStorage for Genesis {
+		fn assimilate_storage(&self, _: &mut sp_core::storage::Storage) -> Result<(), String> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn presets_are_loaded_by_id() {
+		let presets = ChainSpecPresets::new()
+			.register(&["dev"], "Development chain", spec("dev"))
+			.register(&["", "main"], "Main chain", spec("main"));
+
+		assert_eq!(presets.load("dev").unwrap().unwrap().id(), "dev");
+		assert_eq!(presets.load("").unwrap().unwrap().id(), "main");
+		assert_eq!(presets.load("main").unwrap().unwrap().id(), "main");
+		assert!(presets.load("spec.json").is_none());
+
+		assert_eq!(presets.list(), "dev: Development chain\nmain, (default): Main chain\n");
+	}
+
+	#[test]
+	#[should_panic(expected = "registered twice")]
+	fn presets_cannot_be_registered_twice() {
+		let _ = ChainSpecPresets::new()
+			.register(&["dev"], "Development chain", spec("dev"))
+			.register(&["dev"], "Another development chain", spec("dev"));
+	}
+}
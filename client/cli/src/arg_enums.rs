@@ -74,6 +74,70 @@ impl Into<sc_tracing::TracingReceiver> for TracingReceiver {
 	}
 }
 
+arg_enum! {
+	/// Which RPC methods are exposed.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	pub enum RpcMethods {
+		Auto,
+		Safe,
+		Unsafe,
+	}
+}
+
+impl Into<sc_service::config::RpcMethods> for RpcMethods {
+	fn into(self) -> sc_service::config::RpcMethods {
+		match self {
+			RpcMethods::Auto => sc_service::config::RpcMethods::Auto,
+			RpcMethods::Safe => sc_service::config::RpcMethods::Safe,
+			RpcMethods::Unsafe => sc_service::config::RpcMethods::Unsafe,
+		}
+	}
+}
+
+arg_enum! {
+	/// What to do with the RPC notifications a connection doesn't consume fast enough.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	pub enum NotificationDropPolicy {
+		DropOldest,
+		DropNewest,
+		Unsubscribe,
+	}
+}
+
+impl Into<sc_service::config::NotificationDropPolicy> for NotificationDropPolicy {
+	fn into(self) -> sc_service::config::NotificationDropPolicy {
+		match self {
+			NotificationDropPolicy::DropOldest => sc_service::config::NotificationDropPolicy::DropOldest,
+			NotificationDropPolicy::DropNewest => sc_service::config::NotificationDropPolicy::DropNewest,
+			NotificationDropPolicy::Unsubscribe =>
+				sc_service::config::NotificationDropPolicy::Unsubscribe,
+		}
+	}
+}
+
+arg_enum! {
+	/// How to synchronize the chain.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum SyncMode {
+		// Download and execute all the blocks.
+		Full,
+		// Download the headers and the state of a recent block.
+		Fast,
+	}
+}
+
+impl Into<sc_network::config::SyncMode> for SyncMode {
+	fn into(self) -> sc_network::config::SyncMode {
+		match self {
+			SyncMode::Full => sc_network::config::SyncMode::Full,
+			SyncMode::Fast => sc_network::config::SyncMode::Fast,
+		}
+	}
+}
+
 arg_enum! {
 	#[allow(missing_docs)]
 	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -82,6 +146,16 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// The cryptography scheme of a key.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum CryptoScheme {
+		Ed25519,
+		Sr25519,
+	}
+}
+
 arg_enum! {
 	/// How to execute blocks
 	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,185 @@
+// Copyright 2018-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt::Debug;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use futures::TryFutureExt;
+use log::info;
+use structopt::StructOpt;
+use sc_service::{
+	Configuration, ServiceBuilderCommand, ChainSpec, Roles, config::DatabaseConfig,
+	chain_ops::ImportBenchmark,
+};
+
+use crate::error;
+use crate::VersionInfo;
+use crate::runtime::run_until_exit;
+use crate::params::SharedParams;
+use crate::params::ImportParams;
+
+/// Sub directory of the chain directory holding the database of the benchmark.
+const BENCHMARK_DB_CONFIG_PATH: &str = "benchmark-import-db";
+
+/// The `benchmark-import` command used to measure the import of blocks.
+#[derive(Debug, StructOpt, Clone)]
+pub struct BenchmarkImportCmd {
+	/// Blocks to import, as written by `export-blocks`.
+	///
+	/// The blocks are imported into a new database, which is removed afterwards. Use
+	/// `--execution native` and `--execution wasm` to compare the execution methods.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+
+	/// Read binary input rather than JSON, as written by `export-blocks --binary true`.
+	#[structopt(long = "binary", value_name = "BOOL", parse(try_from_str), default_value("false"))]
+	pub binary: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl BenchmarkImportCmd {
+	/// Run the benchmark-import command
+	pub fn run<B, BC, BB>(
+		self,
+		config: Configuration,
+		builder: B,
+	) -> error::Result<()>
+	where
+		B: FnOnce(Configuration) -> Result<BC, sc_service::error::Error>,
+		BC: ServiceBuilderCommand<Block = BB> + Unpin,
+		BB: sp_runtime::traits::Block + Debug,
+	{
+		let input = fs::File::open(&self.input)?;
+		let input_size = input.metadata()?.len();
+		let execution = config.execution_strategies.importing;
+		let wasm_method = config.wasm_method;
+		let database_path = match config.expect_database() {
+			DatabaseConfig::Path { path, .. } => Some(path.clone()),
+			DatabaseConfig::Custom(_) => None,
+		};
+
+		let binary = self.binary;
+		let measured = Arc::new(Mutex::new(None));
+		let result = {
+			let measured = measured.clone();
+			let threads = self.shared_params.runtime_threads();
+			run_until_exit(config, self.shared_params.shutdown_timeout(), threads, move |config| {
+				Ok(builder(config)?.benchmark_import(input, binary).map_ok(move |benchmark| {
+					*measured.lock().expect("only locked here and below; qed") = Some(benchmark);
+				}))
+			})
+		};
+		if let Some(path) = database_path {
+			let _ = fs::remove_dir_all(path);
+		}
+		result?;
+
+		let benchmark = measured.lock().expect("only locked here and above; qed").take();
+		match benchmark {
+			Some(benchmark) => print!("{}", report(&benchmark, input_size, execution, wasm_method)),
+			None => info!("Interrupted before all the blocks were imported"),
+		}
+
+		Ok(())
+	}
+
+	/// Update and prepare a `Configuration` with command line parameters
+	pub fn update_config<F>(
+		&self,
+		mut config: &mut Configuration,
+		spec_factory: F,
+		version: &VersionInfo,
+	) -> error::Result<()> where
+		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
+	{
+		self.shared_params.update_config(&mut config, spec_factory, version)?;
+
+		let path = config.in_chain_config_dir(BENCHMARK_DB_CONFIG_PATH)
+			.expect("We provided a base_path/config_dir.");
+		if path.exists() {
+			fs::remove_dir_all(&path)?;
+		}
+		config.database = Some(DatabaseConfig::Path { path, cache_size: None });
+
+		self.import_params.update_config(&mut config, Roles::FULL, self.shared_params.dev)?;
+		config.use_in_memory_keystore()?;
+
+		Ok(())
+	}
+}
+
+/// Describe `benchmark`, the import of `input_size` bytes of blocks.
+fn report(
+	benchmark: &ImportBenchmark,
+	input_size: u64,
+	execution: sc_client_api::ExecutionStrategy,
+	wasm_method: sc_service::config::WasmExecutionMethod,
+) -> String {
+	let seconds = benchmark.elapsed.as_secs_f64();
+	let mut out = format!(
+		"Imported {} blocks in {:.3} s ({:.2} blocks/s) with {:?} execution ({:?} Wasm)\n",
+		benchmark.blocks,
+		seconds,
+		if seconds > 0.0 { benchmark.blocks as f64 / seconds } else { 0.0 },
+		execution,
+		wasm_method,
+	);
+	match benchmark.bytes_written {
+		Some(bytes) if input_size > 0 => out.push_str(&format!(
+			"Wrote {} bytes to the database for {} bytes of blocks (write amplification {:.2})\n",
+			bytes,
+			input_size,
+			bytes as f64 / input_size as f64,
+		)),
+		_ => out.push_str("The database does not report the bytes written\n"),
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+	use sc_client_api::ExecutionStrategy;
+	use sc_service::config::WasmExecutionMethod;
+
+	#[test]
+	fn report_includes_rates() {
+		let benchmark = ImportBenchmark {
+			blocks: 100,
+			elapsed: Duration::from_secs(4),
+			bytes_written: Some(3000),
+		};
+		let report = report(
+			&benchmark,
+			1000,
+			ExecutionStrategy::AlwaysWasm,
+			WasmExecutionMethod::Interpreted,
+		);
+
+		assert!(report.contains("Imported 100 blocks in 4.000 s (25.00 blocks/s)"));
+		assert!(report.contains("AlwaysWasm execution (Interpreted Wasm)"));
+		assert!(report.contains("write amplification 3.00"));
+	}
+}
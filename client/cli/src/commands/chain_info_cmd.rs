@@ -0,0 +1,84 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt::Debug;
+use structopt::StructOpt;
+use sc_service::{Configuration, ChainSpec, Roles};
+
+use crate::error;
+use crate::VersionInfo;
+use crate::params::{SharedParams, PruningParams};
+
+/// The `chain-info` command used to print the state of the chain and of the database.
+///
+/// The database must exist already, nothing is written to it.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ChainInfoCmd {
+	/// Skip measuring the size of each database column, which reads the whole database.
+	#[structopt(long = "no-column-sizes")]
+	pub no_column_sizes: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+impl ChainInfoCmd {
+	/// Run the `chain-info` command
+	pub fn run<BB>(self, config: Configuration) -> error::Result<()>
+	where
+		BB: sp_runtime::traits::Block + Debug,
+	{
+		let info = sc_service::chain_ops::database_chain_info::<BB>(&config)?;
+		let column_sizes = if self.no_column_sizes {
+			Vec::new()
+		} else {
+			sc_service::chain_ops::database_column_sizes::<BB>(&config)?
+		};
+
+		println!("Genesis hash: {:?}", info.genesis_hash);
+		println!("Best block: #{} ({:?})", info.best_number, info.best_hash);
+		println!("Finalized block: #{} ({:?})", info.finalized_number, info.finalized_hash);
+		if !column_sizes.is_empty() {
+			println!("Database size per column (bytes):");
+			for (column, size) in column_sizes {
+				println!("  {}: {}", column, size);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Update and prepare a `Configuration` with command line parameters
+	pub fn update_config<F>(
+		&self,
+		mut config: &mut Configuration,
+		spec_factory: F,
+		version: &VersionInfo,
+	) -> error::Result<()> where
+		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
+	{
+		self.shared_params.update_config(&mut config, spec_factory, version)?;
+		self.pruning_params.update_config(&mut config, Roles::FULL, true)?;
+		config.use_in_memory_keystore()?;
+
+		Ok(())
+	}
+}
@@ -75,7 +75,8 @@ impl CheckBlockCmd {
 		};
 
 		let start = std::time::Instant::now();
-		run_until_exit(config, |config| {
+		let threads = self.shared_params.runtime_threads();
+		run_until_exit(config, self.shared_params.shutdown_timeout(), threads, |config| {
 			Ok(builder(config)?.check_block(block_id))
 		})?;
 		println!("Completed in {} ms.", start.elapsed().as_millis());
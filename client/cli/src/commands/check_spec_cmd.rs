@@ -0,0 +1,260 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use serde_json::Value;
+use sc_service::ChainSpec;
+
+use crate::error;
+use crate::VersionInfo;
+use crate::params::SharedParams;
+
+/// The `check-spec` command used to validate a chain specification before starting a node
+/// with it.
+#[derive(Debug, StructOpt, Clone)]
+pub struct CheckSpecCmd {
+	/// Path of the chain specification to check.
+	#[structopt(parse(from_os_str))]
+	pub path: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+/// The problems found in a chain specification.
+#[derive(Debug, Default, PartialEq)]
+pub struct SpecReport {
+	/// Problems preventing the genesis from being built.
+	pub errors: Vec<String>,
+	/// Suspicious parts of the spec, e.g. modules left unconfigured.
+	pub warnings: Vec<String>,
+}
+
+impl CheckSpecCmd {
+	/// Run the check-spec command.
+	///
+	/// The spec is parsed with `spec_factory`. If given, the runtime genesis of `reference`,
+	/// usually a development spec of the same runtime, tells which modules the genesis configures.
+	pub fn run<F>(self, spec_factory: F, reference: Option<&dyn ChainSpec>) -> error::Result<()>
+	where
+		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
+	{
+		let json = fs::read(&self.path)?;
+		let report = check_spec(&json, || spec_factory(&self.path.to_string_lossy()), reference);
+
+		for warning in &report.warnings {
+			println!("warning: {}", warning);
+		}
+		for error in &report.errors {
+			println!("error: {}", error);
+		}
+
+		if report.errors.is_empty() {
+			println!("Chain spec {} is valid", self.path.display());
+			Ok(())
+		} else {
+			Err(error::Error::ChainSpec(format!(
+				"{} error(s) found in chain spec {}",
+				report.errors.len(),
+				self.path.display(),
+			)))
+		}
+	}
+
+	/// Initialize
+	pub fn init(&self, version: &VersionInfo) -> error::Result<()> {
+		self.shared_params.init(version)
+	}
+}
+
+/// Check the chain spec `json`, loaded with `load`, against the genesis of `reference`.
+pub fn check_spec<F>(json: &[u8], load: F, reference: Option<&dyn ChainSpec>) -> SpecReport
+where
+	F: FnOnce() -> Result<Box<dyn ChainSpec>, String>,
+{
+	let mut report = SpecReport::default();
+
+	let spec: Value = match serde_json::from_slice(json) {
+		Ok(spec) => spec,
+		Err(e) => {
+			report.errors.push(format!(
+				"invalid JSON at line {}, column {}: {}",
+				e.line(),
+				e.column(),
+				e,
+			));
+			return report
+		},
+	};
+
+	check_raw_genesis(&spec["genesis"]["raw"], &mut report);
+
+	if let (Some(runtime), Some(reference)) = (spec["genesis"]["runtime"].as_object(), reference) {
+		let reference = reference.as_json(false).ok()
+			.and_then(|json| serde_json::from_str::<Value>(&json).ok());
+		if let Some(modules) = reference.as_ref().and_then(|r| r["genesis"]["runtime"].as_object()) {
+			let expected = modules.keys().map(|m| m.as_str()).collect::<Vec<_>>().join(", ");
+			for module in runtime.keys().filter(|m| !modules.contains_key(*m)) {
+				report.errors.push(format!(
+					"genesis.runtime: unknown module `{}`, expected one of: {}",
+					module,
+					expected,
+				));
+			}
+			for module in modules.keys() {
+				match runtime.get(module) {
+					None | Some(Value::Null) => report.warnings.push(format!(
+						"genesis.runtime: module `{}` is not configured, its storage stays empty",
+						module,
+					)),
+					Some(_) => {},
+				}
+			}
+		}
+	}
+
+	// Unknown modules are already reported, with the list of the expected ones.
+	if report.errors.is_empty() {
+		match load() {
+			Ok(spec) => if let Err(e) = spec.as_storage_builder().build_storage() {
+				report.errors.push(format!("genesis could not be built: {}", e));
+			},
+			Err(e) => report.errors.push(e),
+		}
+	}
+
+	report
+}
+
+/// Check the keys and values of a raw genesis are hex encoded.
+fn check_raw_genesis(raw: &Value, report: &mut SpecReport) {
+	// Older specs store the top and children storages in an array.
+	let (top, children) = match raw {
+		Value::Object(raw) => (raw.get("top"), raw.get("children")),
+		Value::Array(raw) => (raw.get(0), raw.get(1)),
+		_ => return,
+	};
+
+	check_raw_storage("genesis.raw.top", top, report);
+	if let Some(children) = children.and_then(|c| c.as_object()) {
+		for (key, child) in children {
+			check_hex(&format!("genesis.raw.children: key {}", key), key, report);
+			let child_path = format!("genesis.raw.children.{}.data", key);
+			check_raw_storage(&child_path, child.get("data"), report);
+		}
+	}
+}
+
+fn check_raw_storage(path: &str, storage: Option<&Value>, report: &mut SpecReport) {
+	let storage = match storage.and_then(|s| s.as_object()) {
+		Some(storage) => storage,
+		None => return,
+	};
+	for (key, value) in storage {
+		check_hex(&format!("{}: key {}", path, key), key, report);
+		match value.as_str() {
+			Some(value) => check_hex(&format!("{}.{}", path, key), value, report),
+			None => report.errors.push(format!("{}.{}: value is not a hex string", path, key)),
+		}
+	}
+}
+
+fn check_hex(what: &str, value: &str, report: &mut SpecReport) {
+	if !value.starts_with("0x") {
+		report.errors.push(format!("{}: `{}` does not start with 0x", what, value));
+	} else if let Err(e) = sp_core::bytes::from_hex(value) {
+		report.errors.push(format!("{}: malformed hex `{}`: {}", what, value, e));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_service::GenericChainSpec;
+	use serde::{Serialize, Deserialize};
+	use sp_core::storage::Storage;
+
+	#[derive(Serialize, Deserialize)]
+	struct Genesis(serde_json::Map<String, Value>);
+
+	impl sp_runtime::BuildStorage for Genesis {
+		fn assimilate_storage(&self, _: &mut Storage) -> Result<(), String> {
+			Ok(())
+		}
+	}
+
+	fn spec(runtime: Value) -> Box<dyn ChainSpec> {
+		let runtime = runtime.as_object().cloned().unwrap_or_default();
+		Box::new(GenericChainSpec::from_genesis(
+			"test",
+			"test-id",
+			move || Genesis(runtime.clone()),
+			Vec::new(),
+			None,
+			None,
+			None,
+			None::<()>,
+		))
+	}
+
+	fn no_load() -> Result<Box<dyn ChainSpec>, String> {
+		Err("not loaded".into())
+	}
+
+	#[test]
+	fn invalid_json_is_reported_with_its_position() {
+		let report = check_spec(b"{\n\"name\": }", no_load, None);
+		assert_eq!(report.errors.len(), 1);
+		assert!(report.errors[0].starts_with("invalid JSON at line 2"));
+	}
+
+	#[test]
+	fn malformed_raw_storage_is_reported() {
+		let json = serde_json::json!({
+			"genesis": { "raw": { "top": { "0x00": "0x0g", "01": "0x01", "0x02": 2 }, "children": {} } },
+		});
+		let report = check_spec(json.to_string().as_bytes(), no_load, None);
+		assert_eq!(report.errors.len(), 3);
+		assert!(report.errors.iter().any(|e| e.contains("malformed hex `0x0g`")));
+		assert!(report.errors.iter().any(|e| e.contains("`01` does not start with 0x")));
+		assert!(report.errors.iter().any(|e| e.contains("0x02: value is not a hex string")));
+	}
+
+	#[test]
+	fn modules_are_checked_against_the_reference() {
+		let reference = spec(serde_json::json!({ "balances": {}, "sudo": {} }));
+		let json = serde_json::json!({
+			"genesis": { "runtime": { "balances": {}, "sudu": {} } },
+		});
+		let report = check_spec(json.to_string().as_bytes(), no_load, Some(&*reference));
+
+		assert_eq!(report.errors, vec![
+			"genesis.runtime: unknown module `sudu`, expected one of: balances, sudo".to_string(),
+		]);
+		assert_eq!(report.warnings, vec![
+			"genesis.runtime: module `sudo` is not configured, its storage stays empty".to_string(),
+		]);
+	}
+
+	#[test]
+	fn load_errors_are_reported() {
+		let report = check_spec(b"{}", no_load, None);
+		assert_eq!(report.errors, vec!["not loaded".to_string()]);
+	}
+}
@@ -0,0 +1,44 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use structopt::{StructOpt, clap::{App, Shell}};
+
+use crate::error;
+use crate::VersionInfo;
+
+/// The `completions` command used to generate shell completions.
+#[derive(Debug, StructOpt, Clone)]
+pub struct CompletionsCmd {
+	/// The shell to generate the completions for.
+	#[structopt(
+		long = "shell",
+		value_name = "SHELL",
+		possible_values = &Shell::variants(),
+		case_insensitive = true
+	)]
+	pub shell: Shell,
+}
+
+impl CompletionsCmd {
+	/// Run the completions command, writing the completions of `app` to stdout.
+	///
+	/// `app` is the clap definition of the whole command line, e.g. from
+	/// [`CustomCommands::app`](crate::CustomCommands::app) to include the custom subcommands.
+	pub fn run(&self, mut app: App, version: &VersionInfo) -> error::Result<()> {
+		app.gen_completions_to(version.executable_name, self.shell, &mut std::io::stdout());
+		Ok(())
+	}
+}
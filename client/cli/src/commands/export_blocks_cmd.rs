@@ -90,7 +90,8 @@ impl ExportBlocksCmd {
 			None => Box::new(io::stdout()),
 		};
 
-		run_until_exit(config, |config| {
+		let threads = self.shared_params.runtime_threads();
+		run_until_exit(config, self.shared_params.shutdown_timeout(), threads, |config| {
 			Ok(builder(config)?.export_blocks(file, from.into(), to, binary))
 		})
 	}
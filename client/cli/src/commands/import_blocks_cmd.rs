@@ -37,6 +37,10 @@ pub struct ImportBlocksCmd {
 	#[structopt(parse(from_os_str))]
 	pub input: Option<PathBuf>,
 
+	/// Read binary input rather than JSON, as written by `export-blocks --binary true`.
+	#[structopt(long = "binary", value_name = "BOOL", parse(try_from_str), default_value("false"))]
+	pub binary: bool,
+
 	/// The default number of 64KB pages to ever allocate for Wasm execution.
 	///
 	/// Don't alter this unless you know what you're doing.
@@ -71,6 +75,7 @@ impl ImportBlocksCmd {
 		<<<BB as BlockT>::Header as HeaderT>::Number as std::str::FromStr>::Err: std::fmt::Debug,
 		<BB as BlockT>::Hash: std::str::FromStr,
 	{
+		let binary = self.binary;
 		let file: Box<dyn ReadPlusSeek + Send> = match &self.input {
 			Some(filename) => Box::new(fs::File::open(filename)?),
 			None => {
@@ -80,8 +85,9 @@ impl ImportBlocksCmd {
 			},
 		};
 
-		run_until_exit(config, |config| {
-			Ok(builder(config)?.import_blocks(file, false))
+		let threads = self.shared_params.runtime_threads();
+		run_until_exit(config, self.shared_params.shutdown_timeout(), threads, |config| {
+			Ok(builder(config)?.import_blocks(file, false, binary))
 		})
 	}
 
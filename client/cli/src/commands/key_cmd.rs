@@ -0,0 +1,310 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use sp_core::{
+	crypto::{KeyTypeId, Pair, Ss58Codec},
+	ed25519, sr25519, hexdisplay::HexDisplay,
+};
+use sc_service::{Configuration, ChainSpec, config::KeystoreConfig};
+
+use crate::error;
+use crate::VersionInfo;
+use crate::arg_enums::CryptoScheme;
+use crate::params::SharedParams;
+use super::runcmd::DEFAULT_KEYSTORE_CONFIG_PATH;
+
+/// The `key` subcommands used to manage keys.
+#[derive(Debug, StructOpt, Clone)]
+pub enum KeySubcommand {
+	/// Generate a random key together with its secret phrase.
+	Generate(GenerateKeyCmd),
+
+	/// Print the public key and address of a secret phrase, secret URI or public URI.
+	Inspect(InspectKeyCmd),
+
+	/// Insert a key into the keystore of the node.
+	Insert(InsertKeyCmd),
+}
+
+/// The `key generate` command.
+#[derive(Debug, StructOpt, Clone)]
+pub struct GenerateKeyCmd {
+	/// The cryptography scheme of the key.
+	#[structopt(
+		long = "scheme",
+		value_name = "SCHEME",
+		possible_values = &CryptoScheme::variants(),
+		case_insensitive = true,
+		default_value = "Sr25519"
+	)]
+	pub scheme: CryptoScheme,
+
+	/// Password protecting the secret phrase.
+	#[structopt(long = "password")]
+	pub password: Option<String>,
+}
+
+/// The `key inspect` command.
+#[derive(Debug, StructOpt, Clone)]
+pub struct InspectKeyCmd {
+	/// A secret phrase or URI, e.g. `<phrase>//hard/soft`, or a public URI.
+	#[structopt(value_name = "URI")]
+	pub uri: String,
+
+	/// The cryptography scheme of the key.
+	#[structopt(
+		long = "scheme",
+		value_name = "SCHEME",
+		possible_values = &CryptoScheme::variants(),
+		case_insensitive = true,
+		default_value = "Sr25519"
+	)]
+	pub scheme: CryptoScheme,
+
+	/// Password protecting the secret phrase.
+	#[structopt(long = "password")]
+	pub password: Option<String>,
+}
+
+/// The `key insert` command.
+#[derive(Debug, StructOpt, Clone)]
+pub struct InsertKeyCmd {
+	/// The secret phrase or URI of the key, e.g. `<phrase>//hard/soft`.
+	#[structopt(long = "suri", value_name = "URI")]
+	pub suri: String,
+
+	/// The key type of the key, e.g. `babe` or `gran`.
+	#[structopt(long = "key-type", value_name = "TYPE")]
+	pub key_type: String,
+
+	/// The cryptography scheme of the key.
+	#[structopt(
+		long = "scheme",
+		value_name = "SCHEME",
+		possible_values = &CryptoScheme::variants(),
+		case_insensitive = true,
+		default_value = "Sr25519"
+	)]
+	pub scheme: CryptoScheme,
+
+	/// Specify custom keystore path.
+	#[structopt(long = "keystore-path", value_name = "PATH", parse(from_os_str))]
+	pub keystore_path: Option<PathBuf>,
+
+	/// Password used by the keystore.
+	#[structopt(long = "password")]
+	pub password: Option<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl KeySubcommand {
+	/// Run the key subcommand
+	pub fn run(self, config: Configuration) -> error::Result<()> {
+		match self {
+			KeySubcommand::Generate(cmd) => cmd.run(),
+			KeySubcommand::Inspect(cmd) => cmd.run(),
+			KeySubcommand::Insert(cmd) => cmd.run(config),
+		}
+	}
+
+	/// Update and prepare a `Configuration` with command line parameters
+	pub fn update_config<F>(
+		&self,
+		config: &mut Configuration,
+		spec_factory: F,
+		version: &VersionInfo,
+	) -> error::Result<()> where
+		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
+	{
+		match self {
+			KeySubcommand::Insert(cmd) => cmd.update_config(config, spec_factory, version),
+			_ => Ok(()),
+		}
+	}
+}
+
+impl GenerateKeyCmd {
+	/// Run the `key generate` command
+	pub fn run(self) -> error::Result<()> {
+		let password = self.password.as_ref().map(String::as_str);
+		let phrase = match self.scheme {
+			CryptoScheme::Ed25519 => ed25519::Pair::generate_with_phrase(password).1,
+			CryptoScheme::Sr25519 => sr25519::Pair::generate_with_phrase(password).1,
+		};
+
+		print!("{}", describe_uri(self.scheme, &phrase, password)?);
+		Ok(())
+	}
+}
+
+impl InspectKeyCmd {
+	/// Run the `key inspect` command
+	pub fn run(self) -> error::Result<()> {
+		let password = self.password.as_ref().map(String::as_str);
+
+		print!("{}", describe_uri(self.scheme, &self.uri, password)?);
+		Ok(())
+	}
+}
+
+impl InsertKeyCmd {
+	/// Run the `key insert` command
+	pub fn run(self, config: Configuration) -> error::Result<()> {
+		let key_type = KeyTypeId::try_from(self.key_type.as_str())
+			.map_err(|_| error::Error::Input("The key type must be 4 characters long".into()))?;
+
+		let (path, password) = match config.keystore {
+			KeystoreConfig::Path { path, password } => (path, password),
+			_ => return Err("The keystore path is not set".into()),
+		};
+		let keystore = sc_keystore::Store::open(path, password)
+			.map_err(|e| format!("Error opening the keystore: {}", e))?;
+
+		let public = match self.scheme {
+			CryptoScheme::Ed25519 => keystore.read()
+				.insert_by_type::<ed25519::Pair>(key_type, &self.suri)
+				.map(|pair| pair.public().to_ss58check()),
+			CryptoScheme::Sr25519 => keystore.read()
+				.insert_by_type::<sr25519::Pair>(key_type, &self.suri)
+				.map(|pair| pair.public().to_ss58check()),
+		}.map_err(|e| format!("Error inserting the key: {}", e))?;
+
+		println!("Inserted the {} key {}", self.key_type, public);
+		Ok(())
+	}
+
+	/// Update and prepare a `Configuration` with command line parameters
+	pub fn update_config<F>(
+		&self,
+		mut config: &mut Configuration,
+		spec_factory: F,
+		version: &VersionInfo,
+	) -> error::Result<()> where
+		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
+	{
+		self.shared_params.update_config(&mut config, spec_factory, version)?;
+
+		let path = self.keystore_path.clone().or(
+			config.in_chain_config_dir(DEFAULT_KEYSTORE_CONFIG_PATH)
+		);
+		config.keystore = KeystoreConfig::Path {
+			path: path.ok_or_else(|| "No `base_path` provided to create keystore path!".to_string())?,
+			password: self.password.clone().map(Into::into),
+		};
+
+		Ok(())
+	}
+}
+
+/// Describe the secret phrase, secret URI or public URI `uri`.
+fn describe_uri(scheme: CryptoScheme, uri: &str, password: Option<&str>) -> error::Result<String> {
+	match scheme {
+		CryptoScheme::Ed25519 => describe_uri_with::<ed25519::Pair>(uri, password),
+		CryptoScheme::Sr25519 => describe_uri_with::<sr25519::Pair>(uri, password),
+	}
+}
+
+fn describe_uri_with<P: Pair>(uri: &str, password: Option<&str>) -> error::Result<String> {
+	if let Ok((pair, seed)) = P::from_string_with_seed(uri, password) {
+		let public = pair.public();
+		Ok(format!(
+			"Secret phrase or URI `{}` is account:\n  \
+			Secret seed:      {}\n  \
+			Public key (hex): 0x{}\n  \
+			SS58 Address:     {}\n",
+			uri,
+			seed.map_or_else(|| "n/a".into(), |seed| format!("0x{}", HexDisplay::from(&seed.as_ref()))),
+			HexDisplay::from(&public.as_ref()),
+			public.to_ss58check(),
+		))
+	} else if let Ok((public, version)) = P::Public::from_string_with_version(uri) {
+		Ok(format!(
+			"Public URI `{}` is account:\n  \
+			Network ID/version: {}\n  \
+			Public key (hex):   0x{}\n  \
+			SS58 Address:       {}\n",
+			uri,
+			String::from(version),
+			HexDisplay::from(&public.as_ref()),
+			public.to_ss58check_with_version(version),
+		))
+	} else {
+		Err(error::Error::Input("Invalid secret phrase or URI".into()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_service::GenericChainSpec;
+
+	const TEST_VERSION_INFO: &'static VersionInfo = &VersionInfo {
+		name: "node-test",
+		version: "0.1.0",
+		commit: "some_commit",
+		executable_name: "node-test",
+		description: "description",
+		author: "author",
+		support_url: "http://example.org",
+		copyright_start_year: 2020,
+	};
+
+	#[test]
+	fn inspect_derives_the_secret_uri() {
+		let alice = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+		let description = describe_uri(CryptoScheme::Sr25519, "//Alice", None).unwrap();
+
+		assert!(description.contains(&alice.to_ss58check()));
+		assert!(describe_uri(CryptoScheme::Sr25519, &alice.to_ss58check(), None)
+			.unwrap()
+			.contains(&alice.to_ss58check()));
+		assert!(describe_uri(CryptoScheme::Ed25519, "not a key", None).is_err());
+	}
+
+	#[test]
+	fn insert_writes_the_key_into_the_keystore() {
+		let keystore_path = tempfile::tempdir().expect("could not create a temp dir");
+		let chain_spec = GenericChainSpec::from_genesis(
+			"test",
+			"test-id",
+			|| (),
+			Vec::new(),
+			None,
+			None,
+			None,
+			None::<()>,
+		);
+
+		let cmd = InsertKeyCmd::from_iter(&[
+			"insert", "--suri", "//Alice", "--key-type", "test", "--scheme", "ed25519", "--keystore-path",
+		].iter().map(|arg| arg.to_string()).chain(Some(keystore_path.path().display().to_string())));
+		let mut config = Configuration::default();
+		cmd.update_config(&mut config, move |_| Ok(Box::new(chain_spec)), TEST_VERSION_INFO).unwrap();
+		cmd.run(config).unwrap();
+
+		let keystore = sc_keystore::Store::open(keystore_path.path(), None).unwrap();
+		let keys = keystore.read()
+			.public_keys_by_type::<ed25519::Public>(KeyTypeId(*b"test"))
+			.unwrap();
+		assert_eq!(keys, vec![ed25519::Pair::from_string("//Alice", None).unwrap().public()]);
+	}
+}
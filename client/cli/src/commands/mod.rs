@@ -16,11 +16,20 @@
 
 mod runcmd;
 mod export_blocks_cmd;
+mod export_state_cmd;
+mod chain_info_cmd;
+mod benchmark_import_cmd;
 mod build_spec_cmd;
 mod import_blocks_cmd;
 mod check_block_cmd;
 mod revert_cmd;
 mod purge_chain_cmd;
+mod package_spec_cmd;
+mod check_spec_cmd;
+mod key_cmd;
+mod sign_cmd;
+mod verify_cmd;
+mod completions_cmd;
 
 use std::fmt::Debug;
 use structopt::StructOpt;
@@ -34,11 +43,20 @@ use crate::params::SharedParams;
 
 pub use crate::commands::runcmd::RunCmd;
 pub use crate::commands::export_blocks_cmd::ExportBlocksCmd;
+pub use crate::commands::export_state_cmd::ExportStateCmd;
+pub use crate::commands::chain_info_cmd::ChainInfoCmd;
+pub use crate::commands::benchmark_import_cmd::BenchmarkImportCmd;
 pub use crate::commands::build_spec_cmd::BuildSpecCmd;
 pub use crate::commands::import_blocks_cmd::ImportBlocksCmd;
 pub use crate::commands::check_block_cmd::CheckBlockCmd;
 pub use crate::commands::revert_cmd::RevertCmd;
 pub use crate::commands::purge_chain_cmd::PurgeChainCmd;
+pub use crate::commands::package_spec_cmd::PackageSpecCmd;
+pub use crate::commands::check_spec_cmd::{CheckSpecCmd, SpecReport, check_spec};
+pub use crate::commands::key_cmd::{KeySubcommand, GenerateKeyCmd, InspectKeyCmd, InsertKeyCmd};
+pub use crate::commands::sign_cmd::SignCmd;
+pub use crate::commands::verify_cmd::VerifyCmd;
+pub use crate::commands::completions_cmd::CompletionsCmd;
 
 /// default sub directory to store network config
 const DEFAULT_NETWORK_CONFIG_PATH : &'static str = "network";
@@ -56,9 +74,18 @@ pub enum Subcommand {
 	/// Export blocks to a file.
 	ExportBlocks(export_blocks_cmd::ExportBlocksCmd),
 
+	/// Export the state of a given block into a chain spec.
+	ExportState(export_state_cmd::ExportStateCmd),
+
+	/// Print the genesis, best and finalized blocks and the size of the database.
+	ChainInfo(chain_info_cmd::ChainInfoCmd),
+
 	/// Import blocks from file.
 	ImportBlocks(import_blocks_cmd::ImportBlocksCmd),
 
+	/// Measure the import of blocks from file into a new database.
+	BenchmarkImport(benchmark_import_cmd::BenchmarkImportCmd),
+
 	/// Validate a single block.
 	CheckBlock(check_block_cmd::CheckBlockCmd),
 
@@ -77,7 +104,10 @@ impl Subcommand {
 		match self {
 			BuildSpec(params) => &params.shared_params,
 			ExportBlocks(params) => &params.shared_params,
+			ExportState(params) => &params.shared_params,
+			ChainInfo(params) => &params.shared_params,
 			ImportBlocks(params) => &params.shared_params,
+			BenchmarkImport(params) => &params.shared_params,
 			CheckBlock(params) => &params.shared_params,
 			Revert(params) => &params.shared_params,
 			PurgeChain(params) => &params.shared_params,
@@ -100,7 +130,10 @@ impl Subcommand {
 		match self {
 			Subcommand::BuildSpec(cmd) => cmd.run(config),
 			Subcommand::ExportBlocks(cmd) => cmd.run(config, builder),
+			Subcommand::ExportState(cmd) => cmd.run(config, builder),
+			Subcommand::ChainInfo(cmd) => cmd.run::<BB>(config),
 			Subcommand::ImportBlocks(cmd) => cmd.run(config, builder),
+			Subcommand::BenchmarkImport(cmd) => cmd.run(config, builder),
 			Subcommand::CheckBlock(cmd) => cmd.run(config, builder),
 			Subcommand::PurgeChain(cmd) => cmd.run(config),
 			Subcommand::Revert(cmd) => cmd.run(config, builder),
@@ -119,7 +152,10 @@ impl Subcommand {
 		match self {
 			Subcommand::BuildSpec(cmd) => cmd.update_config(&mut config, spec_factory, version),
 			Subcommand::ExportBlocks(cmd) => cmd.update_config(&mut config, spec_factory, version),
+			Subcommand::ExportState(cmd) => cmd.update_config(&mut config, spec_factory, version),
+			Subcommand::ChainInfo(cmd) => cmd.update_config(&mut config, spec_factory, version),
 			Subcommand::ImportBlocks(cmd) => cmd.update_config(&mut config, spec_factory, version),
+			Subcommand::BenchmarkImport(cmd) => cmd.update_config(&mut config, spec_factory, version),
 			Subcommand::CheckBlock(cmd) => cmd.update_config(&mut config, spec_factory, version),
 			Subcommand::PurgeChain(cmd) => cmd.update_config(&mut config, spec_factory, version),
 			Subcommand::Revert(cmd) => cmd.update_config(&mut config, spec_factory, version),
@@ -0,0 +1,127 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use log::info;
+use sc_service::{Configuration, ChainSpec};
+use sp_core::{hashing::blake2_256, hexdisplay::HexDisplay, storage::well_known_keys};
+
+use crate::error;
+use crate::VersionInfo;
+use crate::params::SharedParams;
+
+/// The `package-spec` command used to produce a chain specification suitable for publishing with
+/// a release.
+///
+/// The specification is written in its raw form, next to a manifest holding the checksums of the
+/// specification and of the runtime code it embeds.
+#[derive(Debug, StructOpt, Clone)]
+pub struct PackageSpecCmd {
+	/// Path to write the raw chain specification to.
+	///
+	/// The manifest is written next to it, with the `.manifest.json` extension.
+	#[structopt(long = "out", parse(from_os_str))]
+	pub out: PathBuf,
+
+	/// Allow packaging a specification which does not list any bootnode.
+	#[structopt(long = "allow-no-bootnodes")]
+	pub allow_no_bootnodes: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl PackageSpecCmd {
+	/// Run the package-spec command.
+	///
+	/// If `expected_code` is given, the runtime code embedded in the genesis of the specification
+	/// must be exactly this code, usually the runtime built together with the node.
+	pub fn run(
+		self,
+		config: Configuration,
+		expected_code: Option<&[u8]>,
+	) -> error::Result<()> {
+		let spec = config.expect_chain_spec();
+		info!("Packaging chain spec {}", spec.id());
+
+		if spec.boot_nodes().is_empty() && !self.allow_no_bootnodes {
+			return Err(error::Error::Input(format!(
+				"Chain spec {} does not list any bootnode, nodes using it could not connect",
+				spec.id(),
+			)));
+		}
+
+		let storage = spec.as_storage_builder().build_storage()?;
+		let code = storage.top.get(well_known_keys::CODE)
+			.ok_or_else(|| error::Error::Input("Chain spec does not contain a runtime".into()))?;
+		let code_hash = blake2_256(code);
+
+		if let Some(expected_code) = expected_code {
+			let expected_hash = blake2_256(expected_code);
+			if code_hash != expected_hash {
+				return Err(error::Error::Input(format!(
+					"Runtime of chain spec {} (0x{}) does not match the runtime of this build (0x{})",
+					spec.id(),
+					HexDisplay::from(&code_hash),
+					HexDisplay::from(&expected_hash),
+				)));
+			}
+		}
+
+		let json = sc_service::chain_ops::build_spec(&*spec, true)?;
+		fs::write(&self.out, &json)?;
+
+		let manifest = serde_json::json!({
+			"id": spec.id(),
+			"name": spec.name(),
+			"spec": self.out.file_name().map(|name| name.to_string_lossy().into_owned()),
+			"specHash": format!("0x{}", HexDisplay::from(&blake2_256(json.as_bytes()))),
+			"codeHash": format!("0x{}", HexDisplay::from(&code_hash)),
+			"codeSize": code.len(),
+			"bootNodes": spec.boot_nodes(),
+		});
+		let manifest_path = self.out.with_extension("manifest.json");
+		fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)
+			.map_err(|e| format!("Error serializing manifest: {}", e))?)?;
+
+		info!("Chain spec written to {:?}, manifest to {:?}", self.out, manifest_path);
+
+		Ok(())
+	}
+
+	/// Update and prepare a `Configuration` with command line parameters
+	pub fn update_config<F>(
+		&self,
+		mut config: &mut Configuration,
+		spec_factory: F,
+		version: &VersionInfo,
+	) -> error::Result<()> where
+		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
+	{
+		self.shared_params.update_config(&mut config, spec_factory, version)?;
+		config.use_in_memory_keystore()?;
+
+		Ok(())
+	}
+
+	/// Initialize
+	pub fn init(&self, version: &VersionInfo) -> error::Result<()> {
+		self.shared_params.init(version)
+	}
+}
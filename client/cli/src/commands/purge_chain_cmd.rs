@@ -95,3 +95,37 @@ impl PurgeChainCmd {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn config_with_database(path: std::path::PathBuf) -> Configuration {
+		let mut config = Configuration::default();
+		config.database = Some(DatabaseConfig::Path { path, cache_size: None });
+		config
+	}
+
+	#[test]
+	fn purge_chain_removes_the_database() {
+		let base_path = tempfile::tempdir().expect("could not create a temp dir");
+		let db_path = base_path.path().join("chains/dev/db");
+		fs::create_dir_all(&db_path).unwrap();
+		fs::write(db_path.join("CURRENT"), b"MANIFEST-000001").unwrap();
+
+		let cmd = PurgeChainCmd::from_iter(&["purge-chain", "-y"]);
+		cmd.run(config_with_database(db_path.clone())).unwrap();
+
+		assert!(base_path.path().join("chains/dev").exists());
+		assert!(!db_path.exists());
+	}
+
+	#[test]
+	fn purge_chain_accepts_a_missing_database() {
+		let base_path = tempfile::tempdir().expect("could not create a temp dir");
+		let db_path = base_path.path().join("chains/dev/db");
+
+		let cmd = PurgeChainCmd::from_iter(&["purge-chain", "-y"]);
+		assert!(cmd.run(config_with_database(db_path)).is_ok());
+	}
+}
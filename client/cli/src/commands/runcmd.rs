@@ -17,6 +17,7 @@
 use std::path::PathBuf;
 use std::net::SocketAddr;
 use std::fs;
+use std::time::Duration;
 use log::info;
 use structopt::{StructOpt, clap::arg_enum};
 use names::{Generator, Name};
@@ -24,7 +25,7 @@ use regex::Regex;
 use chrono::prelude::*;
 use sc_service::{
 	AbstractService, Configuration, ChainSpec, Roles,
-	config::{KeystoreConfig, PrometheusConfig},
+	config::{KeystoreConfig, PrometheusConfig, HealthConfig, SelfCheckConfig, SubscriptionLimits},
 };
 use sc_telemetry::TelemetryEndpoints;
 
@@ -34,13 +35,17 @@ use crate::params::ImportParams;
 use crate::params::SharedParams;
 use crate::params::NetworkConfigurationParams;
 use crate::params::TransactionPoolParams;
-use crate::runtime::run_service_until_exit;
+use crate::arg_enums::{NotificationDropPolicy, RpcMethods};
+use crate::runtime::{run_service_until_exit, run_full_and_light_until_exit};
 
 /// The maximum number of characters for a node name.
 const NODE_NAME_MAX_LENGTH: usize = 32;
 
+/// The `--name` value deriving the node name from the network key.
+const NODE_NAME_AUTO: &str = "auto";
+
 /// default sub directory for the key store
-const DEFAULT_KEYSTORE_CONFIG_PATH : &'static str = "keystore";
+pub(crate) const DEFAULT_KEYSTORE_CONFIG_PATH : &'static str = "keystore";
 
 arg_enum! {
 	/// Whether off-chain workers are enabled.
@@ -95,6 +100,7 @@ pub struct RunCmd {
 	/// Default is local. Note: not all RPC methods are safe to be exposed publicly. Use a RPC proxy
 	/// server to filter out dangerous methods. More details: https://github.com/paritytech/substrate/wiki/Public-RPC.
 	/// Use `--unsafe-rpc-external` to suppress the warning if you understand the risks.
+	/// The unsafe methods are denied on public interfaces unless `--rpc-methods Unsafe` is passed.
 	#[structopt(long = "rpc-external")]
 	pub rpc_external: bool,
 
@@ -104,11 +110,56 @@ pub struct RunCmd {
 	#[structopt(long = "unsafe-rpc-external")]
 	pub unsafe_rpc_external: bool,
 
+	/// RPC methods to expose.
+	///
+	/// - `Unsafe`: Exposes every RPC method.
+	/// - `Safe`: Exposes only a safe subset of RPC methods, denying the ones managing the node
+	///   (e.g. `author_insertKey`, `system_addReservedPeer`) or expensive to serve.
+	/// - `Auto`: Acts as `Safe` on the servers listening on a public interface, and as `Unsafe`
+	///   on the ones listening on localhost.
+	#[structopt(
+		long = "rpc-methods",
+		value_name = "METHOD SET",
+		possible_values = &RpcMethods::variants(),
+		case_insensitive = true,
+		default_value = "Auto",
+		verbatim_doc_comment
+	)]
+	pub rpc_methods: RpcMethods,
+
+	/// Maximum number of active subscriptions of a single RPC connection.
+	#[structopt(long = "rpc-max-subscriptions-per-connection", value_name = "COUNT", default_value = "1024")]
+	pub rpc_max_subscriptions_per_connection: usize,
+
+	/// Maximum number of notifications of a single RPC subscription waiting to be sent.
+	#[structopt(long = "rpc-max-buffered-notifications", value_name = "COUNT", default_value = "1024")]
+	pub rpc_max_buffered_notifications: usize,
+
+	/// What to do with the notifications of a subscription once its buffer is full.
+	///
+	/// - `DropOldest`: Drops the oldest buffered notification.
+	/// - `DropNewest`: Drops the new notification.
+	/// - `Unsubscribe`: Cancels the subscription.
+	#[structopt(
+		long = "rpc-notification-drop-policy",
+		value_name = "POLICY",
+		possible_values = &NotificationDropPolicy::variants(),
+		case_insensitive = true,
+		default_value = "Unsubscribe",
+		verbatim_doc_comment
+	)]
+	pub rpc_notification_drop_policy: NotificationDropPolicy,
+
+	/// Log the RPC calls taking longer than this many milliseconds.
+	#[structopt(long = "rpc-slow-call-threshold", value_name = "MILLISECONDS")]
+	pub rpc_slow_call_threshold: Option<u64>,
+
 	/// Listen to all Websocket interfaces.
 	///
 	/// Default is local. Note: not all RPC methods are safe to be exposed publicly. Use a RPC proxy
 	/// server to filter out dangerous methods. More details: https://github.com/paritytech/substrate/wiki/Public-RPC.
 	/// Use `--unsafe-ws-external` to suppress the warning if you understand the risks.
+	/// The unsafe methods are denied on public interfaces unless `--rpc-methods Unsafe` is passed.
 	#[structopt(long = "ws-external")]
 	pub ws_external: bool,
 
@@ -132,6 +183,13 @@ pub struct RunCmd {
 	#[structopt(long = "ws-port", value_name = "PORT")]
 	pub ws_port: Option<u16>,
 
+	/// Specify IPC RPC server socket path.
+	///
+	/// The IPC server is only started if a path is given. It exposes the unsafe RPC methods
+	/// unless `--rpc-methods Safe` is passed.
+	#[structopt(long = "ipc-path", value_name = "PATH")]
+	pub ipc_path: Option<String>,
+
 	/// Maximum number of WS RPC server connections.
 	#[structopt(long = "ws-max-connections", value_name = "COUNT")]
 	pub ws_max_connections: Option<usize>,
@@ -145,6 +203,15 @@ pub struct RunCmd {
 	#[structopt(long = "rpc-cors", value_name = "ORIGINS", parse(try_from_str = parse_cors))]
 	pub rpc_cors: Option<Cors>,
 
+	/// Specify the hosts allowed in the `Host` header of requests to the HTTP & WS RPC servers.
+	///
+	/// A comma-separated list of hosts (domain or domain:port, `*` wildcards are supported)
+	/// allowed besides the listening address. Value of `all` will disable host validation.
+	/// Default is to allow only the listening address, or all hosts if origin validation is
+	/// disabled.
+	#[structopt(long = "rpc-allowed-hosts", value_name = "HOSTS", parse(try_from_str = parse_allowed_hosts))]
+	pub rpc_allowed_hosts: Option<AllowedHosts>,
+
 	/// Specify Prometheus data source server TCP Port.
 	#[structopt(long = "prometheus-port", value_name = "PORT")]
 	pub prometheus_port: Option<u16>,
@@ -155,15 +222,51 @@ pub struct RunCmd {
 	#[structopt(long = "no-prometheus")]
 	pub no_prometheus: bool,
 
+	/// Serve the `/health` and `/ready` probes over HTTP on this TCP port.
+	///
+	/// `/health` fails when the node is stuck, `/ready` also fails while it is syncing,
+	/// without peers or with finality lagging behind. Disabled by default.
+	#[structopt(long = "health-port", value_name = "PORT")]
+	pub health_port: Option<u16>,
+
+	/// Listen to all health endpoint interfaces.
+	///
+	/// Default is local.
+	#[structopt(long = "health-external")]
+	pub health_external: bool,
+
+	/// Do not run the startup self-check.
+	///
+	/// By default the node checks the free disk space and that the database belongs to the
+	/// chain specification before starting the network.
+	#[structopt(long = "no-self-check")]
+	pub no_self_check: bool,
+
+	/// Minimum free disk space, in MiB, required on the disk holding the database.
+	#[structopt(long = "min-free-disk-space", value_name = "MiB", default_value = "512")]
+	pub min_free_disk_space: u64,
+
+	/// Check at startup that the local clock is in sync with this SNTP server.
+	///
+	/// Given as `host:port`, e.g. `pool.ntp.org:123`. The clock isn't checked by default.
+	#[structopt(long = "ntp-server", value_name = "HOST:PORT")]
+	pub ntp_server: Option<String>,
+
+	/// Maximum difference, in seconds, allowed between the local clock and the SNTP server.
+	#[structopt(long = "max-clock-skew", value_name = "SECONDS", default_value = "2")]
+	pub max_clock_skew: u64,
+
 	/// The human-readable name for this node.
 	///
-	/// The node name will be reported to the telemetry server, if enabled.
+	/// The node name will be reported to the telemetry server, if enabled. With `auto`, a
+	/// human-readable name is derived from the network key, stable as long as the key is. This
+	/// needs a persistent network key. Without a name, a random one is generated on each start.
 	#[structopt(long = "name", value_name = "NAME")]
 	pub name: Option<String>,
 
 	/// Disable connecting to the Substrate telemetry server.
 	///
-	/// Telemetry is on by default on global chains.
+	/// Telemetry is on by default on global chains. This overrides `--telemetry-url`.
 	#[structopt(long = "no-telemetry")]
 	pub no_telemetry: bool,
 
@@ -172,7 +275,10 @@ pub struct RunCmd {
 	/// This flag can be passed multiple times as a mean to specify multiple
 	/// telemetry endpoints. Verbosity levels range from 0-9, with 0 denoting
 	/// the least verbosity. If no verbosity level is specified the default is
-	/// 0.
+	/// 0. The URL and the verbosity are given as one argument, e.g.
+	/// `--telemetry-url 'wss://example.com/submit 5'`.
+	///
+	/// The endpoints given replace the ones of the chain specification.
 	#[structopt(long = "telemetry-url", value_name = "URL VERBOSITY", parse(try_from_str = parse_telemetry_endpoints))]
 	pub telemetry_endpoints: Vec<(String, u8)>,
 
@@ -188,6 +294,13 @@ pub struct RunCmd {
 	)]
 	pub offchain_worker: OffchainWorkerEnabled,
 
+	/// Number of threads the offchain workers run on.
+	///
+	/// The workers run on their own threads, separate from block import. Workers for new
+	/// blocks are skipped while all of these threads are busy. Defaults to one per CPU.
+	#[structopt(long = "offchain-worker-threads", value_name = "COUNT")]
+	pub offchain_worker_threads: Option<usize>,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub shared_params: SharedParams,
@@ -272,6 +385,34 @@ pub struct RunCmd {
 	/// The default value is 8 and the values higher than 256 are ignored.
 	#[structopt(long = "max-runtime-instances", default_value = "8")]
 	pub max_runtime_instances: usize,
+
+	/// Detach the node from the terminal and run it in the background.
+	///
+	/// The standard input and outputs are redirected to `/dev/null`, use `--log-file` to keep
+	/// the logs. SIGTERM and SIGINT stop the node gracefully.
+	#[structopt(long = "daemonize")]
+	pub daemonize: bool,
+
+	/// Write the process id of the node to this file.
+	///
+	/// The file is removed when the node exits.
+	#[structopt(long = "pid-file", value_name = "PATH", parse(from_os_str))]
+	pub pid_file: Option<PathBuf>,
+
+	/// The format of the status line printed regularly: coloured, plain or json.
+	#[structopt(long = "informant-format", value_name = "FORMAT", default_value = "coloured")]
+	pub informant_format: sc_informant::OutputFormat,
+
+	/// Run a light client in the process of the full node, syncing from the full node only.
+	///
+	/// Useful to serve light client RPC with the trust assumptions of the full node, mostly
+	/// for testing. The light client stores its data under `light` in the base path.
+	#[structopt(long = "embedded-light-client", conflicts_with = "light")]
+	pub embedded_light_client: bool,
+
+	/// Specify HTTP RPC server TCP port of the embedded light client.
+	#[structopt(long = "light-rpc-port", value_name = "PORT", default_value = "9934")]
+	pub light_rpc_port: u16,
 }
 
 impl RunCmd {
@@ -342,20 +483,6 @@ impl RunCmd {
 
 		self.import_params.update_config(&mut config, role, is_dev)?;
 
-		config.name = match (self.name.as_ref(), keyring) {
-			(Some(name), _) => name.to_string(),
-			(_, Some(keyring)) => keyring.to_string(),
-			(None, None) => generate_node_name(),
-		};
-		if let Err(msg) = is_node_name_valid(&config.name) {
-			return Err(error::Error::Input(
-				format!("Invalid node name '{}'. Reason: {}. If unsure, use none.",
-					config.name,
-					msg,
-				)
-			));
-		}
-
 		// set sentry mode (i.e. act as an authority but **never** actively participate)
 		config.sentry_mode = self.sentry;
 
@@ -365,6 +492,7 @@ impl RunCmd {
 			(OffchainWorkerEnabled::Never, _) => false,
 			(OffchainWorkerEnabled::WhenValidating, _) => false,
 		};
+		config.offchain_worker_threads = self.offchain_worker_threads;
 
 		config.roles = role;
 		config.disable_grandpa = self.no_grandpa;
@@ -380,6 +508,21 @@ impl RunCmd {
 			is_dev,
 		)?;
 
+		config.name = match (self.name.as_ref().map(|name| name.as_str()), keyring) {
+			(Some(NODE_NAME_AUTO), _) => node_name_from_key(&config.network.node_key)?,
+			(Some(name), _) => name.to_string(),
+			(_, Some(keyring)) => keyring.to_string(),
+			(None, None) => generate_node_name(),
+		};
+		if let Err(msg) = is_node_name_valid(&config.name) {
+			return Err(error::Error::Input(
+				format!("Invalid node name '{}'. Reason: {}. If unsure, use none.",
+					config.name,
+					msg,
+				)
+			));
+		}
+
 		self.pool_config.update_config(&mut config)?;
 
 		config.dev_key_seed = keyring
@@ -400,7 +543,17 @@ impl RunCmd {
 			config.rpc_ws = Some(parse_address(&format!("{}:{}", ws_interface, 9944), self.ws_port)?);
 		}
 
+		if self.ipc_path.is_some() {
+			config.rpc_ipc = self.ipc_path.clone();
+		}
 		config.rpc_ws_max_connections = self.ws_max_connections;
+		config.rpc_methods = self.rpc_methods.into();
+		config.rpc_subscription_limits = SubscriptionLimits {
+			max_subscriptions_per_connection: self.rpc_max_subscriptions_per_connection,
+			max_buffered_notifications: self.rpc_max_buffered_notifications,
+			drop_policy: self.rpc_notification_drop_policy.into(),
+		};
+		config.rpc_slow_call_threshold = self.rpc_slow_call_threshold.map(Duration::from_millis);
 		config.rpc_cors = self.rpc_cors.clone().unwrap_or_else(|| if is_dev {
 			log::warn!("Running in --dev mode, RPC CORS has been disabled.");
 			Cors::All
@@ -413,6 +566,11 @@ impl RunCmd {
 				"https://polkadot.js.org".into(),
 			])
 		}).into();
+		config.rpc_allowed_hosts = match self.rpc_allowed_hosts.clone() {
+			Some(allowed_hosts) => allowed_hosts.into(),
+			// Only the listening address, unless origin validation is disabled.
+			None => config.rpc_cors.as_ref().map(|_| Vec::new()),
+		};
 
 		// Override telemetry
 		if self.no_telemetry {
@@ -433,6 +591,22 @@ impl RunCmd {
 			));
 		}
 
+		// Override health endpoint
+		if let Some(health_port) = self.health_port {
+			let health_interface: &str = if self.health_external { "0.0.0.0" } else { "127.0.0.1" };
+			config.health_config = Some(HealthConfig::new(
+				parse_address(&format!("{}:{}", health_interface, health_port), None)?,
+			));
+		}
+
+		if !self.no_self_check {
+			config.self_check = Some(SelfCheckConfig {
+				min_free_disk_space: self.min_free_disk_space * 1024 * 1024,
+				ntp_server: self.ntp_server.clone(),
+				max_clock_skew: Duration::from_secs(self.max_clock_skew),
+			});
+		}
+
 		config.tracing_targets = self.import_params.tracing_targets.clone().into();
 		config.tracing_receiver = self.import_params.tracing_receiver.clone().into();
 
@@ -465,13 +639,42 @@ impl RunCmd {
 		info!("Node name: {}", config.name);
 		info!("Roles: {}", config.display_role());
 
+		// Detach before the tokio runtime spawns its threads.
+		let _pid_file = if self.daemonize {
+			crate::daemonize(self.pid_file.as_ref().map(|path| path.as_path()))?
+		} else if let Some(path) = &self.pid_file {
+			Some(crate::PidFile::create(path, std::process::id())?)
+		} else {
+			None
+		};
+
+		if self.embedded_light_client {
+			let rpc_interface: &str = interface_str(self.rpc_external, self.unsafe_rpc_external, self.validator)?;
+			let light_rpc_http = parse_address(&format!("{}:{}", rpc_interface, self.light_rpc_port), None)?;
+			return run_full_and_light_until_exit(
+				config,
+				self.shared_params.shutdown_timeout(),
+				self.shared_params.runtime_threads(),
+				Some(light_rpc_http),
+				self.informant_format,
+				new_full,
+				new_light,
+			)
+		}
+
 		match config.roles {
 			Roles::LIGHT => run_service_until_exit(
 				config,
+				self.shared_params.shutdown_timeout(),
+				self.shared_params.runtime_threads(),
+				self.informant_format,
 				new_light,
 			),
 			_ => run_service_until_exit(
 				config,
+				self.shared_params.shutdown_timeout(),
+				self.shared_params.runtime_threads(),
+				self.informant_format,
 				new_full,
 			),
 		}
@@ -492,10 +695,18 @@ impl RunCmd {
 /// Check whether a node name is considered as valid
 pub fn is_node_name_valid(_name: &str) -> Result<(), &str> {
 	let name = _name.to_string();
+	if name.trim().is_empty() {
+		return Err("Node name should not be empty");
+	}
+
 	if name.chars().count() >= NODE_NAME_MAX_LENGTH {
 		return Err("Node name too long");
 	}
 
+	if name.chars().any(|c| c.is_control()) {
+		return Err("Node name should not contain control characters");
+	}
+
 	let invalid_chars = r"[\\.@]";
 	let re = Regex::new(invalid_chars).unwrap();
 	if re.is_match(&name) {
@@ -530,6 +741,43 @@ fn generate_node_name() -> String {
 	result
 }
 
+/// A human-readable name derived from the peer id of `node_key`.
+///
+/// Fails if `node_key` is generated anew on each start, the network would then not use the key
+/// the name is derived from.
+fn node_name_from_key(node_key: &sc_network::config::NodeKeyConfig) -> error::Result<String> {
+	use sc_network::config::{NodeKeyConfig, Secret};
+
+	let keypair = match node_key {
+		NodeKeyConfig::Ed25519(Secret::New) => return Err(error::Error::Input(format!(
+			"The node name '{}' needs a persistent node key, see `--node-key-file`.",
+			NODE_NAME_AUTO,
+		))),
+		node_key => node_key.clone().into_keypair()?,
+	};
+	let peer_id = keypair.public().into_peer_id();
+	let mut seed = sp_core::hashing::blake2_256(peer_id.as_bytes());
+
+	loop {
+		let index = |bytes: &[u8], len: usize| {
+			let mut buf = [0u8; 8];
+			buf.copy_from_slice(bytes);
+			(u64::from_le_bytes(buf) % len as u64) as usize
+		};
+		let node_name = format!(
+			"{}-{}-{:04}",
+			names::ADJECTIVES[index(&seed[0..8], names::ADJECTIVES.len())],
+			names::NOUNS[index(&seed[8..16], names::NOUNS.len())],
+			index(&seed[16..24], 10_000),
+		);
+
+		if node_name.chars().count() < NODE_NAME_MAX_LENGTH {
+			return Ok(node_name)
+		}
+		seed = sp_core::hashing::blake2_256(&seed);
+	}
+}
+
 fn parse_address(
 	address: &str,
 	port: Option<u16>,
@@ -565,19 +813,28 @@ fn interface_str(
 	}
 }
 
+/// Highest verbosity level of the telemetry.
+const MAX_TELEMETRY_VERBOSITY: u8 = 9;
+
+/// Parse a telemetry URL followed by an optional verbosity level.
+///
 /// Default to verbosity level 0, if none is provided.
 fn parse_telemetry_endpoints(s: &str) -> Result<(String, u8), Box<dyn std::error::Error>> {
-	let pos = s.find(' ');
-	match pos {
-		None => {
-			Ok((s.to_owned(), 0))
-		},
-		Some(pos_) => {
-			let verbosity = s[pos_ + 1..].parse()?;
-			let url = s[..pos_].parse()?;
-			Ok((url, verbosity))
-		}
+	let (url, verbosity) = match s.find(' ') {
+		None => (s, 0),
+		Some(pos) => (&s[..pos], s[pos + 1..].trim().parse()?),
+	};
+	if verbosity > MAX_TELEMETRY_VERBOSITY {
+		return Err(format!(
+			"Telemetry verbosity {} is above the maximum of {}",
+			verbosity,
+			MAX_TELEMETRY_VERBOSITY,
+		).into())
 	}
+	sc_telemetry::url_to_multiaddr(url)
+		.map_err(|e| format!("Invalid telemetry URL {}: {}", url, e))?;
+
+	Ok((url.to_owned(), verbosity))
 }
 
 /// CORS setting
@@ -618,6 +875,40 @@ fn parse_cors(s: &str) -> Result<Cors, Box<dyn std::error::Error>> {
 	Ok(if is_all { Cors::All } else { Cors::List(origins) })
 }
 
+/// Allowed hosts setting
+///
+/// The type is introduced to overcome `Option<Option<T>>`
+/// handling of `structopt`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AllowedHosts {
+	/// All hosts allowed
+	All,
+	/// Only the listening address and the hosts on the list are allowed.
+	List(Vec<String>),
+}
+
+impl From<AllowedHosts> for Option<Vec<String>> {
+	fn from(allowed_hosts: AllowedHosts) -> Self {
+		match allowed_hosts {
+			AllowedHosts::All => None,
+			AllowedHosts::List(list) => Some(list),
+		}
+	}
+}
+
+/// Parse allowed hosts
+fn parse_allowed_hosts(s: &str) -> Result<AllowedHosts, Box<dyn std::error::Error>> {
+	let mut hosts = Vec::new();
+	for part in s.split(',') {
+		match part {
+			"all" | "*" => return Ok(AllowedHosts::All),
+			other => hosts.push(other.to_owned()),
+		}
+	}
+
+	Ok(AllowedHosts::List(hosts))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -634,6 +925,39 @@ mod tests {
 		copyright_start_year: 2020,
 	};
 
+	#[test]
+	fn telemetry_endpoints_are_parsed() {
+		assert_eq!(
+			parse_telemetry_endpoints("wss://telemetry.example.com/submit").unwrap(),
+			("wss://telemetry.example.com/submit".to_string(), 0),
+		);
+		assert_eq!(
+			parse_telemetry_endpoints("wss://telemetry.example.com/submit 5").unwrap(),
+			("wss://telemetry.example.com/submit".to_string(), 5),
+		);
+		assert_eq!(
+			parse_telemetry_endpoints("/ip4/127.0.0.1/tcp/8000/ws 9").unwrap(),
+			("/ip4/127.0.0.1/tcp/8000/ws".to_string(), 9),
+		);
+	}
+
+	#[test]
+	fn invalid_telemetry_endpoints_are_rejected() {
+		assert!(parse_telemetry_endpoints("wss://telemetry.example.com/submit 10").is_err());
+		assert!(parse_telemetry_endpoints("wss://telemetry.example.com/submit high").is_err());
+		assert!(parse_telemetry_endpoints("telemetry.example.com 1").is_err());
+	}
+
+	#[test]
+	fn allowed_hosts_are_parsed() {
+		assert_eq!(
+			parse_allowed_hosts("example.com,*.example.org:9933").unwrap(),
+			AllowedHosts::List(vec!["example.com".into(), "*.example.org:9933".into()]),
+		);
+		assert_eq!(parse_allowed_hosts("example.com,all").unwrap(), AllowedHosts::All);
+		assert_eq!(parse_allowed_hosts("*").unwrap(), AllowedHosts::All);
+	}
+
 	#[test]
 	fn tests_node_name_good() {
 		assert!(is_node_name_valid("short name").is_ok());
@@ -647,6 +971,36 @@ mod tests {
 		assert!(is_node_name_valid("https://visit.me").is_err());
 		assert!(is_node_name_valid("www.visit.me").is_err());
 		assert!(is_node_name_valid("email@domain").is_err());
+		assert!(is_node_name_valid("").is_err());
+		assert!(is_node_name_valid("   ").is_err());
+		assert!(is_node_name_valid("new\nline").is_err());
+		assert!(is_node_name_valid("escape\u{1b}[31m").is_err());
+	}
+
+	#[test]
+	fn node_name_from_key_is_stable() {
+		use sc_network::config::{NodeKeyConfig, Secret};
+
+		let dir = tempfile::tempdir().unwrap();
+		let key = || NodeKeyConfig::Ed25519(Secret::File(dir.path().join("secret_ed25519")));
+
+		let name = node_name_from_key(&key()).unwrap();
+		assert_eq!(node_name_from_key(&key()).unwrap(), name);
+		assert!(is_node_name_valid(&name).is_ok());
+
+		let other_dir = tempfile::tempdir().unwrap();
+		let other_key = NodeKeyConfig::Ed25519(Secret::File(other_dir.path().join("secret_ed25519")));
+		assert_ne!(node_name_from_key(&other_key).unwrap(), name);
+	}
+
+	#[test]
+	fn node_name_from_key_needs_a_persistent_key() {
+		use sc_network::config::{NodeKeyConfig, Secret};
+
+		match node_name_from_key(&NodeKeyConfig::Ed25519(Secret::New)) {
+			Err(error::Error::Input(_)) => {},
+			other => panic!("Expected an input error, got {:?}", other),
+		}
 	}
 
 	#[test]
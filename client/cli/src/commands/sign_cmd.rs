@@ -0,0 +1,99 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use structopt::StructOpt;
+use sp_core::{crypto::Pair, ed25519, sr25519, hexdisplay::HexDisplay};
+
+use crate::error;
+use crate::arg_enums::CryptoScheme;
+
+/// The `sign` command used to sign a message.
+#[derive(Debug, StructOpt, Clone)]
+pub struct SignCmd {
+	/// The secret phrase or URI of the signing key, e.g. `<phrase>//hard/soft`.
+	#[structopt(long = "suri", value_name = "URI")]
+	pub suri: String,
+
+	/// The message to sign.
+	#[structopt(value_name = "MESSAGE")]
+	pub message: String,
+
+	/// The message is hex encoded.
+	#[structopt(long = "hex")]
+	pub hex: bool,
+
+	/// The cryptography scheme of the key.
+	#[structopt(
+		long = "scheme",
+		value_name = "SCHEME",
+		possible_values = &CryptoScheme::variants(),
+		case_insensitive = true,
+		default_value = "Sr25519"
+	)]
+	pub scheme: CryptoScheme,
+
+	/// Password protecting the secret phrase.
+	#[structopt(long = "password")]
+	pub password: Option<String>,
+}
+
+impl SignCmd {
+	/// Run the sign command
+	pub fn run(self) -> error::Result<()> {
+		let message = decode_message(&self.message, self.hex)?;
+		let password = self.password.as_ref().map(String::as_str);
+		let signature = match self.scheme {
+			CryptoScheme::Ed25519 => sign::<ed25519::Pair>(&self.suri, password, &message)?,
+			CryptoScheme::Sr25519 => sign::<sr25519::Pair>(&self.suri, password, &message)?,
+		};
+
+		println!("0x{}", HexDisplay::from(&signature));
+		Ok(())
+	}
+}
+
+/// Decode the message given on the command line, as hex if `hex` is set.
+pub(crate) fn decode_message(message: &str, hex: bool) -> error::Result<Vec<u8>> {
+	if hex {
+		sp_core::bytes::from_hex(message)
+			.map_err(|e| error::Error::Input(format!("Invalid hex message: {}", e)))
+	} else {
+		Ok(message.as_bytes().to_vec())
+	}
+}
+
+fn sign<P: Pair>(suri: &str, password: Option<&str>, message: &[u8]) -> error::Result<Vec<u8>> {
+	let pair = P::from_string(suri, password)
+		.map_err(|_| error::Error::Input("Invalid secret phrase or URI".into()))?;
+	Ok(pair.sign(message).as_ref().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::commands::verify_cmd::verify;
+
+	#[test]
+	fn signatures_are_verified() {
+		let message = decode_message("0x1234", true).unwrap();
+		let signature = sign::<sr25519::Pair>("//Alice", None, &message).unwrap();
+		let alice = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+		let alice = sp_core::crypto::Ss58Codec::to_ss58check(&alice);
+
+		assert!(verify::<sr25519::Pair>(&signature, &alice, &message).unwrap());
+		assert!(!verify::<sr25519::Pair>(&signature, &alice, b"other message").unwrap());
+	}
+}
@@ -0,0 +1,99 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use structopt::StructOpt;
+use sp_core::{crypto::{Pair, Ss58Codec}, ed25519, sr25519};
+
+use crate::error;
+use crate::arg_enums::CryptoScheme;
+use super::sign_cmd::decode_message;
+
+/// The `verify` command used to verify the signature of a message.
+#[derive(Debug, StructOpt, Clone)]
+pub struct VerifyCmd {
+	/// The hex encoded signature.
+	#[structopt(value_name = "SIGNATURE")]
+	pub signature: String,
+
+	/// The public key of the signer, SS58 or hex encoded.
+	#[structopt(value_name = "PUBLIC")]
+	pub public: String,
+
+	/// The signed message.
+	#[structopt(value_name = "MESSAGE")]
+	pub message: String,
+
+	/// The message is hex encoded.
+	#[structopt(long = "hex")]
+	pub hex: bool,
+
+	/// The cryptography scheme of the key.
+	#[structopt(
+		long = "scheme",
+		value_name = "SCHEME",
+		possible_values = &CryptoScheme::variants(),
+		case_insensitive = true,
+		default_value = "Sr25519"
+	)]
+	pub scheme: CryptoScheme,
+}
+
+impl VerifyCmd {
+	/// Run the verify command
+	pub fn run(self) -> error::Result<()> {
+		let message = decode_message(&self.message, self.hex)?;
+		let signature = sp_core::bytes::from_hex(&self.signature)
+			.map_err(|e| error::Error::Input(format!("Invalid hex signature: {}", e)))?;
+		let valid = match self.scheme {
+			CryptoScheme::Ed25519 => verify::<ed25519::Pair>(&signature, &self.public, &message)?,
+			CryptoScheme::Sr25519 => verify::<sr25519::Pair>(&signature, &self.public, &message)?,
+		};
+
+		if valid {
+			println!("Signature verifies correctly.");
+			Ok(())
+		} else {
+			Err(error::Error::Other("Signature invalid.".into()))
+		}
+	}
+}
+
+/// Whether `signature` is a signature of `message` by the SS58 or hex encoded `public` key.
+pub(crate) fn verify<P: Pair>(signature: &[u8], public: &str, message: &[u8]) -> error::Result<bool> where
+	P::Signature: Default + AsMut<[u8]>,
+{
+	let mut typed_signature = P::Signature::default();
+	if signature.len() != typed_signature.as_ref().len() {
+		return Err(error::Error::Input(format!(
+			"Invalid signature length {}, expected {}",
+			signature.len(),
+			typed_signature.as_ref().len(),
+		)))
+	}
+	typed_signature.as_mut().copy_from_slice(signature);
+
+	let public = match sp_core::bytes::from_hex(public) {
+		Ok(raw) if raw.len() == P::Public::default().as_ref().len() => {
+			let mut typed_public = P::Public::default();
+			typed_public.as_mut().copy_from_slice(&raw);
+			typed_public
+		},
+		_ => P::Public::from_string(public)
+			.map_err(|_| error::Error::Input("Invalid public key".into()))?,
+	};
+
+	Ok(P::verify(&typed_signature, message, &public))
+}
@@ -0,0 +1,278 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Configuration files of the node.
+//!
+//! `--config <FILE>` reads command line options from a TOML file. The keys of the file are the
+//! long names of the options of the command `--config` is given to:
+//!
+//! ```toml
+//! base-path = "/var/lib/substrate"
+//! rpc-port = 9933
+//! bootnodes = ["/ip4/127.0.0.1/tcp/30333/p2p/QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV"]
+//! validator = true
+//! ```
+//!
+//! Options given on the command line override the ones of the file.
+//!
+//! The [`ReloadableOptions`] are applied again when the node receives SIGHUP, reading the file
+//! again.
+
+use std::{ffi::OsString, fs, path::Path, sync::RwLock};
+use lazy_static::lazy_static;
+use structopt::{StructOpt, clap::{App, AppSettings}};
+
+use crate::RunCmd;
+
+/// The long names of the options which also have a short name.
+const SHORT_OPTIONS: &[(&str, &str)] = &[("-d", "base-path"), ("-l", "log")];
+
+/// Replace `--config <FILE>` in the command line `args` by the options of the file, followed by
+/// `--config <FILE>` itself.
+pub fn expand_config_file<I>(args: I) -> Result<Vec<OsString>, String> where
+	I: IntoIterator,
+	I::Item: Into<OsString>,
+{
+	let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+
+	let (position, path) = match find_config_file(&args) {
+		Some(found) => found,
+		None => return Ok(args),
+	};
+
+	let content = fs::read_to_string(&path)
+		.map_err(|e| format!("Error reading config file {}: {}", path.display(), e))?;
+	let options = config_file_options(&content, &args)
+		.map_err(|e| format!("Error parsing config file {}: {}", path.display(), e))?;
+
+	args.splice(position..position, options);
+	Ok(args)
+}
+
+/// The position and the path of the `--config` option in `args`.
+fn find_config_file(args: &[OsString]) -> Option<(usize, std::path::PathBuf)> {
+	args.iter().enumerate().find_map(|(position, arg)| {
+		let arg = arg.to_str()?;
+		if arg == "--config" {
+			args.get(position + 1).map(|path| (position, Path::new(path).to_path_buf()))
+		} else if arg.starts_with("--config=") {
+			Some((position, Path::new(&arg["--config=".len()..]).to_path_buf()))
+		} else {
+			None
+		}
+	})
+}
+
+/// The command line options of the TOML file `content`, except the ones given in `args`.
+fn config_file_options(content: &str, args: &[OsString]) -> Result<Vec<OsString>, String> {
+	let table = match content.parse::<toml::Value>().map_err(|e| e.to_string())? {
+		toml::Value::Table(table) => table,
+		_ => return Err("Expected a table of options".into()),
+	};
+
+	let given: Vec<&str> = args.iter()
+		.filter_map(|arg| arg.to_str())
+		.filter_map(|arg| if arg.starts_with("--") {
+			Some(arg[2..].split('=').next().unwrap_or_default())
+		} else {
+			SHORT_OPTIONS.iter().find(|(short, _)| arg.starts_with(short)).map(|(_, long)| *long)
+		})
+		.collect();
+
+	let mut options = Vec::new();
+	for (key, value) in table {
+		if key == "config" {
+			return Err("A config file cannot include another one".into())
+		}
+		if given.contains(&key.as_str()) {
+			continue
+		}
+
+		let flag = OsString::from(format!("--{}", key));
+		let values = match value {
+			toml::Value::Array(values) => values,
+			value => vec![value],
+		};
+		for value in values {
+			match value {
+				toml::Value::Boolean(true) => options.push(flag.clone()),
+				toml::Value::Boolean(false) => {},
+				toml::Value::String(value) => options.extend(vec![flag.clone(), value.into()]),
+				toml::Value::Integer(value) => options.extend(vec![flag.clone(), value.to_string().into()]),
+				toml::Value::Float(value) => options.extend(vec![flag.clone(), value.to_string().into()]),
+				_ => return Err(format!("Unsupported value for `{}`", key)),
+			}
+		}
+	}
+
+	Ok(options)
+}
+
+lazy_static! {
+	/// The definition of the command line the node was parsed with, to parse it again on SIGHUP.
+	static ref CLI: RwLock<Option<fn() -> App<'static, 'static>>> = Default::default();
+}
+
+/// Record `T` as the definition of the command line, used by [`reloadable_options`].
+pub(crate) fn set_cli<T: StructOpt>() {
+	if let Ok(mut cli) = CLI.write() {
+		*cli = Some(T::clap);
+	}
+}
+
+/// The options applied again, without restarting, when the node receives SIGHUP.
+#[derive(Debug, Default, PartialEq)]
+pub struct ReloadableOptions {
+	/// The log pattern: the directives of the subcommand followed by the ones given with `--log`.
+	pub log_pattern: String,
+	/// The reserved nodes given with `--reserved-nodes`.
+	pub reserved_nodes: Vec<String>,
+}
+
+/// The reloadable options of the command line `args`, expanded with the config file.
+///
+/// `args` are parsed like at startup, with the definition given to [`from_iter`](crate::from_iter)
+/// whose top level is a [`RunCmd`]. The log pattern keeps the directives of the subcommand the
+/// logger was initialized with.
+pub fn reloadable_options<I>(args: I) -> Result<ReloadableOptions, String> where
+	I: IntoIterator,
+	I::Item: Into<OsString> + Clone,
+{
+	let cli = CLI.read().ok()
+		.and_then(|cli| *cli)
+		.ok_or_else(|| "The command line was not parsed by sc_cli".to_string())?;
+	parse_reloadable_options(cli(), args, &crate::subcommand_log_filter())
+}
+
+fn parse_reloadable_options<I>(
+	app: App<'static, 'static>,
+	args: I,
+	log_filter: &str,
+) -> Result<ReloadableOptions, String> where
+	I: IntoIterator,
+	I::Item: Into<OsString> + Clone,
+{
+	let args = expand_config_file(args)?;
+	let matches = app
+		.settings(&[AppSettings::ArgsNegateSubcommands, AppSettings::SubcommandsNegateReqs])
+		.get_matches_from_safe(args)
+		.map_err(|e| e.message)?;
+	let run = RunCmd::from_clap(&matches);
+
+	Ok(ReloadableOptions {
+		log_pattern: run.shared_params.log_pattern(log_filter),
+		reserved_nodes: run.network_config.reserved_nodes,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+
+	fn args(args: &[&str]) -> Vec<OsString> {
+		args.iter().map(OsString::from).collect()
+	}
+
+	#[test]
+	fn config_file_options_are_expanded() {
+		let mut file = tempfile::NamedTempFile::new().unwrap();
+		writeln!(
+			file,
+			"base-path = \"/tmp/node\"\nrpc-port = 9933\nbootnodes = [\"a\", \"b\"]\nvalidator = true\nlight = false",
+		).unwrap();
+		let path = file.path().display().to_string();
+
+		assert_eq!(
+			expand_config_file(args(&["node", "--config", &path, "--name", "Alice"])).unwrap(),
+			args(&[
+				"node", "--base-path", "/tmp/node", "--bootnodes", "a", "--bootnodes", "b",
+				"--rpc-port", "9933", "--validator", "--config", &path, "--name", "Alice",
+			]),
+		);
+	}
+
+	#[test]
+	fn command_line_options_override_config_file_options() {
+		let mut file = tempfile::NamedTempFile::new().unwrap();
+		writeln!(file, "base-path = \"/tmp/node\"\nrpc-port = 9933\nlog = \"info\"").unwrap();
+		let config = format!("--config={}", file.path().display());
+
+		assert_eq!(
+			expand_config_file(args(&["node", "-d", "/tmp/other", &config, "--rpc-port=9944"])).unwrap(),
+			args(&["node", "-d", "/tmp/other", "--log", "info", &config, "--rpc-port=9944"]),
+		);
+	}
+
+	#[test]
+	fn command_lines_without_config_file_are_unchanged() {
+		assert_eq!(expand_config_file(args(&["node", "--dev"])).unwrap(), args(&["node", "--dev"]));
+		assert!(expand_config_file(args(&["node", "--config", "/does/not/exist.toml"])).is_err());
+	}
+
+	fn reloadable_options(args: Vec<OsString>, log_filter: &str) -> ReloadableOptions {
+		parse_reloadable_options(RunCmd::clap(), args, log_filter).unwrap()
+	}
+
+	#[test]
+	fn reloadable_options_are_read_from_the_config_file() {
+		let mut file = tempfile::NamedTempFile::new().unwrap();
+		writeln!(file, "log = \"sync=trace\"\nreserved-nodes = [\"a\", \"b\"]").unwrap();
+		let path = file.path().display().to_string();
+
+		assert_eq!(
+			reloadable_options(args(&["node", "--config", &path, "--reserved-nodes", "c"]), ""),
+			ReloadableOptions { log_pattern: "sync=trace".into(), reserved_nodes: vec!["c".into()] },
+		);
+		assert_eq!(
+			reloadable_options(args(&["node", "-lgrandpa=debug", "--config", &path]), ""),
+			ReloadableOptions {
+				log_pattern: "grandpa=debug".into(),
+				reserved_nodes: vec!["a".into(), "b".into()],
+			},
+		);
+	}
+
+	#[test]
+	fn reloadable_options_are_parsed_like_at_startup() {
+		let options = reloadable_options(
+			args(&["node", "--reserved-nodes", "a", "b", "--validator", "--log=sync=trace"]),
+			"",
+		);
+		assert_eq!(options.log_pattern, "sync=trace");
+		assert_eq!(options.reserved_nodes, vec!["a".to_string(), "b".to_string()]);
+
+		let options = reloadable_options(
+			args(&["node", "--reserved-nodes=a", "--name", "node", "--reserved-nodes", "b"]),
+			"",
+		);
+		assert_eq!(options.reserved_nodes, vec!["a".to_string(), "b".to_string()]);
+
+		// a command line which doesn't parse isn't applied.
+		assert!(parse_reloadable_options(RunCmd::clap(), args(&["node", "--no-such-flag"]), "")
+			.is_err());
+	}
+
+	#[test]
+	fn reloadable_log_pattern_keeps_the_subcommand_log_filter() {
+		let log_pattern = |args| reloadable_options(args, "my-command=debug").log_pattern;
+		assert_eq!(
+			log_pattern(args(&["node", "--log", "sync=trace"])),
+			"my-command=debug,sync=trace",
+		);
+		assert_eq!(log_pattern(args(&["node"])), "my-command=debug");
+	}
+}
@@ -0,0 +1,354 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Subcommands registered at runtime by the binaries built on top of a node.
+//!
+//! A binary registers its subcommands in [`CustomCommands`] and parses the command line with
+//! [`CustomCommands::from_iter`] instead of [`from_iter`](crate::from_iter). The registered
+//! subcommands are then accepted next to the ones of the parsed `StructOpt` type.
+//!
+//! Every registered subcommand accepts the [`SharedParams`], and runs with a
+//! [`CustomCommandContext`] giving access to the chain they select, either through the local
+//! database or through a running node.
+
+use std::{any::Any, sync::Arc};
+use serde::de::DeserializeOwned;
+use structopt::{StructOpt, clap::{self, App, AppSettings, ArgMatches}};
+use sc_service::{
+	Configuration, ChainSpec, NativeExecutionDispatch, TFullBackend, TFullClient,
+	config::KeystoreConfig,
+};
+use sp_runtime::traits::Block as BlockT;
+
+use crate::{error, VersionInfo, SharedParams, NodeHandle};
+
+/// Function loading the chain spec of the given identifier.
+pub type SpecFactory<'a> = &'a dyn Fn(&str) -> Result<Box<dyn ChainSpec>, String>;
+
+/// A subcommand with its own clap definition and handler.
+pub trait CustomCommandProvider {
+	/// The clap definition of the subcommand, its name is the name of the subcommand.
+	fn app(&self) -> App<'static, 'static>;
+
+	/// Run the subcommand with the arguments matched by its definition.
+	fn run(
+		&self,
+		matches: &ArgMatches<'static>,
+		context: &mut CustomCommandContext,
+	) -> error::Result<()>;
+
+	/// The log directives of the subcommand, e.g. `my-command=debug`.
+	///
+	/// They are merged with the ones given with `--log`, which take precedence.
+	fn log_filter(&self) -> Option<String> {
+		None
+	}
+}
+
+/// What a custom subcommand runs with.
+///
+/// The chain spec, the client and the backend of the chain selected by the shared parameters
+/// are only opened on first use, a subcommand which doesn't need them doesn't pay for them.
+pub struct CustomCommandContext<'a> {
+	shared_params: SharedParams,
+	config: Configuration,
+	spec_factory: SpecFactory<'a>,
+	version: &'a VersionInfo,
+	full_parts: Option<Box<dyn Any>>,
+}
+
+/// The full client and backend opened by a [`CustomCommandContext`].
+type FullParts<TBl, TRtApi, TExecDisp> = (
+	Arc<TFullClient<TBl, TRtApi, TExecDisp>>,
+	Arc<TFullBackend<TBl>>,
+);
+
+impl<'a> CustomCommandContext<'a> {
+	/// Create the context of a subcommand, from the arguments it matched.
+	pub fn new(
+		matches: &ArgMatches<'static>,
+		config: Configuration,
+		spec_factory: SpecFactory<'a>,
+		version: &'a VersionInfo,
+	) -> Self {
+		CustomCommandContext {
+			shared_params: SharedParams::from_clap(matches),
+			config,
+			spec_factory,
+			version,
+			full_parts: None,
+		}
+	}
+
+	/// Initialize substrate for `command`, see [`SharedParams::init_with_log_filter`].
+	pub fn init(&self, command: &dyn CustomCommandProvider) -> error::Result<()> {
+		let log_filter = command.log_filter().unwrap_or_default();
+		self.shared_params.init_with_log_filter(&log_filter, self.version)
+	}
+
+	/// The shared parameters given to the subcommand.
+	pub fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	/// The version of the binary.
+	pub fn version(&self) -> &VersionInfo {
+		self.version
+	}
+
+	/// The configuration of the chain selected by the shared parameters.
+	pub fn config(&mut self) -> error::Result<&Configuration> {
+		if self.config.chain_spec.is_none() {
+			self.shared_params.update_config(&mut self.config, self.spec_factory, self.version)?;
+		}
+		Ok(&self.config)
+	}
+
+	/// The chain spec selected by the shared parameters.
+	pub fn chain_spec(&mut self) -> error::Result<&dyn ChainSpec> {
+		Ok(self.config()?.expect_chain_spec())
+	}
+
+	/// The client of the local database of the chain.
+	pub fn full_client<TBl, TRtApi, TExecDisp>(
+		&mut self,
+	) -> error::Result<Arc<TFullClient<TBl, TRtApi, TExecDisp>>> where
+		TBl: BlockT,
+		TRtApi: 'static,
+		TExecDisp: NativeExecutionDispatch + 'static,
+	{
+		Ok(self.full_parts::<TBl, TRtApi, TExecDisp>()?.0.clone())
+	}
+
+	/// The backend of the local database of the chain.
+	pub fn full_backend<TBl, TRtApi, TExecDisp>(
+		&mut self,
+	) -> error::Result<Arc<TFullBackend<TBl>>> where
+		TBl: BlockT,
+		TRtApi: 'static,
+		TExecDisp: NativeExecutionDispatch + 'static,
+	{
+		Ok(self.full_parts::<TBl, TRtApi, TExecDisp>()?.1.clone())
+	}
+
+	/// Read access to the chain, through the node listening at `url` if given and through the
+	/// local database otherwise.
+	pub fn node_handle<TBl, TRtApi, TExecDisp>(
+		&mut self,
+		url: Option<&str>,
+	) -> error::Result<NodeHandle<TBl, TRtApi, TExecDisp>> where
+		TBl: BlockT + DeserializeOwned,
+		TBl::Header: DeserializeOwned,
+		TRtApi: 'static,
+		TExecDisp: NativeExecutionDispatch + 'static,
+	{
+		match url {
+			Some(url) => NodeHandle::connect(url),
+			None => Ok(NodeHandle::Local(self.full_client::<TBl, TRtApi, TExecDisp>()?)),
+		}
+	}
+
+	fn full_parts<TBl, TRtApi, TExecDisp>(
+		&mut self,
+	) -> error::Result<&FullParts<TBl, TRtApi, TExecDisp>> where
+		TBl: BlockT,
+		TRtApi: 'static,
+		TExecDisp: NativeExecutionDispatch + 'static,
+	{
+		if self.full_parts.is_none() {
+			self.config()?;
+			if let KeystoreConfig::None = self.config.keystore {
+				self.config.use_in_memory_keystore()?;
+			}
+			let (client, backend) = sc_service::new_full_client_and_backend::<
+				TBl, TRtApi, TExecDisp,
+			>(&self.config)?;
+			let parts: FullParts<TBl, TRtApi, TExecDisp> = (Arc::new(client), backend);
+			self.full_parts = Some(Box::new(parts));
+		}
+
+		self.full_parts.as_ref()
+			.and_then(|parts| parts.downcast_ref::<FullParts<TBl, TRtApi, TExecDisp>>())
+			.ok_or_else(|| "The client was already opened for another runtime".into())
+	}
+}
+
+/// The custom subcommands of a binary.
+#[derive(Default)]
+pub struct CustomCommands {
+	providers: Vec<(String, Box<dyn CustomCommandProvider>)>,
+}
+
+/// The command line parsed by [`CustomCommands::from_iter`].
+pub enum ParsedCommand<'a, T> {
+	/// The command line of the `StructOpt` type.
+	Cli(T),
+	/// A registered subcommand, with the arguments it matched.
+	Custom(&'a dyn CustomCommandProvider, ArgMatches<'static>),
+}
+
+impl CustomCommands {
+	/// An empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register the subcommand of `provider`.
+	///
+	/// Panics if a subcommand of the same name is already registered.
+	pub fn register(mut self, provider: impl CustomCommandProvider + 'static) -> Self {
+		let name = provider.app().get_name().to_string();
+		assert!(
+			self.get(&name).is_none(),
+			"The custom subcommand `{}` is registered twice", name,
+		);
+		self.providers.push((name, Box::new(provider)));
+		self
+	}
+
+	/// The provider of the subcommand `name`, if registered.
+	pub fn get(&self, name: &str) -> Option<&dyn CustomCommandProvider> {
+		self.providers.iter()
+			.find(|(provider_name, _)| provider_name == name)
+			.map(|(_, provider)| &**provider)
+	}
+
+	/// The clap definition of `T` together with the registered subcommands.
+	pub fn app<'a, 'b, T: StructOpt>(&self, version: &VersionInfo) -> App<'a, 'b> {
+		let app = self.providers.iter().fold(T::clap(), |app, (_, provider)| {
+			app.subcommand(SharedParams::augment_clap(provider.app()))
+		});
+		app
+			.name(version.executable_name)
+			.author(version.author)
+			.about(version.description)
+	}
+
+	/// Equivalent of [`from_iter`](crate::from_iter), also accepting the registered
+	/// subcommands.
+	///
+	/// Print the error message and quit the program in case of failure.
+	pub fn from_iter<T, I>(&self, iter: I, version: &VersionInfo) -> ParsedCommand<T>
+	where
+		T: StructOpt + Sized,
+		I: IntoIterator,
+		I::Item: Into<std::ffi::OsString> + Clone,
+	{
+		let mut full_version = sc_service::config::full_version_from_strs(
+			version.version,
+			version.commit
+		);
+		full_version.push_str("\n");
+
+		let app = self.app::<T>(version)
+			.version(full_version.as_str())
+			.settings(&[
+				AppSettings::GlobalVersion,
+				AppSettings::ArgsNegateSubcommands,
+				AppSettings::SubcommandsNegateReqs,
+			]);
+
+		let iter = crate::expand_config_file(iter)
+			.unwrap_or_else(|e| clap::Error::with_description(&e, clap::ErrorKind::Io).exit());
+		let matches = app.get_matches_from(iter);
+		if let (name, Some(sub_matches)) = matches.subcommand() {
+			if let Some(provider) = self.get(name) {
+				return ParsedCommand::Custom(provider, sub_matches.clone())
+			}
+		}
+		crate::config_file::set_cli::<T>();
+		ParsedCommand::Cli(T::from_clap(&matches))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use structopt::clap::{Arg, SubCommand};
+
+	const TEST_VERSION_INFO: &'static VersionInfo = &VersionInfo {
+		name: "node-test",
+		version: "0.1.0",
+		commit: "some_commit",
+		executable_name: "node-test",
+		description: "description",
+		author: "author",
+		support_url: "http://example.org",
+		copyright_start_year: 2020,
+	};
+
+	#[derive(Debug, StructOpt)]
+	struct Cli {
+		#[structopt(subcommand)]
+		subcommand: Option<crate::Subcommand>,
+	}
+
+	struct Hello;
+
+	impl CustomCommandProvider for Hello {
+		fn app(&self) -> App<'static, 'static> {
+			SubCommand::with_name("hello").arg(Arg::with_name("who").required(true))
+		}
+
+		fn run(&self, _: &ArgMatches<'static>, _: &mut CustomCommandContext) -> error::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn registered_subcommands_are_parsed() {
+		let commands = CustomCommands::new().register(Hello);
+
+		let args = &["node-test", "hello", "world", "--dev"];
+		match commands.from_iter::<Cli, _>(args, TEST_VERSION_INFO) {
+			ParsedCommand::Custom(_, matches) => {
+				assert_eq!(matches.value_of("who"), Some("world"));
+
+				let context = CustomCommandContext::new(
+					&matches,
+					Configuration::from_version(TEST_VERSION_INFO),
+					&|_| Err("No chain spec in tests".into()),
+					TEST_VERSION_INFO,
+				);
+				assert!(context.shared_params().dev);
+			},
+			ParsedCommand::Cli(cli) => panic!("Parsed as {:?}", cli),
+		}
+
+		match commands.from_iter::<Cli, _>(&["node-test", "purge-chain"], TEST_VERSION_INFO) {
+			ParsedCommand::Cli(cli) => assert!(cli.subcommand.is_some()),
+			ParsedCommand::Custom(..) => panic!("Parsed as a custom subcommand"),
+		}
+	}
+
+	#[test]
+	fn completions_include_registered_subcommands() {
+		let commands = CustomCommands::new().register(Hello);
+		let mut completions = Vec::new();
+		commands.app::<Cli>(TEST_VERSION_INFO)
+			.gen_completions_to("node-test", clap::Shell::Bash, &mut completions);
+
+		let completions = String::from_utf8(completions).unwrap();
+		assert!(completions.contains("hello"));
+		assert!(completions.contains("purge-chain"));
+	}
+
+	#[test]
+	#[should_panic(expected = "registered twice")]
+	fn subcommands_cannot_be_registered_twice() {
+		let _ = CustomCommands::new().register(Hello).register(Hello);
+	}
+}
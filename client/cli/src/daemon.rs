@@ -0,0 +1,172 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detaching the node from the terminal and tracking its process id in a PID file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::warn;
+use crate::error;
+
+/// A PID file, removed when dropped.
+pub struct PidFile {
+	path: PathBuf,
+}
+
+impl PidFile {
+	/// Write `pid` to the file at `path`.
+	///
+	/// Fails if the file belongs to a process which is still running. A stale file, left by a
+	/// process which did not exit cleanly, is overwritten.
+	pub fn create(path: &Path, pid: u32) -> error::Result<Self> {
+		if let Some(running) = read_pid(path).filter(|pid| is_running(*pid)) {
+			return Err(error::Error::Input(format!(
+				"PID file {} belongs to the running process {}",
+				path.display(),
+				running,
+			)))
+		}
+
+		let pid_file = PidFile { path: path.to_owned() };
+		pid_file.set_pid(pid)?;
+		Ok(pid_file)
+	}
+
+	/// Replace the process id written in the file.
+	pub fn set_pid(&self, pid: u32) -> error::Result<()> {
+		fs::write(&self.path, format!("{}\n", pid))?;
+		Ok(())
+	}
+}
+
+impl Drop for PidFile {
+	fn drop(&mut self) {
+		if let Err(e) = fs::remove_file(&self.path) {
+			warn!("Failed to remove the PID file {}: {}", self.path.display(), e);
+		}
+	}
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+	fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+	use nix::{sys::signal::kill, unistd::Pid};
+
+	kill(Pid::from_raw(pid as i32), None).is_ok()
+}
+
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+	false
+}
+
+/// Detach the process from the terminal, writing the process id of the detached process to
+/// `pid_file`.
+///
+/// The PID file is checked and claimed before forking, so that nothing is left running if it
+/// belongs to another process. The parent then writes the process id of the child to it and
+/// exits, so that init systems expecting a forking service find the PID file in place. If that
+/// write fails, the child is terminated. The child starts a new session and
+/// its standard input and outputs are redirected to `/dev/null`. The working directory is kept,
+/// the relative paths given on the command line stay valid.
+///
+/// This must be called before any thread is spawned, only the calling thread survives the fork.
+#[cfg(unix)]
+pub fn daemonize(pid_file: Option<&Path>) -> error::Result<Option<PidFile>> {
+	use std::os::unix::io::AsRawFd;
+	use nix::{sys::signal::{kill, Signal}, unistd::{dup2, fork, setsid, ForkResult}};
+
+	let nix_error = |e: nix::Error| error::Error::Other(format!("Failed to daemonize: {}", e));
+
+	let pid_file = match pid_file {
+		Some(path) => Some(PidFile::create(path, std::process::id())?),
+		None => None,
+	};
+
+	match fork().map_err(nix_error)? {
+		ForkResult::Parent { child } => {
+			if let Some(pid_file) = pid_file {
+				if let Err(e) = pid_file.set_pid(child.as_raw() as u32) {
+					let _ = kill(child, Signal::SIGTERM);
+					return Err(e)
+				}
+				// The child owns the file from now on.
+				std::mem::forget(pid_file);
+			}
+			std::process::exit(0)
+		},
+		ForkResult::Child => {},
+	}
+
+	setsid().map_err(nix_error)?;
+
+	let dev_null = fs::OpenOptions::new().read(true).write(true).open("/dev/null")?;
+	for fd in &[0, 1, 2] {
+		dup2(dev_null.as_raw_fd(), *fd).map_err(nix_error)?;
+	}
+
+	Ok(pid_file)
+}
+
+/// Detaching the process is only supported on unix.
+#[cfg(not(unix))]
+pub fn daemonize(_pid_file: Option<&Path>) -> error::Result<Option<PidFile>> {
+	Err(error::Error::Input("--daemonize is only supported on unix".into()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pid_file_is_written_and_removed() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("node.pid");
+
+		let pid_file = PidFile::create(&path, 42).unwrap();
+		assert_eq!(read_pid(&path), Some(42));
+
+		pid_file.set_pid(43).unwrap();
+		assert_eq!(read_pid(&path), Some(43));
+
+		drop(pid_file);
+		assert!(!path.exists());
+	}
+
+	#[test]
+	fn stale_pid_file_is_overwritten() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("node.pid");
+		fs::write(&path, "not a pid").unwrap();
+
+		let _pid_file = PidFile::create(&path, 42).unwrap();
+		assert_eq!(read_pid(&path), Some(42));
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn pid_file_of_running_process_is_refused() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("node.pid");
+		fs::write(&path, format!("{}", std::process::id())).unwrap();
+
+		assert!(PidFile::create(&path, 42).is_err());
+		assert_eq!(read_pid(&path), Some(std::process::id()));
+	}
+}
@@ -33,6 +33,9 @@ pub enum Error {
 	/// Input error
 	#[from(ignore)]
 	Input(String),
+	/// The chain specification could not be loaded
+	#[from(ignore)]
+	ChainSpec(String),
 	/// Invalid listen multiaddress
 	#[display(fmt="Invalid listen multiaddress")]
 	InvalidListenMultiaddress,
@@ -63,8 +66,101 @@ impl std::error::Error for Error {
 			Error::Service(ref err) => Some(err),
 			Error::Client(ref err) => Some(err),
 			Error::Input(_) => None,
+			Error::ChainSpec(_) => None,
 			Error::InvalidListenMultiaddress => None,
 			Error::Other(_) => None,
 		}
 	}
 }
+
+/// The class of an [`Error`], each with its own process exit code.
+///
+/// Orchestration tooling can tell from the exit code of the node whether restarting it may help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+	/// Any failure not covered by another class.
+	Other = 1,
+	/// Invalid command line arguments or configuration.
+	Config = 2,
+	/// The chain specification could not be loaded.
+	ChainSpec = 3,
+	/// The database could not be opened or is corrupted.
+	Database = 4,
+	/// A server could not bind to its address.
+	NetworkBind = 5,
+}
+
+impl Error {
+	/// The exit code of the process failing with this error.
+	pub fn exit_code(&self) -> ExitCode {
+		match self {
+			Error::Cli(_) | Error::Input(_) | Error::InvalidListenMultiaddress => ExitCode::Config,
+			Error::ChainSpec(_) => ExitCode::ChainSpec,
+			Error::Io(err) if is_bind_error(err) => ExitCode::NetworkBind,
+			Error::Client(err) if is_database_error(err) => ExitCode::Database,
+			Error::Service(err) => service_exit_code(err),
+			_ => ExitCode::Other,
+		}
+	}
+}
+
+fn service_exit_code(err: &sc_service::Error) -> ExitCode {
+	use sc_network::error::Error as NetworkError;
+
+	match err {
+		sc_service::Error::Io(err) if is_bind_error(err) => ExitCode::NetworkBind,
+		sc_service::Error::Network(NetworkError::Io(err)) if is_bind_error(err) =>
+			ExitCode::NetworkBind,
+		sc_service::Error::Client(err) if is_database_error(err) => ExitCode::Database,
+		sc_service::Error::Network(NetworkError::Client(err)) if is_database_error(err) =>
+			ExitCode::Database,
+		_ => ExitCode::Other,
+	}
+}
+
+fn is_bind_error(err: &std::io::Error) -> bool {
+	use std::io::ErrorKind;
+
+	match err.kind() {
+		ErrorKind::AddrInUse | ErrorKind::AddrNotAvailable | ErrorKind::PermissionDenied => true,
+		_ => false,
+	}
+}
+
+fn is_database_error(err: &sp_blockchain::Error) -> bool {
+	match err {
+		sp_blockchain::Error::Backend(_) => true,
+		sp_blockchain::Error::Blockchain(err) => is_database_error(err),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io;
+
+	#[test]
+	fn errors_map_to_their_exit_code() {
+		let bind = || io::Error::new(io::ErrorKind::AddrInUse, "address in use");
+		let database = || sp_blockchain::Error::Backend("corrupted".into());
+
+		assert_eq!(Error::Input("bad".into()).exit_code(), ExitCode::Config);
+		assert_eq!(Error::InvalidListenMultiaddress.exit_code(), ExitCode::Config);
+		assert_eq!(Error::ChainSpec("missing".into()).exit_code(), ExitCode::ChainSpec);
+		assert_eq!(Error::Io(bind()).exit_code(), ExitCode::NetworkBind);
+		assert_eq!(Error::Service(sc_service::Error::Io(bind())).exit_code(), ExitCode::NetworkBind);
+		assert_eq!(Error::Client(database()).exit_code(), ExitCode::Database);
+		assert_eq!(
+			Error::Service(sc_service::Error::Client(
+				sp_blockchain::Error::Blockchain(Box::new(database())),
+			)).exit_code(),
+			ExitCode::Database,
+		);
+		assert_eq!(
+			Error::Io(io::Error::new(io::ErrorKind::NotFound, "missing")).exit_code(),
+			ExitCode::Other,
+		);
+		assert_eq!(Error::Other("failed".into()).exit_code(), ExitCode::Other);
+	}
+}
@@ -24,6 +24,12 @@ mod arg_enums;
 mod error;
 mod runtime;
 mod commands;
+mod custom_commands;
+mod node_handle;
+mod config_file;
+mod daemon;
+mod log_file;
+mod remote_chain_spec;
 
 pub use sc_service::config::VersionInfo;
 
@@ -36,9 +42,18 @@ pub use params::*;
 pub use commands::*;
 pub use arg_enums::*;
 pub use error::*;
+pub use config_file::{expand_config_file, reloadable_options, ReloadableOptions};
+pub use log_file::RotatingFile;
+pub use daemon::{daemonize, PidFile};
+pub use custom_commands::{
+	CustomCommandProvider, CustomCommands, CustomCommandContext, ParsedCommand, SpecFactory,
+};
+pub use node_handle::{NodeHandle, RemoteNode};
 use log::info;
 use lazy_static::lazy_static;
-pub use crate::runtime::{run_until_exit, run_service_until_exit};
+pub use crate::runtime::{
+	run_until_exit, run_service_until_exit, run_full_and_light_until_exit, RuntimeThreads,
+};
 
 /// Helper function used to parse the command line arguments. This is the equivalent of
 /// `structopt`'s `from_iter()` except that it takes a `VersionInfo` argument to provide the name of
@@ -65,12 +80,17 @@ where
 ///
 /// Gets the struct from any iterator such as a `Vec` of your making.
 /// Print the error message and quit the program in case of failure.
+///
+/// The options of the configuration file given with `--config` are read as if they were given
+/// on the command line, see [`expand_config_file`].
 pub fn from_iter<T, I>(iter: I, version: &VersionInfo) -> T
 where
 	T: StructOpt + Sized,
 	I: IntoIterator,
 	I::Item: Into<std::ffi::OsString> + Clone,
 {
+	let iter = expand_config_file(iter)
+		.unwrap_or_else(|e| clap::Error::with_description(&e, clap::ErrorKind::Io).exit());
 	let app = T::clap();
 
 	let mut full_version = sc_service::config::full_version_from_strs(
@@ -90,7 +110,9 @@ where
 			AppSettings::SubcommandsNegateReqs,
 		]);
 
-	T::from_clap(&app.get_matches_from(iter))
+	let cli = T::from_clap(&app.get_matches_from(iter));
+	config_file::set_cli::<T>();
+	cli
 }
 
 /// Helper function used to parse the command line arguments. This is the equivalent of
@@ -113,6 +135,8 @@ where
 	I: IntoIterator,
 	I::Item: Into<std::ffi::OsString> + Clone,
 {
+	let iter = expand_config_file(iter)
+		.map_err(|e| clap::Error::with_description(&e, clap::ErrorKind::Io))?;
 	let app = T::clap();
 
 	let mut full_version = sc_service::config::full_version_from_strs(
@@ -129,6 +153,7 @@ where
 
 	let matches = app.get_matches_from_safe(iter)?;
 
+	config_file::set_cli::<T>();
 	Ok(T::from_clap(&matches))
 }
 
@@ -140,6 +165,15 @@ where
 /// 2. Raise the FD limit
 /// 3. Initialize the logger
 pub fn init(logger_pattern: &str, version: &VersionInfo) -> error::Result<()> {
+	init_with_log_file(logger_pattern, None, version)
+}
+
+/// Initialize substrate like [`init`], also writing the logs to `log_file`.
+pub fn init_with_log_file(
+	logger_pattern: &str,
+	log_file: Option<RotatingFile>,
+	version: &VersionInfo,
+) -> error::Result<()> {
 	let full_version = sc_service::config::full_version_from_strs(
 		version.version,
 		version.commit
@@ -147,16 +181,37 @@ pub fn init(logger_pattern: &str, version: &VersionInfo) -> error::Result<()> {
 	sp_panic_handler::set(version.support_url, &full_version);
 
 	fdlimit::raise_fd_limit();
-	init_logger(logger_pattern);
+	init_logger_with_file(logger_pattern, log_file);
 
 	Ok(())
 }
 
 /// Initialize the logger
 pub fn init_logger(pattern: &str) {
-	use ansi_term::Colour;
+	init_logger_with_file(pattern, None)
+}
 
-	let mut builder = env_logger::Builder::new();
+lazy_static! {
+	/// The filter of the logger, replaced when the log filter is reloaded.
+	static ref LOG_FILTER: std::sync::RwLock<Option<env_logger::filter::Filter>> = Default::default();
+	/// The log directives of the subcommand, kept when the log filter is reloaded.
+	static ref SUBCOMMAND_LOG_FILTER: std::sync::RwLock<String> = Default::default();
+}
+
+fn set_subcommand_log_filter(log_filter: &str) {
+	if let Ok(mut current) = SUBCOMMAND_LOG_FILTER.write() {
+		*current = log_filter.to_string();
+	}
+}
+
+/// The log directives of the subcommand the logger was initialized with.
+fn subcommand_log_filter() -> String {
+	SUBCOMMAND_LOG_FILTER.read().map(|log_filter| log_filter.clone()).unwrap_or_default()
+}
+
+/// The filter of the logger, built from the default directives, `RUST_LOG` and `pattern`.
+fn log_filter(pattern: &str) -> env_logger::filter::Filter {
+	let mut builder = env_logger::filter::Builder::new();
 	// Disable info logging by default for some modules:
 	builder.filter(Some("ws"), log::LevelFilter::Off);
 	builder.filter(Some("hyper"), log::LevelFilter::Warn);
@@ -167,12 +222,56 @@ pub fn init_logger(pattern: &str) {
 	builder.filter(None, log::LevelFilter::Info);
 
 	if let Ok(lvl) = std::env::var("RUST_LOG") {
-		builder.parse_filters(&lvl);
+		builder.parse(&lvl);
+	}
+
+	builder.parse(pattern);
+	builder.build()
+}
+
+/// Replace the log filter of the logger installed by [`init`] with the one of `pattern`.
+pub fn reload_log_filter(pattern: &str) {
+	let filter = log_filter(pattern);
+	log::set_max_level(filter.filter());
+	if let Ok(mut current) = LOG_FILTER.write() {
+		*current = Some(filter);
 	}
+}
+
+/// Logger writing the records matching `LOG_FILTER` with the wrapped logger.
+struct ReloadableLogger(env_logger::Logger);
+
+impl log::Log for ReloadableLogger {
+	fn enabled(&self, metadata: &log::Metadata) -> bool {
+		LOG_FILTER.read().ok()
+			.and_then(|filter| filter.as_ref().map(|filter| filter.enabled(metadata)))
+			.unwrap_or(false)
+	}
+
+	fn log(&self, record: &log::Record) {
+		let matches = LOG_FILTER.read().ok()
+			.and_then(|filter| filter.as_ref().map(|filter| filter.matches(record)))
+			.unwrap_or(false);
+		if matches {
+			self.0.log(record);
+		}
+	}
+
+	fn flush(&self) {
+		self.0.flush()
+	}
+}
+
+fn init_logger_with_file(pattern: &str, log_file: Option<RotatingFile>) {
+	use ansi_term::Colour;
+
+	let mut builder = env_logger::Builder::new();
+	// The records are filtered by `LOG_FILTER`.
+	builder.filter(None, log::LevelFilter::Trace);
 
-	builder.parse_filters(pattern);
 	let isatty = atty::is(atty::Stream::Stderr);
 	let enable_color = isatty;
+	let log_file = log_file.map(std::sync::Mutex::new);
 
 	builder.format(move |buf, record| {
 		let now = time::now();
@@ -207,11 +306,26 @@ pub fn init_logger(pattern: &str) {
 			output = kill_color(output.as_ref());
 		}
 
+		if let Some(log_file) = &log_file {
+			let line = if enable_color { kill_color(output.as_ref()) } else { output.clone() };
+			if let Ok(mut log_file) = log_file.lock() {
+				// the logs are still written to stderr if the log file is unavailable.
+				let _ = log_file.write_line(&line);
+			}
+		}
+
 		writeln!(buf, "{}", output)
 	});
 
-	if builder.try_init().is_err() {
+	let filter = log_filter(pattern);
+	let max_level = filter.filter();
+	if let Ok(mut current) = LOG_FILTER.write() {
+		*current = Some(filter);
+	}
+	if log::set_boxed_logger(Box::new(ReloadableLogger(builder.build()))).is_err() {
 		info!("Not registering Substrate logger, as there is already a global logger registered!");
+	} else {
+		log::set_max_level(max_level);
 	}
 }
 
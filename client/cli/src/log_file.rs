@@ -0,0 +1,130 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Log file rotated by size or age.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// A log file, rotated to `<path>.1`, `<path>.2`, ... once it is too large or too old.
+pub struct RotatingFile {
+	path: PathBuf,
+	file: File,
+	size: u64,
+	opened_at: Instant,
+	max_size: Option<u64>,
+	max_age: Option<Duration>,
+	keep: usize,
+}
+
+impl RotatingFile {
+	/// Open the log file at `path`, appending to it if it exists.
+	///
+	/// The file is rotated before writing once it holds `max_size` bytes or was opened for
+	/// `max_age`, and the last `keep` rotated files are kept.
+	pub fn open(
+		path: PathBuf,
+		max_size: Option<u64>,
+		max_age: Option<Duration>,
+		keep: usize,
+	) -> io::Result<Self> {
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let file = OpenOptions::new().create(true).append(true).open(&path)?;
+		let size = file.metadata()?.len();
+
+		Ok(RotatingFile { path, file, size, opened_at: Instant::now(), max_size, max_age, keep })
+	}
+
+	/// Write `line` to the file, rotating it first if needed.
+	pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+		if self.should_rotate() {
+			self.rotate()?;
+		}
+		writeln!(self.file, "{}", line)?;
+		self.size += line.len() as u64 + 1;
+
+		Ok(())
+	}
+
+	fn should_rotate(&self) -> bool {
+		self.size > 0 && (
+			self.max_size.map_or(false, |max_size| self.size >= max_size) ||
+			self.max_age.map_or(false, |max_age| self.opened_at.elapsed() >= max_age)
+		)
+	}
+
+	fn rotate(&mut self) -> io::Result<()> {
+		if self.keep == 0 {
+			fs::remove_file(&self.path)?;
+		} else {
+			for index in (1..self.keep).rev() {
+				let rotated = rotated_path(&self.path, index);
+				if rotated.exists() {
+					fs::rename(&rotated, rotated_path(&self.path, index + 1))?;
+				}
+			}
+			fs::rename(&self.path, rotated_path(&self.path, 1))?;
+		}
+
+		self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+		self.size = 0;
+		self.opened_at = Instant::now();
+
+		Ok(())
+	}
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+	let mut rotated = path.as_os_str().to_owned();
+	rotated.push(format!(".{}", index));
+	rotated.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn log_files_are_rotated_by_size() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("node.log");
+		let mut file = RotatingFile::open(path.clone(), Some(10), None, 2).unwrap();
+
+		for line in &["first line", "second line", "third line", "fourth line"] {
+			file.write_line(line).unwrap();
+		}
+
+		assert_eq!(fs::read_to_string(&path).unwrap(), "fourth line\n");
+		assert_eq!(fs::read_to_string(rotated_path(&path, 1)).unwrap(), "third line\n");
+		assert_eq!(fs::read_to_string(rotated_path(&path, 2)).unwrap(), "second line\n");
+		assert!(!rotated_path(&path, 3).exists());
+	}
+
+	#[test]
+	fn log_files_are_appended_to() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("node.log");
+
+		RotatingFile::open(path.clone(), None, None, 1).unwrap().write_line("first run").unwrap();
+		RotatingFile::open(path.clone(), None, None, 1).unwrap().write_line("second run").unwrap();
+
+		assert_eq!(fs::read_to_string(&path).unwrap(), "first run\nsecond run\n");
+	}
+}
@@ -0,0 +1,172 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chain access for subcommands.
+//!
+//! A [`NodeHandle`] reads the chain either from a running node over RPC or from the local
+//! database. Subcommands only deal with the handle and get both access paths for free, custom
+//! subcommands open it with [`CustomCommandContext::node_handle`].
+//!
+//! [`CustomCommandContext::node_handle`]: crate::CustomCommandContext::node_handle
+
+use std::sync::Arc;
+use codec::Decode;
+use serde::de::DeserializeOwned;
+use sc_client_api::StorageProvider;
+use sc_service::{NativeExecutionDispatch, TFullClient};
+use sp_core::storage::StorageKey;
+use sp_runtime::{
+	generic::{BlockId, SignedBlock},
+	traits::{Block as BlockT, Header as HeaderT, NumberFor},
+};
+use jsonrpc_core_client::{transports::{http, ws}, RpcChannel};
+use sc_rpc_api::{author::AuthorClient, chain::ChainClient, state::StateClient};
+use sp_rpc::{list::ListOrValue, number::NumberOrHex};
+
+use crate::error;
+
+/// Read access to the chain, either of a running node or of the local database.
+pub enum NodeHandle<TBl: BlockT, TRtApi, TExecDisp> {
+	/// The local database.
+	Local(Arc<TFullClient<TBl, TRtApi, TExecDisp>>),
+	/// A running node, queried over RPC.
+	Remote(RemoteNode<TBl>),
+}
+
+/// A running node, queried over RPC.
+pub struct RemoteNode<TBl: BlockT> {
+	runtime: tokio01::runtime::current_thread::Runtime,
+	state: StateClient<TBl::Hash>,
+	chain: ChainClient<NumberFor<TBl>, TBl::Hash, TBl::Header, SignedBlock<TBl>>,
+	author: AuthorClient<TBl::Hash, TBl::Hash>,
+}
+
+impl<TBl> RemoteNode<TBl> where
+	TBl: BlockT + DeserializeOwned,
+	TBl::Header: DeserializeOwned,
+{
+	/// Connect to the node listening at `url`, either an `http://` or a `ws://` endpoint.
+	pub fn connect(url: &str) -> error::Result<Self> {
+		let mut runtime = tokio01::runtime::current_thread::Runtime::new()?;
+		let channel: RpcChannel = if url.starts_with("ws://") || url.starts_with("wss://") {
+			let connect = ws::try_connect(url).map_err(rpc_error)?;
+			runtime.block_on(connect)
+		} else {
+			runtime.block_on(http::connect(url))
+		}.map_err(rpc_error)?;
+
+		Ok(RemoteNode {
+			runtime,
+			state: channel.clone().into(),
+			chain: channel.clone().into(),
+			author: channel.into(),
+		})
+	}
+
+	/// Generate new session keys in the keystore of the node and return their public keys,
+	/// SCALE encoded.
+	pub fn rotate_keys(&mut self) -> error::Result<Vec<u8>> {
+		let keys = self.runtime.block_on(self.author.rotate_keys()).map_err(rpc_error)?;
+		Ok(keys.0)
+	}
+}
+
+impl<TBl, TRtApi, TExecDisp> NodeHandle<TBl, TRtApi, TExecDisp> where
+	TBl: BlockT + DeserializeOwned,
+	TBl::Header: DeserializeOwned,
+	TExecDisp: NativeExecutionDispatch + 'static,
+{
+	/// Connect to the node listening at `url`, see [`RemoteNode::connect`].
+	pub fn connect(url: &str) -> error::Result<Self> {
+		RemoteNode::connect(url).map(NodeHandle::Remote)
+	}
+
+	/// Number of the best block.
+	pub fn best_number(&mut self) -> error::Result<NumberFor<TBl>> {
+		match self {
+			NodeHandle::Local(client) => Ok(client.chain_info().best_number),
+			NodeHandle::Remote(node) => {
+				let header = node.runtime.block_on(node.chain.header(None)).map_err(rpc_error)?;
+				Ok(*header.ok_or("Node did not return its best header")?.number())
+			},
+		}
+	}
+
+	/// Hash of the canonical block with the given number.
+	pub fn block_hash(&mut self, number: NumberFor<TBl>) -> error::Result<Option<TBl::Hash>> {
+		match self {
+			NodeHandle::Local(client) => Ok(client.block_hash(number)?),
+			NodeHandle::Remote(node) => {
+				let number = ListOrValue::Value(NumberOrHex::Number(number));
+				let hash = node.runtime.block_on(node.chain.block_hash(Some(number)))
+					.map_err(rpc_error)?;
+				match hash {
+					ListOrValue::Value(hash) => Ok(hash),
+					ListOrValue::List(_) =>
+						Err("Node returned a list of hashes for a single number".into()),
+				}
+			},
+		}
+	}
+
+	/// Hash of the canonical block with the given number, which must be known.
+	pub fn expect_block_hash(&mut self, number: NumberFor<TBl>) -> error::Result<TBl::Hash> {
+		self.block_hash(number)?
+			.ok_or_else(|| format!("Block #{} is not known", number).into())
+	}
+
+	/// Storage value of `key` at the block `at`.
+	pub fn storage(&mut self, at: TBl::Hash, key: StorageKey) -> error::Result<Option<Vec<u8>>> {
+		match self {
+			NodeHandle::Local(client) =>
+				Ok(client.storage(&BlockId::Hash(at), &key)?.map(|data| data.0)),
+			NodeHandle::Remote(node) => {
+				let data = node.runtime.block_on(node.state.storage(key, Some(at)))
+					.map_err(rpc_error)?;
+				Ok(data.map(|data| data.0))
+			},
+		}
+	}
+
+	/// Storage value of `key` at the block `at`, decoded as `T`.
+	pub fn decoded_storage<T: Decode>(
+		&mut self,
+		at: TBl::Hash,
+		key: StorageKey,
+	) -> error::Result<Option<T>> {
+		self.storage(at, key)?
+			.map(|encoded| T::decode(&mut &encoded[..]))
+			.transpose()
+			.map_err(|e| format!("Error decoding storage: {}", e).into())
+	}
+
+	/// All the storage keys starting with `prefix` at the block `at`.
+	pub fn storage_keys(
+		&mut self,
+		at: TBl::Hash,
+		prefix: StorageKey,
+	) -> error::Result<Vec<StorageKey>> {
+		match self {
+			NodeHandle::Local(client) => Ok(client.storage_keys(&BlockId::Hash(at), &prefix)?),
+			NodeHandle::Remote(node) =>
+				node.runtime.block_on(node.state.storage_keys(prefix, Some(at))).map_err(rpc_error),
+		}
+	}
+}
+
+fn rpc_error(e: jsonrpc_core_client::RpcError) -> error::Error {
+	error::Error::Other(format!("RPC error: {}", e))
+}
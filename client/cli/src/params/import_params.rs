@@ -189,3 +189,62 @@ pub struct ExecutionStrategies {
 	)]
 	pub execution: Option<ExecutionStrategy>,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_client_api::ExecutionStrategy as Strategy;
+	use sc_service::Roles;
+
+	fn update_config(args: &[&str], is_dev: bool) -> Configuration {
+		let params = ImportParams::from_iter(std::iter::once("node-test").chain(args.iter().cloned()));
+		let mut config = Configuration::default();
+		params.update_config(&mut config, Roles::FULL, is_dev).unwrap();
+		config
+	}
+
+	#[test]
+	fn wasm_is_interpreted_by_default() {
+		let config = update_config(&[], false);
+		assert_eq!(config.wasm_method, sc_service::config::WasmExecutionMethod::Interpreted);
+
+		let config = update_config(&["--wasm-execution", "interpreted"], false);
+		assert_eq!(config.wasm_method, sc_service::config::WasmExecutionMethod::Interpreted);
+	}
+
+	#[test]
+	fn execution_applies_to_all_contexts() {
+		let config = update_config(&["--execution", "wasm"], true);
+		let strategies = config.execution_strategies;
+		for strategy in &[
+			strategies.syncing,
+			strategies.importing,
+			strategies.block_construction,
+			strategies.offchain_worker,
+			strategies.other,
+		] {
+			assert_eq!(*strategy, Strategy::AlwaysWasm);
+		}
+	}
+
+	#[test]
+	fn execution_strategies_default_per_context() {
+		let strategies = update_config(&["--execution-syncing", "both"], false).execution_strategies;
+		assert_eq!(strategies.syncing, Strategy::Both);
+		assert_eq!(strategies.importing, Strategy::NativeElseWasm);
+		assert_eq!(strategies.block_construction, Strategy::AlwaysWasm);
+
+		// development chains execute natively unless told otherwise.
+		let strategies = update_config(&["--execution-syncing", "both"], true).execution_strategies;
+		assert_eq!(strategies.syncing, Strategy::Both);
+		assert_eq!(strategies.importing, Strategy::NativeWhenPossible);
+		assert_eq!(strategies.block_construction, Strategy::NativeWhenPossible);
+	}
+
+	#[test]
+	fn conflicting_execution_flags_are_rejected() {
+		assert!(ImportParams::from_iter_safe(
+			&["node-test", "--execution", "wasm", "--execution-syncing", "native"]
+		).is_err());
+	}
+}
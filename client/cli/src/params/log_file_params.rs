@@ -0,0 +1,59 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use structopt::StructOpt;
+
+use crate::error;
+use crate::log_file::RotatingFile;
+
+/// Parameters of the log file.
+#[derive(Debug, StructOpt, Clone)]
+pub struct LogFileParams {
+	/// Also write the logs to the given file.
+	#[structopt(long = "log-file", value_name = "PATH", parse(from_os_str))]
+	pub log_file: Option<PathBuf>,
+
+	/// Rotate the log file once it reaches the given size, in MiB.
+	#[structopt(long = "log-file-max-size", value_name = "MIB")]
+	pub log_file_max_size: Option<u64>,
+
+	/// Rotate the log file once it is older than the given number of hours.
+	#[structopt(long = "log-file-max-age", value_name = "HOURS")]
+	pub log_file_max_age: Option<u64>,
+
+	/// Number of rotated log files to keep.
+	#[structopt(long = "log-file-keep", value_name = "COUNT", default_value = "5")]
+	pub log_file_keep: usize,
+}
+
+impl LogFileParams {
+	/// Open the log file, if any.
+	pub fn open(&self) -> error::Result<Option<RotatingFile>> {
+		let path = match &self.log_file {
+			Some(path) => path.clone(),
+			None => return Ok(None),
+		};
+
+		Ok(Some(RotatingFile::open(
+			path,
+			self.log_file_max_size.map(|size| size * 1024 * 1024),
+			self.log_file_max_age.map(|hours| Duration::from_secs(hours * 60 * 60)),
+			self.log_file_keep,
+		)?))
+	}
+}
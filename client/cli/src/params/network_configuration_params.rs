@@ -18,22 +18,36 @@ use std::path::PathBuf;
 use std::iter;
 use std::net::Ipv4Addr;
 use structopt::StructOpt;
+use log::warn;
 use sc_network::{
-	config::{NonReservedPeerMode, TransportConfig}, multiaddr::Protocol,
+	config::{NonReservedPeerMode, TransportConfig, parse_str_addr}, multiaddr::Protocol, Multiaddr,
 };
 use sc_service::Configuration;
 
 use crate::error;
+use crate::arg_enums::SyncMode;
 use crate::params::node_key_params::NodeKeyParams;
 
 /// Parameters used to create the network configuration.
 #[derive(Debug, StructOpt, Clone)]
 pub struct NetworkConfigurationParams {
 	/// Specify a list of bootnodes.
+	///
+	/// They are used in addition to the bootnodes of the chain spec, unless
+	/// `--replace-bootnodes` is given.
 	#[structopt(long = "bootnodes", value_name = "URL")]
 	pub bootnodes: Vec<String>,
 
+	/// Use the `--bootnodes` instead of the bootnodes of the chain spec.
+	///
+	/// Useful when the bootnodes hard-coded in the chain spec went stale.
+	#[structopt(long = "replace-bootnodes")]
+	pub replace_bootnodes: bool,
+
 	/// Specify a list of reserved node addresses.
+	///
+	/// The addresses must end with the peer id of the node, e.g.
+	/// `/ip4/10.0.0.1/tcp/30333/p2p/<PEER_ID>`.
 	#[structopt(long = "reserved-nodes", value_name = "URL")]
 	pub reserved_nodes: Vec<String>,
 
@@ -52,10 +66,32 @@ pub struct NetworkConfigurationParams {
 	)]
 	pub sentry_nodes: Vec<String>,
 
+	/// Specify a list of addresses of the validators this sentry node proxies for.
+	///
+	/// The validators are always kept connected as reserved nodes, and their addresses are
+	/// never shared with other nodes of the network.
+	#[structopt(
+		long = "sentry-validators",
+		value_name = "URL",
+		conflicts_with_all = &[ "validator", "light", "sentry_nodes" ]
+	)]
+	pub sentry_validators: Vec<String>,
+
 	/// Listen on this multiaddress.
+	///
+	/// Can be repeated to listen on several addresses, e.g. both
+	/// `/ip4/0.0.0.0/tcp/30333` and `/ip6/::/tcp/30333`.
 	#[structopt(long = "listen-addr", value_name = "LISTEN_ADDR")]
 	pub listen_addr: Vec<String>,
 
+	/// Advertise this multiaddress to the other nodes, in addition to the
+	/// addresses the node listens on and the ones it discovers.
+	///
+	/// Can be repeated. DNS addresses (e.g. `/dns4/example.com/tcp/30333`)
+	/// are accepted.
+	#[structopt(long = "public-addr", value_name = "PUBLIC_ADDR")]
+	pub public_addr: Vec<String>,
+
 	/// Specify p2p protocol TCP port.
 	///
 	/// Only used if --listen-addr is not specified.
@@ -76,6 +112,14 @@ pub struct NetworkConfigurationParams {
 	#[structopt(long = "in-peers", value_name = "COUNT", default_value = "25")]
 	pub in_peers: u32,
 
+	/// Maximum upload rate of all the connections together, in KiB per second.
+	#[structopt(long = "max-upload-rate", value_name = "KIB_PER_SEC")]
+	pub max_upload_rate: Option<u64>,
+
+	/// Maximum download rate of all the connections together, in KiB per second.
+	#[structopt(long = "max-download-rate", value_name = "KIB_PER_SEC")]
+	pub max_download_rate: Option<u64>,
+
 	/// Disable mDNS discovery.
 	///
 	/// By default, the network will use mDNS to discover other nodes on the
@@ -83,6 +127,14 @@ pub struct NetworkConfigurationParams {
 	#[structopt(long = "no-mdns")]
 	pub no_mdns: bool,
 
+	/// Disable port mapping.
+	///
+	/// By default, the node maps its listening ports on the router with UPnP or NAT-PMP and
+	/// advertises the resulting external addresses. This disables it. Automatically implied when
+	/// using --dev.
+	#[structopt(long = "no-port-mapping")]
+	pub no_port_mapping: bool,
+
 	/// Maximum number of peers to ask the same blocks in parallel.
 	///
 	/// This allows downlading announced blocks from multiple peers. Decrease to save
@@ -90,6 +142,51 @@ pub struct NetworkConfigurationParams {
 	#[structopt(long = "max-parallel-downloads", value_name = "COUNT", default_value = "5")]
 	pub max_parallel_downloads: u32,
 
+	/// Maximum number of blocks to request from a peer at once.
+	///
+	/// During the initial sync, each peer is asked for a different range of
+	/// this many blocks. Decrease on slow connections. Peers don't return more
+	/// than 128 blocks per response.
+	#[structopt(long = "max-blocks-per-request", value_name = "COUNT", default_value = "128")]
+	pub max_blocks_per_request: u32,
+
+	/// Blockchain syncing mode.
+	///
+	/// - `Full`: Download and execute all the blocks.
+	/// - `Fast`: Download the headers, then the state of a recent finalized block, and only
+	///   execute the blocks after it. Only used when the node has no blocks executed beyond
+	///   genesis. Requires a consensus engine that verifies headers without state, such as
+	///   BABE with GRANDPA, not Aura.
+	#[structopt(
+		long = "sync",
+		value_name = "SYNC_MODE",
+		possible_values = &SyncMode::variants(),
+		case_insensitive = true,
+		default_value = "Full",
+		verbatim_doc_comment
+	)]
+	pub sync: SyncMode,
+
+	/// Reputation under which a peer is disconnected and no longer connected to.
+	#[structopt(long = "peer-ban-threshold", value_name = "REPUTATION", allow_hyphen_values = true)]
+	pub peer_ban_threshold: Option<i32>,
+
+	/// Speed at which the reputation of the peers returns to zero.
+	///
+	/// Each second, reputations move towards zero by `1 / DIVISOR` of their value.
+	#[structopt(long = "peer-reputation-decay", value_name = "DIVISOR")]
+	pub peer_reputation_decay: Option<u32>,
+
+	/// Reputation change applied to a peer when it disconnects from us.
+	#[structopt(long = "peer-disconnect-cost", value_name = "COST", allow_hyphen_values = true)]
+	pub peer_disconnect_cost: Option<i32>,
+
+	/// Override the reputation change of an offence, given by the reason of its reports.
+	///
+	/// For example, `--peer-reputation-cost "Unexpected response packet=-1000"`.
+	#[structopt(long = "peer-reputation-cost", value_name = "REASON=COST")]
+	pub peer_reputation_cost: Vec<String>,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub node_key_params: NodeKeyParams,
@@ -108,20 +205,63 @@ impl NetworkConfigurationParams {
 		client_id: String,
 		is_dev: bool,
 	) -> error::Result<()> {
-		config.network.boot_nodes.extend(self.bootnodes.clone());
+		for bootnode in self.bootnodes.iter() {
+			parse_str_addr(bootnode).map_err(|e| error::Error::Input(
+				format!("Invalid bootnode address {}: {}", bootnode, e)
+			))?;
+		}
+		if self.replace_bootnodes {
+			config.network.boot_nodes.clear();
+		}
+		for bootnode in self.bootnodes.iter() {
+			if !config.network.boot_nodes.contains(bootnode) {
+				config.network.boot_nodes.push(bootnode.clone());
+			}
+		}
 		config.network.config_path = Some(config_path.clone());
 		config.network.net_config_path = Some(config_path.clone());
 
+		// the network only warns about invalid reserved nodes, which would leave a node
+		// restricted to its reserved nodes without any peer.
+		for reserved in self.reserved_nodes.iter() {
+			parse_str_addr(reserved).map_err(|e| error::Error::Input(
+				format!("Invalid reserved node address {}: {}", reserved, e)
+			))?;
+		}
 		config.network.reserved_nodes.extend(self.reserved_nodes.clone());
 		if self.reserved_only {
+			if config.network.reserved_nodes.is_empty() {
+				warn!("Only reserved nodes are allowed but none is specified with `--reserved-nodes`");
+			}
 			config.network.non_reserved_mode = NonReservedPeerMode::Deny;
 		}
 
 		config.network.sentry_nodes.extend(self.sentry_nodes.clone());
 
+		// as for reserved nodes, an invalid address would silently leave the validator unreachable.
+		for validator in self.sentry_validators.iter() {
+			parse_str_addr(validator).map_err(|e| error::Error::Input(
+				format!("Invalid sentry validator address {}: {}", validator, e)
+			))?;
+		}
+		config.network.sentry_validators.extend(self.sentry_validators.clone());
+
 		for addr in self.listen_addr.iter() {
-			let addr = addr.parse().ok().ok_or(error::Error::InvalidListenMultiaddress)?;
-			config.network.listen_addresses.push(addr);
+			let addr: Multiaddr = addr.parse().ok().ok_or(error::Error::InvalidListenMultiaddress)?;
+			// a node can't listen on a domain name, only advertise it.
+			match addr.iter().next() {
+				Some(Protocol::Dns4(_)) | Some(Protocol::Dns6(_)) => return Err(error::Error::Input(
+					format!("Can't listen on DNS address {}, use `--public-addr` to advertise it", addr)
+				)),
+				_ => config.network.listen_addresses.push(addr),
+			}
+		}
+
+		for addr in self.public_addr.iter() {
+			let addr = addr.parse().map_err(|e| error::Error::Input(
+				format!("Invalid public address {}: {}", addr, e)
+			))?;
+			config.network.public_addresses.push(addr);
 		}
 
 		if config.network.listen_addresses.is_empty() {
@@ -148,10 +288,219 @@ impl NetworkConfigurationParams {
 			allow_private_ipv4: !self.no_private_ipv4,
 			wasm_external_transport: None,
 			use_yamux_flow_control: self.use_yamux_flow_control,
+			enable_port_mapping: !is_dev && !self.no_port_mapping,
 		};
+		config.network.bandwidth_limits.upload = self.max_upload_rate.map(|rate| rate.saturating_mul(1024));
+		config.network.bandwidth_limits.download = self.max_download_rate.map(|rate| rate.saturating_mul(1024));
 
 		config.network.max_parallel_downloads = self.max_parallel_downloads;
+		if self.max_blocks_per_request == 0 || self.max_blocks_per_request > 128 {
+			return Err(error::Error::Input(
+				"`--max-blocks-per-request` must be between 1 and 128".into()
+			));
+		}
+		config.network.max_blocks_per_request = self.max_blocks_per_request;
+		config.network.sync_mode = self.sync.into();
+
+		if let Some(ban_threshold) = self.peer_ban_threshold {
+			config.network.reputation.ban_threshold = ban_threshold;
+		}
+		if let Some(decay) = self.peer_reputation_decay {
+			if decay == 0 || decay > i32::max_value() as u32 {
+				return Err(error::Error::Input(
+					format!("Invalid reputation decay divisor {}", decay)
+				))
+			}
+			config.network.reputation.decay_divisor = decay as i32;
+		}
+		if let Some(disconnect_cost) = self.peer_disconnect_cost {
+			config.network.reputation.disconnect_cost = disconnect_cost;
+		}
+		for cost in self.peer_reputation_cost.iter() {
+			let mut parts = cost.rsplitn(2, '=');
+			let value = parts.next().and_then(|value| value.trim().parse().ok());
+			match (parts.next(), value) {
+				(Some(reason), Some(value)) =>
+					config.network.reputation.cost_overrides.insert(reason.to_string(), value),
+				_ => return Err(error::Error::Input(
+					format!("Invalid reputation cost {}, expected REASON=COST", cost)
+				)),
+			};
+		}
 
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_network::PeerId;
+
+	fn update_config(args: &[&str]) -> error::Result<Configuration> {
+		let params = NetworkConfigurationParams::from_iter(
+			iter::once("node-test").chain(args.iter().cloned())
+		);
+		let mut config = Configuration::default();
+		let config_path = tempfile::tempdir()?.into_path();
+		params.update_config(&mut config, config_path, "test".into(), false)?;
+		Ok(config)
+	}
+
+	#[test]
+	fn reserved_only_restricts_to_the_reserved_nodes() {
+		let reserved = format!("/ip4/10.0.0.1/tcp/30333/p2p/{}", PeerId::random());
+		let config = update_config(&["--reserved-nodes", &reserved, "--reserved-only"]).unwrap();
+
+		assert_eq!(config.network.reserved_nodes, vec![reserved]);
+		assert_eq!(config.network.non_reserved_mode, NonReservedPeerMode::Deny);
+	}
+
+	#[test]
+	fn invalid_reserved_nodes_are_rejected() {
+		assert!(update_config(&["--reserved-nodes", "/ip4/10.0.0.1/tcp/30333"]).is_err());
+		assert!(update_config(&["--reserved-nodes", "10.0.0.1:30333"]).is_err());
+	}
+
+	#[test]
+	fn reputation_parameters_are_configurable() {
+		let config = update_config(&[
+			"--peer-ban-threshold", "-1000",
+			"--peer-reputation-decay", "100",
+			"--peer-disconnect-cost", "-10",
+			"--peer-reputation-cost", "Unexpected response packet=-1000",
+		]).unwrap();
+
+		assert_eq!(config.network.reputation.ban_threshold, -1000);
+		assert_eq!(config.network.reputation.decay_divisor, 100);
+		assert_eq!(config.network.reputation.disconnect_cost, -10);
+		assert_eq!(
+			config.network.reputation.cost_overrides.get("Unexpected response packet"),
+			Some(&-1000),
+		);
+	}
+
+	#[test]
+	fn invalid_reputation_parameters_are_rejected() {
+		assert!(update_config(&["--peer-reputation-decay", "0"]).is_err());
+		assert!(update_config(&["--peer-reputation-cost", "Bad protocol"]).is_err());
+		assert!(update_config(&["--peer-reputation-cost", "Bad protocol=high"]).is_err());
+	}
+
+	#[test]
+	fn sync_mode_is_configurable() {
+		let config = update_config(&[]).unwrap();
+		assert_eq!(config.network.sync_mode, sc_network::config::SyncMode::Full);
+
+		let config = update_config(&["--sync", "fast"]).unwrap();
+		assert_eq!(config.network.sync_mode, sc_network::config::SyncMode::Fast);
+	}
+
+	#[test]
+	fn port_mapping_can_be_disabled() {
+		let port_mapping = |config: Configuration| match config.network.transport {
+			TransportConfig::Normal { enable_port_mapping, .. } => enable_port_mapping,
+			TransportConfig::MemoryOnly => false,
+		};
+
+		assert!(port_mapping(update_config(&[]).unwrap()));
+		assert!(!port_mapping(update_config(&["--no-port-mapping"]).unwrap()));
+	}
+
+	#[test]
+	fn bandwidth_limits_are_configurable() {
+		let config = update_config(&[]).unwrap();
+		assert_eq!(config.network.bandwidth_limits, sc_network::config::BandwidthLimits::default());
+
+		let config = update_config(&["--max-upload-rate", "100", "--max-download-rate", "2048"]).unwrap();
+		assert_eq!(config.network.bandwidth_limits.upload, Some(100 * 1024));
+		assert_eq!(config.network.bandwidth_limits.download, Some(2048 * 1024));
+	}
+
+	#[test]
+	fn sentry_validators_must_be_valid() {
+		let validator = format!("/ip4/10.0.0.1/tcp/30333/p2p/{}", PeerId::random());
+		let config = update_config(&["--sentry-validators", &validator]).unwrap();
+		assert_eq!(config.network.sentry_validators, vec![validator]);
+
+		assert!(update_config(&["--sentry-validators", "/ip4/10.0.0.1/tcp/30333"]).is_err());
+	}
+
+	#[test]
+	fn listens_on_and_advertises_all_given_addresses() {
+		let config = update_config(&[
+			"--listen-addr", "/ip4/0.0.0.0/tcp/30333",
+			"--listen-addr", "/ip6/::/tcp/30333",
+			"--public-addr", "/dns4/example.com/tcp/30333",
+			"--public-addr", "/ip6/2001:db8::1/tcp/30333",
+		]).unwrap();
+
+		assert_eq!(config.network.listen_addresses, vec![
+			"/ip4/0.0.0.0/tcp/30333".parse::<Multiaddr>().unwrap(),
+			"/ip6/::/tcp/30333".parse().unwrap(),
+		]);
+		assert_eq!(config.network.public_addresses, vec![
+			"/dns4/example.com/tcp/30333".parse::<Multiaddr>().unwrap(),
+			"/ip6/2001:db8::1/tcp/30333".parse().unwrap(),
+		]);
+	}
+
+	#[test]
+	fn unusable_listen_and_public_addresses_are_rejected() {
+		assert!(update_config(&["--listen-addr", "/dns4/example.com/tcp/30333"]).is_err());
+		assert!(update_config(&["--public-addr", "not an address"]).is_err());
+	}
+
+	#[test]
+	fn max_blocks_per_request_is_bounded() {
+		assert_eq!(update_config(&[]).unwrap().network.max_blocks_per_request, 128);
+		let config = update_config(&["--max-blocks-per-request", "32"]).unwrap();
+		assert_eq!(config.network.max_blocks_per_request, 32);
+
+		assert!(update_config(&["--max-blocks-per-request", "0"]).is_err());
+		assert!(update_config(&["--max-blocks-per-request", "129"]).is_err());
+	}
+
+	fn update_config_with_boot_nodes(
+		spec_boot_nodes: &[String],
+		args: &[&str],
+	) -> error::Result<Vec<String>> {
+		let params = NetworkConfigurationParams::from_iter(
+			iter::once("node-test").chain(args.iter().cloned())
+		);
+		let mut config = Configuration::default();
+		config.network.boot_nodes = spec_boot_nodes.to_vec();
+		let config_path = tempfile::tempdir()?.into_path();
+		params.update_config(&mut config, config_path, "test".into(), false)?;
+		Ok(config.network.boot_nodes)
+	}
+
+	#[test]
+	fn bootnodes_are_appended_to_the_spec_ones() {
+		let spec = format!("/ip4/10.0.0.1/tcp/30333/p2p/{}", PeerId::random());
+		let cli = format!("/ip4/10.0.0.2/tcp/30333/p2p/{}", PeerId::random());
+
+		assert_eq!(
+			update_config_with_boot_nodes(&[spec.clone()], &["--bootnodes", &cli, "--bootnodes", &spec])
+				.unwrap(),
+			vec![spec, cli],
+		);
+	}
+
+	#[test]
+	fn bootnodes_replace_the_spec_ones() {
+		let spec = format!("/ip4/10.0.0.1/tcp/30333/p2p/{}", PeerId::random());
+		let cli = format!("/ip4/10.0.0.2/tcp/30333/p2p/{}", PeerId::random());
+
+		assert_eq!(
+			update_config_with_boot_nodes(&[spec], &["--bootnodes", &cli, "--replace-bootnodes"])
+				.unwrap(),
+			vec![cli],
+		);
+	}
+
+	#[test]
+	fn invalid_bootnodes_are_rejected() {
+		assert!(update_config_with_boot_nodes(&[], &["--bootnodes", "10.0.0.1:30333"]).is_err());
+	}
+}
@@ -14,10 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{path::PathBuf, str::FromStr};
+use std::{fs, path::PathBuf, str::FromStr};
 use structopt::StructOpt;
 use sc_service::Configuration;
-use sc_network::config::NodeKeyConfig;
+use sc_network::config::{NodeKeyConfig, PreSharedKey};
 use sp_core::H256;
 
 use crate::error;
@@ -88,6 +88,17 @@ pub struct NodeKeyParams {
 	/// the chosen type.
 	#[structopt(long = "node-key-file", value_name = "FILE")]
 	pub node_key_file: Option<PathBuf>,
+
+	/// The file from which to read the pre-shared key of a private network.
+	///
+	/// Only the nodes holding the same key can connect to each other. The file
+	/// uses the `swarm.key` format of libp2p private networks:
+	///
+	///   /key/swarm/psk/1.0.0/
+	///   /base16/
+	///   <64 hex characters>
+	#[structopt(long = "node-key-psk", value_name = "FILE")]
+	pub node_key_psk: Option<PathBuf>,
 }
 
 impl NodeKeyParams {
@@ -117,6 +128,18 @@ impl NodeKeyParams {
 			}
 		};
 
+		if let Some(path) = self.node_key_psk.as_ref() {
+			let psk = fs::read_to_string(path)
+				.map_err(|e| error::Error::Input(
+					format!("Failed to read pre-shared key file {}: {}", path.display(), e)
+				))?
+				.parse::<PreSharedKey>()
+				.map_err(|e| error::Error::Input(
+					format!("Invalid pre-shared key in {}: {}", path.display(), e)
+				))?;
+			config.network.pre_shared_key = Some(psk);
+		}
+
 		Ok(&config.network.node_key)
 	}
 }
@@ -151,7 +174,8 @@ mod tests {
 				let params = NodeKeyParams {
 					node_key_type,
 					node_key: Some(format!("{:x}", H256::from_slice(sk.as_ref()))),
-					node_key_file: None
+					node_key_file: None,
+					node_key_psk: None,
 				};
 				params.update_config(&mut config, net_config_dir).and_then(|c| match c {
 					NodeKeyConfig::Ed25519(sc_network::config::Secret::Input(ref ski))
@@ -177,7 +201,8 @@ mod tests {
 				let params = NodeKeyParams {
 					node_key_type,
 					node_key: None,
-					node_key_file: Some(file.clone())
+					node_key_file: Some(file.clone()),
+					node_key_psk: None,
 				};
 				params.update_config(&mut config, net_config_dir).and_then(|c| match c {
 					NodeKeyConfig::Ed25519(sc_network::config::Secret::File(ref f))
@@ -202,7 +227,8 @@ mod tests {
 				f(NodeKeyParams {
 					node_key_type,
 					node_key: None,
-					node_key_file: None
+					node_key_file: None,
+					node_key_psk: None,
 				})
 			})
 		}
@@ -238,4 +264,28 @@ mod tests {
 		assert!(no_config_dir().is_ok());
 		assert!(some_config_dir(&PathBuf::from_str("x").unwrap()).is_ok());
 	}
+
+	#[test]
+	fn test_node_key_psk_file() {
+		let psk_params = |file: PathBuf| NodeKeyParams {
+			node_key_type: NodeKeyType::Ed25519,
+			node_key: None,
+			node_key_file: None,
+			node_key_psk: Some(file),
+		};
+		let tmp = tempfile::Builder::new().prefix("alice").tempdir().unwrap();
+
+		let file = tmp.path().join("swarm.key");
+		fs::write(&file, format!("/key/swarm/psk/1.0.0/\n/base16/\n{}\n", "ab".repeat(32))).unwrap();
+		let mut config = Configuration::default();
+		psk_params(file).update_config(&mut config, None).unwrap();
+		assert_eq!(config.network.pre_shared_key, Some(PreSharedKey::new([0xab; 32])));
+
+		let file = tmp.path().join("invalid.key");
+		fs::write(&file, "not a key").unwrap();
+		assert!(psk_params(file).update_config(&mut Configuration::default(), None).is_err());
+
+		let missing = tmp.path().join("missing.key");
+		assert!(psk_params(missing).update_config(&mut Configuration::default(), None).is_err());
+	}
 }
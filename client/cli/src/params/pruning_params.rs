@@ -27,6 +27,9 @@ pub struct PruningParams {
 	/// Default is to keep all block states if the node is running as a
 	/// validator (i.e. 'archive'), otherwise state is only kept for the last
 	/// 256 blocks.
+	///
+	/// Archive and pruned nodes store the state differently, a database created
+	/// with one of them cannot be opened with the other.
 	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
 	pub pruning: Option<String>,
 }
@@ -64,3 +67,37 @@ impl PruningParams {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_service::Roles;
+
+	fn pruning(pruning: Option<&str>, role: Roles, unsafe_pruning: bool) -> error::Result<PruningMode> {
+		let params = PruningParams { pruning: pruning.map(Into::into) };
+		let mut config = Configuration::default();
+		params.update_config(&mut config, role, unsafe_pruning)?;
+		Ok(config.pruning)
+	}
+
+	#[test]
+	fn validators_default_to_archive() {
+		assert_eq!(pruning(None, Roles::AUTHORITY, false).unwrap(), PruningMode::ArchiveAll);
+		assert_eq!(pruning(None, Roles::FULL, false).unwrap(), PruningMode::default());
+		assert_eq!(pruning(Some("archive"), Roles::FULL, false).unwrap(), PruningMode::ArchiveAll);
+	}
+
+	#[test]
+	fn pruned_validators_require_unsafe_pruning() {
+		assert!(pruning(Some("100"), Roles::AUTHORITY, false).is_err());
+		assert_eq!(
+			pruning(Some("100"), Roles::AUTHORITY, true).unwrap(),
+			PruningMode::keep_blocks(100),
+		);
+	}
+
+	#[test]
+	fn invalid_pruning_modes_are_rejected() {
+		assert!(pruning(Some("all"), Roles::FULL, false).is_err());
+	}
+}
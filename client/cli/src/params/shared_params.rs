@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 use app_dirs::{AppInfo, AppDataType};
 use sc_service::{
@@ -23,17 +23,37 @@ use sc_service::{
 
 use crate::VersionInfo;
 use crate::error;
+use crate::remote_chain_spec;
+use crate::params::LogFileParams;
+use crate::RuntimeThreads;
 
 /// default sub directory to store database
 const DEFAULT_DB_CONFIG_PATH : &'static str = "db";
 
+/// sub directory of the base path caching the chain specs given by URL
+const REMOTE_CHAIN_SPEC_CACHE_PATH : &'static str = "chain-specs";
+
 /// Shared parameters used by all `CoreParams`.
 #[derive(Debug, StructOpt, Clone)]
 pub struct SharedParams {
 	/// Specify the chain specification (one of dev, local or staging).
+	///
+	/// A chain specification given by `http://` or `https://` URL is downloaded and cached
+	/// under the base path.
 	#[structopt(long = "chain", value_name = "CHAIN_SPEC")]
 	pub chain: Option<String>,
 
+	/// Expected SHA-256 hash, hex encoded, of the chain specification given by URL.
+	#[structopt(long = "chain-spec-sha256", value_name = "HASH")]
+	pub chain_spec_sha256: Option<String>,
+
+	/// JSON merge patch (RFC 7386) to overlay on the chain specification, e.g. to change the
+	/// balances, the authorities or the code of a base spec.
+	///
+	/// Multiple patches are applied in order.
+	#[structopt(long = "chain-patch", value_name = "PATH", parse(from_os_str))]
+	pub chain_patch: Vec<PathBuf>,
+
 	/// Specify the development chain.
 	#[structopt(long = "dev")]
 	pub dev: bool,
@@ -45,6 +65,33 @@ pub struct SharedParams {
 	/// Sets a custom logging filter.
 	#[structopt(short = "l", long = "log", value_name = "LOG_PATTERN")]
 	pub log: Option<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub log_file_params: LogFileParams,
+
+	/// Force the exit if the node has not shut down this many seconds after being asked to.
+	///
+	/// The tasks which failed to stop are reported. `0` waits forever.
+	#[structopt(long = "shutdown-timeout", value_name = "SECONDS", default_value = "60")]
+	pub shutdown_timeout: u64,
+
+	/// Number of threads of the tokio runtime running the tasks of the node.
+	///
+	/// Defaults to the number of CPUs.
+	#[structopt(long = "tokio-threads", value_name = "COUNT")]
+	pub tokio_threads: Option<usize>,
+
+	/// Maximum number of threads of the tokio runtime running blocking operations.
+	#[structopt(long = "blocking-threads", value_name = "COUNT")]
+	pub blocking_threads: Option<usize>,
+
+	/// Read options from a TOML file, keyed by their long name.
+	///
+	/// Options given on the command line override the ones of the file. On SIGHUP, the node
+	/// reads the file again and applies its `log` and `reserved-nodes` options.
+	#[structopt(long = "config", value_name = "FILE", parse(from_os_str))]
+	pub config: Option<PathBuf>,
 }
 
 impl SharedParams {
@@ -57,11 +104,28 @@ impl SharedParams {
 	) -> error::Result<&'a dyn ChainSpec> where
 		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
 	{
-		let chain_key = match self.chain {
+		let mut chain_key = match self.chain {
 			Some(ref chain) => chain.clone(),
 			None => if self.dev { "dev".into() } else { "".into() }
 		};
-		let spec = spec_factory(&chain_key)?;
+		if remote_chain_spec::is_url(&chain_key) {
+			let cache_dir = base_path(self, version).join(REMOTE_CHAIN_SPEC_CACHE_PATH);
+			let path = remote_chain_spec::fetch_chain_spec(
+				&chain_key,
+				self.chain_spec_sha256.as_ref().map(|h| h.as_str()),
+				&cache_dir,
+			).map_err(|e| error::Error::ChainSpec(e.to_string()))?;
+			chain_key = path.to_string_lossy().into_owned();
+		}
+		let mut spec = spec_factory(&chain_key).map_err(error::Error::ChainSpec)?;
+		for path in self.chain_patch.iter() {
+			let patch_error = |e: String| error::Error::ChainSpec(
+				format!("Error applying chain patch {}: {}", path.display(), e)
+			);
+			let patch = std::fs::read(path).map_err(|e| patch_error(e.to_string()))?;
+			let patch = serde_json::from_slice(&patch).map_err(|e| patch_error(e.to_string()))?;
+			spec.patch(&patch).map_err(patch_error)?;
+		}
 		config.network.boot_nodes = spec.boot_nodes().to_vec();
 		config.telemetry_endpoints = spec.telemetry_endpoints().clone();
 
@@ -83,6 +147,22 @@ impl SharedParams {
 		Ok(config.expect_chain_spec())
 	}
 
+	/// The time to wait for the node to shut down, if limited.
+	pub fn shutdown_timeout(&self) -> Option<Duration> {
+		match self.shutdown_timeout {
+			0 => None,
+			seconds => Some(Duration::from_secs(seconds)),
+		}
+	}
+
+	/// The number of threads of the tokio runtime.
+	pub fn runtime_threads(&self) -> RuntimeThreads {
+		RuntimeThreads {
+			core: self.tokio_threads,
+			blocking: self.blocking_threads,
+		}
+	}
+
 	/// Initialize substrate. This must be done only once.
 	///
 	/// This method:
@@ -91,7 +171,30 @@ impl SharedParams {
 	/// 2. Raise the FD limit
 	/// 3. Initialize the logger
 	pub fn init(&self, version: &VersionInfo) -> error::Result<()> {
-		crate::init(self.log.as_ref().map(|v| v.as_ref()).unwrap_or(""), version)
+		self.init_with_log_filter("", version)
+	}
+
+	/// Initialize substrate like [`init`](Self::init), applying the `log_filter` directives of
+	/// a subcommand before the ones given with `--log`, which take precedence.
+	///
+	/// The `log_filter` directives are kept when the log filter is reloaded.
+	pub fn init_with_log_filter(&self, log_filter: &str, version: &VersionInfo) -> error::Result<()> {
+		crate::set_subcommand_log_filter(log_filter);
+		crate::init_with_log_file(
+			&self.log_pattern(log_filter),
+			self.log_file_params.open()?,
+			version,
+		)
+	}
+
+	/// The `log_filter` directives followed by the ones given with `--log`.
+	pub(crate) fn log_pattern(&self, log_filter: &str) -> String {
+		let user_filter = self.log.as_ref().map(|v| v.as_str()).unwrap_or("");
+		match (log_filter.is_empty(), user_filter.is_empty()) {
+			(true, _) => user_filter.to_string(),
+			(false, true) => log_filter.to_string(),
+			(false, false) => format!("{},{}", log_filter, user_filter),
+		}
 	}
 }
 
@@ -107,3 +210,96 @@ fn base_path(cli: &SharedParams, version: &VersionInfo) -> PathBuf {
 			).expect("app directories exist on all supported platforms; qed")
 		)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_service::GenericChainSpec;
+
+	const TEST_VERSION_INFO: &'static VersionInfo = &VersionInfo {
+		name: "node-test",
+		version: "0.1.0",
+		commit: "some_commit",
+		executable_name: "node-test",
+		description: "description",
+		author: "author",
+		support_url: "http://example.org",
+		copyright_start_year: 2020,
+	};
+
+	fn test_spec(_: &str) -> Result<Box<dyn ChainSpec>, String> {
+		Ok(Box::new(GenericChainSpec::from_genesis(
+			"test",
+			"test-id",
+			|| (),
+			Vec::new(),
+			None,
+			None,
+			None,
+			None::<()>,
+		)))
+	}
+
+	#[test]
+	fn runtime_threads_are_parsed() {
+		let params = SharedParams::from_iter(&["node-test"]);
+		assert_eq!(params.runtime_threads(), RuntimeThreads::default());
+
+		let params = SharedParams::from_iter(&[
+			"node-test",
+			"--tokio-threads", "4",
+			"--blocking-threads", "16",
+		]);
+		assert_eq!(params.runtime_threads(), RuntimeThreads { core: Some(4), blocking: Some(16) });
+	}
+
+	#[test]
+	fn chain_patches_are_applied_in_order() {
+		let dir = tempfile::tempdir().unwrap();
+		let first = dir.path().join("first.json");
+		let second = dir.path().join("second.json");
+		std::fs::write(&first, r#"{ "id": "first", "name": "patched" }"#).unwrap();
+		std::fs::write(&second, r#"{ "id": "second" }"#).unwrap();
+
+		let params = SharedParams::from_iter(&[
+			"node-test",
+			"--chain-patch", first.to_str().unwrap(),
+			"--chain-patch", second.to_str().unwrap(),
+			"-d", dir.path().to_str().unwrap(),
+		]);
+		let mut config = Configuration::from_version(TEST_VERSION_INFO);
+		let spec = params.update_config(&mut config, test_spec, TEST_VERSION_INFO).unwrap();
+
+		assert_eq!(spec.id(), "second");
+		assert_eq!(spec.name(), "patched");
+	}
+
+	#[test]
+	fn invalid_chain_patch_is_a_chain_spec_error() {
+		let dir = tempfile::tempdir().unwrap();
+		let patch = dir.path().join("patch.json");
+		std::fs::write(&patch, "not json").unwrap();
+
+		let params = SharedParams::from_iter(&[
+			"node-test",
+			"--chain-patch", patch.to_str().unwrap(),
+			"-d", dir.path().to_str().unwrap(),
+		]);
+		let mut config = Configuration::from_version(TEST_VERSION_INFO);
+		match params.update_config(&mut config, test_spec, TEST_VERSION_INFO) {
+			Err(error::Error::ChainSpec(_)) => {},
+			_ => panic!("Expected a chain spec error"),
+		}
+	}
+
+	#[test]
+	fn log_filter_of_subcommand_is_overridden_by_log() {
+		let params = SharedParams::from_iter(&["node-test"]);
+		assert_eq!(params.log_pattern(""), "");
+		assert_eq!(params.log_pattern("my-command=debug"), "my-command=debug");
+
+		let params = SharedParams::from_iter(&["node-test", "--log", "my-command=trace"]);
+		assert_eq!(params.log_pattern(""), "my-command=trace");
+		assert_eq!(params.log_pattern("my-command=debug"), "my-command=debug,my-command=trace");
+	}
+}
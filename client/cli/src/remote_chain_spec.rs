@@ -0,0 +1,136 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chain specs given by URL.
+//!
+//! The spec is downloaded into a cache directory and loaded from there. With an expected hash,
+//! a cached spec matching the hash is used without downloading it again. Without, the cached
+//! spec is only used if the download fails.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+use sp_core::{hashing::{sha2_256, twox_128}, hexdisplay::HexDisplay};
+
+use crate::error;
+
+/// Whether the `--chain` value `chain` is a URL.
+pub fn is_url(chain: &str) -> bool {
+	chain.starts_with("https://") || chain.starts_with("http://")
+}
+
+/// Download the chain spec at `url` into `cache_dir` and return the path of the cached spec.
+///
+/// `expected_sha256` is the hex encoded SHA-256 hash the spec must have, if any.
+pub fn fetch_chain_spec(
+	url: &str,
+	expected_sha256: Option<&str>,
+	cache_dir: &Path,
+) -> error::Result<PathBuf> {
+	let path = cache_dir.join(format!("{}.json", HexDisplay::from(&twox_128(url.as_bytes()))));
+	let cached = fs::read(&path).ok();
+
+	let content = match (cached, expected_sha256) {
+		(Some(cached), Some(expected)) if check_sha256(&cached, expected).is_ok() => return Ok(path),
+		(cached, _) => match download(url) {
+			Ok(content) => content,
+			Err(e) => match cached {
+				Some(_) if expected_sha256.is_none() => {
+					warn!("{}, using the cached chain spec {}", e, path.display());
+					return Ok(path)
+				},
+				_ => return Err(e),
+			},
+		},
+	};
+
+	if let Some(expected) = expected_sha256 {
+		check_sha256(&content, expected)?;
+	}
+	fs::create_dir_all(cache_dir)?;
+	fs::write(&path, &content)?;
+	info!("Downloaded the chain spec {} to {}", url, path.display());
+
+	Ok(path)
+}
+
+/// Check that `content` has the hex encoded SHA-256 hash `expected`.
+fn check_sha256(content: &[u8], expected: &str) -> error::Result<()> {
+	let expected = expected.trim_start_matches("0x").to_lowercase();
+	let actual = format!("{}", HexDisplay::from(&sha2_256(content)));
+	if actual != expected {
+		return Err(error::Error::Input(format!(
+			"The chain spec hash 0x{} does not match the expected hash 0x{}",
+			actual,
+			expected,
+		)))
+	}
+
+	Ok(())
+}
+
+fn download(url: &str) -> error::Result<Vec<u8>> {
+	let uri: hyper::Uri = url.parse()
+		.map_err(|e| error::Error::Input(format!("Invalid chain spec URL {}: {}", url, e)))?;
+
+	let mut runtime = tokio::runtime::Builder::new()
+		.basic_scheduler()
+		.enable_all()
+		.build()?;
+	let content = runtime.block_on(async {
+		let client = hyper::Client::builder()
+			.build::<_, hyper::Body>(hyper_rustls::HttpsConnector::new());
+		let response = client.get(uri).await.map_err(|e| e.to_string())?;
+		if !response.status().is_success() {
+			return Err(format!("Unexpected response status {}", response.status()))
+		}
+		hyper::body::to_bytes(response.into_body()).await.map_err(|e| e.to_string())
+	}).map_err(|e| format!("Error downloading the chain spec {}: {}", url, e))?;
+
+	Ok(content.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SPEC: &[u8] = b"{}";
+	const SPEC_SHA256: &str = "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a";
+
+	#[test]
+	fn chain_spec_hashes_are_checked() {
+		assert!(check_sha256(SPEC, SPEC_SHA256).is_ok());
+		assert!(check_sha256(SPEC, &format!("0x{}", SPEC_SHA256.to_uppercase())).is_ok());
+		assert!(check_sha256(b"[]", SPEC_SHA256).is_err());
+	}
+
+	#[test]
+	fn cached_chain_specs_matching_the_hash_are_not_downloaded() {
+		let cache_dir = tempfile::tempdir().unwrap();
+		let url = "https://example.invalid/spec.json";
+		let path = cache_dir.path().join(format!("{}.json", HexDisplay::from(&twox_128(url.as_bytes()))));
+		fs::write(&path, SPEC).unwrap();
+
+		assert_eq!(fetch_chain_spec(url, Some(SPEC_SHA256), cache_dir.path()).unwrap(), path);
+	}
+
+	#[test]
+	fn urls_are_recognized() {
+		assert!(is_url("https://example.com/spec.json"));
+		assert!(!is_url("dev"));
+		assert!(!is_url("./spec.json"));
+	}
+}
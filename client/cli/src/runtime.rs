@@ -14,16 +14,23 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::{Arc, mpsc}, thread, time::Duration};
 
-use futures::{Future, future, future::FutureExt};
+use futures::{Future, future, future::{FutureExt, TryFutureExt}};
 use futures::select;
 use futures::pin_mut;
-use sc_service::{AbstractService, Configuration};
+use log::{error, info};
+use sc_network::{
+	ExHashT, Multiaddr, NetworkService, NetworkStateInfo, config::parse_str_addr, multiaddr::Protocol,
+};
+use sc_service::{AbstractService, Configuration, RunningTasks};
+use sp_runtime::traits::Block as BlockT;
 use crate::error;
 
+/// Run `func` until it ends or the process receives SIGTERM or SIGINT, calling `on_reload` when
+/// it receives SIGHUP.
 #[cfg(target_family = "unix")]
-async fn main<F, E>(func: F) -> Result<(), Box<dyn std::error::Error>>
+async fn main<F, E>(func: F, mut on_reload: impl FnMut()) -> Result<(), Box<dyn std::error::Error>>
 where
 	F: Future<Output = Result<(), E>> + future::FusedFuture,
 	E: 'static + std::error::Error,
@@ -32,6 +39,7 @@ where
 
 	let mut stream_int = signal(SignalKind::interrupt())?;
 	let mut stream_term = signal(SignalKind::terminate())?;
+	let mut stream_hup = signal(SignalKind::hangup())?;
 
 	let t1 = stream_int.recv().fuse();
 	let t2 = stream_term.recv().fuse();
@@ -39,17 +47,26 @@ where
 
 	pin_mut!(t1, t2, t3);
 
-	select! {
-		_ = t1 => {},
-		_ = t2 => {},
-		res = t3 => res?,
+	loop {
+		let t4 = stream_hup.recv().fuse();
+		pin_mut!(t4);
+
+		select! {
+			_ = t1 => break,
+			_ = t2 => break,
+			res = t3 => {
+				res?;
+				break
+			},
+			_ = t4 => on_reload(),
+		}
 	}
 
 	Ok(())
 }
 
 #[cfg(not(unix))]
-async fn main<F, E>(func: F) -> Result<(), Box<dyn std::error::Error>>
+async fn main<F, E>(func: F, _on_reload: impl FnMut()) -> Result<(), Box<dyn std::error::Error>>
 where
 	F: Future<Output = Result<(), E>> + future::FusedFuture,
 	E: 'static + std::error::Error,
@@ -69,18 +86,82 @@ where
 	Ok(())
 }
 
-fn build_runtime() -> Result<tokio::runtime::Runtime, std::io::Error> {
-	tokio::runtime::Builder::new()
+/// The number of threads of the tokio runtime, the tokio defaults are used when `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RuntimeThreads {
+	/// Number of threads running the tasks of the node.
+	pub core: Option<usize>,
+	/// Maximum number of threads running blocking operations.
+	pub blocking: Option<usize>,
+}
+
+fn build_runtime(threads: RuntimeThreads) -> error::Result<tokio::runtime::Runtime> {
+	let mut builder = tokio::runtime::Builder::new();
+	builder
 		.thread_name("main-tokio-")
 		.threaded_scheduler()
-		.enable_all()
-		.build()
+		.enable_all();
+
+	if threads.core == Some(0) || threads.blocking == Some(0) {
+		return Err(error::Error::Input("The tokio runtime needs at least one thread".into()))
+	}
+	if let Some(core) = threads.core {
+		builder.core_threads(core);
+	}
+	// The maximum number of threads of tokio includes the core threads.
+	if let Some(blocking) = threads.blocking {
+		builder.max_threads(threads.core.unwrap_or_else(num_cpus::get) + blocking);
+	}
+
+	Ok(builder.build()?)
+}
+
+/// Shut `runtime` down, giving up after `timeout`.
+///
+/// The tasks of `running_tasks` still running at the timeout are reported.
+fn shutdown_runtime(
+	runtime: tokio::runtime::Runtime,
+	timeout: Option<Duration>,
+	running_tasks: Option<RunningTasks>,
+) -> error::Result<()> {
+	let timeout = match timeout {
+		Some(timeout) => timeout,
+		None => {
+			drop(runtime);
+			return Ok(())
+		},
+	};
+
+	let (tx, rx) = mpsc::channel();
+	thread::Builder::new()
+		.name("tokio-shutdown".into())
+		.spawn(move || {
+			drop(runtime);
+			let _ = tx.send(());
+		})?;
+
+	if rx.recv_timeout(timeout).is_ok() {
+		return Ok(())
+	}
+
+	for (name, count) in running_tasks.map(|tasks| tasks.names()).unwrap_or_default() {
+		error!("Task `{}` failed to stop ({} running)", name, count);
+	}
+	Err(error::Error::Other(format!(
+		"The node did not shut down within {} seconds, forcing the exit",
+		timeout.as_secs(),
+	)))
 }
 
 /// A helper function that runs a future with tokio and stops if the process receives the signal
 /// SIGTERM or SIGINT
+///
+/// The runtime is built with the given number of `threads`.
+/// The exit is forced if the runtime has not shut down within `shutdown_timeout`.
 pub fn run_until_exit<FUT, ERR, F>(
 	mut config: Configuration,
+	shutdown_timeout: Option<Duration>,
+	threads: RuntimeThreads,
 	future_builder: F,
 ) -> error::Result<()>
 where
@@ -88,7 +169,7 @@ where
 	FUT: Future<Output = Result<(), ERR>> + future::Future,
 	ERR: 'static + std::error::Error,
 {
-	let mut runtime = build_runtime()?;
+	let mut runtime = build_runtime(threads)?;
 
 	config.task_executor = {
 		let runtime_handle = runtime.handle().clone();
@@ -99,43 +180,158 @@ where
 	let f = f.fuse();
 	pin_mut!(f);
 
-	runtime.block_on(main(f)).map_err(|e| e.to_string())?;
+	runtime.block_on(main(f, || {})).map_err(|e| e.to_string())?;
 
-	Ok(())
+	shutdown_runtime(runtime, shutdown_timeout, None)
 }
 
 /// A helper function that runs an `AbstractService` with tokio and stops if the process receives
 /// the signal SIGTERM or SIGINT
+///
+/// The runtime is built with the given number of `threads`.
+/// The exit is forced if the runtime has not shut down within `shutdown_timeout`, reporting the
+/// tasks of the service which failed to stop. The informant prints in `informant_format`.
 pub fn run_service_until_exit<T, F>(
 	mut config: Configuration,
+	shutdown_timeout: Option<Duration>,
+	threads: RuntimeThreads,
+	informant_format: sc_informant::OutputFormat,
 	service_builder: F,
 ) -> error::Result<()>
 where
 	F: FnOnce(Configuration) -> Result<T, sc_service::error::Error>,
 	T: AbstractService + Unpin,
 {
-	let mut runtime = build_runtime()?;
+	let mut runtime = build_runtime(threads)?;
 
 	config.task_executor = {
 		let runtime_handle = runtime.handle().clone();
 		Some(Arc::new(move |fut| { runtime_handle.spawn(fut); }))
 	};
 
+	let reserved_nodes = config.network.reserved_nodes.clone();
 	let service = service_builder(config)?;
+	let on_reload = reload_config(service.network(), reserved_nodes);
 
-	let informant_future = sc_informant::build(&service, sc_informant::OutputFormat::Coloured);
+	let informant_future = sc_informant::build(&service, informant_format);
 	let _informant_handle = runtime.spawn(informant_future);
 
 	// we eagerly drop the service so that the internal exit future is fired,
 	// but we need to keep holding a reference to the global telemetry guard
 	// and drop the runtime first.
 	let _telemetry = service.telemetry();
+	let running_tasks = service.running_tasks();
 
 	let f = service.fuse();
 	pin_mut!(f);
 
-	runtime.block_on(main(f)).map_err(|e| e.to_string())?;
-	drop(runtime);
+	runtime.block_on(main(f, on_reload)).map_err(|e| e.to_string())?;
 
-	Ok(())
+	shutdown_runtime(runtime, shutdown_timeout, Some(running_tasks))
+}
+
+/// The closure applying the reloadable options of the command line and of the config file again,
+/// starting from the `reserved_nodes` of the node served by `network`.
+///
+/// The telemetry endpoints can't be reloaded, the telemetry is connected once at startup.
+fn reload_config<B, H>(
+	network: Arc<NetworkService<B, H>>,
+	mut reserved_nodes: Vec<String>,
+) -> impl FnMut() where
+	B: BlockT,
+	H: ExHashT,
+{
+	move || {
+		let options = match crate::reloadable_options(std::env::args_os()) {
+			Ok(options) => options,
+			Err(e) => {
+				error!("Failed to reload the configuration: {}", e);
+				return
+			},
+		};
+
+		crate::reload_log_filter(&options.log_pattern);
+
+		for node in reserved_nodes.iter().filter(|node| !options.reserved_nodes.contains(node)) {
+			if let Ok((peer_id, _)) = parse_str_addr(node) {
+				network.remove_reserved_peer(peer_id);
+			}
+		}
+		for node in options.reserved_nodes.iter().filter(|node| !reserved_nodes.contains(node)) {
+			if let Err(e) = network.add_reserved_peer(node.clone()) {
+				error!("Failed to add the reserved node {}: {}", node, e);
+			}
+		}
+		reserved_nodes = options.reserved_nodes;
+
+		info!("Configuration reloaded");
+	}
+}
+
+/// The address the full node configured by `config` is reachable at from the same machine.
+fn local_listen_address(config: &Configuration) -> error::Result<Multiaddr> {
+	let address = config.network.listen_addresses.first()
+		.ok_or_else(|| error::Error::Input("The full node does not listen on any address".into()))?;
+	address.iter()
+		.map(|protocol| match protocol {
+			Protocol::Ip4(ip) if ip.is_unspecified() => Ok(Protocol::Ip4([127, 0, 0, 1].into())),
+			Protocol::Ip6(ip) if ip.is_unspecified() => Ok(Protocol::Ip6(std::net::Ipv6Addr::LOCALHOST)),
+			Protocol::Tcp(0) => Err(error::Error::Input(
+				"An embedded light client needs the full node to listen on a fixed port".into()
+			)),
+			protocol => Ok(protocol),
+		})
+		.collect()
+}
+
+/// A helper function that runs a full `AbstractService` and a light `AbstractService` embedded in
+/// the same process with tokio, and stops if the process receives the signal SIGTERM or SIGINT
+///
+/// The light client only syncs from the full node, and serves its RPC on `light_rpc_http`. The
+/// exit is forced like in `run_service_until_exit`.
+pub fn run_full_and_light_until_exit<TF, TL, FF, FL>(
+	mut config: Configuration,
+	shutdown_timeout: Option<Duration>,
+	threads: RuntimeThreads,
+	light_rpc_http: Option<SocketAddr>,
+	informant_format: sc_informant::OutputFormat,
+	full_builder: FF,
+	light_builder: FL,
+) -> error::Result<()>
+where
+	FF: FnOnce(Configuration) -> Result<TF, sc_service::error::Error>,
+	FL: FnOnce(Configuration) -> Result<TL, sc_service::error::Error>,
+	TF: AbstractService + Unpin,
+	TL: AbstractService + Unpin,
+{
+	let mut runtime = build_runtime(threads)?;
+
+	config.task_executor = {
+		let runtime_handle = runtime.handle().clone();
+		Some(Arc::new(move |fut| { runtime_handle.spawn(fut); }))
+	};
+
+	let mut light_config = config.embedded_light_client(light_rpc_http)?;
+	let full_address = local_listen_address(&config)?;
+
+	let reserved_nodes = config.network.reserved_nodes.clone();
+	let full_service = full_builder(config)?;
+	let on_reload = reload_config(full_service.network(), reserved_nodes);
+	let full_node = format!("{}/p2p/{}", full_address, full_service.network().local_peer_id());
+	light_config.network.boot_nodes.push(full_node.clone());
+	light_config.network.reserved_nodes.push(full_node);
+	let light_service = light_builder(light_config)?;
+
+	let informant_future = sc_informant::build(&full_service, informant_format);
+	let _informant_handle = runtime.spawn(informant_future);
+
+	let _telemetry = full_service.telemetry();
+	let running_tasks = full_service.running_tasks();
+
+	let f = future::try_join(full_service, light_service).map_ok(drop).fuse();
+	pin_mut!(f);
+
+	runtime.block_on(main(f, on_reload)).map_err(|e| e.to_string())?;
+
+	shutdown_runtime(runtime, shutdown_timeout, Some(running_tasks))
 }
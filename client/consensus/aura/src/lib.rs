@@ -631,6 +631,11 @@ impl<B: BlockT, C, P> Verifier<B> for AuraVerifier<C, P> where
 			}
 		}
 	}
+
+	fn verifies_headers_only(&self) -> bool {
+		// The authorities may be read from the state of the parent block.
+		false
+	}
 }
 
 fn initialize_authorities_cache<A, B, C>(client: &C) -> Result<(), ConsensusError> where
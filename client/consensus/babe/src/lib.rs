@@ -254,7 +254,11 @@ impl Config {
 	{
 		trace!(target: "babe", "Getting slot duration");
 		match sc_consensus_slots::SlotDuration::get_or_compute(client, |a, b| a.configuration(b)).map(Self) {
-			Ok(s) => Ok(s),
+			Ok(s) => {
+				check_configuration(&s)
+					.map_err(|e| ClientError::Msg(format!("Invalid BABE configuration: {}", e)))?;
+				Ok(s)
+			},
 			Err(s) => {
 				warn!(target: "babe", "Failed to get slot duration");
 				Err(s)
@@ -275,6 +279,35 @@ impl Config {
 	}
 }
 
+/// Checks that the BABE configuration of a chain allows authoring and verifying blocks.
+///
+/// The configuration comes from the genesis state of the chain, and invalid values would
+/// otherwise only show up as divisions by zero or meaningless slot thresholds.
+fn check_configuration(config: &BabeConfiguration) -> Result<(), String> {
+	if config.slot_duration == 0 {
+		return Err("the slot duration is zero".into());
+	}
+	if config.epoch_length == 0 {
+		return Err("the epoch length is zero".into());
+	}
+
+	let (numerator, denominator) = config.c;
+	if denominator == 0 || numerator > denominator {
+		return Err(format!("`c` must be between 0 and 1, got {}/{}", numerator, denominator));
+	}
+	if numerator == 0 && !config.secondary_slots {
+		return Err("`c` is zero and secondary slots are disabled, no slot can be claimed".into());
+	}
+
+	let total_weight = config.genesis_authorities.iter()
+		.fold(0u64, |total, (_, weight)| total.saturating_add(*weight));
+	if !config.genesis_authorities.is_empty() && total_weight == 0 {
+		return Err("the genesis authorities have no weight".into());
+	}
+
+	Ok(())
+}
+
 impl std::ops::Deref for Config {
 	type Target = BabeConfiguration;
 
@@ -536,6 +536,34 @@ fn can_author_block() {
 	}
 }
 
+#[test]
+fn invalid_configurations_are_rejected() {
+	let valid = crate::BabeConfiguration {
+		slot_duration: 1000,
+		epoch_length: 100,
+		c: (1, 4),
+		genesis_authorities: vec![(AuthorityPair::from_seed(&[1; 32]).public(), 1)],
+		randomness: [0; 32],
+		secondary_slots: false,
+	};
+	assert!(check_configuration(&valid).is_ok());
+
+	let check = |change: fn(&mut crate::BabeConfiguration)| {
+		let mut config = valid.clone();
+		change(&mut config);
+		check_configuration(&config)
+	};
+
+	assert!(check(|c| c.slot_duration = 0).is_err());
+	assert!(check(|c| c.epoch_length = 0).is_err());
+	assert!(check(|c| c.c = (1, 0)).is_err());
+	assert!(check(|c| c.c = (5, 4)).is_err());
+	assert!(check(|c| c.c = (0, 4)).is_err());
+	assert!(check(|c| { c.c = (0, 4); c.secondary_slots = true; }).is_ok());
+	assert!(check(|c| c.genesis_authorities[0].1 = 0).is_err());
+	assert!(check(|c| c.genesis_authorities.clear()).is_ok());
+}
+
 // Propose and import a new BABE block on top of the given parent.
 fn propose_and_import_block<Transaction>(
 	parent: &TestHeader,
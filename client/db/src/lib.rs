@@ -339,6 +339,59 @@ pub(crate) mod columns {
 	/// Offchain workers local storage
 	pub const OFFCHAIN: u32 = 9;
 	pub const CACHE: u32 = 10;
+
+	/// Names of the columns, indexed by column.
+	pub const NAMES: [&str; crate::utils::NUM_COLUMNS as usize] = [
+		"meta",
+		"state",
+		"state_meta",
+		"key_lookup",
+		"header",
+		"body",
+		"justification",
+		"changes_trie",
+		"aux",
+		"offchain",
+		"cache",
+	];
+}
+
+/// Size of the keys and values stored in each column of the database of a full client, with
+/// the names of the columns.
+///
+/// All the entries are read, this is slow on large databases. Fails if there is no database.
+pub fn column_sizes<Block: BlockT>(
+	settings: &DatabaseSettings,
+) -> ClientResult<Vec<(&'static str, u64)>> {
+	let db = crate::utils::open_existing_database::<Block>(settings, DatabaseType::Full)?;
+	Ok(columns::NAMES.iter().enumerate().map(|(column, name)| {
+		let size = db.iter(column as u32)
+			.map(|(key, value)| (key.len() + value.len()) as u64)
+			.sum();
+		(*name, size)
+	}).collect())
+}
+
+/// Genesis, best and finalized blocks of the database of a full client.
+///
+/// Fails if there is no database or no chain in it. Unlike building a client, this doesn't write
+/// anything to the database, in particular the genesis block is not initialized.
+pub fn chain_info<Block: BlockT>(
+	settings: &DatabaseSettings,
+) -> ClientResult<sc_client::blockchain::Info<Block>> {
+	let db = crate::utils::open_existing_database::<Block>(settings, DatabaseType::Full)?;
+	if crate::utils::read_genesis_hash::<Block::Hash>(&*db)?.is_none() {
+		return Err(sp_blockchain::Error::Backend("No chain in the database".into()));
+	}
+
+	let meta = read_meta::<Block>(&*db, columns::HEADER)?;
+	Ok(sc_client::blockchain::Info {
+		best_hash: meta.best_hash,
+		best_number: meta.best_number,
+		genesis_hash: meta.genesis_hash,
+		finalized_hash: meta.finalized_hash,
+		finalized_number: meta.finalized_number,
+	})
 }
 
 struct PendingBlock<Block: BlockT> {
@@ -535,6 +588,7 @@ pub struct BlockImportOperation<Block: BlockT> {
 	finalized_blocks: Vec<(BlockId<Block>, Option<Justification>)>,
 	set_head: Option<BlockId<Block>>,
 	commit_state: bool,
+	reset_state: bool,
 }
 
 impl<Block: BlockT> BlockImportOperation<Block> {
@@ -604,23 +658,25 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 			child_content.data.into_iter().map(|(k, v)| (k, Some(v))), child_content.child_info),
 		);
 
-		let mut changes_trie_config: Option<ChangesTrieConfiguration> = None;
+		// The storage is not necessarily the genesis one, it may also be downloaded from peers.
+		let changes_trie_config: Option<ChangesTrieConfiguration> = match storage.top
+			.get(well_known_keys::CHANGES_TRIE_CONFIG)
+		{
+			Some(v) => Some(
+				Decode::decode(&mut &v[..])
+					.map_err(|_| sp_blockchain::Error::ErrorReadingChangesTriesConfig)?
+			),
+			None => None,
+		};
 		let (root, transaction) = self.old_state.full_storage_root(
-			storage.top.into_iter().map(|(k, v)| {
-				if k == well_known_keys::CHANGES_TRIE_CONFIG {
-					changes_trie_config = Some(
-						Decode::decode(&mut &v[..])
-							.expect("changes trie configuration is encoded properly at genesis")
-					);
-				}
-				(k, Some(v))
-			}),
+			storage.top.into_iter().map(|(k, v)| (k, Some(v))),
 			child_delta
 		);
 
 		self.db_updates = transaction;
 		self.changes_trie_config_update = Some(changes_trie_config);
 		self.commit_state = true;
+		self.reset_state = true;
 		Ok(root)
 	}
 
@@ -1030,6 +1086,11 @@ impl<Block: BlockT> Backend<Block> {
 						implies existence of blocks with all numbers before it; qed")
 			};
 
+			// Blocks imported without state (e.g. before a downloaded state) are not tracked.
+			if self.storage.state_db.is_pruned(&hash, new_canonical) {
+				return Ok(())
+			}
+
 			trace!(target: "db", "Canonicalize block #{} ({:?})", new_canonical, hash);
 			let commit = self.storage.state_db.canonicalize_block(&hash)
 				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
@@ -1132,12 +1193,18 @@ impl<Block: BlockT> Backend<Block> {
 				self.state_usage.tally_writes(ops, bytes);
 
 				let number_u64 = number.saturated_into::<u64>();
-				let commit = self.storage.state_db.insert_block(
-					&hash,
-					number_u64,
-					&pending_block.header.parent_hash(),
-					changeset,
-				).map_err(|e: sc_state_db::Error<io::Error>|
+				let commit = if operation.reset_state && number_u64 > 0 {
+					// The complete state of a non-genesis block was imported, the state of its
+					// ancestors is not available.
+					self.storage.state_db.import_state(&hash, number_u64, changeset)
+				} else {
+					self.storage.state_db.insert_block(
+						&hash,
+						number_u64,
+						&pending_block.header.parent_hash(),
+						changeset,
+					)
+				}.map_err(|e: sc_state_db::Error<io::Error>|
 					sp_blockchain::Error::from(format!("State database error: {:?}", e))
 				)?;
 				apply_state_commit(&mut transaction, commit);
@@ -1307,9 +1374,12 @@ impl<Block: BlockT> Backend<Block> {
 			let lookup_key = utils::number_and_hash_to_lookup_key(f_num, f_hash.clone())?;
 			transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
 
-			let commit = self.storage.state_db.canonicalize_block(&f_hash)
-				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
-			apply_state_commit(transaction, commit);
+			// Blocks imported without state are finalized without canonicalizing their state.
+			if !self.storage.state_db.is_pruned(&f_hash, f_num.saturated_into::<u64>()) {
+				let commit = self.storage.state_db.canonicalize_block(&f_hash)
+					.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
+				apply_state_commit(transaction, commit);
+			}
 
 			if !f_num.is_zero() {
 				let new_changes_trie_cache_ops = self.changes_tries_storage.finalize(
@@ -1396,6 +1466,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			finalized_blocks: Vec::new(),
 			set_head: None,
 			commit_state: false,
+			reset_state: false,
 		})
 	}
 
@@ -1805,6 +1876,67 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn column_sizes_are_measured() {
+		let backend = Backend::<Block>::new_test(2, 0);
+		insert_header(&backend, 0, Default::default(), None, Default::default());
+
+		let sizes = column_sizes::<Block>(&DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			pruning: PruningMode::keep_blocks(2),
+			source: DatabaseSettingsSrc::Custom(backend.storage.db.clone()),
+		}).unwrap();
+
+		assert_eq!(sizes.len(), columns::NAMES.len());
+		assert_eq!(sizes[columns::HEADER as usize].0, "header");
+		assert!(sizes[columns::HEADER as usize].1 > 0);
+		assert_eq!(sizes[columns::OFFCHAIN as usize].1, 0);
+	}
+
+	#[test]
+	fn chain_info_requires_an_existing_database() {
+		let dir = tempfile::tempdir().unwrap();
+		let settings = |path: PathBuf| DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			pruning: PruningMode::keep_blocks(2),
+			source: DatabaseSettingsSrc::Path { path, cache_size: None },
+		};
+
+		// nothing is created at a missing path.
+		let missing = dir.path().join("missing");
+		assert!(chain_info::<Block>(&settings(missing.clone())).is_err());
+		assert!(column_sizes::<Block>(&settings(missing.clone())).is_err());
+		assert!(!missing.exists());
+
+		// a database without a chain isn't initialized.
+		let empty = dir.path().join("empty");
+		let open = |path| crate::utils::open_database::<Block>(&settings(path), DatabaseType::Full);
+		drop(open(empty.clone()).unwrap());
+		assert!(chain_info::<Block>(&settings(empty.clone())).is_err());
+		let db = open(empty).unwrap();
+		assert!(crate::utils::read_genesis_hash::<H256>(&*db).unwrap().is_none());
+	}
+
+	#[test]
+	fn chain_info_reads_the_chain() {
+		let backend = Backend::<Block>::new_test(2, 0);
+		let hash0 = insert_header(&backend, 0, Default::default(), None, Default::default());
+		let hash1 = insert_header(&backend, 1, hash0, None, Default::default());
+
+		let info = chain_info::<Block>(&DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			pruning: PruningMode::keep_blocks(2),
+			source: DatabaseSettingsSrc::Custom(backend.storage.db.clone()),
+		}).unwrap();
+
+		assert_eq!(info.genesis_hash, hash0);
+		assert_eq!(info.best_hash, hash1);
+		assert_eq!(info.best_number, 1);
+	}
+
 	#[test]
 	fn set_state_data() {
 		let db = Backend::<Block>::new_test(2, 0);
@@ -53,6 +53,23 @@ pub fn upgrade_db<Block: BlockT>(db_path: &Path, db_type: DatabaseType) -> sp_bl
 	update_version(db_path)
 }
 
+/// Check that there is a database of the current version at `db_path`, without creating or
+/// upgrading it.
+pub fn ensure_current_version(db_path: &Path) -> sp_blockchain::Result<()> {
+	if !version_file_path(db_path).exists() {
+		return Err(sp_blockchain::Error::Backend(
+			format!("No database at {}", db_path.display())
+		));
+	}
+
+	match current_version(db_path)? {
+		CURRENT_VERSION => Ok(()),
+		db_version => Err(sp_blockchain::Error::Backend(
+			format!("Database version {} is not the current version {}", db_version, CURRENT_VERSION)
+		)),
+	}
+}
+
 /// Migration from version0 to version1:
 /// 1) the number of columns has changed from 10 to 11;
 /// 2) changes tries configuration are now cached.
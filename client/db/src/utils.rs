@@ -218,12 +218,35 @@ pub fn db_err(err: io::Error) -> sp_blockchain::Error {
 pub fn open_database<Block: BlockT>(
 	config: &DatabaseSettings,
 	db_type: DatabaseType,
+) -> sp_blockchain::Result<Arc<dyn KeyValueDB>> {
+	open_database_inner::<Block>(config, db_type, true)
+}
+
+/// Open an existing RocksDB database to inspect it.
+///
+/// Unlike `open_database`, a missing database is an error instead of being created, and the
+/// database is neither upgraded nor marked with its type, so nothing is written to it.
+pub fn open_existing_database<Block: BlockT>(
+	config: &DatabaseSettings,
+	db_type: DatabaseType,
+) -> sp_blockchain::Result<Arc<dyn KeyValueDB>> {
+	open_database_inner::<Block>(config, db_type, false)
+}
+
+fn open_database_inner<Block: BlockT>(
+	config: &DatabaseSettings,
+	db_type: DatabaseType,
+	create: bool,
 ) -> sp_blockchain::Result<Arc<dyn KeyValueDB>> {
 	let db: Arc<dyn KeyValueDB> = match &config.source {
 		#[cfg(any(feature = "kvdb-rocksdb", test))]
 		DatabaseSettingsSrc::Path { path, cache_size } => {
-			// first upgrade database to required version
-			crate::upgrade::upgrade_db::<Block>(&path, db_type)?;
+			if create {
+				// first upgrade database to required version
+				crate::upgrade::upgrade_db::<Block>(&path, db_type)?;
+			} else {
+				crate::upgrade::ensure_current_version(&path)?;
+			}
 
 			// and now open database assuming that it has the latest version
 			let mut db_config = DatabaseConfig::with_columns(NUM_COLUMNS);
@@ -255,7 +278,16 @@ pub fn open_database<Block: BlockT>(
 		DatabaseSettingsSrc::Custom(db) => db.clone(),
 	};
 
-	check_database_type(&*db, db_type)?;
+	if create {
+		check_database_type(&*db, db_type)?;
+	} else {
+		match db.get(COLUMN_META, meta_keys::TYPE).map_err(db_err)? {
+			Some(ref stored_type) if db_type.as_str().as_bytes() == &**stored_type => (),
+			_ => return Err(sp_blockchain::Error::Backend(
+				format!("No {} database found", db_type.as_str())
+			)),
+		}
+	}
 
 	Ok(db)
 }
@@ -24,6 +24,87 @@ use std::{convert::{TryFrom, TryInto}, fmt};
 use wasm_timer::Instant;
 use crate::OutputFormat;
 
+/// The status of the node printed regularly by the informant.
+pub struct InformantStatus<'a, B: BlockT> {
+	/// Information about the chain.
+	pub info: &'a ClientInfo<B>,
+	/// Status of the network.
+	pub net_status: &'a NetworkStatus<B>,
+	/// Import speed since the previous status, such as `5.4 bps`. Empty for the first status.
+	pub speed: String,
+	/// Number of transactions ready in the transaction pool.
+	pub ready_transactions: usize,
+}
+
+impl<'a, B: BlockT> InformantStatus<'a, B> {
+	/// Number of blocks between the best block and the finalized block.
+	pub fn finality_lag(&self) -> NumberFor<B> {
+		self.info.chain.best_number.saturating_sub(self.info.chain.finalized_number)
+	}
+}
+
+/// Formats the status line printed regularly by the informant.
+///
+/// Implement it to add fields to the status line or to print it in another format.
+pub trait InformantFormat<B: BlockT> {
+	/// The line printed for `status`.
+	fn format(&self, status: &InformantStatus<B>) -> String;
+}
+
+impl<B: BlockT> InformantFormat<B> for OutputFormat {
+	fn format(&self, status: &InformantStatus<B>) -> String {
+		let info = status.info;
+		let net_status = status.net_status;
+		let speed = if status.speed.is_empty() { String::new() } else { format!(" {}", status.speed) };
+		let (sync_status, target) = match (&net_status.sync_state, net_status.best_seen_block) {
+			(SyncState::Idle, _) => ("Idle".into(), "".into()),
+			(SyncState::Downloading, None) => (format!("Syncing{}", speed), "".into()),
+			(SyncState::Downloading, Some(n)) => (format!("Syncing{}", speed), format!(", target=#{}", n)),
+		};
+
+		match self {
+			OutputFormat::Coloured => format!(
+				"{}{} ({} peers), best: #{} ({}), finalized #{} ({}), ⬇ {} ⬆ {}",
+				Colour::White.bold().paint(&sync_status),
+				target,
+				Colour::White.bold().paint(format!("{}", net_status.num_connected_peers)),
+				Colour::White.paint(format!("{}", info.chain.best_number)),
+				info.chain.best_hash,
+				Colour::White.paint(format!("{}", info.chain.finalized_number)),
+				info.chain.finalized_hash,
+				TransferRateFormat(net_status.average_download_per_sec),
+				TransferRateFormat(net_status.average_upload_per_sec),
+			),
+			OutputFormat::Plain => format!(
+				"{}{} ({} peers), best: #{} ({}), finalized #{} ({}), ⬇ {} ⬆ {}",
+				sync_status,
+				target,
+				net_status.num_connected_peers,
+				info.chain.best_number,
+				info.chain.best_hash,
+				info.chain.finalized_number,
+				info.chain.finalized_hash,
+				TransferRateFormat(net_status.average_download_per_sec),
+				TransferRateFormat(net_status.average_upload_per_sec),
+			),
+			OutputFormat::Json => serde_json::json!({
+				"syncing": net_status.sync_state == SyncState::Downloading,
+				"speed": status.speed,
+				"target": net_status.best_seen_block.map(|n| n.to_string()),
+				"peers": net_status.num_connected_peers,
+				"best_number": info.chain.best_number.to_string(),
+				"best_hash": format!("{:?}", info.chain.best_hash),
+				"finalized_number": info.chain.finalized_number.to_string(),
+				"finalized_hash": format!("{:?}", info.chain.finalized_hash),
+				"finality_lag": status.finality_lag().to_string(),
+				"ready_transactions": status.ready_transactions,
+				"download_per_sec": net_status.average_download_per_sec,
+				"upload_per_sec": net_status.average_upload_per_sec,
+			}).to_string(),
+		}
+	}
+}
+
 /// State of the informant display system.
 ///
 /// This is the system that handles the line that gets regularly printed and that looks something
@@ -37,19 +118,19 @@ use crate::OutputFormat;
 /// Call `InformantDisplay::new` to initialize the state, then regularly call `display` with the
 /// information to display.
 ///
-pub struct InformantDisplay<B: BlockT> {
+pub struct InformantDisplay<B: BlockT, F> {
 	/// Head of chain block number from the last time `display` has been called.
 	/// `None` if `display` has never been called.
 	last_number: Option<NumberFor<B>>,
 	/// The last time `display` or `new` has been called.
 	last_update: Instant,
 	/// The format to print output in.
-	format: OutputFormat,
+	format: F,
 }
 
-impl<B: BlockT> InformantDisplay<B> {
+impl<B: BlockT, F: InformantFormat<B>> InformantDisplay<B, F> {
 	/// Builds a new informant display system.
-	pub fn new(format: OutputFormat) -> InformantDisplay<B> {
+	pub fn new(format: F) -> InformantDisplay<B, F> {
 		InformantDisplay {
 			last_number: None,
 			last_update: Instant::now(),
@@ -58,50 +139,19 @@ impl<B: BlockT> InformantDisplay<B> {
 	}
 
 	/// Displays the informant by calling `info!`.
-	pub fn display(&mut self, info: &ClientInfo<B>, net_status: NetworkStatus<B>) {
+	pub fn display(&mut self, info: &ClientInfo<B>, net_status: NetworkStatus<B>, ready_transactions: usize) {
 		let best_number = info.chain.best_number;
-		let best_hash = info.chain.best_hash;
-		let finalized_number = info.chain.finalized_number;
-		let num_connected_peers = net_status.num_connected_peers;
 		let speed = speed::<B>(best_number, self.last_number, self.last_update);
 		self.last_update = Instant::now();
 		self.last_number = Some(best_number);
 
-		let (status, target) = match (net_status.sync_state, net_status.best_seen_block) {
-			(SyncState::Idle, _) => ("Idle".into(), "".into()),
-			(SyncState::Downloading, None) => (format!("Syncing{}", speed), "".into()),
-			(SyncState::Downloading, Some(n)) => (format!("Syncing{}", speed), format!(", target=#{}", n)),
+		let status = InformantStatus {
+			info,
+			net_status: &net_status,
+			speed: speed.trim().to_string(),
+			ready_transactions,
 		};
-
-		if self.format == OutputFormat::Coloured {
-			info!(
-				target: "substrate",
-				"{}{} ({} peers), best: #{} ({}), finalized #{} ({}), ⬇ {} ⬆ {}",
-				Colour::White.bold().paint(&status),
-				target,
-				Colour::White.bold().paint(format!("{}", num_connected_peers)),
-				Colour::White.paint(format!("{}", best_number)),
-				best_hash,
-				Colour::White.paint(format!("{}", finalized_number)),
-				info.chain.finalized_hash,
-				TransferRateFormat(net_status.average_download_per_sec),
-				TransferRateFormat(net_status.average_upload_per_sec),
-			);
-		} else {
-			info!(
-				target: "substrate",
-				"{}{} ({} peers), best: #{} ({}), finalized #{} ({}), ⬇ {} ⬆ {}",
-				status,
-				target,
-				num_connected_peers,
-				best_number,
-				best_hash,
-				finalized_number,
-				info.chain.finalized_hash,
-				TransferRateFormat(net_status.average_download_per_sec),
-				TransferRateFormat(net_status.average_upload_per_sec),
-			);
-		}
+		info!(target: "substrate", "{}", self.format.format(&status));
 	}
 }
 
@@ -20,20 +20,42 @@ use sc_client_api::BlockchainEvents;
 use futures::prelude::*;
 use log::{info, warn, trace};
 use sp_runtime::traits::Header;
-use sc_service::AbstractService;
+use sc_service::{AbstractService, TransactionPool};
 use std::time::Duration;
 
 mod display;
 
+pub use display::{InformantFormat, InformantStatus};
+
 /// The format to print telemetry output in.
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum OutputFormat {
 	Coloured,
 	Plain,
+	/// One JSON object per line, for log pipelines.
+	Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"coloured" => Ok(OutputFormat::Coloured),
+			"plain" => Ok(OutputFormat::Plain),
+			"json" => Ok(OutputFormat::Json),
+			_ => Err(format!("Unknown informant format `{}`, expected coloured, plain or json", s)),
+		}
+	}
 }
 
 /// Creates an informant in the form of a `Future` that must be polled regularly.
-pub fn build(service: &impl AbstractService, format: OutputFormat) -> impl futures::Future<Output = ()> {
+///
+/// The status of the node is printed regularly in `format`.
+pub fn build<S: AbstractService>(
+	service: &S,
+	format: impl InformantFormat<S::Block> + Send + 'static,
+) -> impl futures::Future<Output = ()> {
 	let client = service.client();
 	let pool = service.transaction_pool();
 
@@ -57,7 +79,7 @@ pub fn build(service: &impl AbstractService, format: OutputFormat) -> impl futur
 				"Subsystems memory [txpool: {} kB]",
 				parity_util_mem::malloc_size(&*pool) / 1024,
 			);
-			display.display(&info, net_status);
+			display.display(&info, net_status, pool.status().ready);
 			future::ready(())
 		});
 
@@ -15,7 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{Network, Validator};
-use crate::state_machine::{ConsensusGossip, TopicNotification, PERIODIC_MAINTENANCE_INTERVAL};
+use crate::state_machine::{ConsensusGossip, TopicLifetime, TopicNotification, PERIODIC_MAINTENANCE_INTERVAL};
 
 use sc_network::message::generic::ConsensusMessage;
 use sc_network::{Event, ReputationChange};
@@ -85,6 +85,21 @@ impl<B: BlockT> GossipEngine<B> {
 		self.state_machine.register_message(topic, message);
 	}
 
+	/// Sets how long the messages of a topic are kept and rebroadcast.
+	pub fn set_topic_lifetime(&mut self, topic: B::Hash, lifetime: TopicLifetime) {
+		self.state_machine.set_topic_lifetime(self.engine_id, topic, lifetime);
+	}
+
+	/// Reports the current round, expiring the topics whose lifetime ended with an earlier round.
+	pub fn note_round(&mut self, round: u64) {
+		self.state_machine.note_round(self.engine_id, round);
+	}
+
+	/// Drops the messages of a topic right away.
+	pub fn expire_topic(&mut self, topic: B::Hash) {
+		self.state_machine.expire_topic(self.engine_id, topic);
+	}
+
 	/// Broadcast all messages with given topic.
 	pub fn broadcast_topic(&mut self, topic: B::Hash, force: bool) {
 		self.state_machine.broadcast_topic(&mut *self.network, topic, force);
@@ -177,7 +192,10 @@ impl<B: BlockT> Future for GossipEngine<B> {
 								.collect()
 						);
 					},
-					Event::Dht(_) => {}
+					Event::Dht(_) |
+					Event::PeerConnected { .. } |
+					Event::PeerDisconnected { .. } |
+					Event::SyncStateChanged(_) => {}
 				}
 				// The network event stream closed. Do the same for [`GossipValidator`].
 				Poll::Ready(None) => return Poll::Ready(()),
@@ -64,6 +64,25 @@ pub struct TopicNotification {
 	pub sender: Option<PeerId>,
 }
 
+/// How long the messages of a topic are kept and rebroadcast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicLifetime {
+	/// The messages expire at the given instant.
+	Until(Instant),
+	/// The messages expire once the engine has moved past the given round, as reported with
+	/// `note_round`.
+	UntilRound(u64),
+}
+
+impl TopicLifetime {
+	fn is_expired(&self, current_round: Option<u64>, now: Instant) -> bool {
+		match *self {
+			TopicLifetime::Until(deadline) => now >= deadline,
+			TopicLifetime::UntilRound(round) => current_round.map_or(false, |current| current > round),
+		}
+	}
+}
+
 struct MessageEntry<B: BlockT> {
 	message_hash: B::Hash,
 	topic: B::Hash,
@@ -169,6 +188,9 @@ pub struct ConsensusGossip<B: BlockT> {
 	known_messages: LruCache<B::Hash, ()>,
 	validators: HashMap<ConsensusEngineId, Arc<dyn Validator<B>>>,
 	next_broadcast: Instant,
+	topic_lifetimes: HashMap<(ConsensusEngineId, B::Hash), TopicLifetime>,
+	/// Latest round reported by each engine.
+	rounds: HashMap<ConsensusEngineId, u64>,
 }
 
 impl<B: BlockT> ConsensusGossip<B> {
@@ -181,6 +203,8 @@ impl<B: BlockT> ConsensusGossip<B> {
 			known_messages: LruCache::new(KNOWN_MESSAGES_CACHE_SIZE),
 			validators: Default::default(),
 			next_broadcast: Instant::now() + REBROADCAST_INTERVAL,
+			topic_lifetimes: HashMap::new(),
+			rounds: HashMap::new(),
 		}
 	}
 
@@ -228,6 +252,11 @@ impl<B: BlockT> ConsensusGossip<B> {
 		message: ConsensusMessage,
 		sender: Option<PeerId>,
 	) {
+		if self.is_topic_expired(message.engine_id, topic) {
+			trace!(target: "gossip", "Not keeping message on expired topic {}", topic);
+			return;
+		}
+
 		if self.known_messages.put(message_hash.clone(), ()).is_none() {
 			self.messages.push(MessageEntry {
 				message_hash,
@@ -252,6 +281,38 @@ impl<B: BlockT> ConsensusGossip<B> {
 		self.register_message_hashed(message_hash, topic, message, None);
 	}
 
+	/// Sets the lifetime of the messages of a topic.
+	///
+	/// Once it has expired, the messages of the topic are no longer kept nor rebroadcast. The
+	/// lifetime itself is forgotten on the next garbage collection.
+	pub fn set_topic_lifetime(
+		&mut self,
+		engine_id: ConsensusEngineId,
+		topic: B::Hash,
+		lifetime: TopicLifetime,
+	) {
+		self.topic_lifetimes.insert((engine_id, topic), lifetime);
+	}
+
+	/// Reports the current round of an engine, expiring the topics whose lifetime ended with an
+	/// earlier round. Rounds never go backwards.
+	pub fn note_round(&mut self, engine_id: ConsensusEngineId, round: u64) {
+		let current = self.rounds.entry(engine_id).or_insert(round);
+		*current = std::cmp::max(*current, round);
+	}
+
+	/// Drops the messages of a topic right away.
+	pub fn expire_topic(&mut self, engine_id: ConsensusEngineId, topic: B::Hash) {
+		self.topic_lifetimes.remove(&(engine_id, topic));
+		self.messages.retain(|entry| entry.topic != topic || entry.message.engine_id != engine_id);
+	}
+
+	fn is_topic_expired(&self, engine_id: ConsensusEngineId, topic: B::Hash) -> bool {
+		self.topic_lifetimes.get(&(engine_id, topic)).map_or(false, |lifetime|
+			lifetime.is_expired(self.rounds.get(&engine_id).cloned(), Instant::now())
+		)
+	}
+
 	/// Call when a peer has been disconnected to stop tracking gossip status.
 	pub fn peer_disconnected(&mut self, network: &mut dyn Network<B>, who: PeerId) {
 		for (engine_id, v) in self.validators.clone() {
@@ -313,7 +374,20 @@ impl<B: BlockT> ConsensusGossip<B> {
 			(check_fn)(entry.topic, &entry.message.data)
 		};
 
-		self.messages.retain(|entry| !message_expired(entry));
+		let now = Instant::now();
+		let rounds = &self.rounds;
+		let topic_lifetimes = &mut self.topic_lifetimes;
+		let topic_expired = |entry: &MessageEntry<B>| {
+			let engine_id = entry.message.engine_id;
+			topic_lifetimes.get(&(engine_id, entry.topic))
+				.map_or(false, |lifetime| lifetime.is_expired(rounds.get(&engine_id).cloned(), now))
+		};
+
+		self.messages.retain(|entry| !topic_expired(entry) && !message_expired(entry));
+		// The messages of the expired topics are gone.
+		topic_lifetimes.retain(|(engine_id, _), lifetime|
+			!lifetime.is_expired(rounds.get(engine_id).cloned(), now)
+		);
 
 		trace!(target: "gossip", "Cleaned up {} stale messages, {} left ({} known)",
 			before - self.messages.len(),
@@ -696,4 +770,68 @@ mod tests {
 		consensus.peer_disconnected(&mut network, peer_id.clone());
 		assert!(!consensus.peers.contains_key(&peer_id));
 	}
+
+	#[test]
+	fn topics_expire_with_their_lifetime() {
+		let engine_id = [0, 0, 0, 0];
+		let (past, future) = (H256::random(), H256::random());
+		let message = |data: u8| ConsensusMessage { data: vec![data], engine_id };
+
+		let mut consensus = ConsensusGossip::<Block>::new();
+		consensus.register_validator_internal(engine_id, Arc::new(AllowAll));
+		consensus.register_message(past, message(1));
+		consensus.register_message(future, message(2));
+
+		let now = Instant::now();
+		let in_an_hour = now + time::Duration::from_secs(3600);
+		consensus.set_topic_lifetime(engine_id, past, TopicLifetime::Until(now));
+		consensus.set_topic_lifetime(engine_id, future, TopicLifetime::Until(in_an_hour));
+
+		consensus.collect_garbage();
+		assert_eq!(consensus.messages.len(), 1);
+		assert_eq!(consensus.messages[0].topic, future);
+		assert_eq!(consensus.topic_lifetimes.len(), 1);
+	}
+
+	#[test]
+	fn topics_expire_after_their_round() {
+		let engine_id = [0, 0, 0, 0];
+		let topic = H256::random();
+		let message = |data: u8| ConsensusMessage { data: vec![data], engine_id };
+
+		let mut consensus = ConsensusGossip::<Block>::new();
+		consensus.register_validator_internal(engine_id, Arc::new(AllowAll));
+		consensus.set_topic_lifetime(engine_id, topic, TopicLifetime::UntilRound(3));
+		consensus.register_message(topic, message(1));
+
+		consensus.note_round(engine_id, 3);
+		consensus.collect_garbage();
+		assert_eq!(consensus.messages.len(), 1);
+
+		// Rounds don't go backwards.
+		consensus.note_round(engine_id, 4);
+		consensus.note_round(engine_id, 2);
+
+		// Messages on the expired topic are no longer kept.
+		consensus.register_message(topic, message(2));
+		assert_eq!(consensus.messages.len(), 1);
+
+		consensus.collect_garbage();
+		assert!(consensus.messages.is_empty());
+		assert!(consensus.topic_lifetimes.is_empty());
+	}
+
+	#[test]
+	fn expired_topics_are_dropped_right_away() {
+		let engine_id = [0, 0, 0, 0];
+		let (expired, other) = (H256::random(), H256::random());
+
+		let mut consensus = ConsensusGossip::<Block>::new();
+		consensus.register_message(expired, ConsensusMessage { data: vec![1], engine_id });
+		consensus.register_message(other, ConsensusMessage { data: vec![2], engine_id });
+
+		consensus.expire_topic(engine_id, expired);
+		assert_eq!(consensus.messages.len(), 1);
+		assert_eq!(consensus.messages[0].topic, other);
+	}
 }
@@ -26,7 +26,7 @@ use libp2p::swarm::{NetworkBehaviourAction, NetworkBehaviourEventProcess, PollPa
 use log::debug;
 use sp_consensus::{BlockOrigin, import_queue::{IncomingBlock, Origin}};
 use sp_runtime::{traits::{Block as BlockT, NumberFor}, Justification};
-use std::{iter, task::Context, task::Poll};
+use std::{collections::HashSet, iter, task::Context, task::Poll};
 use void;
 
 /// General behaviour of the network. Combines all protocols together.
@@ -66,6 +66,7 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 		user_agent: String,
 		local_public_key: PublicKey,
 		known_addresses: Vec<(PeerId, Multiaddr)>,
+		private_peers: HashSet<PeerId>,
 		enable_mdns: bool,
 		allow_private_ipv4: bool,
 		discovery_only_if_under_num: u64,
@@ -78,6 +79,7 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 			discovery: DiscoveryBehaviour::new(
 				local_public_key,
 				known_addresses,
+				private_peers,
 				enable_mdns,
 				allow_private_ipv4,
 				discovery_only_if_under_num,
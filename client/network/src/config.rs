@@ -22,23 +22,24 @@
 pub use crate::chain::{Client, FinalityProofProvider};
 pub use crate::on_demand_layer::OnDemand;
 pub use crate::service::{TransactionPool, EmptyTransactionPool};
-pub use libp2p::{identity, core::PublicKey, wasm_ext::ExtTransport, build_multiaddr};
+pub use libp2p::{identity, core::PublicKey, wasm_ext::ExtTransport, build_multiaddr, pnet::PreSharedKey};
 
 // Note: this re-export shouldn't be part of the public API of the crate and will be removed in
 // the future.
 #[doc(hidden)]
 pub use crate::protocol::ProtocolConfig;
+pub use sc_peerset::ReputationConfig;
 
 use crate::service::ExHashT;
 
 use bitflags::bitflags;
 use sp_consensus::{block_validation::BlockAnnounceValidator, import_queue::ImportQueue};
-use sp_runtime::traits::{Block as BlockT};
+use sp_runtime::{traits::{Block as BlockT}, ConsensusEngineId};
 use libp2p::identity::{Keypair, ed25519};
 use libp2p::wasm_ext;
 use libp2p::{PeerId, Multiaddr, multiaddr};
 use core::{fmt, iter};
-use std::{future::Future, pin::Pin};
+use std::{borrow::Cow, future::Future, pin::Pin};
 use std::{error::Error, fs, io::{self, Write}, net::Ipv4Addr, path::{Path, PathBuf}, sync::Arc};
 use zeroize::Zeroize;
 use prometheus_endpoint::Registry;
@@ -266,6 +267,9 @@ pub struct NetworkConfiguration {
 	pub boot_nodes: Vec<String>,
 	/// The node key configuration, which determines the node's network identity keypair.
 	pub node_key: NodeKeyConfig,
+	/// Pre-shared key of a private network. If set, only the nodes holding the same key can
+	/// connect to each other.
+	pub pre_shared_key: Option<PreSharedKey>,
 	/// Maximum allowed number of incoming connections.
 	pub in_peers: u32,
 	/// Number of outgoing connections we're trying to maintain.
@@ -274,16 +278,28 @@ pub struct NetworkConfiguration {
 	pub reserved_nodes: Vec<String>,
 	/// The non-reserved peer mode.
 	pub non_reserved_mode: NonReservedPeerMode,
+	/// Parameters of the peer reputation system, such as the ban threshold.
+	pub reputation: ReputationConfig,
 	/// List of sentry node public addresses.
 	pub sentry_nodes: Vec<String>,
+	/// List of addresses of the validators this sentry node proxies for. They are always kept
+	/// connected, and their addresses are never shared with other nodes.
+	pub sentry_validators: Vec<String>,
 	/// Client identifier. Sent over the wire for debugging purposes.
 	pub client_version: String,
 	/// Name of the node. Sent over the wire for debugging purposes.
 	pub node_name: String,
 	/// Configuration for the transport layer.
 	pub transport: TransportConfig,
+	/// Caps on the bandwidth used by the node.
+	pub bandwidth_limits: BandwidthLimits,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// Maximum number of blocks to request from a peer at once. Peers don't return more than
+	/// 128 blocks per response.
+	pub max_blocks_per_request: u32,
+	/// How to synchronize the chain when the node starts with no state.
+	pub sync_mode: SyncMode,
 }
 
 impl Default for NetworkConfiguration {
@@ -295,11 +311,14 @@ impl Default for NetworkConfiguration {
 			public_addresses: Vec::new(),
 			boot_nodes: Vec::new(),
 			node_key: NodeKeyConfig::Ed25519(Secret::New),
+			pre_shared_key: None,
 			in_peers: 25,
 			out_peers: 75,
 			reserved_nodes: Vec::new(),
 			non_reserved_mode: NonReservedPeerMode::Accept,
+			reputation: Default::default(),
 			sentry_nodes: Vec::new(),
+			sentry_validators: Vec::new(),
 			client_version: "unknown".into(),
 			node_name: "unknown".into(),
 			transport: TransportConfig::Normal {
@@ -307,8 +326,12 @@ impl Default for NetworkConfiguration {
 				allow_private_ipv4: true,
 				wasm_external_transport: None,
 				use_yamux_flow_control: false,
+				enable_port_mapping: false,
 			},
+			bandwidth_limits: Default::default(),
 			max_parallel_downloads: 5,
+			max_blocks_per_request: 128,
+			sync_mode: SyncMode::Full,
 		}
 	}
 }
@@ -366,6 +389,11 @@ pub enum TransportConfig {
 		wasm_external_transport: Option<wasm_ext::ExtTransport>,
 		/// Use flow control for yamux streams if set to true.
 		use_yamux_flow_control: bool,
+
+		/// If true, the node maps its listening ports on the router with UPnP or NAT-PMP and
+		/// advertises the resulting external addresses, so that it can be dialed from behind a
+		/// NAT.
+		enable_port_mapping: bool,
 	},
 
 	/// Only allow connections within the same process.
@@ -373,6 +401,15 @@ pub enum TransportConfig {
 	MemoryOnly,
 }
 
+/// Caps on the bandwidth used by all the connections of the node together.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BandwidthLimits {
+	/// Maximum upload rate, in bytes per second. `None` for no limit.
+	pub upload: Option<u64>,
+	/// Maximum download rate, in bytes per second. `None` for no limit.
+	pub download: Option<u64>,
+}
+
 /// The policy for connections to non-reserved peers.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum NonReservedPeerMode {
@@ -393,6 +430,34 @@ impl NonReservedPeerMode {
 	}
 }
 
+/// The strategy used to synchronize the chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncMode {
+	/// Download and execute all the blocks from genesis. This is the default.
+	Full,
+	/// Download the headers, then the state of a recent justified block instead of executing
+	/// all the blocks before it. Only used when the node has no state beyond genesis.
+	///
+	/// The import queue must import blocks from their header, see
+	/// `ImportQueue::imports_headers_only`.
+	Fast,
+}
+
+/// Configuration of a notifications protocol registered with
+/// `NetworkService::notifications_protocol`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotificationsProtocolConfig {
+	/// Identifier of the protocol in network events.
+	pub engine_id: ConsensusEngineId,
+	/// Name of the protocol negotiated with peers, for example `/my-chain/bridge/1`.
+	pub protocol_name: Cow<'static, [u8]>,
+	/// Maximum size of a notification, in bytes. Larger incoming notifications close the
+	/// substream, and larger outgoing ones are refused.
+	pub max_notification_size: usize,
+	/// Message sent to peers when opening a substream with them.
+	pub handshake: Vec<u8>,
+}
+
 /// The configuration of a node's secret key, describing the type of key
 /// and how it is obtained. A node's identity keypair is the result of
 /// the evaluation of the node key configuration.
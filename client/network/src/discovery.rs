@@ -58,7 +58,7 @@ use libp2p::{swarm::toggle::Toggle};
 use libp2p::mdns::{Mdns, MdnsEvent};
 use libp2p::multiaddr::Protocol;
 use log::{debug, info, trace, warn, error};
-use std::{cmp, collections::VecDeque, time::Duration};
+use std::{cmp, collections::{HashSet, VecDeque}, time::Duration};
 use std::task::{Context, Poll};
 use sp_core::hexdisplay::HexDisplay;
 
@@ -67,6 +67,9 @@ pub struct DiscoveryBehaviour {
 	/// User-defined list of nodes and their addresses. Typically includes bootstrap nodes and
 	/// reserved nodes.
 	user_defined: Vec<(PeerId, Multiaddr)>,
+	/// Nodes whose addresses must never be shared with other nodes, such as the validators a
+	/// sentry node proxies for. They are kept out of the Kademlia routing table.
+	private_peers: HashSet<PeerId>,
 	/// Kademlia requests and answers.
 	kademlia: Kademlia<MemoryStore>,
 	/// Discovers nodes on the local network.
@@ -92,10 +95,12 @@ pub struct DiscoveryBehaviour {
 impl DiscoveryBehaviour {
 	/// Builds a new `DiscoveryBehaviour`.
 	///
-	/// `user_defined` is a list of known address for nodes that never expire.
+	/// `user_defined` is a list of known address for nodes that never expire. The addresses of
+	/// `private_peers` are used to reach them, but never handed out to other nodes.
 	pub async fn new(
 		local_public_key: PublicKey,
 		user_defined: Vec<(PeerId, Multiaddr)>,
+		private_peers: HashSet<PeerId>,
 		enable_mdns: bool,
 		allow_private_ipv4: bool,
 		discovery_only_if_under_num: u64,
@@ -109,11 +114,14 @@ impl DiscoveryBehaviour {
 		let store = MemoryStore::new(local_id.clone());
 		let mut kademlia = Kademlia::new(local_id.clone(), store);
 		for (peer_id, addr) in &user_defined {
-			kademlia.add_address(peer_id, addr.clone());
+			if !private_peers.contains(peer_id) {
+				kademlia.add_address(peer_id, addr.clone());
+			}
 		}
 
 		DiscoveryBehaviour {
 			user_defined,
+			private_peers,
 			kademlia,
 			next_kad_random_query: Delay::new(Duration::new(0, 0)),
 			duration_to_next_kad: Duration::from_secs(1),
@@ -159,9 +167,24 @@ impl DiscoveryBehaviour {
 	/// **Note**: It is important that you call this method, otherwise the discovery mechanism will
 	/// not properly work.
 	pub fn add_self_reported_address(&mut self, peer_id: &PeerId, addr: Multiaddr) {
+		if self.private_peers.contains(peer_id) {
+			trace!(target: "sub-libp2p", "Not adding address of private peer {:?} to the DHT", peer_id);
+			return;
+		}
+
 		self.kademlia.add_address(peer_id, addr);
 	}
 
+	/// Kademlia inserts the nodes we dial in its routing table. Private peers are presented to it
+	/// as if they had dialed us, so that their address doesn't end up there.
+	fn hide_private_dialer(&self, peer_id: &PeerId, endpoint: ConnectedPoint) -> ConnectedPoint {
+		match endpoint {
+			ConnectedPoint::Dialer { address } if self.private_peers.contains(peer_id) =>
+				ConnectedPoint::Listener { local_addr: address.clone(), send_back_addr: address },
+			endpoint => endpoint,
+		}
+	}
+
 	/// Start fetching a record from the DHT.
 	///
 	/// A corresponding `ValueFound` or `ValueNotFound` event will later be generated.
@@ -261,15 +284,19 @@ impl NetworkBehaviour for DiscoveryBehaviour {
 
 	fn inject_connected(&mut self, peer_id: PeerId, endpoint: ConnectedPoint) {
 		self.num_connections += 1;
+		let endpoint = self.hide_private_dialer(&peer_id, endpoint);
 		NetworkBehaviour::inject_connected(&mut self.kademlia, peer_id, endpoint)
 	}
 
 	fn inject_disconnected(&mut self, peer_id: &PeerId, endpoint: ConnectedPoint) {
 		self.num_connections -= 1;
+		let endpoint = self.hide_private_dialer(peer_id, endpoint);
 		NetworkBehaviour::inject_disconnected(&mut self.kademlia, peer_id, endpoint)
 	}
 
 	fn inject_replaced(&mut self, peer_id: PeerId, closed: ConnectedPoint, opened: ConnectedPoint) {
+		let closed = self.hide_private_dialer(&peer_id, closed);
+		let opened = self.hide_private_dialer(&peer_id, opened);
 		NetworkBehaviour::inject_replaced(&mut self.kademlia, peer_id, closed, opened)
 	}
 
@@ -503,7 +530,7 @@ mod tests {
 	use libp2p::core::upgrade;
 	use libp2p::core::transport::{Transport, MemoryTransport};
 	use libp2p::core::upgrade::{InboundUpgradeExt, OutboundUpgradeExt};
-	use libp2p::swarm::Swarm;
+	use libp2p::swarm::{NetworkBehaviour, Swarm};
 	use std::{collections::HashSet, task::Poll};
 	use super::{DiscoveryBehaviour, DiscoveryOut};
 
@@ -538,7 +565,8 @@ mod tests {
 				let user_defined = user_defined.clone();
 				let keypair_public = keypair.public();
 				async move {
-					DiscoveryBehaviour::new(keypair_public, user_defined, false, true, 50).await
+					DiscoveryBehaviour::new(keypair_public, user_defined, HashSet::new(), false, true, 50)
+						.await
 				}
 			});
 			let mut swarm = Swarm::new(transport, behaviour, keypair.public().into_peer_id());
@@ -598,4 +626,29 @@ mod tests {
 
 		futures::executor::block_on(fut);
 	}
+
+	#[test]
+	fn private_peers_are_kept_out_of_the_dht() {
+		let local = Keypair::generate_ed25519().public();
+		let public_peer = Keypair::generate_ed25519().public().into_peer_id();
+		let private_peer = Keypair::generate_ed25519().public().into_peer_id();
+		let addr = |port: u16| format!("/ip4/1.2.3.4/tcp/{}", port).parse::<Multiaddr>().unwrap();
+
+		let mut behaviour = futures::executor::block_on(DiscoveryBehaviour::new(
+			local,
+			vec![(public_peer.clone(), addr(1)), (private_peer.clone(), addr(2))],
+			vec![private_peer.clone()].into_iter().collect(),
+			false,
+			true,
+			50,
+		));
+		behaviour.add_self_reported_address(&private_peer, addr(3));
+
+		let known_peers = behaviour.known_peers().cloned().collect::<Vec<_>>();
+		assert_eq!(known_peers, vec![public_peer]);
+
+		// The private peer can still be reached.
+		let private_addresses = NetworkBehaviour::addresses_of_peer(&mut behaviour, &private_peer);
+		assert_eq!(private_addresses, vec![addr(2)]);
+	}
 }
@@ -46,7 +46,10 @@ pub enum Error {
 		second_id: PeerId,
 	},
 	/// Prometheus metrics error.
-	Prometheus(prometheus_endpoint::PrometheusError)
+	Prometheus(prometheus_endpoint::PrometheusError),
+	/// Fast sync is configured, but the import queue can't import blocks from their header.
+	#[display(fmt = "Fast sync is not supported by the consensus engine")]
+	FastSyncNotSupported,
 }
 
 // Make `Debug` use the `Display` implementation.
@@ -63,6 +66,7 @@ impl std::error::Error for Error {
 			Error::Client(ref err) => Some(err),
 			Error::DuplicateBootnode { .. } => None,
 			Error::Prometheus(ref err) => Some(err),
+			Error::FastSyncNotSupported => None,
 		}
 	}
 }
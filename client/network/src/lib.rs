@@ -166,6 +166,7 @@ mod chain;
 mod debug_info;
 mod discovery;
 mod on_demand_layer;
+mod port_mapping;
 mod protocol;
 mod service;
 mod transport;
@@ -175,7 +176,10 @@ pub mod config;
 pub mod error;
 pub mod network_state;
 
-pub use service::{NetworkService, NetworkStateInfo, NetworkWorker, ExHashT, ReportHandle};
+pub use service::{
+	NetworkService, NetworkStateInfo, NetworkWorker, ExHashT, ReportHandle,
+	NotificationError, NotificationEvent, NotificationsReceiver, NotificationsSender,
+};
 pub use protocol::PeerInfo;
 pub use protocol::event::{Event, DhtEvent};
 pub use protocol::sync::SyncState;
@@ -190,7 +194,7 @@ pub use protocol::message;
 #[doc(hidden)]
 pub use protocol::message::Status as StatusMessage;
 
-pub use sc_peerset::ReputationChange;
+pub use sc_peerset::{PeerReputation, ReputationChange, ReputationConfig};
 
 /// Extension trait for `NetworkBehaviour` that also accepts discovering nodes.
 trait DiscoveryNetBehaviour {
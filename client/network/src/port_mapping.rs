@@ -0,0 +1,480 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Maps the listening ports of the node on the router with UPnP, or NAT-PMP if no UPnP router
+//! answers, so that nodes behind a NAT can be dialed by others.
+//!
+//! The router is searched for and the ports are mapped from a background thread, as the
+//! requests are blocking. The external addresses of the mapped ports are sent to the network
+//! worker, which advertises them. The thread removes the mappings and stops as soon as the
+//! worker drops its `PortMapping` handle.
+
+use futures::{prelude::*, channel::{mpsc, oneshot}};
+use libp2p::{Multiaddr, multiaddr::Protocol};
+use std::{net::Ipv4Addr, pin::Pin, task::{Context, Poll}};
+
+/// Handle to the port mapping thread, yielding the external addresses of the mapped ports.
+///
+/// Dropping the handle stops the thread, without waiting for the next renewal.
+pub struct PortMapping {
+	addresses: mpsc::UnboundedReceiver<Multiaddr>,
+	_stop: oneshot::Sender<()>,
+}
+
+impl Stream for PortMapping {
+	type Item = Multiaddr;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Multiaddr>> {
+		self.addresses.poll_next_unpin(cx)
+	}
+}
+
+/// A listening TCP port to map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LocalPort {
+	/// The address the node listens on, `None` if it listens on all the interfaces.
+	ip: Option<Ipv4Addr>,
+	port: u16,
+}
+
+/// Returns the TCP ports of the IPv4 addresses in `listen_addresses` that need to be mapped.
+///
+/// Ports chosen by the operating system (port 0) are unknown in advance and can't be mapped.
+fn ports_to_map(listen_addresses: &[Multiaddr]) -> Vec<LocalPort> {
+	let mut ports: Vec<LocalPort> = Vec::new();
+	for addr in listen_addresses {
+		let mut iter = addr.iter();
+		match (iter.next(), iter.next()) {
+			(Some(Protocol::Ip4(ip)), Some(Protocol::Tcp(port)))
+				if port != 0 && (ip.is_unspecified() || ip.is_private()) &&
+					!ports.iter().any(|p| p.port == port) =>
+				ports.push(LocalPort {
+					ip: if ip.is_unspecified() { None } else { Some(ip) },
+					port,
+				}),
+			_ => {},
+		}
+	}
+	ports
+}
+
+/// Builds the external address of a mapped port.
+fn external_address(ip: Ipv4Addr, port: u16) -> Multiaddr {
+	let mut addr = Multiaddr::from(Protocol::Ip4(ip));
+	addr.push(Protocol::Tcp(port));
+	addr
+}
+
+/// Blocks until `interval` has elapsed or `stop` is cancelled. Returns `false` in the latter
+/// case.
+#[cfg(not(target_os = "unknown"))]
+fn wait_for_renewal(stop: &mut oneshot::Receiver<()>, interval: std::time::Duration) -> bool {
+	let renewal = futures_timer::Delay::new(interval);
+	match futures::executor::block_on(future::select(stop, renewal)) {
+		future::Either::Left(_) => false,
+		future::Either::Right(_) => true,
+	}
+}
+
+/// Starts mapping the TCP ports of `listen_addresses` on the router, and keeps renewing the
+/// mappings in a background thread.
+///
+/// The external addresses of the mapped ports are yielded by the returned handle. When it is
+/// dropped, the thread removes the mappings and stops. Returns `None` if there is nothing to
+/// map.
+#[cfg(not(target_os = "unknown"))]
+pub fn start(listen_addresses: &[Multiaddr]) -> Option<PortMapping> {
+	use log::{debug, warn};
+	use std::{collections::HashSet, thread, time::Duration};
+
+	/// Duration of the port mappings. They are renewed before they expire.
+	const LEASE_DURATION: Duration = Duration::from_secs(60 * 60);
+	/// Interval between two renewals of the mappings, or two searches of the router if none
+	/// was found.
+	const RENEW_INTERVAL: Duration = Duration::from_secs(20 * 60);
+
+	let ports = ports_to_map(listen_addresses);
+	if ports.is_empty() {
+		return None;
+	}
+
+	let (tx, rx) = mpsc::unbounded();
+	let (stop_tx, mut stop_rx) = oneshot::channel();
+	let spawned = thread::Builder::new().name("port-mapping".into()).spawn(move || {
+		let mut reported = HashSet::new();
+		let mut mapped: Option<mapping::Mappings> = None;
+		'renew: loop {
+			match mapping::map_ports(&ports, LEASE_DURATION) {
+				Ok((mappings, addresses)) => {
+					mapped = Some(mappings);
+					for addr in addresses {
+						if reported.insert(addr.clone()) && tx.unbounded_send(addr).is_err() {
+							break 'renew;
+						}
+					}
+				},
+				Err(err) => debug!(target: "sub-libp2p", "Port mapping failed: {}", err),
+			}
+			if !wait_for_renewal(&mut stop_rx, RENEW_INTERVAL) {
+				break;
+			}
+		}
+
+		// The router would forward the ports to the stopped node until the lease expires.
+		if let Some(mappings) = mapped {
+			mappings.remove();
+		}
+	});
+
+	if let Err(err) = spawned {
+		warn!(target: "sub-libp2p", "Failed to start the port mapping thread: {}", err);
+		return None;
+	}
+	Some(PortMapping { addresses: rx, _stop: stop_tx })
+}
+
+/// Port mapping isn't available in the browser.
+#[cfg(target_os = "unknown")]
+pub fn start(_: &[Multiaddr]) -> Option<PortMapping> {
+	None
+}
+
+/// Parses the default gateway out of the content of `/proc/net/route`.
+///
+/// The gateway of the route to the `00000000` destination is written as the hexadecimal value
+/// of the address in memory, i.e. in network byte order.
+#[cfg(any(target_os = "linux", test))]
+fn default_gateway_from_routes(routes: &str) -> Option<Ipv4Addr> {
+	routes.lines().skip(1).find_map(|line| {
+		let mut fields = line.split_whitespace().skip(1);
+		if fields.next()? != "00000000" {
+			return None;
+		}
+		let gateway = u32::from_str_radix(fields.next()?, 16).ok()?;
+		Some(Ipv4Addr::from(gateway.to_ne_bytes()))
+	})
+}
+
+#[cfg(not(target_os = "unknown"))]
+mod mapping {
+	use super::{LocalPort, external_address};
+	use libp2p::Multiaddr;
+	use log::debug;
+	use std::{net::{IpAddr, Ipv4Addr, SocketAddrV4, UdpSocket}, time::Duration};
+
+	/// Description of the mappings, shown in the router interface.
+	const DESCRIPTION: &str = "substrate";
+
+	/// The ports mapped on a router.
+	pub enum Mappings {
+		/// Ports mapped with UPnP, to the same external ports.
+		Igd(igd::Gateway, Vec<u16>),
+		/// Ports mapped with NAT-PMP, with the local port they were mapped from.
+		NatPmp(SocketAddrV4, Vec<LocalPort>),
+	}
+
+	impl Mappings {
+		/// Removes all the mappings from the router.
+		pub fn remove(self) {
+			match self {
+				Mappings::Igd(gateway, ports) => for port in ports {
+					if let Err(err) = gateway.remove_port(igd::PortMappingProtocol::TCP, port) {
+						debug!(target: "sub-libp2p", "Failed to unmap port {}: {}", port, err);
+					}
+				},
+				Mappings::NatPmp(gateway, ports) => for port in ports {
+					if let Err(err) = nat_pmp::map_tcp(gateway, port, 0) {
+						debug!(target: "sub-libp2p", "Failed to unmap port {}: {}", port.port, err);
+					}
+				},
+			}
+		}
+	}
+
+	/// Searches for the router and maps `ports` on it for `lease`, with UPnP or else NAT-PMP.
+	/// Returns the mappings and the external addresses of the ports that were mapped.
+	pub fn map_ports(
+		ports: &[LocalPort],
+		lease: Duration,
+	) -> Result<(Mappings, Vec<Multiaddr>), String> {
+		let err = match igd::search_gateway(Default::default()) {
+			Ok(gateway) => return map_igd(gateway, ports, lease),
+			Err(err) => err,
+		};
+		debug!(target: "sub-libp2p", "No UPnP router found ({}), trying NAT-PMP", err);
+
+		let gateway = SocketAddrV4::new(default_gateway()?, nat_pmp::PORT);
+		map_nat_pmp(gateway, ports, lease)
+	}
+
+	fn map_igd(
+		gateway: igd::Gateway,
+		ports: &[LocalPort],
+		lease: Duration,
+	) -> Result<(Mappings, Vec<Multiaddr>), String> {
+		let external_ip = gateway.get_external_ip().map_err(|e| e.to_string())?;
+		check_external_ip(external_ip)?;
+
+		// The local address on the network of the router, for the ports listening on all the
+		// interfaces.
+		let route_ip = local_ip_towards(*gateway.addr.ip())?;
+
+		let mut mapped = Vec::new();
+		let mut addresses = Vec::new();
+		for port in ports {
+			let result = gateway.add_port(
+				igd::PortMappingProtocol::TCP,
+				port.port,
+				SocketAddrV4::new(port.ip.unwrap_or(route_ip), port.port),
+				lease.as_secs() as u32,
+				DESCRIPTION,
+			);
+			match result {
+				Ok(()) => {
+					mapped.push(port.port);
+					addresses.push(external_address(external_ip, port.port));
+				},
+				Err(err) => debug!(target: "sub-libp2p", "Failed to map port {}: {}", port.port, err),
+			}
+		}
+		Ok((Mappings::Igd(gateway, mapped), addresses))
+	}
+
+	fn map_nat_pmp(
+		gateway: SocketAddrV4,
+		ports: &[LocalPort],
+		lease: Duration,
+	) -> Result<(Mappings, Vec<Multiaddr>), String> {
+		let external_ip = nat_pmp::external_ip(gateway)?;
+		check_external_ip(external_ip)?;
+
+		let mut mapped = Vec::new();
+		let mut addresses = Vec::new();
+		for &port in ports {
+			match nat_pmp::map_tcp(gateway, port, lease.as_secs() as u32) {
+				Ok(external_port) => {
+					mapped.push(port);
+					addresses.push(external_address(external_ip, external_port));
+				},
+				Err(err) => debug!(target: "sub-libp2p", "Failed to map port {}: {}", port.port, err),
+			}
+		}
+		Ok((Mappings::NatPmp(gateway, mapped), addresses))
+	}
+
+	fn check_external_ip(external_ip: Ipv4Addr) -> Result<(), String> {
+		if external_ip.is_private() || external_ip.is_unspecified() {
+			// Behind several NATs, mapping ports on the nearest router isn't enough.
+			return Err(format!("the router has no public address ({})", external_ip));
+		}
+		Ok(())
+	}
+
+	/// The local address used to reach `ip`.
+	fn local_ip_towards(ip: Ipv4Addr) -> Result<Ipv4Addr, String> {
+		// Connecting a UDP socket only selects the route, nothing is sent.
+		let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+		socket.connect((ip, 9)).map_err(|e| e.to_string())?;
+		match socket.local_addr().map_err(|e| e.to_string())?.ip() {
+			IpAddr::V4(ip) => Ok(ip),
+			IpAddr::V6(ip) => Err(format!("unexpected local address {}", ip)),
+		}
+	}
+
+	/// The default gateway, read from the routing table.
+	#[cfg(target_os = "linux")]
+	fn default_gateway() -> Result<Ipv4Addr, String> {
+		let routes = std::fs::read_to_string("/proc/net/route").map_err(|e| e.to_string())?;
+		super::default_gateway_from_routes(&routes).ok_or_else(|| "no default route".into())
+	}
+
+	/// The default gateway, assumed to be the first address of the local /24 network.
+	#[cfg(not(target_os = "linux"))]
+	fn default_gateway() -> Result<Ipv4Addr, String> {
+		// Any public address selects the default route.
+		let [a, b, c, _] = local_ip_towards(Ipv4Addr::new(1, 1, 1, 1))?.octets();
+		Ok(Ipv4Addr::new(a, b, c, 1))
+	}
+
+	/// Client side of NAT-PMP, see RFC 6886.
+	pub mod nat_pmp {
+		use super::LocalPort;
+		use std::{io, net::{Ipv4Addr, SocketAddrV4, UdpSocket}, time::Duration};
+
+		/// Port the NAT-PMP server of the router listens on.
+		pub const PORT: u16 = 5351;
+		/// Number of times a request is sent before giving up.
+		const ATTEMPTS: u32 = 4;
+		/// Time to wait for the response to the first request, doubled after each attempt.
+		const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+		/// Asks `gateway` for its external address.
+		pub fn external_ip(gateway: SocketAddrV4) -> Result<Ipv4Addr, String> {
+			let response = request(gateway, None, &[0, 0], 12)?;
+			Ok(Ipv4Addr::new(response[8], response[9], response[10], response[11]))
+		}
+
+		/// Maps `port` on `gateway` for `lifetime` seconds, or removes the mapping if it is
+		/// zero. Returns the external port, which may differ from the local one.
+		pub fn map_tcp(gateway: SocketAddrV4, port: LocalPort, lifetime: u32) -> Result<u16, String> {
+			// Removing a mapping requests the external port 0.
+			let external_port = if lifetime == 0 { 0 } else { port.port };
+			let mut packet = [0u8; 12];
+			packet[1] = 2;
+			packet[4..6].copy_from_slice(&port.port.to_be_bytes());
+			packet[6..8].copy_from_slice(&external_port.to_be_bytes());
+			packet[8..12].copy_from_slice(&lifetime.to_be_bytes());
+
+			// The router maps the port to the address the request is sent from.
+			let response = request(gateway, port.ip, &packet, 16)?;
+			Ok(u16::from_be_bytes([response[10], response[11]]))
+		}
+
+		/// Sends `packet` to `gateway` from `local_ip`, and returns the successful response to
+		/// it, which is at least `response_len` bytes long.
+		fn request(
+			gateway: SocketAddrV4,
+			local_ip: Option<Ipv4Addr>,
+			packet: &[u8],
+			response_len: usize,
+		) -> Result<Vec<u8>, String> {
+			let socket = UdpSocket::bind((local_ip.unwrap_or(Ipv4Addr::UNSPECIFIED), 0))
+				.map_err(|e| e.to_string())?;
+			socket.connect(gateway).map_err(|e| e.to_string())?;
+
+			let mut timeout = INITIAL_TIMEOUT;
+			let mut response = [0u8; 16];
+			for _ in 0..ATTEMPTS {
+				socket.send(packet).map_err(|e| e.to_string())?;
+				socket.set_read_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+				match socket.recv(&mut response) {
+					// The opcode of a response is the one of its request plus 128.
+					Ok(len) if len >= response_len && response[0] == 0 &&
+						response[1] == packet[1] + 128 =>
+					{
+						return match u16::from_be_bytes([response[2], response[3]]) {
+							0 => Ok(response[..len].to_vec()),
+							code => Err(format!("NAT-PMP request failed with result code {}", code)),
+						}
+					},
+					Ok(_) => {},
+					Err(ref err) if err.kind() == io::ErrorKind::WouldBlock ||
+						err.kind() == io::ErrorKind::TimedOut => {},
+					Err(err) => return Err(err.to_string()),
+				}
+				timeout *= 2;
+			}
+			Err(format!("no NAT-PMP response from {}", gateway))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::mapping::nat_pmp;
+	use std::{net::UdpSocket, thread, time::Duration};
+
+	#[test]
+	fn only_known_ipv4_tcp_ports_are_mapped() {
+		let addresses = [
+			"/ip4/0.0.0.0/tcp/30333",
+			"/ip4/192.168.1.10/tcp/30334",
+			"/ip4/0.0.0.0/tcp/30333/ws",
+			"/ip4/0.0.0.0/tcp/0",
+			"/ip4/127.0.0.1/tcp/30335",
+			"/ip4/1.2.3.4/tcp/30336",
+			"/ip6/::/tcp/30337",
+		].iter().map(|a| a.parse().unwrap()).collect::<Vec<Multiaddr>>();
+
+		assert_eq!(ports_to_map(&addresses), vec![
+			LocalPort { ip: None, port: 30333 },
+			LocalPort { ip: Some(Ipv4Addr::new(192, 168, 1, 10)), port: 30334 },
+		]);
+	}
+
+	#[test]
+	fn external_address_is_ip4_tcp() {
+		assert_eq!(
+			external_address(Ipv4Addr::new(1, 2, 3, 4), 30333),
+			"/ip4/1.2.3.4/tcp/30333".parse::<Multiaddr>().unwrap(),
+		);
+	}
+
+	#[test]
+	fn waiting_for_renewal_stops_when_the_handle_is_dropped() {
+		let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+		assert!(wait_for_renewal(&mut stop_rx, Duration::from_millis(10)));
+
+		drop(stop_tx);
+		let start = std::time::Instant::now();
+		assert!(!wait_for_renewal(&mut stop_rx, Duration::from_secs(60 * 60)));
+		assert!(start.elapsed() < Duration::from_secs(60));
+	}
+
+	#[test]
+	#[cfg(target_endian = "little")]
+	fn default_gateway_is_read_from_the_routes() {
+		let routes = "\
+			Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+			eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+			eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+
+		assert_eq!(default_gateway_from_routes(routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+		assert_eq!(default_gateway_from_routes(&routes[..routes.rfind("eth0").unwrap()]), None);
+	}
+
+	#[test]
+	fn nat_pmp_maps_and_removes_ports() {
+		let router = UdpSocket::bind("127.0.0.1:0").unwrap();
+		let gateway = match router.local_addr().unwrap() {
+			std::net::SocketAddr::V4(addr) => addr,
+			_ => unreachable!(),
+		};
+
+		// a router with the external address 1.2.3.4, mapping every port 1000 higher.
+		let server = thread::spawn(move || {
+			let mut requests = Vec::new();
+			let mut packet = [0u8; 12];
+			for _ in 0..3 {
+				let (len, from) = router.recv_from(&mut packet).unwrap();
+				let mut response = vec![0, packet[1] + 128, 0, 0, 0, 0, 0, 0];
+				if packet[1] == 0 {
+					response.extend_from_slice(&[1, 2, 3, 4]);
+				} else {
+					let port = u16::from_be_bytes([packet[4], packet[5]]);
+					response.extend_from_slice(&packet[4..6]);
+					response.extend_from_slice(&(port + 1000).to_be_bytes());
+					response.extend_from_slice(&packet[8..12]);
+				}
+				router.send_to(&response, from).unwrap();
+				requests.push(packet[..len].to_vec());
+			}
+			requests
+		});
+
+		let port = LocalPort { ip: Some(Ipv4Addr::LOCALHOST), port: 30333 };
+		assert_eq!(nat_pmp::external_ip(gateway), Ok(Ipv4Addr::new(1, 2, 3, 4)));
+		assert_eq!(nat_pmp::map_tcp(gateway, port, 3600), Ok(31333));
+		assert!(nat_pmp::map_tcp(gateway, port, 0).is_ok());
+
+		let requests = server.join().unwrap();
+		assert_eq!(requests[0], vec![0, 0]);
+		assert_eq!(requests[1], vec![0, 2, 0, 0, 0x76, 0x7d, 0x76, 0x7d, 0, 0, 0x0e, 0x10]);
+		// removing a mapping requests the external port 0 for 0 seconds.
+		assert_eq!(requests[2], vec![0, 2, 0, 0, 0x76, 0x7d, 0, 0, 0, 0, 0, 0]);
+	}
+}
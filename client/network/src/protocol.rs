@@ -38,10 +38,10 @@ use sp_arithmetic::traits::SaturatedConversion;
 use message::{BlockAnnounce, BlockAttributes, Direction, FromBlock, Message, RequestId};
 use message::generic::Message as GenericMessage;
 use light_dispatch::{LightDispatch, LightDispatchNetwork, RequestData};
-use prometheus_endpoint::{Registry, Gauge, GaugeVec, PrometheusError, Opts, register, U64};
+use prometheus_endpoint::{Registry, CounterVec, Gauge, GaugeVec, PrometheusError, Opts, register, U64};
 use sync::{ChainSync, SyncState};
 use crate::service::{TransactionPool, ExHashT};
-use crate::config::{BoxFinalityProofRequestBuilder, Roles};
+use crate::config::{BoxFinalityProofRequestBuilder, Roles, SyncMode};
 use rustc_hex::ToHex;
 use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -90,12 +90,17 @@ const MAX_KNOWN_BLOCKS: usize = 1024; // ~32kb per peer + LruHashSet overhead
 const MAX_KNOWN_EXTRINSICS: usize = 4096; // ~128kb per peer + overhead
 
 /// Current protocol version.
-pub(crate) const CURRENT_VERSION: u32 = 6;
+pub(crate) const CURRENT_VERSION: u32 = 7;
 /// Lowest version we support
 pub(crate) const MIN_VERSION: u32 = 3;
+/// Lowest version supporting state requests.
+const MIN_STATE_VERSION: u32 = 7;
 
 // Maximum allowed entries in `BlockResponse`
 const MAX_BLOCK_DATA_RESPONSE: u32 = 128;
+/// Size of the storage entries in a `StateResponse` after which the chunk is cut, in bytes,
+/// including child tries. The requesting side reads the chunk from the proof with the same limit.
+const MAX_STATE_RESPONSE_SIZE: usize = 1024 * 1024;
 /// When light node connects to the full node and the full node is behind light node
 /// for at least `LIGHT_MAXIMAL_BLOCKS_DIFFERENCE` blocks, we consider it not useful
 /// and disconnect to free connection slot.
@@ -144,6 +149,8 @@ struct Metrics {
 	fork_targets: Gauge<U64>,
 	finality_proofs: GaugeVec<U64>,
 	justifications: GaugeVec<U64>,
+	messages: CounterVec<U64>,
+	messages_bytes: CounterVec<U64>,
 }
 
 impl Metrics {
@@ -189,6 +196,26 @@ impl Metrics {
 				)?;
 				register(g, r)?
 			},
+			messages: {
+				let c = CounterVec::new(
+					Opts::new(
+						"sub_libp2p_legacy_messages_total",
+						"Number of messages on the legacy substream, per message type",
+					),
+					&["direction", "message"],
+				)?;
+				register(c, r)?
+			},
+			messages_bytes: {
+				let c = CounterVec::new(
+					Opts::new(
+						"sub_libp2p_legacy_messages_bytes_total",
+						"Number of bytes on the legacy substream, per message type",
+					),
+					&["direction", "message"],
+				)?;
+				register(c, r)?
+			},
 		})
 	}
 }
@@ -245,6 +272,8 @@ struct Peer<B: BlockT, H: ExHashT> {
 	info: PeerInfo<B>,
 	/// Current block request, if any.
 	block_request: Option<(Instant, message::BlockRequest<B>)>,
+	/// Id of the current state request, if any.
+	state_request: Option<(Instant, message::RequestId)>,
 	/// Requests we are no longer interested in.
 	obsolete_requests: HashMap<message::RequestId, Instant>,
 	/// Holds a set of transactions known to this peer.
@@ -409,6 +438,10 @@ pub struct ProtocolConfig {
 	pub roles: Roles,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// Maximum number of blocks to request from a peer at once.
+	pub max_blocks_per_request: u32,
+	/// Strategy used to synchronize the chain.
+	pub sync_mode: SyncMode,
 }
 
 impl Default for ProtocolConfig {
@@ -416,6 +449,8 @@ impl Default for ProtocolConfig {
 		ProtocolConfig {
 			roles: Roles::FULL,
 			max_parallel_downloads: 5,
+			max_blocks_per_request: MAX_BLOCK_DATA_RESPONSE,
+			sync_mode: SyncMode::Full,
 		}
 	}
 }
@@ -442,6 +477,8 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			finality_proof_request_builder,
 			block_announce_validator,
 			config.max_parallel_downloads,
+			config.max_blocks_per_request,
+			config.sync_mode,
 		);
 
 		let important_peers = {
@@ -522,6 +559,16 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		self.behaviour.peerset_debug_info()
 	}
 
+	/// Returns the peers reserved in the peerset manager.
+	pub fn reserved_peers(&self) -> HashSet<PeerId> {
+		self.behaviour.reserved_peers()
+	}
+
+	/// Returns the reputations of the peers known to the peerset manager.
+	pub fn reputations(&mut self) -> Vec<sc_peerset::PeerReputation> {
+		self.behaviour.reputations()
+	}
+
 	/// Returns the number of peers we're connected to.
 	pub fn num_connected_peers(&self) -> usize {
 		self.context_data.peers.values().count()
@@ -676,6 +723,10 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 				self.on_finality_proof_request(who, request),
 			GenericMessage::FinalityProofResponse(response) =>
 				return self.on_finality_proof_response(who, response),
+			GenericMessage::StateRequest(request) =>
+				self.on_state_request(who, request),
+			GenericMessage::StateResponse(response) =>
+				return self.on_state_response(who, response),
 			GenericMessage::RemoteReadChildRequest(request) =>
 				self.on_remote_read_child_request(who, request),
 			GenericMessage::Consensus(msg) =>
@@ -956,7 +1007,10 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		let mut aborting = Vec::new();
 		{
 			for (who, peer) in self.context_data.peers.iter() {
-				if peer.block_request.as_ref().map_or(false, |(t, _)| (tick - *t).as_secs() > REQUEST_TIMEOUT_SEC) {
+				let timed_out = |t: &Instant| (tick - *t).as_secs() > REQUEST_TIMEOUT_SEC;
+				if peer.block_request.as_ref().map_or(false, |(t, _)| timed_out(t))
+					|| peer.state_request.as_ref().map_or(false, |(t, _)| timed_out(t))
+				{
 					log!(
 						target: "sync",
 						if self.important_peers.contains(who) { Level::Warn } else { Level::Trace },
@@ -1070,6 +1124,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			let peer = Peer {
 				info,
 				block_request: None,
+				state_request: None,
 				known_extrinsics: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_EXTRINSICS)
 					.expect("Constant is nonzero")),
 				known_blocks: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_BLOCKS)
@@ -1130,18 +1185,28 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 
 	/// Registers a new notifications protocol.
 	///
+	/// `handshake` is sent to peers when opening a substream, and incoming notifications larger
+	/// than `max_notification_size` are refused.
+	///
 	/// You are very strongly encouraged to call this method very early on. Any connection open
 	/// will retain the protocols that were registered then, and not any new one.
 	pub fn register_notifications_protocol(
 		&mut self,
 		engine_id: ConsensusEngineId,
 		protocol_name: impl Into<Cow<'static, [u8]>>,
+		handshake: Vec<u8>,
+		max_notification_size: Option<usize>,
 	) -> Vec<event::Event> {
 		let protocol_name = protocol_name.into();
 		if self.protocol_name_by_engine.insert(engine_id, protocol_name.clone()).is_some() {
 			error!(target: "sub-libp2p", "Notifications protocol already registered: {:?}", protocol_name);
 		} else {
-			self.behaviour.register_notif_protocol(protocol_name.clone(), engine_id, Vec::new());
+			self.behaviour.register_notif_protocol(
+				protocol_name.clone(),
+				engine_id,
+				handshake,
+				max_notification_size,
+			);
 			self.protocol_engine_by_name.insert(protocol_name, engine_id);
 		}
 
@@ -1831,6 +1896,63 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		}
 	}
 
+	fn on_state_request(
+		&mut self,
+		who: PeerId,
+		request: message::StateRequest<B::Hash>,
+	) {
+		trace!(target: "sync", "State request from {} for {}", who, request.block);
+		let proof = match self.context_data.chain.state_chunk_proof(
+			&BlockId::Hash(request.block),
+			&request.start,
+			MAX_STATE_RESPONSE_SIZE,
+		) {
+			Ok(proof) => proof,
+			Err(error) => {
+				trace!(target: "sync", "State request from {} for {} failed with: {}",
+					who,
+					request.block,
+					error
+				);
+				StorageProof::empty()
+			},
+		};
+		self.send_message(
+			&who,
+			GenericMessage::StateResponse(message::StateResponse {
+				id: request.id,
+				block: request.block,
+				proof,
+			}),
+		);
+	}
+
+	fn on_state_response(
+		&mut self,
+		who: PeerId,
+		response: message::StateResponse<B::Hash>,
+	) -> CustomMessageOutcome<B> {
+		trace!(target: "sync", "State response from {} for {}", who, response.block);
+		if let Some(peer) = self.context_data.peers.get_mut(&who) {
+			if peer.state_request.take().map_or(true, |(_, id)| id != response.id) {
+				trace!(target: "sync", "Unexpected response packet from {} ({})", who, response.id);
+				self.peerset_handle.report_peer(who.clone(), rep::UNEXPECTED_RESPONSE);
+				self.behaviour.disconnect_peer(&who);
+				return CustomMessageOutcome::None
+			}
+		}
+		match self.sync.on_state_data(who, response) {
+			Ok(sync::OnStateData::Nothing) => CustomMessageOutcome::None,
+			Ok(sync::OnStateData::Import(origin, block)) =>
+				CustomMessageOutcome::BlockImport(origin, vec![block]),
+			Err(sync::BadPeer(id, repu)) => {
+				self.behaviour.disconnect_peer(&id);
+				self.peerset_handle.report_peer(id, repu);
+				CustomMessageOutcome::None
+			}
+		}
+	}
+
 	fn on_remote_body_response(
 		&mut self,
 		peer: PeerId,
@@ -1897,6 +2019,20 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 				.set(m.finality_proofs.failed_requests.into());
 			metrics.finality_proofs.with_label_values(&["importing"])
 				.set(m.finality_proofs.importing_requests.into());
+
+			// The statistics are cumulative, only the increase since the last report is added.
+			for (id, stats) in &self.context_data.stats {
+				let directions = [
+					("in", stats.count_in, stats.bytes_in),
+					("out", stats.count_out, stats.bytes_out),
+				];
+				for &(direction, count, bytes) in &directions {
+					let counter = metrics.messages.with_label_values(&[direction, id]);
+					counter.inc_by(count.saturating_sub(counter.get()));
+					let counter = metrics.messages_bytes.with_label_values(&[direction, id]);
+					counter.inc_by(bytes.saturating_sub(counter.get()));
+				}
+			}
 		}
 	}
 }
@@ -1934,6 +2070,13 @@ fn send_request<B: BlockT, H: ExHashT>(
 			peer.block_request = Some((Instant::now(), r.clone()));
 		}
 	}
+	if let GenericMessage::StateRequest(ref mut r) = message {
+		if let Some(ref mut peer) = peers.get_mut(who) {
+			r.id = peer.next_request_id;
+			peer.next_request_id = peer.next_request_id + 1;
+			peer.state_request = Some((Instant::now(), r.id));
+		}
+	}
 	send_message::<B>(behaviour, stats, who, message)
 }
 
@@ -2022,6 +2165,18 @@ impl<B: BlockT, H: ExHashT> NetworkBehaviour for Protocol<B, H> {
 				&id,
 				GenericMessage::FinalityProofRequest(r))
 		}
+		let peers = &self.context_data.peers;
+		let state_request = self.sync.state_request(|who| {
+			peers.get(who).map_or(false, |p| p.info.protocol_version >= MIN_STATE_VERSION)
+		});
+		if let Some((id, r)) = state_request {
+			send_request(
+				&mut self.behaviour,
+				&mut self.context_data.stats,
+				&mut self.context_data.peers,
+				&id,
+				GenericMessage::StateRequest(r))
+		}
 
 		let event = match self.behaviour.poll(cx, params) {
 			Poll::Pending => return Poll::Pending,
@@ -2134,6 +2289,8 @@ mod tests {
 			ProtocolConfig {
 				roles: Roles::FULL,
 				max_parallel_downloads: 10,
+				max_blocks_per_request: 128,
+				sync_mode: SyncMode::Full,
 			},
 			client.clone(),
 			Arc::new(AlwaysBadChecker),
@@ -2147,6 +2304,7 @@ mod tests {
 				bootnodes: Vec::new(),
 				reserved_only: false,
 				reserved_nodes: Vec::new(),
+				reputation: Default::default(),
 			},
 			Box::new(DefaultBlockAnnounceValidator::new(client.clone())),
 			None
@@ -17,6 +17,7 @@
 //! Network event types. These are are not the part of the protocol, but rather
 //! events that happen on the network like DHT get/put results received.
 
+use super::sync::SyncState;
 use crate::config::Roles;
 use bytes::Bytes;
 use libp2p::core::PeerId;
@@ -47,6 +48,22 @@ pub enum Event {
 	/// Event generated by a DHT.
 	Dht(DhtEvent),
 
+	/// Established a connection with the given node.
+	PeerConnected {
+		/// Node we are now connected to.
+		remote: PeerId,
+	},
+
+	/// Closed the connection with the given node. Always matches a corresponding previous
+	/// `PeerConnected` event.
+	PeerDisconnected {
+		/// Node we are no longer connected to.
+		remote: PeerId,
+	},
+
+	/// The node started (`Downloading`) or finished (`Idle`) a major sync.
+	SyncStateChanged(SyncState),
+
 	/// Opened a substream with the given node with the given notifications protocol.
 	///
 	/// The protocol is always one of the notification protocols that have been registered.
@@ -29,7 +29,7 @@ use log::{debug, error, trace, warn};
 use rand::distributions::{Distribution as _, Uniform};
 use smallvec::SmallVec;
 use sp_runtime::ConsensusEngineId;
-use std::{borrow::Cow, collections::hash_map::Entry, cmp};
+use std::{borrow::Cow, collections::{HashSet, hash_map::Entry}, cmp};
 use std::{error, mem, pin::Pin, str, time::Duration};
 use std::task::{Context, Poll};
 use wasm_timer::Instant;
@@ -84,7 +84,7 @@ pub struct GenericProto {
 	legacy_protocol: RegisteredProtocol,
 
 	/// Notification protocols. Entries are only ever added and not removed.
-	notif_protocols: Vec<(Cow<'static, [u8]>, ConsensusEngineId, Vec<u8>)>,
+	notif_protocols: Vec<(Cow<'static, [u8]>, ConsensusEngineId, Vec<u8>, Option<usize>)>,
 
 	/// Receiver for instructions about who to connect to or disconnect from.
 	peerset: sc_peerset::Peerset,
@@ -282,13 +282,17 @@ impl GenericProto {
 	///
 	/// You are very strongly encouraged to call this method very early on. Any open connection
 	/// will retain the protocols that were registered then, and not any new one.
+	///
+	/// Incoming notifications larger than `max_notification_size` close the substream. If `None`,
+	/// the default limit of the codec applies.
 	pub fn register_notif_protocol(
 		&mut self,
 		protocol_name: impl Into<Cow<'static, [u8]>>,
 		engine_id: ConsensusEngineId,
-		handshake_msg: impl Into<Vec<u8>>
+		handshake_msg: impl Into<Vec<u8>>,
+		max_notification_size: Option<usize>,
 	) {
-		self.notif_protocols.push((protocol_name.into(), engine_id, handshake_msg.into()));
+		self.notif_protocols.push((protocol_name.into(), engine_id, handshake_msg.into(), max_notification_size));
 	}
 
 	/// Returns the number of discovered nodes that we keep in memory.
@@ -463,6 +467,16 @@ impl GenericProto {
 		self.peerset.debug_info()
 	}
 
+	/// Returns the peers reserved in the peerset.
+	pub fn reserved_peers(&self) -> HashSet<PeerId> {
+		self.peerset.reserved_peers()
+	}
+
+	/// Returns the reputations of the peers known to the peerset.
+	pub fn reputations(&mut self) -> Vec<sc_peerset::PeerReputation> {
+		self.peerset.reputations()
+	}
+
 	/// Function that is called when the peerset wants us to connect to a node.
 	fn peerset_report_connect(&mut self, peer_id: PeerId) {
 		let mut occ_entry = match self.peers.entry(peer_id) {
@@ -83,6 +83,9 @@ pub struct NotifsHandlerProto {
 	/// Prototypes for handlers for outbound substreams.
 	out_handlers: Vec<(NotifsOutHandlerProto, ConsensusEngineId)>,
 
+	/// Handshake message of each notifications protocol, in the same order as the handlers.
+	handshakes: Vec<Vec<u8>>,
+
 	/// Prototype for handler for backwards-compatibility.
 	legacy: LegacyProtoHandlerProto,
 }
@@ -97,6 +100,9 @@ pub struct NotifsHandler {
 	/// Handlers for outbound substreams.
 	out_handlers: Vec<(NotifsOutHandler, ConsensusEngineId)>,
 
+	/// Handshake message of each notifications protocol, in the same order as the handlers.
+	handshakes: Vec<Vec<u8>>,
+
 	/// Handler for backwards-compatibility.
 	legacy: LegacyProtoHandler,
 
@@ -137,6 +143,7 @@ impl IntoProtocolsHandler for NotifsHandlerProto {
 				.into_iter()
 				.map(|(p, e)| (p.into_handler(remote_peer_id, connected_point), e))
 				.collect(),
+			handshakes: self.handshakes,
 			legacy: self.legacy.into_handler(remote_peer_id, connected_point),
 			enabled: EnabledState::Initial,
 			pending_in: Vec::new(),
@@ -238,12 +245,25 @@ pub enum NotifsHandlerOut {
 
 impl NotifsHandlerProto {
 	/// Builds a new handler.
-	pub fn new(legacy: RegisteredProtocol, list: impl Into<Vec<(Cow<'static, [u8]>, ConsensusEngineId, Vec<u8>)>>) -> Self {
+	///
+	/// Each entry of `list` contains the name of a notifications protocol, its engine id, the
+	/// handshake message to send, and the maximum size of incoming notifications.
+	pub fn new(
+		legacy: RegisteredProtocol,
+		list: impl Into<Vec<(Cow<'static, [u8]>, ConsensusEngineId, Vec<u8>, Option<usize>)>>,
+	) -> Self {
 		let list = list.into();
 
 		NotifsHandlerProto {
-			in_handlers: list.clone().into_iter().map(|(p, e, _)| (NotifsInHandlerProto::new(p), e)).collect(),
-			out_handlers: list.clone().into_iter().map(|(p, e, _)| (NotifsOutHandlerProto::new(p), e)).collect(),
+			in_handlers: list.clone().into_iter().map(|(p, e, _, max)| {
+				let mut proto = NotifsInHandlerProto::new(p);
+				if let Some(max) = max {
+					proto = proto.with_max_notification_size(max);
+				}
+				(proto, e)
+			}).collect(),
+			out_handlers: list.clone().into_iter().map(|(p, e, _, _)| (NotifsOutHandlerProto::new(p), e)).collect(),
+			handshakes: list.into_iter().map(|(_, _, h, _)| h).collect(),
 			legacy: LegacyProtoHandlerProto::new(legacy),
 		}
 	}
@@ -304,13 +324,14 @@ impl ProtocolsHandler for NotifsHandler {
 			NotifsHandlerIn::Enable => {
 				self.enabled = EnabledState::Enabled;
 				self.legacy.inject_event(LegacyProtoHandlerIn::Enable);
-				for (handler, _) in &mut self.out_handlers {
+				for (num, (handler, _)) in self.out_handlers.iter_mut().enumerate() {
 					handler.inject_event(NotifsOutHandlerIn::Enable {
-						initial_message: vec![]
+						initial_message: self.handshakes[num].clone(),
 					});
 				}
 				for num in self.pending_in.drain(..) {
-					self.in_handlers[num].0.inject_event(NotifsInHandlerIn::Accept(vec![]));
+					let handshake = self.handshakes[num].clone();
+					self.in_handlers[num].0.inject_event(NotifsInHandlerIn::Accept(handshake));
 				}
 			},
 			NotifsHandlerIn::Disable => {
@@ -441,8 +462,10 @@ impl ProtocolsHandler for NotifsHandler {
 					ProtocolsHandlerEvent::Custom(NotifsInHandlerOut::OpenRequest(_)) =>
 						match self.enabled {
 							EnabledState::Initial => self.pending_in.push(handler_num),
-							EnabledState::Enabled =>
-								handler.inject_event(NotifsInHandlerIn::Accept(vec![])),
+							EnabledState::Enabled => {
+								let handshake = self.handshakes[handler_num].clone();
+								handler.inject_event(NotifsInHandlerIn::Accept(handshake))
+							},
 							EnabledState::Disabled =>
 								handler.inject_event(NotifsInHandlerIn::Refuse),
 						},
@@ -114,6 +114,12 @@ impl NotifsInHandlerProto {
 			in_protocol: NotificationsIn::new(protocol_name),
 		}
 	}
+
+	/// Sets the maximum size of the notifications accepted on the substream.
+	pub fn with_max_notification_size(mut self, max: usize) -> Self {
+		self.in_protocol = self.in_protocol.with_max_notification_size(max);
+		self
+	}
 }
 
 impl IntoProtocolsHandler for NotifsInHandlerProto {
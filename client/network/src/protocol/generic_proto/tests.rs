@@ -79,6 +79,7 @@ fn build_nodes() -> (Swarm<CustomProtoWithAddr>, Swarm<CustomProtoWithAddr>) {
 			},
 			reserved_only: false,
 			reserved_nodes: Vec::new(),
+			reputation: Default::default(),
 		});
 
 		let behaviour = CustomProtoWithAddr {
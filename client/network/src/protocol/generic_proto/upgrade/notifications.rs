@@ -53,6 +53,8 @@ const MAX_PENDING_MESSAGES: usize = 256;
 pub struct NotificationsIn {
 	/// Protocol name to use when negotiating the substream.
 	protocol_name: Cow<'static, [u8]>,
+	/// Maximum allowed size of a notification, if different from the codec default.
+	max_notification_size: Option<usize>,
 }
 
 /// Upgrade that opens a substream, waits for the remote to accept by sending back a status
@@ -105,9 +107,17 @@ impl NotificationsIn {
 	pub fn new(protocol_name: impl Into<Cow<'static, [u8]>>) -> Self {
 		NotificationsIn {
 			protocol_name: protocol_name.into(),
+			max_notification_size: None,
 		}
 	}
 
+	/// Sets the maximum size of the notifications accepted on the substream. Larger
+	/// notifications produce an error on the substream.
+	pub fn with_max_notification_size(mut self, max: usize) -> Self {
+		self.max_notification_size = Some(max);
+		self
+	}
+
 	/// Returns the name of the protocol that we accept.
 	pub fn protocol_name(&self) -> &[u8] {
 		&self.protocol_name
@@ -149,8 +159,13 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 				socket.read(&mut initial_message).await?;
 			}
 
+			let mut codec = UviBytes::default();
+			if let Some(max) = self.max_notification_size {
+				codec.set_max_len(max);
+			}
+
 			let substream = NotificationsInSubstream {
-				socket: Framed::new(socket, UviBytes::default()),
+				socket: Framed::new(socket, codec),
 				handshake: NotificationsInSubstreamHandshake::NotSent,
 			};
 
@@ -1200,6 +1200,7 @@ mod tests {
 			bootnodes: Vec::new(),
 			reserved_only: false,
 			reserved_nodes: Vec::new(),
+			reputation: Default::default(),
 		};
 		sc_peerset::Peerset::from_config(cfg)
 	}
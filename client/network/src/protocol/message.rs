@@ -25,6 +25,7 @@ pub use self::generic::{
 	RemoteChangesRequest, RemoteChangesResponse,
 	FinalityProofRequest, FinalityProofResponse,
 	FromBlock, RemoteReadChildRequest,
+	StateRequest, StateResponse,
 };
 use sc_client_api::StorageProof;
 
@@ -219,6 +220,10 @@ pub mod generic {
 		FinalityProofResponse(FinalityProofResponse<Hash>),
 		/// Batch of consensus protocol messages.
 		ConsensusBatch(Vec<ConsensusMessage>),
+		/// State request.
+		StateRequest(StateRequest<Hash>),
+		/// State response.
+		StateResponse(StateResponse<Hash>),
 	}
 
 	impl<Header, Hash, Number, Extrinsic> Message<Header, Hash, Number, Extrinsic> {
@@ -243,6 +248,8 @@ pub mod generic {
 				Message::FinalityProofRequest(_) => "FinalityProofRequest",
 				Message::FinalityProofResponse(_) => "FinalityProofResponse",
 				Message::ConsensusBatch(_) => "ConsensusBatch",
+				Message::StateRequest(_) => "StateRequest",
+				Message::StateResponse(_) => "StateResponse",
 			}
 		}
 	}
@@ -506,4 +513,29 @@ pub mod generic {
 		/// Finality proof (if available).
 		pub proof: Option<Vec<u8>>,
 	}
+
+	#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+	/// Request for a chunk of the state of a block.
+	pub struct StateRequest<H> {
+		/// Unique request id.
+		pub id: RequestId,
+		/// Hash of the block to request the state of.
+		pub block: H,
+		/// Where the chunk starts, as returned by the previous chunk. Empty for the first request.
+		///
+		/// See `sp_state_machine::read_state_chunk` for the chunks format.
+		pub start: Vec<Vec<u8>>,
+	}
+
+	#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+	/// Chunk of the state of a block.
+	pub struct StateResponse<H> {
+		/// Id of a request this response was made for.
+		pub id: RequestId,
+		/// Hash of the block (the same as in the StateRequest).
+		pub block: H,
+		/// Proof of the chunk against the state root of the block. Empty if the state is not
+		/// available.
+		pub proof: StorageProof,
+	}
 }
@@ -34,32 +34,34 @@ use sp_consensus::{BlockOrigin, BlockStatus,
 	import_queue::{IncomingBlock, BlockImportResult, BlockImportError}
 };
 use crate::{
-	config::{Roles, BoxFinalityProofRequestBuilder},
+	config::{Roles, BoxFinalityProofRequestBuilder, SyncMode},
 	protocol::message::{self, generic::FinalityProofRequest, BlockAnnounce, BlockAttributes, BlockRequest, BlockResponse,
-	FinalityProofResponse},
+	FinalityProofResponse, StateRequest, StateResponse},
 };
+use sc_client_api::state_chunk_proof_check;
 use either::Either;
 use extra_requests::ExtraRequests;
 use libp2p::PeerId;
 use log::{debug, trace, warn, info, error};
+use sp_core::storage::{Storage, StorageChild, OwnedChildInfo, well_known_keys};
 use sp_runtime::{
 	Justification,
 	generic::BlockId,
-	traits::{Block as BlockT, Header, NumberFor, Zero, One, CheckedSub, SaturatedConversion}
+	traits::{Block as BlockT, Header, HashFor, NumberFor, Zero, One, CheckedSub, SaturatedConversion}
 };
-use std::{fmt, ops::Range, collections::{HashMap, HashSet, VecDeque}, sync::Arc};
+use std::{fmt, ops::Range, collections::{BTreeMap, HashMap, HashSet, VecDeque}, sync::Arc};
 
 mod blocks;
 mod extra_requests;
 
-/// Maximum blocks to request in a single packet.
-const MAX_BLOCKS_TO_REQUEST: usize = 128;
-
 /// Maximum blocks to store in the import queue.
 const MAX_IMPORTING_BLOCKS: usize = 2048;
 
-/// Maximum blocks to download ahead of any gap.
-const MAX_DOWNLOAD_AHEAD: u32 = 2048;
+/// Maximum number of requests worth of blocks to download ahead of any gap.
+///
+/// The window grows with the size of the requests, so that the same number of peers can download
+/// ranges concurrently whatever the request size.
+const MAX_DOWNLOAD_AHEAD_REQUESTS: u32 = 16;
 
 /// We use a heuristic that with a high likelihood, by the time
 /// `MAJOR_SYNC_BLOCKS` have been imported we'll be on the same
@@ -71,6 +73,15 @@ const MAJOR_SYNC_BLOCKS: u8 = 5;
 /// Number of recently announced blocks to track for each peer.
 const ANNOUNCE_HISTORY_SIZE: usize = 64;
 
+/// During fast sync, headers are only downloaded up to this far behind the best block seen,
+/// so that they are likely to be finalized and come with their justification, if any.
+const FAST_SYNC_TARGET_DEPTH: u32 = 64;
+
+/// During fast sync, the state is downloaded for the first block with a justification that is
+/// at most this far behind the headers download limit. With GRANDPA, peers keep the
+/// justification of at least every 512th finalized block.
+const FAST_SYNC_TARGET_WINDOW: u32 = 512;
+
 mod rep {
 	use sc_peerset::ReputationChange as Rep;
 	/// Reputation change when a peer sent us a message that led to a
@@ -98,6 +109,10 @@ mod rep {
 
 	/// Reputation change when a peer sent us invlid ancestry result.
 	pub const UNKNOWN_ANCESTOR:Rep = Rep::new(-(1 << 16), "DB Error");
+
+	/// Reputation change for peers which send us a state response we didn't ask for
+	/// or with unordered keys.
+	pub const BAD_STATE: Rep = Rep::new(-(1 << 29), "Bad state");
 }
 
 /// The main data structure which contains all the state for a chains
@@ -138,8 +153,41 @@ pub struct ChainSync<B: BlockT> {
 	block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	max_parallel_downloads: u32,
+	/// Maximum number of blocks to request in a single packet.
+	max_blocks_per_request: u32,
 	/// Total number of processed blocks (imported or failed).
 	processed_blocks: usize,
+	/// Progress of the fast sync, if it is in progress.
+	fast_sync: Option<FastSync<B>>,
+}
+
+/// Progress of the fast sync, which imports the headers of the chain, then the state of a
+/// recent block instead of executing all the blocks before it.
+enum FastSync<B: BlockT> {
+	/// Downloading the headers up to a justified block to download the state of.
+	///
+	/// Blocks up to the given number are not selected, as their state was not available.
+	Headers(NumberFor<B>),
+	/// Downloading the state of a block.
+	State(StateDownload<B>),
+	/// The block and its state are queued for import, with the peer that sent the block.
+	Importing(B::Hash, Option<PeerId>),
+}
+
+/// The state of a block being downloaded.
+struct StateDownload<B: BlockT> {
+	/// The block, held back from the import queue until its state is complete.
+	block: IncomingBlock<B>,
+	/// Number of the block.
+	number: NumberFor<B>,
+	/// State root of the block, which the chunks are checked against.
+	state_root: B::Hash,
+	/// Top trie pairs downloaded so far.
+	top: BTreeMap<Vec<u8>, Vec<u8>>,
+	/// Child tries downloaded so far.
+	children: HashMap<Vec<u8>, StorageChild>,
+	/// Where the next chunk starts, as returned by the previous one.
+	next: Vec<Vec<u8>>,
 }
 
 /// All the data we have about a Peer that we are trying to sync with
@@ -194,7 +242,9 @@ pub enum PeerSyncState<B: BlockT> {
 	/// Downloading justification for given block hash.
 	DownloadingJustification(B::Hash),
 	/// Downloading finality proof for given block hash.
-	DownloadingFinalityProof(B::Hash)
+	DownloadingFinalityProof(B::Hash),
+	/// Downloading the state of the given block hash.
+	DownloadingState(B::Hash),
 }
 
 impl<B: BlockT> PeerSyncState<B> {
@@ -250,6 +300,15 @@ pub enum OnBlockData<B: BlockT> {
 	Request(PeerId, BlockRequest<B>)
 }
 
+/// Result of [`ChainSync::on_state_data`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnStateData<B: BlockT> {
+	/// The state needs no further handling.
+	Nothing,
+	/// The state is complete, the block should be imported with it.
+	Import(BlockOrigin, IncomingBlock<B>),
+}
+
 /// Result of [`ChainSync::on_block_announce`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OnBlockAnnounce {
@@ -296,10 +355,24 @@ impl<B: BlockT> ChainSync<B> {
 		request_builder: Option<BoxFinalityProofRequestBuilder<B>>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		max_parallel_downloads: u32,
+		max_blocks_per_request: u32,
+		sync_mode: SyncMode,
 	) -> Self {
 		let mut required_block_attributes = BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION;
 
-		if role.is_full() {
+		// Fast sync is only possible if nothing has been executed beyond genesis, or the
+		// headers of a previous fast sync were imported without state.
+		let fast_sync = if sync_mode == SyncMode::Fast && role.is_full() && (
+			info.best_number.is_zero() ||
+			client.block_status(&BlockId::Hash(info.best_hash)).ok() == Some(BlockStatus::InChainPruned)
+		) {
+			debug!(target: "sync", "Starting fast sync from #{} ({})", info.best_number, info.best_hash);
+			Some(FastSync::Headers(Zero::zero()))
+		} else {
+			None
+		};
+
+		if role.is_full() && fast_sync.is_none() {
 			required_block_attributes |= BlockAttributes::BODY
 		}
 
@@ -320,7 +393,10 @@ impl<B: BlockT> ChainSync<B> {
 			is_idle: false,
 			block_announce_validator,
 			max_parallel_downloads,
+			// A request for no block would never make progress.
+			max_blocks_per_request: max_blocks_per_request.max(1),
 			processed_blocks: 0,
+			fast_sync,
 		}
 	}
 
@@ -577,6 +653,34 @@ impl<B: BlockT> ChainSync<B> {
 		})
 	}
 
+	/// Get the request for the next chunk of state to download, if any.
+	///
+	/// `can_serve` tells whether a peer supports state requests.
+	pub fn state_request(
+		&mut self,
+		can_serve: impl Fn(&PeerId) -> bool,
+	) -> Option<(PeerId, StateRequest<B::Hash>)> {
+		let download = match &self.fast_sync {
+			Some(FastSync::State(download)) => download,
+			_ => return None,
+		};
+		let hash = download.block.hash;
+		if self.peers.values().any(|p| p.state == PeerSyncState::DownloadingState(hash)) {
+			// We only request one chunk at a time.
+			return None
+		}
+		let (id, peer) = self.peers.iter_mut().find(|(id, peer)| {
+			peer.state.is_available() && peer.best_number >= download.number && can_serve(id)
+		})?;
+		trace!(target: "sync", "New state request for {} to {}, {} keys so far", hash, id, download.top.len());
+		peer.state = PeerSyncState::DownloadingState(hash);
+		Some((id.clone(), message::generic::StateRequest {
+			id: 0,
+			block: hash,
+			start: download.next.clone(),
+		}))
+	}
+
 	/// Get an iterator over all block requests of all peers.
 	pub fn block_requests(&mut self) -> impl Iterator<Item = (PeerId, BlockRequest<B>)> + '_ {
 		if self.is_idle {
@@ -586,6 +690,27 @@ impl<B: BlockT> ChainSync<B> {
 			trace!(target: "sync", "Too many blocks in the queue.");
 			return Either::Left(std::iter::empty())
 		}
+		let max_number = match self.fast_sync {
+			Some(FastSync::Headers(_)) => {
+				let best_seen = self.peers.values().map(|p| p.best_number).max();
+				let min_length = FAST_SYNC_TARGET_DEPTH + FAST_SYNC_TARGET_WINDOW;
+				match best_seen {
+					Some(best_seen) if self.best_queued_number.is_zero()
+						&& best_seen <= min_length.into() =>
+					{
+						debug!(target: "sync", "Chain is too short for fast sync, downloading all blocks");
+						self.fast_sync = None;
+						self.required_block_attributes |= BlockAttributes::BODY;
+						None
+					},
+					_ => Some(self.fast_sync_limit()),
+				}
+			},
+			// No new blocks until the state is downloaded.
+			Some(FastSync::State(_)) => return Either::Left(std::iter::empty()),
+			Some(FastSync::Importing(..)) | None => None,
+		};
+		let headers_only = max_number.is_some();
 		let major_sync = self.status().state == SyncState::Downloading;
 		let blocks = &mut self.blocks;
 		let attrs = &self.required_block_attributes;
@@ -596,6 +721,7 @@ impl<B: BlockT> ChainSync<B> {
 		let client = &self.client;
 		let queue = &self.queue_blocks;
 		let max_parallel = if major_sync { 1 } else { self.max_parallel_downloads };
+		let max_blocks_per_request = self.max_blocks_per_request;
 		let iter = self.peers.iter_mut().filter_map(move |(id, peer)| {
 			if !peer.state.is_available() {
 				trace!(target: "sync", "Peer {} is busy", id);
@@ -607,8 +733,10 @@ impl<B: BlockT> ChainSync<B> {
 				blocks,
 				attrs,
 				max_parallel,
+				max_blocks_per_request,
 				last_finalized,
 				best_queued,
+				max_number,
 			) {
 				peer.state = PeerSyncState::DownloadingNew(range.start);
 				trace!(
@@ -621,6 +749,9 @@ impl<B: BlockT> ChainSync<B> {
 				);
 				have_requests = true;
 				Some((id.clone(), req))
+			} else if headers_only {
+				// Forks are not synced while only importing headers.
+				None
 			} else if let Some((hash, req)) = fork_sync_request(
 				id,
 				fork_targets,
@@ -683,6 +814,7 @@ impl<B: BlockT> ChainSync<B> {
 										origin: block_data.origin,
 										allow_missing_state: true,
 										import_existing: false,
+										imported_state: None,
 									}
 								}).collect()
 						}
@@ -697,6 +829,7 @@ impl<B: BlockT> ChainSync<B> {
 									origin: Some(who.clone()),
 									allow_missing_state: true,
 									import_existing: false,
+									imported_state: None,
 								}
 							}).collect()
 						}
@@ -756,8 +889,12 @@ impl<B: BlockT> ChainSync<B> {
 
 						| PeerSyncState::Available
 						| PeerSyncState::DownloadingJustification(..)
-						| PeerSyncState::DownloadingFinalityProof(..) => Vec::new()
+						| PeerSyncState::DownloadingFinalityProof(..)
+						| PeerSyncState::DownloadingState(..) => Vec::new()
 					}
+				} else if let Some(FastSync::Headers(_)) | Some(FastSync::State(_)) = self.fast_sync {
+					// Announced blocks can't be imported before the state is downloaded.
+					Vec::new()
 				} else {
 					// When request.is_none() this is a block announcement. Just accept blocks.
 					blocks.into_iter().map(|b| {
@@ -769,6 +906,7 @@ impl<B: BlockT> ChainSync<B> {
 							origin: Some(who.clone()),
 							allow_missing_state: true,
 							import_existing: false,
+							imported_state: None,
 						}
 					}).collect()
 				}
@@ -776,6 +914,10 @@ impl<B: BlockT> ChainSync<B> {
 				Vec::new()
 			};
 
+		if request.is_some() {
+			new_blocks = self.fast_sync_blocks(new_blocks);
+		}
+
 		let orig_len = new_blocks.len();
 		new_blocks.retain(|b| !self.queue_blocks.contains(&b.hash));
 		if new_blocks.len() != orig_len {
@@ -892,6 +1034,109 @@ impl<B: BlockT> ChainSync<B> {
 		Ok(OnBlockFinalityProof::Nothing)
 	}
 
+	/// Handle a response from the remote to a state request that we made.
+	///
+	/// Returns `OnStateData::Import` once the state is complete, with the block that must
+	/// be imported in the import queue.
+	pub fn on_state_data(
+		&mut self,
+		who: PeerId,
+		response: StateResponse<B::Hash>,
+	) -> Result<OnStateData<B>, BadPeer> {
+		let peer =
+			if let Some(peer) = self.peers.get_mut(&who) {
+				peer
+			} else {
+				error!(target: "sync", "Called on_state_data with a bad peer ID");
+				return Ok(OnStateData::Nothing)
+			};
+
+		let hash = match peer.state {
+			PeerSyncState::DownloadingState(hash) => hash,
+			_ => return Ok(OnStateData::Nothing),
+		};
+		peer.state = PeerSyncState::Available;
+		self.is_idle = false;
+
+		if hash != response.block {
+			info!(
+				target: "sync",
+				"Invalid state provided by {}: requested: {:?} got: {:?}", who, hash, response.block
+			);
+			return Err(BadPeer(who, rep::BAD_STATE));
+		}
+
+		let mut download = match self.fast_sync.take() {
+			Some(FastSync::State(download)) if download.block.hash == hash => download,
+			other => {
+				self.fast_sync = other;
+				return Ok(OnStateData::Nothing)
+			},
+		};
+
+		if response.proof.is_empty() {
+			// The peer doesn't have the state, probably pruned it already. Import the block
+			// without state, and download more headers to try with a more recent block.
+			debug!(target: "sync", "Peer {} has no state for {}, selecting a newer block", who, hash);
+			self.fast_sync = Some(FastSync::Headers(download.number));
+			self.on_block_queued(&hash, download.number);
+			self.queue_blocks.insert(hash);
+			return Ok(OnStateData::Import(BlockOrigin::NetworkInitialSync, download.block))
+		}
+
+		let chunk = match state_chunk_proof_check::<HashFor<B>>(
+			download.state_root,
+			response.proof,
+			&download.next,
+			super::MAX_STATE_RESPONSE_SIZE,
+		) {
+			Ok(chunk) => chunk,
+			Err(e) => {
+				info!(target: "sync", "Invalid state proof provided by {} for {:?}: {}", who, hash, e);
+				self.fast_sync = Some(FastSync::State(download));
+				return Err(BadPeer(who, rep::BAD_STATE));
+			},
+		};
+
+		for (key, value) in chunk.top {
+			// Child tries roots are calculated again when importing the state.
+			if !well_known_keys::is_child_storage_key(&key) {
+				download.top.insert(key, value);
+			}
+		}
+		for (storage_key, data) in chunk.children {
+			// Only default child tries are read by the chunks.
+			let prefix = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
+			let child_info = OwnedChildInfo::new_default(storage_key[prefix.len()..].to_vec());
+			download.children.entry(storage_key)
+				.or_insert_with(|| StorageChild { data: BTreeMap::new(), child_info })
+				.data
+				.extend(data);
+		}
+
+		if let Some(next) = chunk.next {
+			download.next = next;
+			self.fast_sync = Some(FastSync::State(download));
+			return Ok(OnStateData::Nothing)
+		}
+
+		info!(
+			target: "sync",
+			"Downloaded state of block #{} ({}), {} keys and {} child tries",
+			download.number,
+			hash,
+			download.top.len(),
+			download.children.len(),
+		);
+		let StateDownload { mut block, number, top, children, .. } = download;
+		block.imported_state = Some(Storage { top, children });
+		self.fast_sync = Some(FastSync::Importing(hash, block.origin.clone()));
+		self.required_block_attributes |= BlockAttributes::BODY;
+		self.on_block_queued(&hash, number);
+		self.queue_blocks.insert(hash);
+		Ok(OnStateData::Import(BlockOrigin::NetworkInitialSync, block))
+	}
+
 	/// A batch of blocks have been processed, with or without errors.
 	///
 	/// Call this when a batch of blocks have been processed by the import
@@ -915,6 +1160,23 @@ impl<B: BlockT> ChainSync<B> {
 		self.processed_blocks += results.len();
 
 		for (result, hash) in results {
+			if let Some(FastSync::Importing(target, origin)) = self.fast_sync.take() {
+				if target != hash {
+					self.fast_sync = Some(FastSync::Importing(target, origin));
+				} else if result.is_ok() {
+					info!(target: "sync", "Imported downloaded state of {:?}, fast sync complete", hash);
+				} else {
+					warn!(target: "sync", "Failed to import downloaded state of {:?}, retrying", hash);
+					// The state was checked against the header, so the header is bad, or its
+					// justification doesn't finalize it.
+					if let (Err(BlockImportError::Other(_)), Some(origin)) = (&result, origin) {
+						output.push(Err(BadPeer(origin, rep::BAD_STATE)));
+					}
+					self.fast_sync = Some(FastSync::Headers(Zero::zero()));
+					self.required_block_attributes.remove(BlockAttributes::BODY);
+				}
+			}
+
 			if has_error {
 				continue;
 			}
@@ -1197,6 +1459,63 @@ impl<B: BlockT> ChainSync<B> {
 		})
 	}
 
+	/// The number of the last header to download during fast sync.
+	fn fast_sync_limit(&self) -> NumberFor<B> {
+		let best_seen = self.peers.values().map(|p| p.best_number).max().unwrap_or_else(Zero::zero);
+		best_seen.checked_sub(&FAST_SYNC_TARGET_DEPTH.into()).unwrap_or_else(Zero::zero)
+	}
+
+	/// Filter downloaded blocks during fast sync.
+	///
+	/// While downloading headers, the first block with a justification close enough to the
+	/// headers download limit is held back, and the state download starts for it. Its header
+	/// and justification are verified when it is imported with the state. Blocks after it are
+	/// discarded.
+	fn fast_sync_blocks(&mut self, blocks: Vec<IncomingBlock<B>>) -> Vec<IncomingBlock<B>> {
+		let after = match self.fast_sync {
+			Some(FastSync::Headers(after)) => after,
+			Some(FastSync::State(_)) => return Vec::new(),
+			Some(FastSync::Importing(..)) | None => return blocks,
+		};
+
+		let min_target = self.fast_sync_limit()
+			.checked_sub(&FAST_SYNC_TARGET_WINDOW.into())
+			.unwrap_or_else(Zero::zero);
+		let mut headers = Vec::with_capacity(blocks.len());
+		for block in blocks {
+			let (number, state_root) = match block.header.as_ref() {
+				Some(header) => (*header.number(), *header.state_root()),
+				None => {
+					headers.push(block);
+					continue
+				},
+			};
+			if block.justification.is_none() || number <= after || number < min_target {
+				headers.push(block);
+				continue
+			}
+
+			info!(target: "sync", "Downloading state of block #{} ({})", number, block.hash);
+			self.fast_sync = Some(FastSync::State(StateDownload {
+				block,
+				number,
+				state_root,
+				top: BTreeMap::new(),
+				children: HashMap::new(),
+				next: Vec::new(),
+			}));
+			// Pending block requests are obsolete, as blocks after this one need their body.
+			self.blocks.clear();
+			for peer in self.peers.values_mut() {
+				if let PeerSyncState::DownloadingNew(_) = peer.state {
+					peer.state = PeerSyncState::Available;
+				}
+			}
+			break
+		}
+		headers
+	}
+
 	/// What is the status of the block corresponding to the given hash?
 	fn block_status(&self, hash: &B::Hash) -> Result<BlockStatus, ClientError> {
 		if self.queue_blocks.contains(hash) {
@@ -1317,8 +1636,10 @@ fn peer_block_request<B: BlockT>(
 	blocks: &mut BlockCollection<B>,
 	attrs: &message::BlockAttributes,
 	max_parallel_downloads: u32,
+	max_blocks_per_request: u32,
 	finalized: NumberFor<B>,
 	best_num: NumberFor<B>,
+	max_number: Option<NumberFor<B>>,
 ) -> Option<(Range<NumberFor<B>>, BlockRequest<B>)> {
 	if peer.common_number < finalized {
 		return None;
@@ -1327,13 +1648,14 @@ fn peer_block_request<B: BlockT>(
 		// Will be downloaded as alternative fork instead.
 		return None;
 	}
+	let peer_best = max_number.map_or(peer.best_number, |max| std::cmp::min(max, peer.best_number));
 	if let Some(range) = blocks.needed_blocks(
 		id.clone(),
-		MAX_BLOCKS_TO_REQUEST,
-		peer.best_number,
+		max_blocks_per_request as usize,
+		peer_best,
 		peer.common_number,
 		max_parallel_downloads,
-		MAX_DOWNLOAD_AHEAD,
+		max_blocks_per_request.saturating_mul(MAX_DOWNLOAD_AHEAD_REQUESTS),
 	) {
 		let request = message::generic::BlockRequest {
 			id: 0,
@@ -1440,6 +1762,8 @@ mod test {
 			None,
 			block_announce_validator,
 			1,
+			128,
+			SyncMode::Full,
 		);
 
 		let (a1_hash, a1_number) = {
@@ -42,14 +42,17 @@ use sp_runtime::{traits::{Block as BlockT, NumberFor}, ConsensusEngineId};
 use prometheus_endpoint::{Registry, Counter, CounterVec, Gauge, GaugeVec, Opts, U64, register, PrometheusError};
 
 use crate::{behaviour::{Behaviour, BehaviourOut}, config::{parse_str_addr, parse_addr}};
-use crate::{transport, config::NonReservedPeerMode, ReputationChange};
-use crate::config::{Params, TransportConfig};
+use crate::{port_mapping, transport, config::NonReservedPeerMode, PeerReputation, ReputationChange};
+use crate::config::{NotificationsProtocolConfig, Params, SyncMode, TransportConfig};
 use crate::error::Error;
 use crate::network_state::{NetworkState, NotConnectedPeer as NetworkStateNotConnectedPeer, Peer as NetworkStatePeer};
 use crate::protocol::{self, Protocol, PeerInfo};
 use crate::protocol::{event::Event, light_dispatch::{AlwaysBadChecker, RequestData}};
 use crate::protocol::sync::SyncState;
 
+pub use notifications::{NotificationError, NotificationEvent, NotificationsReceiver, NotificationsSender};
+
+mod notifications;
 
 /// Minimum Requirements for a Hash within Networking
 pub trait ExHashT: std::hash::Hash + Eq + std::fmt::Debug + Clone + Send + Sync + 'static {}
@@ -200,6 +203,13 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				}
 			)?;
 
+		// Fast sync imports blocks from their header and justification only.
+		if params.network_config.sync_mode == SyncMode::Fast
+			&& !params.import_queue.imports_headers_only()
+		{
+			return Err(Error::FastSyncNotSupported);
+		}
+
 		// Initialize the reserved peers.
 		for reserved in params.network_config.reserved_nodes.iter() {
 			if let Ok((peer_id, addr)) = parse_str_addr(reserved) {
@@ -210,12 +220,25 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			}
 		}
 
+		// The validators we are a sentry for are reserved peers, whose addresses are kept private.
+		let mut private_peers = HashSet::new();
+		for validator in params.network_config.sentry_validators.iter() {
+			if let Ok((peer_id, addr)) = parse_str_addr(validator) {
+				reserved_nodes.push(peer_id.clone());
+				private_peers.insert(peer_id.clone());
+				known_addresses.push((peer_id, addr));
+			} else {
+				warn!(target: "sub-libp2p", "Not a valid sentry validator address: {}", validator);
+			}
+		}
+
 		let peerset_config = sc_peerset::PeersetConfig {
 			in_peers: params.network_config.in_peers,
 			out_peers: params.network_config.out_peers,
 			bootnodes,
 			reserved_only: params.network_config.non_reserved_mode == NonReservedPeerMode::Deny,
 			reserved_nodes,
+			reputation: params.network_config.reputation.clone(),
 		};
 
 		// Private and public keys configuration.
@@ -234,6 +257,8 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			protocol::ProtocolConfig {
 				roles: params.roles,
 				max_parallel_downloads: params.network_config.max_parallel_downloads,
+				max_blocks_per_request: params.network_config.max_blocks_per_request,
+				sync_mode: params.network_config.sync_mode,
 			},
 			params.chain.clone(),
 			checker.clone(),
@@ -247,7 +272,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 		)?;
 
 		// Build the swarm.
-		let (mut swarm, bandwidth): (Swarm::<B, H>, _) = {
+		let (mut swarm, bandwidth, port_mapping): (Swarm::<B, H>, _, _) = {
 			let user_agent = format!(
 				"{} ({})",
 				params.network_config.client_version,
@@ -266,6 +291,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				user_agent,
 				local_public,
 				known_addresses,
+				private_peers,
 				match params.network_config.transport {
 					TransportConfig::MemoryOnly => false,
 					TransportConfig::Normal { enable_mdns, .. } => enable_mdns,
@@ -278,19 +304,31 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				block_requests,
 				light_client_handler
 			));
+			let port_mapping = match params.network_config.transport {
+				TransportConfig::Normal { enable_port_mapping: true, .. } =>
+					port_mapping::start(&params.network_config.listen_addresses),
+				_ => None,
+			};
 			let (transport, bandwidth) = {
 				let (config_mem, config_wasm, flowctrl) = match params.network_config.transport {
 					TransportConfig::MemoryOnly => (true, None, false),
 					TransportConfig::Normal { wasm_external_transport, use_yamux_flow_control, .. } =>
 						(false, wasm_external_transport, use_yamux_flow_control)
 				};
-				transport::build_transport(local_identity, config_mem, config_wasm, flowctrl)
+				transport::build_transport(
+					local_identity,
+					config_mem,
+					config_wasm,
+					flowctrl,
+					&params.network_config.bandwidth_limits,
+					params.network_config.pre_shared_key,
+				)
 			};
 			let mut builder = SwarmBuilder::new(transport, behaviour, local_peer_id.clone());
 			if let Some(spawner) = params.executor {
 				builder = builder.executor_fn(spawner);
 			}
-			(builder.build(), bandwidth)
+			(builder.build(), bandwidth, port_mapping)
 		};
 
 		// Listen on multiaddresses.
@@ -328,6 +366,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			from_worker,
 			light_client_rqs: params.on_demand.and_then(|od| od.extract_receiver()),
 			event_streams: Vec::new(),
+			port_mapping,
 			metrics: match params.metrics_registry {
 				Some(registry) => Some(Metrics::register(&registry)?),
 				None => None
@@ -475,6 +514,16 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			.collect()
 	}
 
+	/// Returns the reserved peers.
+	pub fn reserved_peers(&self) -> HashSet<PeerId> {
+		self.network_service.user_protocol().reserved_peers()
+	}
+
+	/// Returns the reputations of the known peers.
+	pub fn reputations(&mut self) -> Vec<PeerReputation> {
+		self.network_service.user_protocol_mut().reputations()
+	}
+
 	/// Removes a `PeerId` from the list of reserved peers.
 	pub fn remove_reserved_peer(&self, peer: PeerId) {
 		self.service.remove_reserved_peer(peer);
@@ -536,9 +585,41 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::RegisterNotifProtocol {
 			engine_id,
 			protocol_name: protocol_name.into(),
+			handshake: Vec::new(),
+			max_notification_size: None,
 		});
 	}
 
+	/// Registers a new notifications protocol, and returns handles to send notifications on it
+	/// and to receive its events.
+	///
+	/// Unlike `register_notifications_protocol`, the handshake sent to peers and the maximum size
+	/// of the notifications are configurable. The receiver gets all the events of the protocol
+	/// from the moment it is registered.
+	///
+	/// As with `register_notifications_protocol`, call this method very early on.
+	pub fn notifications_protocol(
+		&self,
+		config: NotificationsProtocolConfig,
+	) -> (NotificationsSender<B, H>, NotificationsReceiver) {
+		let (tx, rx) = mpsc::unbounded();
+		// The worker processes messages in order, so the event stream exists before the protocol.
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::EventStream(tx));
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::RegisterNotifProtocol {
+			engine_id: config.engine_id,
+			protocol_name: config.protocol_name,
+			handshake: config.handshake,
+			max_notification_size: Some(config.max_notification_size),
+		});
+
+		let sender = NotificationsSender::new(
+			self.to_worker.clone(),
+			config.engine_id,
+			config.max_notification_size,
+		);
+		(sender, NotificationsReceiver::new(rx, config.engine_id))
+	}
+
 	/// You may call this when new transactons are imported by the transaction pool.
 	///
 	/// All transactions will be fetched from the `TransactionPool` that was passed at
@@ -626,6 +707,16 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		self.peerset.remove_reserved_peer(peer);
 	}
 
+	/// Disconnects from a peer and refuses to connect to it again until it is unbanned.
+	pub fn ban_peer(&self, peer: PeerId) {
+		self.peerset.ban_peer(peer);
+	}
+
+	/// Lifts the ban of a peer and resets its reputation.
+	pub fn unban_peer(&self, peer: PeerId) {
+		self.peerset.unban_peer(peer);
+	}
+
 	/// Adds a `PeerId` and its address as reserved. The string should encode the address
 	/// and peer ID of the remote node.
 	pub fn add_reserved_peer(&self, peer: String) -> Result<(), String> {
@@ -743,6 +834,8 @@ enum ServiceToWorkerMsg<B: BlockT, H: ExHashT> {
 	RegisterNotifProtocol {
 		engine_id: ConsensusEngineId,
 		protocol_name: Cow<'static, [u8]>,
+		handshake: Vec<u8>,
+		max_notification_size: Option<usize>,
 	},
 	DisconnectPeer(PeerId),
 }
@@ -770,6 +863,8 @@ pub struct NetworkWorker<B: BlockT + 'static, H: ExHashT> {
 	light_client_rqs: Option<mpsc::UnboundedReceiver<RequestData<B>>>,
 	/// Senders for events that happen on the network.
 	event_streams: Vec<mpsc::UnboundedSender<Event>>,
+	/// External addresses of the ports mapped on the router, if port mapping is enabled.
+	port_mapping: Option<port_mapping::PortMapping>,
 	/// Prometheus network metrics.
 	metrics: Option<Metrics>,
 }
@@ -783,6 +878,7 @@ struct Metrics {
 	is_major_syncing: Gauge<U64>,
 	kbuckets_num_nodes: Gauge<U64>,
 	network_per_sec_bytes: GaugeVec<U64>,
+	notifications_bytes_total: CounterVec<U64>,
 	notifications_total: CounterVec<U64>,
 	num_event_stream_channels: Gauge<U64>,
 	opened_notification_streams: GaugeVec<U64>,
@@ -824,6 +920,13 @@ impl Metrics {
 				),
 				&["direction"]
 			)?, registry)?,
+			notifications_bytes_total: register(CounterVec::new(
+				Opts::new(
+					"sub_libp2p_notifications_bytes_total",
+					"Total size of the notifications sent to and received from all nodes"
+				),
+				&["direction", "protocol"]
+			)?, registry)?,
 			notifications_total: register(CounterVec::new(
 				Opts::new(
 					"sub_libp2p_notifications_total",
@@ -866,8 +969,10 @@ impl Metrics {
 				self.opened_notification_streams.with_label_values(&[&engine_id_to_string(&engine_id)]).dec();
 			},
 			Event::NotificationsReceived { messages, .. } => {
-				for (engine_id, _) in messages {
-					self.notifications_total.with_label_values(&["in", &engine_id_to_string(&engine_id)]).inc();
+				for (engine_id, message) in messages {
+					let protocol = engine_id_to_string(&engine_id);
+					self.notifications_total.with_label_values(&["in", &protocol]).inc();
+					self.notifications_bytes_total.with_label_values(&["in", &protocol]).inc_by(message.len() as u64);
 				}
 			},
 			_ => {}
@@ -886,6 +991,14 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 			protocol: &mut this.network_service,
 		});
 
+		// Advertise the addresses of newly mapped ports.
+		if let Some(port_mapping) = this.port_mapping.as_mut() {
+			while let Poll::Ready(Some(addr)) = port_mapping.poll_next_unpin(cx) {
+				info!(target: "sub-libp2p", "Mapped port on the router, external address: {}", addr);
+				Swarm::<B, H>::add_external_address(&mut this.network_service, addr);
+			}
+		}
+
 		// Check for new incoming light client requests.
 		if let Some(light_client_rqs) = this.light_client_rqs.as_mut() {
 			while let Poll::Ready(Some(rq)) = light_client_rqs.poll_next_unpin(cx) {
@@ -922,13 +1035,25 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 					this.event_streams.push(sender),
 				ServiceToWorkerMsg::WriteNotification { message, engine_id, target } => {
 					if let Some(metrics) = this.metrics.as_ref() {
-						metrics.notifications_total.with_label_values(&["out", &engine_id_to_string(&engine_id)]).inc();
+						let protocol = engine_id_to_string(&engine_id);
+						metrics.notifications_total.with_label_values(&["out", &protocol]).inc();
+						metrics.notifications_bytes_total.with_label_values(&["out", &protocol])
+							.inc_by(message.len() as u64);
 					}
 					this.network_service.user_protocol_mut().write_notification(target, engine_id, message)
 				},
-				ServiceToWorkerMsg::RegisterNotifProtocol { engine_id, protocol_name } => {
-					let events = this.network_service.user_protocol_mut()
-						.register_notifications_protocol(engine_id, protocol_name);
+				ServiceToWorkerMsg::RegisterNotifProtocol {
+					engine_id,
+					protocol_name,
+					handshake,
+					max_notification_size,
+				} => {
+					let events = this.network_service.user_protocol_mut().register_notifications_protocol(
+						engine_id,
+						protocol_name,
+						handshake,
+						max_notification_size,
+					);
 					for event in events {
 						this.event_streams.retain(|sender| sender.unbounded_send(event.clone()).is_ok());
 					}
@@ -980,12 +1105,16 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 					if let Some(metrics) = this.metrics.as_ref() {
 						metrics.connections.inc();
 					}
+					let ev = Event::PeerConnected { remote: peer_id };
+					this.event_streams.retain(|sender| sender.unbounded_send(ev.clone()).is_ok());
 				},
 				Poll::Ready(SwarmEvent::Disconnected(peer_id)) => {
 					trace!(target: "sub-libp2p", "Libp2p => Disconnected({:?})", peer_id);
 					if let Some(metrics) = this.metrics.as_ref() {
 						metrics.connections.dec();
 					}
+					let ev = Event::PeerDisconnected { remote: peer_id };
+					this.event_streams.retain(|sender| sender.unbounded_send(ev.clone()).is_ok());
 				},
 				Poll::Ready(SwarmEvent::NewListenAddr(addr)) =>
 					trace!(target: "sub-libp2p", "Libp2p => NewListenAddr({})", addr),
@@ -1007,12 +1136,16 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 			*this.external_addresses.lock() = external_addresses;
 		}
 
-		let is_major_syncing = match this.network_service.user_protocol_mut().sync_state() {
+		let sync_state = this.network_service.user_protocol_mut().sync_state();
+		let is_major_syncing = match sync_state {
 			SyncState::Idle => false,
 			SyncState::Downloading => true,
 		};
 
-		this.is_major_syncing.store(is_major_syncing, Ordering::Relaxed);
+		if this.is_major_syncing.swap(is_major_syncing, Ordering::Relaxed) != is_major_syncing {
+			let ev = Event::SyncStateChanged(sync_state);
+			this.event_streams.retain(|sender| sender.unbounded_send(ev.clone()).is_ok());
+		}
 
 		if let Some(metrics) = this.metrics.as_ref() {
 			metrics.network_per_sec_bytes.with_label_values(&["in"]).set(this.service.bandwidth.average_download_per_sec());
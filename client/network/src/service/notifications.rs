@@ -0,0 +1,229 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Handles to a notifications protocol registered with `NetworkService::notifications_protocol`.
+
+use super::{ExHashT, ServiceToWorkerMsg};
+use crate::config::Roles;
+use crate::protocol::event::Event;
+
+use bytes::Bytes;
+use futures::{prelude::*, channel::mpsc, ready};
+use libp2p::PeerId;
+use sp_runtime::{traits::Block as BlockT, ConsensusEngineId};
+use std::{collections::VecDeque, pin::Pin, task::{Context, Poll}};
+
+/// Event of a notifications protocol, produced by a [`NotificationsReceiver`].
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+	/// Opened a substream with the given node.
+	Opened {
+		/// Node we opened the substream with.
+		remote: PeerId,
+		/// Roles of the remote.
+		roles: Roles,
+	},
+	/// Closed the substream with the given node.
+	Closed {
+		/// Node we closed the substream with.
+		remote: PeerId,
+	},
+	/// Received a notification from the given node.
+	Received {
+		/// Node we received the notification from.
+		remote: PeerId,
+		/// The notification.
+		message: Bytes,
+	},
+}
+
+/// Error returned by [`NotificationsSender::send`].
+#[derive(Debug, derive_more::Display)]
+pub enum NotificationError {
+	/// The notification is larger than the maximum size of the protocol.
+	#[display(fmt = "Notification of {} bytes is larger than the maximum of {} bytes", size, max)]
+	TooLarge {
+		/// Size of the notification.
+		size: usize,
+		/// Maximum size of the protocol.
+		max: usize,
+	},
+	/// The network worker has shut down.
+	#[display(fmt = "The network worker has shut down")]
+	Closed,
+}
+
+impl std::error::Error for NotificationError {}
+
+/// Sends notifications on a notifications protocol.
+///
+/// As with `NetworkService::write_notification`, delivery is not guaranteed: notifications to
+/// nodes we have no open substream with are discarded.
+pub struct NotificationsSender<B: BlockT + 'static, H: ExHashT> {
+	to_worker: mpsc::UnboundedSender<ServiceToWorkerMsg<B, H>>,
+	engine_id: ConsensusEngineId,
+	max_notification_size: usize,
+}
+
+impl<B: BlockT + 'static, H: ExHashT> NotificationsSender<B, H> {
+	pub(super) fn new(
+		to_worker: mpsc::UnboundedSender<ServiceToWorkerMsg<B, H>>,
+		engine_id: ConsensusEngineId,
+		max_notification_size: usize,
+	) -> Self {
+		NotificationsSender { to_worker, engine_id, max_notification_size }
+	}
+
+	/// Returns the engine id of the protocol.
+	pub fn engine_id(&self) -> ConsensusEngineId {
+		self.engine_id
+	}
+
+	/// Sends a notification to the given node.
+	pub fn send(&self, target: PeerId, message: impl Into<Vec<u8>>) -> Result<(), NotificationError> {
+		let message = message.into();
+		if message.len() > self.max_notification_size {
+			return Err(NotificationError::TooLarge {
+				size: message.len(),
+				max: self.max_notification_size,
+			});
+		}
+
+		self.to_worker.unbounded_send(ServiceToWorkerMsg::WriteNotification {
+			message,
+			engine_id: self.engine_id,
+			target,
+		}).map_err(|_| NotificationError::Closed)
+	}
+}
+
+impl<B: BlockT + 'static, H: ExHashT> Clone for NotificationsSender<B, H> {
+	fn clone(&self) -> Self {
+		NotificationsSender {
+			to_worker: self.to_worker.clone(),
+			engine_id: self.engine_id,
+			max_notification_size: self.max_notification_size,
+		}
+	}
+}
+
+/// Stream of the events of a notifications protocol.
+///
+/// The stream ends when the network worker shuts down.
+pub struct NotificationsReceiver {
+	events: mpsc::UnboundedReceiver<Event>,
+	engine_id: ConsensusEngineId,
+	/// Notifications received in a batch and not yielded yet.
+	pending: VecDeque<NotificationEvent>,
+}
+
+impl NotificationsReceiver {
+	pub(super) fn new(events: mpsc::UnboundedReceiver<Event>, engine_id: ConsensusEngineId) -> Self {
+		NotificationsReceiver { events, engine_id, pending: VecDeque::new() }
+	}
+
+	/// Returns the engine id of the protocol.
+	pub fn engine_id(&self) -> ConsensusEngineId {
+		self.engine_id
+	}
+}
+
+impl Stream for NotificationsReceiver {
+	type Item = NotificationEvent;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+		let this = &mut *self;
+		loop {
+			if let Some(event) = this.pending.pop_front() {
+				return Poll::Ready(Some(event));
+			}
+
+			match ready!(this.events.poll_next_unpin(cx)) {
+				Some(Event::NotificationStreamOpened { remote, engine_id, roles })
+					if engine_id == this.engine_id =>
+					return Poll::Ready(Some(NotificationEvent::Opened { remote, roles })),
+				Some(Event::NotificationStreamClosed { remote, engine_id })
+					if engine_id == this.engine_id =>
+					return Poll::Ready(Some(NotificationEvent::Closed { remote })),
+				Some(Event::NotificationsReceived { remote, messages }) => {
+					let engine_id = this.engine_id;
+					this.pending.extend(messages.into_iter()
+						.filter(|(id, _)| *id == engine_id)
+						.map(|(_, message)| NotificationEvent::Received { remote: remote.clone(), message })
+					);
+				},
+				Some(_) => {},
+				None => return Poll::Ready(None),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::executor::block_on;
+
+	const ENGINE_ID: ConsensusEngineId = *b"test";
+	const OTHER_ENGINE_ID: ConsensusEngineId = *b"othr";
+
+	#[test]
+	fn receiver_only_yields_events_of_its_protocol() {
+		let (tx, rx) = mpsc::unbounded();
+		let remote = PeerId::random();
+
+		tx.unbounded_send(Event::NotificationStreamOpened {
+			remote: remote.clone(),
+			engine_id: OTHER_ENGINE_ID,
+			roles: Roles::FULL,
+		}).unwrap();
+		tx.unbounded_send(Event::NotificationStreamOpened {
+			remote: remote.clone(),
+			engine_id: ENGINE_ID,
+			roles: Roles::FULL,
+		}).unwrap();
+		tx.unbounded_send(Event::NotificationsReceived {
+			remote: remote.clone(),
+			messages: vec![
+				(ENGINE_ID, Bytes::from(&b"one"[..])),
+				(OTHER_ENGINE_ID, Bytes::from(&b"other"[..])),
+				(ENGINE_ID, Bytes::from(&b"two"[..])),
+			],
+		}).unwrap();
+		tx.unbounded_send(Event::NotificationStreamClosed {
+			remote: remote.clone(),
+			engine_id: ENGINE_ID,
+		}).unwrap();
+		drop(tx);
+
+		let events = block_on(NotificationsReceiver::new(rx, ENGINE_ID).collect::<Vec<_>>());
+		let messages = events.iter().filter_map(|event| match event {
+			NotificationEvent::Received { message, .. } => Some(&message[..]),
+			_ => None,
+		}).collect::<Vec<_>>();
+
+		assert_eq!(events.len(), 4);
+		match events[0] {
+			NotificationEvent::Opened { remote: ref opened, .. } => assert_eq!(opened, &remote),
+			ref event => panic!("Unexpected event: {:?}", event),
+		}
+		assert_eq!(messages, vec![&b"one"[..], &b"two"[..]]);
+		match events[3] {
+			NotificationEvent::Closed { remote: ref closed } => assert_eq!(closed, &remote),
+			ref event => panic!("Unexpected event: {:?}", event),
+		}
+	}
+}
@@ -14,30 +14,44 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::config::{BandwidthLimits, PreSharedKey};
 use futures::prelude::*;
 use libp2p::{
 	InboundUpgradeExt, OutboundUpgradeExt, PeerId, Transport,
-	mplex, identity, bandwidth, wasm_ext, noise
+	mplex, identity, bandwidth, wasm_ext, noise, pnet
 };
 #[cfg(not(target_os = "unknown"))]
 use libp2p::{tcp, dns, websocket};
-use libp2p::core::{self, upgrade, transport::boxed::Boxed, transport::OptionalTransport, muxing::StreamMuxerBox};
+use libp2p::core::{
+	self, either::EitherOutput, upgrade, transport::boxed::Boxed, transport::OptionalTransport,
+	muxing::StreamMuxerBox
+};
 use std::{io, sync::Arc, time::Duration, usize};
 
 pub use self::bandwidth::BandwidthSinks;
 
+mod rate_limit;
+
 /// Builds the transport that serves as a common ground for all connections.
 ///
 /// If `memory_only` is true, then only communication within the same process are allowed. Only
 /// addresses with the format `/memory/...` are allowed.
 ///
+/// The total bandwidth of all the connections spawned with this transport is capped by
+/// `bandwidth_limits`.
+///
+/// If a `pre_shared_key` is passed, only the nodes holding the same key can connect to us and
+/// be connected to.
+///
 /// Returns a `BandwidthSinks` object that allows querying the average bandwidth produced by all
 /// the connections spawned with this transport.
 pub fn build_transport(
 	keypair: identity::Keypair,
 	memory_only: bool,
 	wasm_external_transport: Option<wasm_ext::ExtTransport>,
-	use_yamux_flow_control: bool
+	use_yamux_flow_control: bool,
+	bandwidth_limits: &BandwidthLimits,
+	pre_shared_key: Option<PreSharedKey>,
 ) -> (Boxed<(PeerId, StreamMuxerBox), io::Error>, Arc<bandwidth::BandwidthSinks>) {
 	// Build configuration objects for encryption mechanisms.
 	let noise_config = {
@@ -91,6 +105,17 @@ pub fn build_transport(
 		OptionalTransport::none()
 	});
 
+	// Private network
+	let transport = transport.and_then(move |socket, _| match pre_shared_key {
+		Some(psk) => future::Either::Left(
+			pnet::PnetConfig::new(psk).handshake(socket).map_ok(EitherOutput::First)
+		),
+		None => future::Either::Right(future::ok(EitherOutput::Second(socket))),
+	});
+
+	let limiter = Arc::new(rate_limit::BandwidthLimiter::new(bandwidth_limits));
+	let transport = transport.map(move |stream, _| rate_limit::RateLimited::new(stream, limiter.clone()));
+
 	let (transport, sinks) = bandwidth::BandwidthLogging::new(transport, Duration::from_secs(5));
 
 	// Encryption
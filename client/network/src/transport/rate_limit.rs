@@ -0,0 +1,193 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Limits the bandwidth shared by all the connections of a transport.
+//!
+//! Each direction has a token bucket refilled at the configured rate, holding at most one second
+//! worth of bytes. Transfers go ahead as long as the bucket isn't empty, and consume the number of
+//! bytes actually transferred, possibly making the bucket go negative. The following transfers
+//! then wait until it has been refilled.
+
+use crate::config::BandwidthLimits;
+
+use futures::{prelude::*, ready};
+use futures_timer::Delay;
+use parking_lot::Mutex;
+use std::{io, pin::Pin, sync::Arc, task::{Context, Poll}, time::Duration};
+use wasm_timer::Instant;
+
+/// Token buckets shared by all the connections.
+pub struct BandwidthLimiter {
+	upload: Option<Mutex<TokenBucket>>,
+	download: Option<Mutex<TokenBucket>>,
+}
+
+impl BandwidthLimiter {
+	/// Builds a limiter enforcing `limits`.
+	pub fn new(limits: &BandwidthLimits) -> Self {
+		let now = Instant::now();
+		BandwidthLimiter {
+			upload: limits.upload.map(|rate| Mutex::new(TokenBucket::new(rate, now))),
+			download: limits.download.map(|rate| Mutex::new(TokenBucket::new(rate, now))),
+		}
+	}
+}
+
+/// Token bucket of one direction.
+struct TokenBucket {
+	/// Refill rate, in bytes per second. Also the capacity of the bucket.
+	rate: f64,
+	/// Available bytes. Negative if more than available has been transferred.
+	tokens: f64,
+	/// When `tokens` was last refilled.
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	fn new(rate: u64, now: Instant) -> Self {
+		// A rate of zero would block all transfers forever.
+		let rate = rate.max(1) as f64;
+		TokenBucket { rate, tokens: rate, last_refill: now }
+	}
+
+	/// Returns how long to wait before transferring, or `None` if a transfer can go ahead.
+	fn wait_time(&mut self, now: Instant) -> Option<Duration> {
+		let elapsed = now.duration_since(self.last_refill);
+		self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.rate);
+		self.last_refill = now;
+
+		if self.tokens >= 1.0 {
+			None
+		} else {
+			Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+		}
+	}
+
+	/// Records that `bytes` have been transferred.
+	fn consume(&mut self, bytes: usize) {
+		self.tokens -= bytes as f64;
+	}
+}
+
+/// Waits until `bucket` allows a transfer.
+fn poll_bucket(bucket: &Mutex<TokenBucket>, delay: &mut Option<Delay>, cx: &mut Context) -> Poll<()> {
+	loop {
+		if let Some(timer) = delay.as_mut() {
+			ready!(timer.poll_unpin(cx));
+			*delay = None;
+		}
+
+		match bucket.lock().wait_time(Instant::now()) {
+			None => return Poll::Ready(()),
+			Some(wait) => *delay = Some(Delay::new(wait)),
+		}
+	}
+}
+
+/// Connection whose transfers are subject to a `BandwidthLimiter`.
+#[pin_project::pin_project]
+pub struct RateLimited<S> {
+	#[pin]
+	inner: S,
+	limiter: Arc<BandwidthLimiter>,
+	read_delay: Option<Delay>,
+	write_delay: Option<Delay>,
+}
+
+impl<S> RateLimited<S> {
+	/// Wraps `inner` to limit its bandwidth with `limiter`.
+	pub fn new(inner: S, limiter: Arc<BandwidthLimiter>) -> Self {
+		RateLimited { inner, limiter, read_delay: None, write_delay: None }
+	}
+}
+
+impl<S: AsyncRead> AsyncRead for RateLimited<S> {
+	fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+		let this = self.project();
+		let bucket = match this.limiter.download {
+			Some(ref bucket) => bucket,
+			None => return this.inner.poll_read(cx, buf),
+		};
+
+		ready!(poll_bucket(bucket, this.read_delay, cx));
+		let read = ready!(this.inner.poll_read(cx, buf))?;
+		bucket.lock().consume(read);
+		Poll::Ready(Ok(read))
+	}
+}
+
+impl<S: AsyncWrite> AsyncWrite for RateLimited<S> {
+	fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+		let this = self.project();
+		let bucket = match this.limiter.upload {
+			Some(ref bucket) => bucket,
+			None => return this.inner.poll_write(cx, buf),
+		};
+
+		ready!(poll_bucket(bucket, this.write_delay, cx));
+		let written = ready!(this.inner.poll_write(cx, buf))?;
+		bucket.lock().consume(written);
+		Poll::Ready(Ok(written))
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+		self.project().inner.poll_flush(cx)
+	}
+
+	fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+		self.project().inner.poll_close(cx)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bucket_allows_bursts_of_one_second() {
+		let start = Instant::now();
+		let mut bucket = TokenBucket::new(1000, start);
+
+		assert_eq!(bucket.wait_time(start), None);
+		bucket.consume(1000);
+		let wait = bucket.wait_time(start).unwrap();
+		assert!(wait > Duration::from_micros(999) && wait <= Duration::from_micros(1001));
+	}
+
+	#[test]
+	fn bucket_waits_until_debt_is_repaid() {
+		let start = Instant::now();
+		let mut bucket = TokenBucket::new(1000, start);
+
+		// Transfers may exceed the available bytes, and are paid back afterwards.
+		bucket.consume(2000);
+		let wait = bucket.wait_time(start).unwrap();
+		assert!(wait > Duration::from_millis(1000) && wait <= Duration::from_millis(1001));
+
+		assert!(bucket.wait_time(start + Duration::from_millis(500)).is_some());
+		assert_eq!(bucket.wait_time(start + Duration::from_millis(1010)), None);
+	}
+
+	#[test]
+	fn bucket_does_not_refill_beyond_one_second() {
+		let start = Instant::now();
+		let mut bucket = TokenBucket::new(1000, start);
+
+		assert_eq!(bucket.wait_time(start + Duration::from_secs(60)), None);
+		bucket.consume(1000);
+		assert!(bucket.wait_time(start + Duration::from_secs(60)).is_some());
+	}
+}
@@ -43,6 +43,7 @@ fn prepare_good_block() -> (TestClient, Hash, u64, PeerId, IncomingBlock<Block>)
 		origin: Some(peer_id.clone()),
 		allow_missing_state: false,
 		import_existing: false,
+		imported_state: None,
 	})
 }
 
@@ -491,6 +491,10 @@ impl<B: BlockT> Verifier<B> for VerifierAdapter<B> {
 			e
 		})
 	}
+
+	fn verifies_headers_only(&self) -> bool {
+		self.verifier.lock().verifies_headers_only()
+	}
 }
 
 impl<B: BlockT> VerifierAdapter<B> {
@@ -605,6 +609,7 @@ pub trait TestNetFactory: Sized {
 			network_config: NetworkConfiguration {
 				listen_addresses: vec![listen_addr.clone()],
 				transport: TransportConfig::MemoryOnly,
+				sync_mode: config.sync_mode,
 				..NetworkConfiguration::default()
 			},
 			chain: client.clone(),
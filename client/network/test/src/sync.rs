@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use sc_network::config::Roles;
+use sc_network::config::{Roles, SyncMode};
 use sp_consensus::BlockOrigin;
 use std::time::Duration;
 use futures::executor::block_on;
@@ -694,3 +694,30 @@ fn imports_stale_once() {
 	assert_eq!(net.peer(1).num_processed_blocks(), 2);
 }
 
+
+#[test]
+fn fast_sync_downloads_state_of_justified_block() {
+	let _ = ::env_logger::try_init();
+	let mut net = TestNet::new(1);
+
+	// The chain must be longer than the fast sync target depth and window.
+	net.peer(0).push_blocks(600, true);
+	net.peer(0).client().finalize_block(BlockId::Number(500), Some(Vec::new()), true).unwrap();
+	net.peer(0).push_blocks(100, false);
+
+	let mut config = ProtocolConfig::default();
+	config.sync_mode = SyncMode::Fast;
+	net.add_full_peer(&config);
+	net.block_until_sync();
+
+	let peer1 = &net.peers()[1];
+	assert!(net.peers()[0].blockchain_canon_equals(peer1));
+	assert!(net.peer(1).client().justification(&BlockId::Number(500)).unwrap().is_some());
+	let client = net.peer(1).client().as_full().unwrap();
+	assert_eq!(client.info().best_number, 700);
+	// Blocks up to the justified one are imported from their header only, and the blocks
+	// after it are executed on top of the downloaded state.
+	assert!(client.body(&BlockId::Number(499)).unwrap().is_none());
+	assert!(client.body(&BlockId::Number(501)).unwrap().is_some());
+	assert!(client.state_at(&BlockId::Number(700)).is_ok());
+}
@@ -59,11 +59,16 @@ pub struct OffchainWorkers<Client, Storage, Block: traits::Block> {
 impl<Client, Storage, Block: traits::Block> OffchainWorkers<Client, Storage, Block> {
 	/// Creates new `OffchainWorkers`.
 	pub fn new(client: Arc<Client>, db: Storage) -> Self {
+		Self::new_with_threads(client, db, num_cpus::get())
+	}
+
+	/// Creates new `OffchainWorkers` running on a dedicated pool of `threads` threads.
+	pub fn new_with_threads(client: Arc<Client>, db: Storage, threads: usize) -> Self {
 		Self {
 			client,
 			db,
 			_block: PhantomData,
-			thread_pool: Mutex::new(ThreadPool::new(num_cpus::get())),
+			thread_pool: Mutex::new(ThreadPool::with_name("offchain-worker".into(), threads.max(1))),
 		}
 	}
 }
@@ -156,8 +161,19 @@ impl<Client, Storage, Block> OffchainWorkers<
 	///
 	/// Note that we should avoid that if we switch to future-based runtime in the future,
 	/// alternatively:
+	///
+	/// The workers are skipped while as many workers as there are threads are already waiting,
+	/// so that slow workers don't pile up behind the imported blocks.
 	fn spawn_worker(&self, f: impl FnOnce() -> () + Send + 'static) {
-		self.thread_pool.lock().execute(f);
+		let thread_pool = self.thread_pool.lock();
+		if thread_pool.queued_count() >= thread_pool.max_count() {
+			warn!(
+				"Skipping offchain workers, {} are already waiting for a thread",
+				thread_pool.queued_count(),
+			);
+			return
+		}
+		thread_pool.execute(f);
 	}
 }
 
@@ -220,4 +236,29 @@ mod tests {
 		assert_eq!(pool.0.status().ready, 1);
 		assert_eq!(pool.0.ready().next().unwrap().is_propagable(), false);
 	}
+
+	#[test]
+	fn workers_are_skipped_while_the_threads_are_busy() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let db = sc_client_db::offchain::LocalStorage::new_test();
+		let offchain = OffchainWorkers::<_, _, Block>::new_with_threads(client, db, 1);
+
+		let (started_tx, started_rx) = std::sync::mpsc::channel();
+		let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+		offchain.spawn_worker(move || {
+			started_tx.send(()).unwrap();
+			let _ = release_rx.recv();
+		});
+		started_rx.recv().unwrap();
+
+		let (ran_tx, ran_rx) = std::sync::mpsc::channel();
+		for worker in 0..2 {
+			let ran_tx = ran_tx.clone();
+			offchain.spawn_worker(move || ran_tx.send(worker).unwrap());
+		}
+		drop(ran_tx);
+		release_tx.send(()).unwrap();
+
+		assert_eq!(ran_rx.iter().collect::<Vec<_>>(), vec![0]);
+	}
 }
@@ -47,6 +47,8 @@ enum Action {
 	SetPriorityGroup(String, HashSet<PeerId>),
 	AddToPriorityGroup(String, PeerId),
 	RemoveFromPriorityGroup(String, PeerId),
+	BanPeer(PeerId),
+	UnbanPeer(PeerId),
 }
 
 /// Description of a reputation adjustment for a node.
@@ -119,6 +121,18 @@ impl PeersetHandle {
 	pub fn remove_from_priority_group(&self, group_id: String, peer_id: PeerId) {
 		let _ = self.tx.unbounded_send(Action::RemoveFromPriorityGroup(group_id, peer_id));
 	}
+
+	/// Bans a peer until it is unbanned: disconnects from it and refuses to connect to it again.
+	///
+	/// Priority peers, such as the reserved ones, are connected to again.
+	pub fn ban_peer(&self, peer_id: PeerId) {
+		let _ = self.tx.unbounded_send(Action::BanPeer(peer_id));
+	}
+
+	/// Lifts the ban of a peer and resets its reputation.
+	pub fn unban_peer(&self, peer_id: PeerId) {
+		let _ = self.tx.unbounded_send(Action::UnbanPeer(peer_id));
+	}
 }
 
 /// Message that can be sent by the peer set manager (PSM).
@@ -148,6 +162,43 @@ impl From<u64> for IncomingIndex {
 	}
 }
 
+/// Parameters of the reputation system of the peer set manager.
+#[derive(Debug, Clone)]
+pub struct ReputationConfig {
+	/// Peers whose reputation is under this value are disconnected and not connected to.
+	pub ban_threshold: i32,
+	/// Each second, the reputation of every peer moves towards zero by this fraction of its value.
+	/// It takes `ln(0.5) / ln(1 - 1 / decay_divisor)` seconds to reduce a reputation by half.
+	pub decay_divisor: i32,
+	/// Reputation change for a peer when we get disconnected from it.
+	pub disconnect_cost: i32,
+	/// Reputation changes applied instead of the reported ones, by reason of the report.
+	pub cost_overrides: HashMap<String, i32>,
+}
+
+impl Default for ReputationConfig {
+	fn default() -> Self {
+		ReputationConfig {
+			ban_threshold: BANNED_THRESHOLD,
+			// With `k = 1 - 1 / 50 = 0.98`, it takes 34.3 seconds to reduce the reputation by half.
+			decay_divisor: 50,
+			disconnect_cost: DISCONNECT_REPUTATION_CHANGE,
+			cost_overrides: HashMap::new(),
+		}
+	}
+}
+
+/// Reputation of a peer known to the peer set manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerReputation {
+	/// Identity of the peer.
+	pub peer_id: PeerId,
+	/// Current reputation of the peer.
+	pub reputation: i32,
+	/// Whether the peer is banned, manually or because of its reputation.
+	pub banned: bool,
+}
+
 /// Configuration to pass when creating the peer set manager.
 #[derive(Debug)]
 pub struct PeersetConfig {
@@ -171,6 +222,9 @@ pub struct PeersetConfig {
 	/// > **Note**: Keep in mind that the networking has to know an address for these nodes,
 	/// >			otherwise it will not be able to connect to them.
 	pub reserved_nodes: Vec<PeerId>,
+
+	/// Parameters of the reputation system.
+	pub reputation: ReputationConfig,
 }
 
 /// Side of the peer set manager owned by the network. In other words, the "receiving" side.
@@ -192,6 +246,10 @@ pub struct Peerset {
 	created: Instant,
 	/// Last time when we updated the reputations of connected nodes.
 	latest_time_update: Instant,
+	/// Parameters of the reputation system.
+	reputation: ReputationConfig,
+	/// Peers banned with `PeersetHandle::ban_peer`, whose reputation stays at the minimum.
+	banned: HashSet<PeerId>,
 }
 
 impl Peerset {
@@ -213,6 +271,8 @@ impl Peerset {
 			message_queue: VecDeque::new(),
 			created: now,
 			latest_time_update: now,
+			reputation: config.reputation,
+			banned: HashSet::new(),
 		};
 
 		peerset.data.set_priority_group(RESERVED_NODES, config.reserved_nodes.into_iter().collect());
@@ -286,14 +346,23 @@ impl Peerset {
 		self.alloc_slots();
 	}
 
-	fn on_report_peer(&mut self, peer_id: PeerId, change: ReputationChange) {
+	fn on_report_peer(&mut self, peer_id: PeerId, mut change: ReputationChange) {
+		// The reputation of manually banned peers is pinned to the minimum.
+		if self.banned.contains(&peer_id) {
+			return
+		}
+
 		// We want reputations to be up-to-date before adjusting them.
 		self.update_time();
 
+		if let Some(value) = self.reputation.cost_overrides.get(change.reason) {
+			change.value = *value;
+		}
+
 		match self.data.peer(&peer_id) {
 			peersstate::Peer::Connected(mut peer) => {
 				peer.add_reputation(change.value);
-				if peer.reputation() < BANNED_THRESHOLD {
+				if peer.reputation() < self.reputation.ban_threshold {
 					debug!(target: "peerset", "Report {}: {:+} to {}. Reason: {}, Disconnecting",
 						peer_id, change.value, peer.reputation(), change.reason
 					);
@@ -310,6 +379,31 @@ impl Peerset {
 		}
 	}
 
+	fn on_ban_peer(&mut self, peer_id: PeerId) {
+		self.banned.insert(peer_id.clone());
+		match self.data.peer(&peer_id) {
+			peersstate::Peer::Connected(mut peer) => {
+				peer.set_reputation(i32::min_value());
+				peer.disconnect();
+				self.message_queue.push_back(Message::Drop(peer_id));
+			},
+			peersstate::Peer::NotConnected(mut peer) => peer.set_reputation(i32::min_value()),
+			peersstate::Peer::Unknown(peer) => peer.discover().set_reputation(i32::min_value()),
+		}
+	}
+
+	fn on_unban_peer(&mut self, peer_id: PeerId) {
+		if !self.banned.remove(&peer_id) {
+			return
+		}
+		match self.data.peer(&peer_id) {
+			peersstate::Peer::Connected(mut peer) => peer.set_reputation(0),
+			peersstate::Peer::NotConnected(mut peer) => peer.set_reputation(0),
+			peersstate::Peer::Unknown(_) => {},
+		}
+		self.alloc_slots();
+	}
+
 	/// Updates the value of `self.latest_time_update` and performs all the updates that happen
 	/// over time, such as reputation increases for staying connected.
 	fn update_time(&mut self) {
@@ -328,19 +422,23 @@ impl Peerset {
 		// If we multiply each second the reputation by `k` (where `k` is between 0 and 1), it
 		// takes `ln(0.5) / ln(k)` seconds to reduce the reputation by half. Use this formula to
 		// empirically determine a value of `k` that looks correct.
+		let decay_divisor = self.reputation.decay_divisor.max(1);
 		for _ in 0..secs_diff {
 			for peer_id in self.data.peers().cloned().collect::<Vec<_>>() {
-				// We use `k = 0.98`, so we divide by `50`. With that value, it takes 34.3 seconds
-				// to reduce the reputation by half.
-				fn reput_tick(reput: i32) -> i32 {
-					let mut diff = reput / 50;
+				if self.banned.contains(&peer_id) {
+					continue
+				}
+
+				// We use `k = 1 - 1 / decay_divisor`, so we divide by `decay_divisor`.
+				let reput_tick = |reput: i32| {
+					let mut diff = reput / decay_divisor;
 					if diff == 0 && reput < 0 {
 						diff = -1;
 					} else if diff == 0 && reput > 0 {
 						diff = 1;
 					}
 					reput.saturating_sub(diff)
-				}
+				};
 				match self.data.peer(&peer_id) {
 					peersstate::Peer::Connected(mut peer) => {
 						let before = peer.reputation();
@@ -396,7 +494,7 @@ impl Peerset {
 			};
 
 			// Don't connect to nodes with an abysmal reputation.
-			if next.reputation() < BANNED_THRESHOLD {
+			if next.reputation() < self.reputation.ban_threshold {
 				break;
 			}
 
@@ -431,7 +529,7 @@ impl Peerset {
 			peersstate::Peer::Unknown(entry) => entry.discover(),
 		};
 
-		if not_connected.reputation() < BANNED_THRESHOLD {
+		if not_connected.reputation() < self.reputation.ban_threshold {
 			self.message_queue.push_back(Message::Reject(index));
 			return
 		}
@@ -455,7 +553,9 @@ impl Peerset {
 		match self.data.peer(&peer_id) {
 			peersstate::Peer::Connected(mut entry) => {
 				// Decrease the node's reputation so that we don't try it again and again and again.
-				entry.add_reputation(DISCONNECT_REPUTATION_CHANGE);
+				if !self.banned.contains(&peer_id) {
+					entry.add_reputation(self.reputation.disconnect_cost);
+				}
 				entry.disconnect();
 			}
 			peersstate::Peer::NotConnected(_) | peersstate::Peer::Unknown(_) =>
@@ -513,6 +613,8 @@ impl Peerset {
 
 				(peer_id.to_base58(), state)
 			}).collect::<HashMap<_, _>>(),
+			"reserved": self.reserved_peers().iter().map(PeerId::to_base58).collect::<Vec<_>>(),
+			"banned": self.banned.iter().map(PeerId::to_base58).collect::<Vec<_>>(),
 			"reserved_only": self.reserved_only,
 			"message_queue": self.message_queue.len(),
 		})
@@ -527,6 +629,32 @@ impl Peerset {
 	pub fn get_priority_group(&self, group_id: &str) -> Option<HashSet<PeerId>> {
 		self.data.get_priority_group(group_id)
 	}
+
+	/// Returns the reserved peers.
+	pub fn reserved_peers(&self) -> HashSet<PeerId> {
+		self.get_priority_group(RESERVED_NODES).unwrap_or_default()
+	}
+
+	/// Returns whether only the reserved peers are allowed to connect.
+	pub fn is_reserved_only(&self) -> bool {
+		self.reserved_only
+	}
+
+	/// Returns the reputations of the known peers.
+	pub fn reputations(&mut self) -> Vec<PeerReputation> {
+		self.update_time();
+
+		let ban_threshold = self.reputation.ban_threshold;
+		self.data.peers().cloned().collect::<Vec<_>>().into_iter().filter_map(|peer_id| {
+			let reputation = match self.data.peer(&peer_id) {
+				peersstate::Peer::Connected(entry) => entry.reputation(),
+				peersstate::Peer::NotConnected(entry) => entry.reputation(),
+				peersstate::Peer::Unknown(_) => return None,
+			};
+			let banned = self.banned.contains(&peer_id) || reputation < ban_threshold;
+			Some(PeerReputation { peer_id, reputation, banned })
+		}).collect()
+	}
 }
 
 impl Stream for Peerset {
@@ -559,6 +687,10 @@ impl Stream for Peerset {
 					self.on_add_to_priority_group(&group_id, peer_id),
 				Action::RemoveFromPriorityGroup(group_id, peer_id) =>
 					self.on_remove_from_priority_group(&group_id, peer_id),
+				Action::BanPeer(peer_id) =>
+					self.on_ban_peer(peer_id),
+				Action::UnbanPeer(peer_id) =>
+					self.on_unban_peer(peer_id),
 			}
 		}
 	}
@@ -568,8 +700,11 @@ impl Stream for Peerset {
 mod tests {
 	use libp2p::PeerId;
 	use futures::prelude::*;
-	use super::{PeersetConfig, Peerset, Message, IncomingIndex, ReputationChange, BANNED_THRESHOLD};
-	use std::{pin::Pin, task::Poll, thread, time::Duration};
+	use super::{
+		PeersetConfig, Peerset, Message, IncomingIndex, ReputationChange, ReputationConfig,
+		PeerReputation, BANNED_THRESHOLD,
+	};
+	use std::{collections::HashMap, pin::Pin, task::Poll, thread, time::Duration};
 
 	fn assert_messages(mut peerset: Peerset, messages: Vec<Message>) -> Peerset {
 		for expected_message in messages {
@@ -598,6 +733,7 @@ mod tests {
 			bootnodes: vec![bootnode],
 			reserved_only: true,
 			reserved_nodes: Vec::new(),
+			reputation: Default::default(),
 		};
 
 		let (peerset, handle) = Peerset::from_config(config);
@@ -610,6 +746,32 @@ mod tests {
 		]);
 	}
 
+	#[test]
+	fn test_peerset_set_reserved_only() {
+		let bootnode = PeerId::random();
+		let reserved_peer = PeerId::random();
+		let config = PeersetConfig {
+			in_peers: 0,
+			out_peers: 2,
+			bootnodes: vec![bootnode.clone()],
+			reserved_only: false,
+			reserved_nodes: vec![reserved_peer.clone()],
+			reputation: Default::default(),
+		};
+
+		let (peerset, handle) = Peerset::from_config(config);
+		let peerset = assert_messages(peerset, vec![
+			Message::Connect(reserved_peer.clone()),
+			Message::Connect(bootnode.clone()),
+		]);
+		assert!(!peerset.is_reserved_only());
+
+		handle.set_reserved_only(true);
+		let peerset = assert_messages(peerset, vec![Message::Drop(bootnode)]);
+		assert!(peerset.is_reserved_only());
+		assert_eq!(peerset.reserved_peers(), vec![reserved_peer].into_iter().collect());
+	}
+
 	#[test]
 	fn test_peerset_incoming() {
 		let bootnode = PeerId::random();
@@ -626,6 +788,7 @@ mod tests {
 			bootnodes: vec![bootnode.clone()],
 			reserved_only: false,
 			reserved_nodes: Vec::new(),
+			reputation: Default::default(),
 		};
 
 		let (mut peerset, _handle) = Peerset::from_config(config);
@@ -653,6 +816,7 @@ mod tests {
 			bootnodes: vec![bootnode.clone()],
 			reserved_only: false,
 			reserved_nodes: vec![],
+			reputation: Default::default(),
 		};
 
 		let (mut peerset, _handle) = Peerset::from_config(config);
@@ -674,6 +838,7 @@ mod tests {
 			bootnodes: vec![],
 			reserved_only: false,
 			reserved_nodes: vec![],
+			reputation: Default::default(),
 		});
 
 		// We ban a node by setting its reputation under the threshold.
@@ -706,5 +871,94 @@ mod tests {
 
 		futures::executor::block_on(fut);
 	}
-}
 
+	#[test]
+	fn test_peerset_manual_ban() {
+		let (mut peerset, handle) = Peerset::from_config(PeersetConfig {
+			in_peers: 25,
+			out_peers: 25,
+			bootnodes: vec![],
+			reserved_only: false,
+			reserved_nodes: vec![],
+			reputation: Default::default(),
+		});
+
+		let peer_id = PeerId::random();
+		handle.ban_peer(peer_id.clone());
+
+		let fut = futures::future::poll_fn(move |cx| {
+			// We need one polling for the message to be processed.
+			assert_eq!(Stream::poll_next(Pin::new(&mut peerset), cx), Poll::Pending);
+
+			// Unlike a ban by reputation, a manual ban doesn't wear off with time.
+			thread::sleep(Duration::from_millis(1500));
+			peerset.incoming(peer_id.clone(), IncomingIndex(1));
+			if let Poll::Ready(msg) = Stream::poll_next(Pin::new(&mut peerset), cx) {
+				assert_eq!(msg.unwrap(), Message::Reject(IncomingIndex(1)));
+			} else {
+				panic!()
+			}
+			assert_eq!(peerset.reputations(), vec![PeerReputation {
+				peer_id: peer_id.clone(),
+				reputation: i32::min_value(),
+				banned: true,
+			}]);
+
+			handle.unban_peer(peer_id.clone());
+			assert_eq!(Stream::poll_next(Pin::new(&mut peerset), cx), Poll::Pending);
+
+			peerset.incoming(peer_id.clone(), IncomingIndex(2));
+			while let Poll::Ready(msg) = Stream::poll_next(Pin::new(&mut peerset), cx) {
+				assert_eq!(msg.unwrap(), Message::Accept(IncomingIndex(2)));
+			}
+
+			Poll::Ready(())
+		});
+
+		futures::executor::block_on(fut);
+	}
+
+	#[test]
+	fn test_peerset_reputation_config() {
+		let mut cost_overrides = HashMap::new();
+		cost_overrides.insert("Ignored".to_string(), 0);
+		let (mut peerset, handle) = Peerset::from_config(PeersetConfig {
+			in_peers: 25,
+			out_peers: 25,
+			bootnodes: vec![],
+			reserved_only: false,
+			reserved_nodes: vec![],
+			reputation: ReputationConfig {
+				ban_threshold: -100,
+				cost_overrides,
+				..Default::default()
+			},
+		});
+
+		let ignored = PeerId::random();
+		let reported = PeerId::random();
+		handle.report_peer(ignored.clone(), ReputationChange::new(-1000, "Ignored"));
+		handle.report_peer(reported.clone(), ReputationChange::new(-1000, "Reported"));
+
+		let fut = futures::future::poll_fn(move |cx| {
+			// We need one polling for the messages to be processed.
+			assert_eq!(Stream::poll_next(Pin::new(&mut peerset), cx), Poll::Pending);
+
+			// The cost of the first report is overridden, the second peer is under the threshold.
+			peerset.incoming(ignored.clone(), IncomingIndex(1));
+			peerset.incoming(reported.clone(), IncomingIndex(2));
+			assert_eq!(
+				Stream::poll_next(Pin::new(&mut peerset), cx),
+				Poll::Ready(Some(Message::Accept(IncomingIndex(1))))
+			);
+			assert_eq!(
+				Stream::poll_next(Pin::new(&mut peerset), cx),
+				Poll::Ready(Some(Message::Reject(IncomingIndex(2))))
+			);
+
+			Poll::Ready(())
+		});
+
+		futures::executor::block_on(fut);
+	}
+}
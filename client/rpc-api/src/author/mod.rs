@@ -68,7 +68,15 @@ pub trait AuthorApi<Hash, BlockHash> {
 	#[rpc(name = "author_pendingExtrinsics")]
 	fn pending_extrinsics(&self) -> Result<Vec<Bytes>>;
 
+	/// Returns the extrinsics that can't be included yet, as they wait for other extrinsics
+	/// (e.g. a lower nonce of the same sender) that aren't in the pool.
+	#[rpc(name = "author_futureExtrinsics")]
+	fn future_extrinsics(&self) -> Result<Vec<Bytes>>;
+
 	/// Remove given extrinsic from the pool and temporarily ban it to prevent reimporting.
+	///
+	/// Both pending and future extrinsics can be removed. The extrinsics depending on the removed
+	/// ones are removed as well, and are part of the returned hashes.
 	#[rpc(name = "author_removeExtrinsic")]
 	fn remove_extrinsic(&self,
 		bytes_or_hash: Vec<hash::ExtrinsicOrHash<Hash>>
@@ -27,6 +27,12 @@ use self::error::{FutureResult, Result};
 
 pub use self::gen_client::Client as ChainClient;
 
+/// Maximum number of headers returned by `chain_getHeadersInRange`.
+pub const MAX_HEADERS_IN_RANGE: u32 = 1000;
+
+/// Maximum number of blocks returned by `chain_getBlocksPaged`.
+pub const MAX_BLOCKS_PAGE_SIZE: u32 = 100;
+
 /// Substrate blockchain API
 #[rpc]
 pub trait ChainApi<Number, Hash, Header, SignedBlock> {
@@ -54,6 +60,28 @@ pub trait ChainApi<Number, Hash, Header, SignedBlock> {
 	#[rpc(name = "chain_getFinalizedHead", alias("chain_getFinalisedHead"))]
 	fn finalized_head(&self) -> Result<Hash>;
 
+	/// Get headers of the blocks numbered `from` to `to` (both included) in the canon chain.
+	///
+	/// At most `MAX_HEADERS_IN_RANGE` headers are returned at once. The range is cut at the best
+	/// block.
+	#[rpc(name = "chain_getHeadersInRange")]
+	fn headers_in_range(
+		&self,
+		from: NumberOrHex<Number>,
+		to: NumberOrHex<Number>,
+	) -> Result<Vec<Header>>;
+
+	/// Get up to `count` blocks of the canon chain, starting at block number `from`.
+	///
+	/// At most `MAX_BLOCKS_PAGE_SIZE` blocks are returned at once. Fewer than `count` blocks
+	/// means the best block was reached; the next page starts right after the last returned block.
+	#[rpc(name = "chain_getBlocksPaged")]
+	fn blocks_paged(
+		&self,
+		from: NumberOrHex<Number>,
+		count: u32,
+	) -> FutureResult<Vec<SignedBlock>>;
+
 	/// All head subscription
 	#[pubsub(subscription = "chain_allHead", subscribe, name = "chain_subscribeAllHeads")]
 	fn subscribe_all_heads(&self, metadata: Self::Metadata, subscriber: Subscriber<Header>);
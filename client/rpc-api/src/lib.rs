@@ -25,11 +25,38 @@ mod helpers;
 mod subscriptions;
 
 pub use jsonrpc_core::IoHandlerExtension as RpcExtension;
-pub use subscriptions::{Subscriptions, TaskExecutor};
+pub use subscriptions::{
+	BufferedSink, ConnectionSubscriptions, NotificationDropPolicy, SubscriptionLimits, Subscriptions,
+	TaskExecutor,
+};
 pub use helpers::Receiver;
 
+/// Methods that shouldn't be exposed publicly, as they manage the node (keys, peers, pool),
+/// expose its local data or are too expensive to serve to anyone.
+pub const UNSAFE_METHODS: &[&str] = &[
+	"author_insertKey",
+	"author_rotateKeys",
+	"author_hasSessionKeys",
+	"author_hasKey",
+	"author_removeExtrinsic",
+	"offchain_localStorageGet",
+	"offchain_localStorageSet",
+	"state_getPairs",
+	"state_queryStorage",
+	"system_addReservedPeer",
+	"system_removeReservedPeer",
+	"system_reservedPeers",
+	"system_setReservedOnly",
+	"system_peerReputations",
+	"system_banPeer",
+	"system_unbanPeer",
+	"system_networkState",
+	"system_peers",
+];
+
 pub mod author;
 pub mod chain;
+pub mod child_state;
 pub mod offchain;
 pub mod state;
 pub mod system;
@@ -17,6 +17,7 @@
 //! Substrate state API.
 
 pub mod error;
+pub mod helpers;
 
 use jsonrpc_core::Result as RpcResult;
 use jsonrpc_core::futures::Future;
@@ -28,6 +29,7 @@ use sp_version::RuntimeVersion;
 use self::error::FutureResult;
 
 pub use self::gen_client::Client as StateClient;
+pub use self::helpers::ReadProof;
 
 /// Substrate state API
 #[rpc]
@@ -35,7 +37,10 @@ pub trait StateApi<Hash> {
 	/// RPC Metadata
 	type Metadata;
 
-	/// Call a contract at a block's state.
+	/// Call a runtime API method at a block's state.
+	///
+	/// `name` is the runtime API method, e.g. `Core_version`, and `bytes` are its SCALE-encoded
+	/// arguments. The SCALE-encoded result is returned. Defaults to the best block.
 	#[rpc(name = "state_call", alias("state_callAt"))]
 	fn call(&self, name: String, bytes: Bytes, hash: Option<Hash>) -> FutureResult<Bytes>;
 
@@ -136,6 +141,23 @@ pub trait StateApi<Hash> {
 		hash: Option<Hash>
 	) -> FutureResult<Vec<StorageChangeSet<Hash>>>;
 
+	/// Query storage entries (by key) at a block, defaulting to the best block.
+	///
+	/// The result contains a single change set, with the values of all the keys.
+	#[rpc(name = "state_queryStorageAt")]
+	fn query_storage_at(
+		&self,
+		keys: Vec<StorageKey>,
+		at: Option<Hash>,
+	) -> FutureResult<Vec<StorageChangeSet<Hash>>>;
+
+	/// Returns proof of storage entries at a specific block's state, defaulting to the best
+	/// block.
+	///
+	/// The proof can be checked against the state root of the block's header.
+	#[rpc(name = "state_getReadProof")]
+	fn read_proof(&self, keys: Vec<StorageKey>, hash: Option<Hash>) -> FutureResult<ReadProof<Hash>>;
+
 	/// New runtime version subscription
 	#[pubsub(
 		subscription = "state_runtimeVersion",
@@ -14,17 +14,20 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, atomic::{self, AtomicUsize}};
 
-use log::{error, warn};
+use log::{debug, error, warn};
 use jsonrpc_pubsub::{SubscriptionId, typed::{Sink, Subscriber}};
 use parking_lot::Mutex;
 use jsonrpc_core::futures::sync::oneshot;
-use jsonrpc_core::futures::{Future, future};
+use jsonrpc_core::futures::{Async, AsyncSink, Future, Poll, StartSend, future};
 
 type Id = u64;
 
+/// Error code of a subscription rejected because its connection has too many subscriptions.
+const TOO_MANY_SUBSCRIPTIONS_ERROR: i64 = 9000;
+
 /// Alias for a an implementation of `futures::future::Executor`.
 pub type TaskExecutor = Arc<dyn future::Executor<Box<dyn Future<Item = (), Error = ()> + Send>> + Send + Sync>;
 
@@ -48,6 +51,53 @@ impl IdProvider {
 	}
 }
 
+/// What to do with a notification that doesn't fit in the buffer of a subscription.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationDropPolicy {
+	/// Drop the oldest buffered notification to make room for the new one.
+	DropOldest,
+	/// Drop the new notification.
+	DropNewest,
+	/// Cancel the subscription.
+	Unsubscribe,
+}
+
+/// Limits protecting the node from connections that subscribe too much or consume too slowly.
+#[derive(Clone, Copy, Debug)]
+pub struct SubscriptionLimits {
+	/// Maximum number of active subscriptions of a single connection.
+	pub max_subscriptions_per_connection: usize,
+	/// Maximum number of notifications of a single subscription waiting to be sent.
+	pub max_buffered_notifications: usize,
+	/// What to do once `max_buffered_notifications` is reached.
+	pub drop_policy: NotificationDropPolicy,
+}
+
+impl Default for SubscriptionLimits {
+	fn default() -> Self {
+		SubscriptionLimits {
+			max_subscriptions_per_connection: 1024,
+			max_buffered_notifications: 1024,
+			drop_policy: NotificationDropPolicy::Unsubscribe,
+		}
+	}
+}
+
+/// Active subscriptions of a single connection.
+///
+/// Shared by all the requests of the connection.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionSubscriptions {
+	active: Arc<AtomicUsize>,
+}
+
+impl ConnectionSubscriptions {
+	/// Returns the number of active subscriptions of the connection.
+	pub fn active(&self) -> usize {
+		self.active.load(atomic::Ordering::Acquire)
+	}
+}
+
 /// Subscriptions manager.
 ///
 /// Takes care of assigning unique subscription ids and
@@ -57,15 +107,22 @@ pub struct Subscriptions {
 	next_id: IdProvider,
 	active_subscriptions: Arc<Mutex<HashMap<Id, oneshot::Sender<()>>>>,
 	executor: TaskExecutor,
+	limits: SubscriptionLimits,
 }
 
 impl Subscriptions {
 	/// Creates new `Subscriptions` object.
 	pub fn new(executor: TaskExecutor) -> Self {
+		Self::with_limits(executor, Default::default())
+	}
+
+	/// Creates new `Subscriptions` object enforcing the given limits.
+	pub fn with_limits(executor: TaskExecutor, limits: SubscriptionLimits) -> Self {
 		Subscriptions {
 			next_id: Default::default(),
 			active_subscriptions: Default::default(),
 			executor,
+			limits,
 		}
 	}
 
@@ -76,29 +133,61 @@ impl Subscriptions {
 		&self.executor
 	}
 
-	/// Creates new subscription for given subscriber.
+	/// Creates new subscription for given subscriber of the `connection`.
 	///
-	/// Second parameter is a function that converts Subscriber sink into a future.
+	/// Third parameter is a function that converts Subscriber sink into a future.
 	/// This future will be driven to completion by the underlying event loop
 	/// or will be cancelled in case #cancel is invoked.
-	pub fn add<T, E, G, R, F>(&self, subscriber: Subscriber<T, E>, into_future: G) -> SubscriptionId where
-		G: FnOnce(Sink<T, E>) -> R,
+	///
+	/// The subscriber is rejected if the connection already has the maximum number of
+	/// subscriptions. Notifications the connection doesn't consume are buffered by the sink,
+	/// up to the configured limit.
+	pub fn add<T, E, G, R, F>(
+		&self,
+		connection: &ConnectionSubscriptions,
+		subscriber: Subscriber<T, E>,
+		into_future: G,
+	) -> SubscriptionId where
+		T: serde::Serialize,
+		E: serde::Serialize,
+		G: FnOnce(BufferedSink<T, E>) -> R,
 		R: future::IntoFuture<Future=F, Item=(), Error=()>,
 		F: future::Future<Item=(), Error=()> + Send + 'static,
 	{
 		let id = self.next_id.next_id();
 		let subscription_id: SubscriptionId = id.into();
+
+		let active = connection.active.fetch_add(1, atomic::Ordering::AcqRel);
+		if active >= self.limits.max_subscriptions_per_connection {
+			connection.active.fetch_sub(1, atomic::Ordering::AcqRel);
+			let _ = subscriber.reject(jsonrpc_core::Error {
+				code: jsonrpc_core::ErrorCode::ServerError(TOO_MANY_SUBSCRIPTIONS_ERROR),
+				message: format!(
+					"At most {} subscriptions are allowed per connection.",
+					self.limits.max_subscriptions_per_connection,
+				),
+				data: None,
+			});
+			return subscription_id;
+		}
+
 		if let Ok(sink) = subscriber.assign_id(subscription_id.clone()) {
 			let (tx, rx) = oneshot::channel();
-			let future = into_future(sink)
+			let active = connection.active.clone();
+			let future = into_future(BufferedSink::new(sink, &self.limits))
 				.into_future()
 				.select(rx.map_err(|e| warn!("Error timeing out: {:?}", e)))
-				.then(|_| Ok(()));
+				.then(move |_| {
+					active.fetch_sub(1, atomic::Ordering::AcqRel);
+					Ok(())
+				});
 
 			self.active_subscriptions.lock().insert(id, tx);
 			if self.executor.execute(Box::new(future)).is_err() {
 				error!("Failed to spawn RPC subscription task");
 			}
+		} else {
+			connection.active.fetch_sub(1, atomic::Ordering::AcqRel);
 		}
 
 		subscription_id
@@ -117,3 +206,67 @@ impl Subscriptions {
 		false
 	}
 }
+
+/// Sink of a subscription, buffering the notifications its connection doesn't consume yet.
+///
+/// It always accepts new notifications, so that the streams feeding it are drained even when the
+/// connection is slow, and applies the drop policy once the buffer is full.
+pub struct BufferedSink<T, E> {
+	inner: Sink<T, E>,
+	buffer: VecDeque<Result<T, E>>,
+	max_buffered: usize,
+	drop_policy: NotificationDropPolicy,
+}
+
+impl<T: serde::Serialize, E: serde::Serialize> BufferedSink<T, E> {
+	fn new(inner: Sink<T, E>, limits: &SubscriptionLimits) -> Self {
+		BufferedSink {
+			inner,
+			buffer: VecDeque::new(),
+			max_buffered: limits.max_buffered_notifications,
+			drop_policy: limits.drop_policy,
+		}
+	}
+
+	/// Send the buffered notifications until the inner sink is full.
+	fn flush(&mut self) -> Poll<(), ()> {
+		while let Some(item) = self.buffer.pop_front() {
+			let sent = self.inner.start_send(item)
+				.map_err(|e| debug!("Error sending notification: {:?}", e))?;
+			if let AsyncSink::NotReady(item) = sent {
+				self.buffer.push_front(item);
+				self.inner.poll_complete().map_err(|e| debug!("Error sending notification: {:?}", e))?;
+				return Ok(Async::NotReady)
+			}
+		}
+		self.inner.poll_complete().map_err(|e| debug!("Error sending notification: {:?}", e))
+	}
+}
+
+impl<T: serde::Serialize, E: serde::Serialize> jsonrpc_core::futures::Sink for BufferedSink<T, E> {
+	type SinkItem = Result<T, E>;
+	type SinkError = ();
+
+	fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+		if self.buffer.len() >= self.max_buffered {
+			match self.drop_policy {
+				NotificationDropPolicy::DropOldest => {
+					self.buffer.pop_front();
+				},
+				NotificationDropPolicy::DropNewest => return Ok(AsyncSink::Ready),
+				NotificationDropPolicy::Unsubscribe => {
+					warn!("Subscription notifications aren't consumed fast enough, unsubscribing.");
+					return Err(())
+				},
+			}
+		}
+
+		self.buffer.push_back(item);
+		self.flush()?;
+		Ok(AsyncSink::Ready)
+	}
+
+	fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+		self.flush()
+	}
+}
@@ -58,6 +58,22 @@ impl fmt::Display for Health {
 	}
 }
 
+/// Sync state struct returned by the RPC
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncState<Number> {
+	/// Best block number when the node started
+	pub starting_block: Number,
+	/// Current best block number
+	pub current_block: Number,
+	/// Highest block number seen on the network, `None` until a peer announced its best block
+	pub highest_block: Option<Number>,
+	/// Number of connected peers
+	pub peers: usize,
+	/// Is the node performing a major sync
+	pub is_major_syncing: bool,
+}
+
 /// Network Peer information
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -74,6 +90,18 @@ pub struct PeerInfo<Hash, Number> {
 	pub best_number: Number,
 }
 
+/// Reputation of a peer known to the network
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerReputation {
+	/// Peer ID
+	pub peer_id: String,
+	/// Current reputation
+	pub reputation: i32,
+	/// Whether the peer is banned
+	pub banned: bool,
+}
+
 /// The role the node is running as
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum NodeRole {
@@ -103,6 +131,20 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_serialize_sync_state() {
+		assert_eq!(
+			::serde_json::to_string(&SyncState {
+				starting_block: 1u32,
+				current_block: 5u32,
+				highest_block: Some(10u32),
+				peers: 3,
+				is_major_syncing: true,
+			}).unwrap(),
+			r#"{"startingBlock":1,"currentBlock":5,"highestBlock":10,"peers":3,"isMajorSyncing":true}"#,
+		);
+	}
+
 	#[test]
 	fn should_serialize_peer_info() {
 		assert_eq!(
@@ -116,4 +158,16 @@ mod tests {
 			r#"{"peerId":"2","roles":"a","protocolVersion":2,"bestHash":5,"bestNumber":6}"#,
 		);
 	}
+
+	#[test]
+	fn should_serialize_peer_reputation() {
+		assert_eq!(
+			::serde_json::to_string(&PeerReputation {
+				peer_id: "2".into(),
+				reputation: -100,
+				banned: false,
+			}).unwrap(),
+			r#"{"peerId":"2","reputation":-100,"banned":false}"#,
+		);
+	}
 }
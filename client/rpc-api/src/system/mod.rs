@@ -25,7 +25,9 @@ use futures::{future::BoxFuture, compat::Compat};
 
 use self::error::Result as SystemResult;
 
-pub use self::helpers::{Properties, SystemInfo, Health, PeerInfo, NodeRole};
+pub use self::helpers::{
+	Properties, SystemInfo, Health, SyncState, PeerInfo, PeerReputation, NodeRole,
+};
 pub use self::gen_client::Client as SystemClient;
 
 /// Substrate system RPC API
@@ -55,6 +57,12 @@ pub trait SystemApi<Hash, Number> {
 	#[rpc(name = "system_health", returns = "Health")]
 	fn system_health(&self) -> Receiver<Health>;
 
+	/// Return the state of the sync of the node.
+	///
+	/// Compare `currentBlock` over time to tell a syncing node from a stalled one.
+	#[rpc(name = "system_syncState", returns = "SyncState<Number>")]
+	fn system_sync_state(&self) -> Receiver<SyncState<Number>>;
+
 	/// Returns currently connected peers
 	#[rpc(name = "system_peers", returns = "Vec<PeerInfo<Hash, Number>>")]
 	fn system_peers(&self) -> Receiver<Vec<PeerInfo<Hash, Number>>>;
@@ -81,6 +89,31 @@ pub trait SystemApi<Hash, Number> {
 	fn system_remove_reserved_peer(&self, peer_id: String)
 		-> Compat<BoxFuture<'static, Result<(), jsonrpc_core::Error>>>;
 
+	/// Returns the PeerIds of the reserved peers.
+	#[rpc(name = "system_reservedPeers", returns = "Vec<String>")]
+	fn system_reserved_peers(&self) -> Receiver<Vec<String>>;
+
+	/// Switches the reserved-only mode. In reserved-only mode, the node disconnects from the
+	/// peers that are not reserved and only connects to and accepts reserved peers.
+	#[rpc(name = "system_setReservedOnly", returns = "()")]
+	fn system_set_reserved_only(&self, reserved_only: bool) -> Receiver<()>;
+
+	/// Returns the reputations of the peers known to the network.
+	#[rpc(name = "system_peerReputations", returns = "Vec<PeerReputation>")]
+	fn system_peer_reputations(&self) -> Receiver<Vec<PeerReputation>>;
+
+	/// Bans a peer: disconnects from it and refuses to connect to it until it is unbanned.
+	/// The string should encode only the PeerId e.g. `QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV`.
+	#[rpc(name = "system_banPeer", returns = "()")]
+	fn system_ban_peer(&self, peer_id: String)
+		-> Compat<BoxFuture<'static, Result<(), jsonrpc_core::Error>>>;
+
+	/// Lifts the ban of a peer and resets its reputation. The string should encode only
+	/// the PeerId.
+	#[rpc(name = "system_unbanPeer", returns = "()")]
+	fn system_unban_peer(&self, peer_id: String)
+		-> Compat<BoxFuture<'static, Result<(), jsonrpc_core::Error>>>;
+
 	/// Returns the roles the node is running as.
 	#[rpc(name = "system_nodeRoles", returns = "Vec<NodeRole>")]
 	fn system_node_roles(&self) -> Receiver<Vec<NodeRole>>;
@@ -18,6 +18,8 @@
 
 #![warn(missing_docs)]
 
+mod middleware;
+
 use std::io;
 use jsonrpc_core::IoHandlerExtension;
 use log::error;
@@ -30,21 +32,40 @@ const MAX_PAYLOAD: usize = 15 * 1024 * 1024;
 const WS_MAX_CONNECTIONS: usize = 100;
 
 /// The RPC IoHandler containing all requested APIs.
-pub type RpcHandler<T> = pubsub::PubSubHandler<T>;
+pub type RpcHandler<T> = pubsub::PubSubHandler<T, RpcMiddleware>;
 
 pub use self::inner::*;
+pub use self::middleware::{RpcMetrics, RpcMiddleware};
 
-/// Construct rpc `IoHandler`
+/// Construct rpc `IoHandler`, leaving out the methods named in `denied_methods`.
+///
+/// Every call goes through `middleware`, which only labels the calls of the methods registered
+/// in the handler.
 pub fn rpc_handler<M: PubSubMetadata>(
-	extension: impl IoHandlerExtension<M>
+	extension: impl IoHandlerExtension<M>,
+	denied_methods: &[&str],
+	middleware: RpcMiddleware,
 ) -> RpcHandler<M> {
-	let mut io = pubsub::PubSubHandler::default();
-	extension.augment(&mut io);
+	let mut all = jsonrpc_core::MetaIoHandler::<M>::default();
+	extension.augment(&mut all);
+
+	let mut methods = all.iter()
+		.map(|(name, _)| name.clone())
+		.filter(|name| !denied_methods.contains(&name.as_str()))
+		.collect::<Vec<String>>();
+	methods.sort();
+
+	let middleware = middleware.with_methods(
+		methods.iter().cloned().chain(std::iter::once("rpc_methods".to_owned()))
+	);
+	let mut io = pubsub::PubSubHandler::new(jsonrpc_core::MetaIoHandler::with_middleware(middleware));
+	io.extend_with(all.iter()
+		.filter(|(name, _)| !denied_methods.contains(&name.as_str()))
+		.map(|(name, method)| (name.clone(), method.clone()))
+	);
 
 	// add an endpoint to list all available methods.
-	let mut methods = io.iter().map(|x| x.0.clone()).collect::<Vec<String>>();
 	io.add_method("rpc_methods", {
-		methods.sort();
 		let methods = serde_json::to_value(&methods)
 			.expect("Serialization of Vec<String> is infallible; qed");
 
@@ -64,6 +85,8 @@ mod inner {
 	pub type HttpServer = http::Server;
 	/// Type alias for ws server
 	pub type WsServer = ws::Server;
+	/// Type alias for ipc server
+	pub type IpcServer = ipc::Server;
 
 	/// Start HTTP server listening on given address.
 	///
@@ -71,12 +94,13 @@ mod inner {
 	pub fn start_http<M: pubsub::PubSubMetadata + Default>(
 		addr: &std::net::SocketAddr,
 		cors: Option<&Vec<String>>,
+		allowed_hosts: Option<&Vec<String>>,
 		io: RpcHandler<M>,
 	) -> io::Result<http::Server> {
 		http::ServerBuilder::new(io)
 			.threads(4)
 			.health_api(("/health", "system_health"))
-			.allowed_hosts(hosts_filtering(cors.is_some()))
+			.allowed_hosts(map_hosts(allowed_hosts))
 			.rest_api(if cors.is_some() {
 				http::RestApi::Secure
 			} else {
@@ -94,13 +118,14 @@ mod inner {
 		addr: &std::net::SocketAddr,
 		max_connections: Option<usize>,
 		cors: Option<&Vec<String>>,
+		allowed_hosts: Option<&Vec<String>>,
 		io: RpcHandler<M>,
 	) -> io::Result<ws::Server> {
 		ws::ServerBuilder::with_meta_extractor(io, |context: &ws::RequestContext| context.sender().into())
 			.max_payload(MAX_PAYLOAD)
 			.max_connections(max_connections.unwrap_or(WS_MAX_CONNECTIONS))
 			.allowed_origins(map_cors(cors))
-			.allowed_hosts(hosts_filtering(cors.is_some()))
+			.allowed_hosts(map_hosts(allowed_hosts))
 			.start(addr)
 			.map_err(|err| match err {
 				ws::Error::Io(io) => io,
@@ -112,24 +137,84 @@ mod inner {
 			})
 	}
 
+	/// Start IPC server listening on given path.
+	///
+	/// The path is a unix domain socket, or a named pipe on Windows.
+	///
+	/// **Note**: Only available if `not(target_os = "unknown")`.
+	pub fn start_ipc<M: pubsub::PubSubMetadata + From<jsonrpc_core::futures::sync::mpsc::Sender<String>>> (
+		path: &str,
+		io: RpcHandler<M>,
+	) -> io::Result<ipc::Server> {
+		ipc::ServerBuilder::with_meta_extractor(io, |context: &ipc::RequestContext| context.sender.clone().into())
+			.start(path)
+	}
+
 	fn map_cors<T: for<'a> From<&'a str>>(
 		cors: Option<&Vec<String>>
 	) -> http::DomainsValidation<T> {
 		cors.map(|x| x.iter().map(AsRef::as_ref).map(Into::into).collect::<Vec<_>>()).into()
 	}
 
-	fn hosts_filtering(enable: bool) -> http::DomainsValidation<http::Host> {
-		if enable {
-			// NOTE The listening address is whitelisted by default.
-			// Setting an empty vector here enables the validation
-			// and allows only the listening address.
-			http::DomainsValidation::AllowOnly(vec![])
-		} else {
-			http::DomainsValidation::Disabled
-		}
+	fn map_hosts(allowed_hosts: Option<&Vec<String>>) -> http::DomainsValidation<http::Host> {
+		// NOTE The listening address is whitelisted by default.
+		// An empty vector enables the validation and allows only the listening address.
+		allowed_hosts.map(|x| x.iter().map(AsRef::as_ref).map(Into::into).collect::<Vec<_>>()).into()
 	}
 }
 
 #[cfg(target_os = "unknown")]
 mod inner {
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+	use jsonrpc_core::{MetaIoHandler, Value};
+
+	#[derive(Clone, Default)]
+	struct Metadata;
+
+	impl jsonrpc_core::Metadata for Metadata {}
+
+	impl PubSubMetadata for Metadata {
+		fn session(&self) -> Option<Arc<pubsub::Session>> {
+			None
+		}
+	}
+
+	fn handler(denied_methods: &[&str]) -> RpcHandler<Metadata> {
+		let mut extension = MetaIoHandler::<Metadata>::default();
+		extension.add_method("safe_method", |_| Ok(Value::Bool(true)));
+		extension.add_method("unsafe_method", |_| Ok(Value::Bool(true)));
+		rpc_handler(extension, denied_methods, RpcMiddleware::default())
+	}
+
+	fn call(io: &RpcHandler<Metadata>, method: &str) -> serde_json::Value {
+		let request = format!(r#"{{"jsonrpc":"2.0","method":"{}","params":[],"id":1}}"#, method);
+		serde_json::from_str(&io.handle_request_sync(&request, Metadata).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn denied_methods_are_not_served() {
+		let io = handler(&["unsafe_method"]);
+
+		assert_eq!(call(&io, "safe_method")["result"], Value::Bool(true));
+		assert_eq!(call(&io, "unsafe_method")["error"]["code"], -32601);
+		assert!(io.iter().all(|(name, _)| name != "unsafe_method"));
+	}
+
+	#[test]
+	fn denied_methods_are_not_listed() {
+		let methods = |io: &RpcHandler<Metadata>| {
+			call(io, "rpc_methods")["result"]["methods"].clone()
+		};
+
+		assert_eq!(methods(&handler(&["unsafe_method"])), serde_json::json!(["safe_method"]));
+		assert_eq!(
+			methods(&handler(&[])),
+			serde_json::json!(["safe_method", "unsafe_method"]),
+		);
+	}
+}
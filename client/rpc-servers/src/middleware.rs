@@ -0,0 +1,241 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Middleware recording metrics of the RPC calls and logging the slow ones.
+
+use std::{collections::HashSet, sync::Arc, time::{Duration, Instant}};
+use jsonrpc_core::{
+	Call, FutureResponse, Metadata, Middleware, Output,
+	futures::{Future, future::Either},
+};
+use log::warn;
+use prometheus_endpoint::{
+	CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, U64,
+	exponential_buckets, register,
+};
+
+/// Label of the calls to methods which are not registered in the handler.
+///
+/// The method names come from the clients, labeling the metrics by any of them would let a client
+/// create series without bound.
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// Metrics of the RPC calls, labeled by method.
+#[derive(Clone)]
+pub struct RpcMetrics {
+	calls_started: CounterVec<U64>,
+	calls_finished: CounterVec<U64>,
+	call_time: HistogramVec,
+}
+
+impl RpcMetrics {
+	/// Create and register the metrics in `registry`.
+	pub fn new(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			calls_started: register(CounterVec::new(
+				Opts::new(
+					"rpc_calls_started",
+					"Number of RPC calls received",
+				),
+				&["method"]
+			)?, registry)?,
+			calls_finished: register(CounterVec::new(
+				Opts::new(
+					"rpc_calls_finished",
+					"Number of RPC calls answered, by whether the answer is an error",
+				),
+				&["method", "is_error"]
+			)?, registry)?,
+			call_time: register(HistogramVec::new(
+				HistogramOpts::new(
+					"rpc_calls_time",
+					"Time taken to answer RPC calls, in seconds",
+				).buckets(exponential_buckets(0.001, 4.0, 9)?),
+				&["method"]
+			)?, registry)?,
+		})
+	}
+}
+
+/// Middleware of the RPC servers, recording the metrics of the calls and logging the ones slower
+/// than a threshold.
+#[derive(Clone, Default)]
+pub struct RpcMiddleware {
+	metrics: Option<RpcMetrics>,
+	slow_call_threshold: Option<Duration>,
+	methods: Arc<HashSet<String>>,
+}
+
+impl RpcMiddleware {
+	/// Create a new middleware. Calls are only logged if `slow_call_threshold` is set.
+	pub fn new(metrics: Option<RpcMetrics>, slow_call_threshold: Option<Duration>) -> Self {
+		RpcMiddleware { metrics, slow_call_threshold, methods: Default::default() }
+	}
+
+	/// Set the methods registered in the handler. Calls to other methods are recorded and logged
+	/// as calls to an `unknown` method.
+	pub fn with_methods(mut self, methods: impl IntoIterator<Item = String>) -> Self {
+		self.methods = Arc::new(methods.into_iter().collect());
+		self
+	}
+}
+
+impl<M: Metadata> Middleware<M> for RpcMiddleware {
+	type Future = FutureResponse;
+	type CallFuture = Box<dyn Future<Item = Option<Output>, Error = ()> + Send>;
+
+	fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X> where
+		F: Fn(Call, M) -> X + Send + Sync,
+		X: Future<Item = Option<Output>, Error = ()> + Send + 'static,
+	{
+		if self.metrics.is_none() && self.slow_call_threshold.is_none() {
+			return Either::B(next(call, meta))
+		}
+
+		let method = match &call {
+			Call::MethodCall(call) => &call.method,
+			Call::Notification(notification) => &notification.method,
+			Call::Invalid { .. } => return Either::B(next(call, meta)),
+		};
+		let method = if self.methods.contains(method) {
+			method.clone()
+		} else {
+			UNKNOWN_METHOD.to_owned()
+		};
+
+		if let Some(metrics) = &self.metrics {
+			metrics.calls_started.with_label_values(&[&method]).inc();
+		}
+
+		let metrics = self.metrics.clone();
+		let slow_call_threshold = self.slow_call_threshold;
+		let started = Instant::now();
+		Either::A(Box::new(next(call, meta).map(move |output| {
+			let elapsed = started.elapsed();
+			if let Some(metrics) = metrics {
+				let is_error = match &output {
+					Some(Output::Failure(_)) => "true",
+					_ => "false",
+				};
+				metrics.calls_finished.with_label_values(&[&method, is_error]).inc();
+				metrics.call_time.with_label_values(&[&method]).observe(elapsed.as_secs_f64());
+			}
+
+			if slow_call_threshold.map_or(false, |threshold| elapsed >= threshold) {
+				warn!(target: "rpc", "Slow RPC call {} took {:?}", method, elapsed);
+			}
+
+			output
+		})))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{Once, atomic::{AtomicUsize, Ordering}};
+	use jsonrpc_core::{Error, MetaIoHandler, Value};
+
+	/// Number of slow call warnings for `test_slow`, counted by `CaptureLogger`.
+	static SLOW_WARNINGS: AtomicUsize = AtomicUsize::new(0);
+	/// Number of slow call warnings for `test_ok`, counted by `CaptureLogger`.
+	static FAST_WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+	struct CaptureLogger;
+
+	impl log::Log for CaptureLogger {
+		fn enabled(&self, metadata: &log::Metadata) -> bool {
+			metadata.target() == "rpc"
+		}
+
+		fn log(&self, record: &log::Record) {
+			if !self.enabled(record.metadata()) {
+				return
+			}
+			let message = record.args().to_string();
+			if message.starts_with("Slow RPC call test_slow took ") {
+				SLOW_WARNINGS.fetch_add(1, Ordering::SeqCst);
+			} else if message.starts_with("Slow RPC call test_ok took ") {
+				FAST_WARNINGS.fetch_add(1, Ordering::SeqCst);
+			}
+		}
+
+		fn flush(&self) {}
+	}
+
+	fn handler(middleware: RpcMiddleware) -> MetaIoHandler<(), RpcMiddleware> {
+		let mut io = MetaIoHandler::with_middleware(middleware.with_methods(
+			vec!["test_ok".to_owned(), "test_err".to_owned(), "test_slow".to_owned()],
+		));
+		io.add_method("test_ok", |_| Ok(Value::Bool(true)));
+		io.add_method("test_err", |_| Err(Error::internal_error()));
+		io.add_method("test_slow", |_| {
+			std::thread::sleep(Duration::from_millis(50));
+			Ok(Value::Null)
+		});
+		io
+	}
+
+	fn call(io: &MetaIoHandler<(), RpcMiddleware>, method: &str) {
+		let request = format!(r#"{{"jsonrpc":"2.0","method":"{}","params":[],"id":1}}"#, method);
+		assert!(io.handle_request_sync(&request, ()).is_some());
+	}
+
+	#[test]
+	fn calls_are_counted_by_known_method() {
+		let registry = Registry::new();
+		let metrics = RpcMetrics::new(&registry).unwrap();
+		let io = handler(RpcMiddleware::new(Some(metrics.clone()), None));
+
+		call(&io, "test_ok");
+		call(&io, "test_ok");
+		call(&io, "test_err");
+		call(&io, "no_such_method");
+		call(&io, "another_missing_method");
+
+		let started = |method| metrics.calls_started.with_label_values(&[method]).get();
+		let finished = |method, is_error| {
+			metrics.calls_finished.with_label_values(&[method, is_error]).get()
+		};
+		assert_eq!(started("test_ok"), 2);
+		assert_eq!(finished("test_ok", "false"), 2);
+		assert_eq!(finished("test_ok", "true"), 0);
+		assert_eq!(started("test_err"), 1);
+		assert_eq!(finished("test_err", "true"), 1);
+		assert_eq!(metrics.call_time.with_label_values(&["test_ok"]).get_sample_count(), 2);
+
+		// calls to methods which don't exist share a single label.
+		assert_eq!(started(UNKNOWN_METHOD), 2);
+		assert_eq!(finished(UNKNOWN_METHOD, "true"), 2);
+		assert_eq!(started("no_such_method"), 0);
+	}
+
+	#[test]
+	fn slow_calls_are_logged() {
+		static INIT: Once = Once::new();
+		INIT.call_once(|| {
+			log::set_logger(&CaptureLogger).unwrap();
+			log::set_max_level(log::LevelFilter::Warn);
+		});
+
+		let io = handler(RpcMiddleware::new(None, Some(Duration::from_millis(20))));
+		call(&io, "test_ok");
+		call(&io, "test_slow");
+
+		assert_eq!(SLOW_WARNINGS.load(Ordering::SeqCst), 1);
+		assert_eq!(FAST_WARNINGS.load(Ordering::SeqCst), 0);
+	}
+}
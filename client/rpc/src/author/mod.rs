@@ -139,6 +139,10 @@ impl<P, Client> AuthorApi<TxHash<P>, BlockHash<P>> for Author<P, Client>
 		Ok(self.pool.ready().map(|tx| tx.data().encode().into()).collect())
 	}
 
+	fn future_extrinsics(&self) -> Result<Vec<Bytes>> {
+		Ok(self.pool.futures().into_iter().map(|tx| tx.data().encode().into()).collect())
+	}
+
 	fn remove_extrinsic(
 		&self,
 		bytes_or_hash: Vec<hash::ExtrinsicOrHash<TxHash<P>>>,
@@ -163,7 +167,7 @@ impl<P, Client> AuthorApi<TxHash<P>, BlockHash<P>> for Author<P, Client>
 	}
 
 	fn watch_extrinsic(&self,
-		_metadata: Self::Metadata,
+		metadata: Self::Metadata,
 		subscriber: Subscriber<TransactionStatus<TxHash<P>, BlockHash<P>>>,
 		xt: Bytes,
 	) {
@@ -182,6 +186,7 @@ impl<P, Client> AuthorApi<TxHash<P>, BlockHash<P>> for Author<P, Client>
 		};
 
 		let subscriptions = self.subscriptions.clone();
+		let connection = metadata.subscriptions().clone();
 		let future = ready(submit())
 			.and_then(|res| res)
 			// convert the watcher into a `Stream`
@@ -190,7 +195,7 @@ impl<P, Client> AuthorApi<TxHash<P>, BlockHash<P>> for Author<P, Client>
 			// start a new subscrition
 			.map(move |result| match result {
 				Ok(watcher) => {
-					subscriptions.add(subscriber, move |sink| {
+					subscriptions.add(&connection, subscriber, move |sink| {
 						sink
 							.sink_map_err(|_| unimplemented!())
 							.send_all(Compat::new(watcher))
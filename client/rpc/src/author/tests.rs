@@ -179,6 +179,26 @@ fn should_return_pending_extrinsics() {
 	);
 }
 
+#[test]
+fn should_return_and_remove_future_extrinsics() {
+	let setup = TestSetup::default();
+	let p = setup.author();
+
+	// nonce 0 is missing, so this one waits in the future queue
+	let ex = uxt(AccountKeyring::Alice, 1);
+	let hash = p.submit_extrinsic(ex.encode().into()).wait().unwrap();
+	assert_matches!(p.pending_extrinsics(), Ok(ref pending) if pending.is_empty());
+	assert_matches!(
+		p.future_extrinsics(),
+		Ok(ref expected) if *expected == vec![Bytes(ex.encode())]
+	);
+
+	let removed = p.remove_extrinsic(vec![hash::ExtrinsicOrHash::Hash(hash)]).unwrap();
+
+	assert_eq!(removed, vec![hash]);
+	assert_eq!(setup.pool.status().future, 0);
+}
+
 #[test]
 fn should_remove_extrinsics() {
 	let setup = TestSetup::default();
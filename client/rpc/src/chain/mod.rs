@@ -27,10 +27,10 @@ use futures::{future, StreamExt, TryStreamExt};
 use log::warn;
 use rpc::{
 	Result as RpcResult,
-	futures::{stream, Future, Sink, Stream},
+	futures::{stream, Future, Sink, Stream, future::{join_all, result}},
 };
 
-use sc_rpc_api::Subscriptions;
+use sc_rpc_api::{ConnectionSubscriptions, Subscriptions};
 use sc_client::{
 	self, BlockchainEvents,
 	light::{fetcher::Fetcher, blockchain::RemoteBlockchain},
@@ -39,7 +39,7 @@ use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use sp_rpc::{number::NumberOrHex, list::ListOrValue};
 use sp_runtime::{
 	generic::{BlockId, SignedBlock},
-	traits::{Block as BlockT, Header, NumberFor},
+	traits::{Block as BlockT, Header, NumberFor, One, SaturatedConversion},
 };
 
 use self::error::{Result, Error, FutureResult};
@@ -95,15 +95,84 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 		Ok(self.client().info().finalized_hash)
 	}
 
+	/// Get headers of the blocks numbered `from` to `to` (both included) in the canon chain.
+	fn headers_in_range(
+		&self,
+		from: NumberOrHex<NumberFor<Block>>,
+		to: NumberOrHex<NumberFor<Block>>,
+	) -> Result<Vec<Block::Header>> {
+		let (from, to) = (from.to_number()?, to.to_number()?);
+		if to < from {
+			return Err(Error::Other(format!("Block #{} is before block #{}.", to, from)))
+		}
+
+		let count = (to - from).saturated_into::<u64>().saturating_add(1);
+		if count > MAX_HEADERS_IN_RANGE as u64 {
+			return Err(Error::Other(format!(
+				"At most {} headers can be queried at once, {} requested.",
+				MAX_HEADERS_IN_RANGE,
+				count,
+			)))
+		}
+
+		self.canon_headers(from, count)
+	}
+
+	/// Get up to `count` blocks of the canon chain, starting at block number `from`.
+	fn blocks_paged(
+		&self,
+		from: NumberOrHex<NumberFor<Block>>,
+		count: u32,
+	) -> FutureResult<Vec<SignedBlock<Block>>> {
+		if count > MAX_BLOCKS_PAGE_SIZE {
+			return Box::new(result(Err(Error::Other(format!(
+				"At most {} blocks can be queried at once, {} requested.",
+				MAX_BLOCKS_PAGE_SIZE,
+				count,
+			)))))
+		}
+
+		let headers = match from.to_number()
+			.map_err(Into::into)
+			.and_then(|from| self.canon_headers(from, count as u64))
+		{
+			Ok(headers) => headers,
+			Err(err) => return Box::new(result(Err(err))),
+		};
+
+		let blocks = headers.into_iter()
+			.map(|header| self.block(Some(header.hash())))
+			.collect::<Vec<_>>();
+		Box::new(join_all(blocks).map(|blocks| blocks.into_iter().filter_map(|block| block).collect()))
+	}
+
+	/// Get headers of up to `count` canon chain blocks, starting at block number `from` and
+	/// stopping at the best block.
+	fn canon_headers(&self, from: NumberFor<Block>, count: u64) -> Result<Vec<Block::Header>> {
+		let best_number = self.client().info().best_number;
+		let mut headers = Vec::new();
+		let mut number = from;
+		while (headers.len() as u64) < count && number <= best_number {
+			match self.client().header(BlockId::number(number)).map_err(client_err)? {
+				Some(header) => headers.push(header),
+				None => break,
+			}
+			number += One::one();
+		}
+
+		Ok(headers)
+	}
+
 	/// All new head subscription
 	fn subscribe_all_heads(
 		&self,
-		_metadata: crate::metadata::Metadata,
+		metadata: crate::metadata::Metadata,
 		subscriber: Subscriber<Block::Header>,
 	) {
 		subscribe_headers(
 			self.client(),
 			self.subscriptions(),
+			metadata.subscriptions(),
 			subscriber,
 			|| self.client().info().best_hash,
 			|| self.client().import_notification_stream()
@@ -124,12 +193,13 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 	/// New best head subscription
 	fn subscribe_new_heads(
 		&self,
-		_metadata: crate::metadata::Metadata,
+		metadata: crate::metadata::Metadata,
 		subscriber: Subscriber<Block::Header>,
 	) {
 		subscribe_headers(
 			self.client(),
 			self.subscriptions(),
+			metadata.subscriptions(),
 			subscriber,
 			|| self.client().info().best_hash,
 			|| self.client().import_notification_stream()
@@ -151,12 +221,13 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 	/// Finalized head subscription
 	fn subscribe_finalized_heads(
 		&self,
-		_metadata: crate::metadata::Metadata,
+		metadata: crate::metadata::Metadata,
 		subscriber: Subscriber<Block::Header>,
 	) {
 		subscribe_headers(
 			self.client(),
 			self.subscriptions(),
+			metadata.subscriptions(),
 			subscriber,
 			|| self.client().info().finalized_hash,
 			|| self.client().finality_notification_stream()
@@ -252,6 +323,22 @@ impl<Block, Client> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, Signe
 		self.backend.finalized_head()
 	}
 
+	fn headers_in_range(
+		&self,
+		from: NumberOrHex<NumberFor<Block>>,
+		to: NumberOrHex<NumberFor<Block>>,
+	) -> Result<Vec<Block::Header>> {
+		self.backend.headers_in_range(from, to)
+	}
+
+	fn blocks_paged(
+		&self,
+		from: NumberOrHex<NumberFor<Block>>,
+		count: u32,
+	) -> FutureResult<Vec<SignedBlock<Block>>> {
+		self.backend.blocks_paged(from, count)
+	}
+
 	fn subscribe_all_heads(&self, metadata: Self::Metadata, subscriber: Subscriber<Block::Header>) {
 		self.backend.subscribe_all_heads(metadata, subscriber)
 	}
@@ -281,6 +368,7 @@ impl<Block, Client> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, Signe
 fn subscribe_headers<Block, Client, F, G, S, ERR>(
 	client: &Arc<Client>,
 	subscriptions: &Subscriptions,
+	connection: &ConnectionSubscriptions,
 	subscriber: Subscriber<Block::Header>,
 	best_block_hash: G,
 	stream: F,
@@ -292,7 +380,7 @@ fn subscribe_headers<Block, Client, F, G, S, ERR>(
 	ERR: ::std::fmt::Debug,
 	S: Stream<Item=Block::Header, Error=ERR> + Send + 'static,
 {
-	subscriptions.add(subscriber, |sink| {
+	subscriptions.add(connection, subscriber, |sink| {
 		// send current head right at the start.
 		let header = client.header(BlockId::Hash(best_block_hash()))
 			.map_err(client_err)
@@ -23,6 +23,7 @@ use substrate_test_runtime_client::{
 };
 use sp_rpc::list::ListOrValue;
 use sc_block_builder::BlockBuilderProvider;
+use sc_rpc_api::SubscriptionLimits;
 
 #[test]
 fn should_return_header() {
@@ -190,6 +191,67 @@ fn should_return_finalized_hash() {
 	);
 }
 
+#[test]
+fn should_return_headers_in_range() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let remote = core.executor();
+
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let api = new_full(client.clone(), Subscriptions::new(Arc::new(remote)));
+
+	let mut hashes = vec![client.genesis_hash()];
+	for _ in 0..3 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		hashes.push(block.hash());
+		client.import(BlockOrigin::Own, block).unwrap();
+	}
+
+	assert_matches!(
+		api.headers_in_range(1u64.into(), 2u64.into()),
+		Ok(ref headers) if headers.iter().map(|h| h.hash()).collect::<Vec<_>>() == &hashes[1..3]
+	);
+
+	// The range is cut at the best block.
+	assert_matches!(
+		api.headers_in_range(0u64.into(), 10u64.into()),
+		Ok(ref headers) if headers.iter().map(|h| h.hash()).collect::<Vec<_>>() == hashes
+	);
+	assert_matches!(api.headers_in_range(5u64.into(), 10u64.into()), Ok(ref headers) if headers.is_empty());
+
+	assert_matches!(api.headers_in_range(2u64.into(), 1u64.into()), Err(Error::Other(_)));
+	assert_matches!(
+		api.headers_in_range(0u64.into(), (MAX_HEADERS_IN_RANGE as u64).into()),
+		Err(Error::Other(_))
+	);
+}
+
+#[test]
+fn should_return_blocks_paged() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let remote = core.executor();
+
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let api = new_full(client.clone(), Subscriptions::new(Arc::new(remote)));
+
+	let mut hashes = vec![client.genesis_hash()];
+	for _ in 0..3 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		hashes.push(block.hash());
+		client.import(BlockOrigin::Own, block).unwrap();
+	}
+
+	let page = api.blocks_paged(0u64.into(), 2).wait().unwrap();
+	assert_eq!(page.iter().map(|b| b.block.hash()).collect::<Vec<_>>(), &hashes[..2]);
+
+	// The last page is shorter.
+	let page = api.blocks_paged(2u64.into(), 2).wait().unwrap();
+	assert_eq!(page.iter().map(|b| b.block.hash()).collect::<Vec<_>>(), &hashes[2..]);
+	let page = api.blocks_paged(4u64.into(), 2).wait().unwrap();
+	assert!(page.is_empty());
+
+	assert_matches!(api.blocks_paged(0u64.into(), MAX_BLOCKS_PAGE_SIZE + 1).wait(), Err(Error::Other(_)));
+}
+
 #[test]
 fn should_notify_about_latest_block() {
 	let mut core = ::tokio::runtime::Runtime::new().unwrap();
@@ -219,6 +281,30 @@ fn should_notify_about_latest_block() {
 	assert_eq!(core.block_on(next.into_future()).unwrap().0, None);
 }
 
+#[test]
+fn should_reject_subscriptions_over_the_connection_limit() {
+	let mut core = ::tokio::runtime::Runtime::new().unwrap();
+	let remote = core.executor();
+	let (first, first_id, _first_transport) = Subscriber::new_test("test");
+	let (second, second_id, _second_transport) = Subscriber::new_test("test");
+	let (other, other_id, _other_transport) = Subscriber::new_test("test");
+
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let limits = SubscriptionLimits { max_subscriptions_per_connection: 1, ..Default::default() };
+	let api = new_full(client.clone(), Subscriptions::with_limits(Arc::new(remote), limits));
+
+	let connection = crate::Metadata::default();
+	api.subscribe_all_heads(connection.clone(), first);
+	api.subscribe_new_heads(connection.clone(), second);
+	// other connections have their own limit.
+	api.subscribe_all_heads(Default::default(), other);
+
+	assert_eq!(core.block_on(first_id), Ok(Ok(SubscriptionId::Number(1))));
+	assert_matches!(core.block_on(second_id), Ok(Err(_)));
+	assert_matches!(core.block_on(other_id), Ok(Ok(_)));
+	assert_eq!(connection.subscriptions().active(), 1);
+}
+
 #[test]
 fn should_notify_about_best_block() {
 	let mut core = ::tokio::runtime::Runtime::new().unwrap();
@@ -22,12 +22,55 @@
 
 mod metadata;
 
-pub use sc_rpc_api::Subscriptions;
+pub use sc_rpc_api::{
+	ConnectionSubscriptions, NotificationDropPolicy, SubscriptionLimits, Subscriptions, UNSAFE_METHODS,
+};
 pub use self::metadata::Metadata;
 pub use rpc::IoHandlerExtension as RpcExtension;
 
+/// Two sets of RPC extensions, served together.
+///
+/// Lets a node add its own RPC modules to the extensions of the node it builds on.
+#[derive(Clone, Debug)]
+pub struct RpcExtensions<A, B>(pub A, pub B);
+
+impl<M, A, B> RpcExtension<M> for RpcExtensions<A, B> where
+	M: rpc::Metadata,
+	A: RpcExtension<M>,
+	B: RpcExtension<M>,
+{
+	fn augment<S: rpc::Middleware<M>>(self, handler: &mut rpc::MetaIoHandler<M, S>) {
+		self.0.augment(handler);
+		self.1.augment(handler);
+	}
+}
+
 pub mod author;
 pub mod chain;
 pub mod offchain;
 pub mod state;
 pub mod system;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn extension(method: &str, result: &str) -> rpc::IoHandler {
+		let mut io = rpc::IoHandler::new();
+		let result = rpc::Value::String(result.into());
+		io.add_method(method, move |_| Ok(result.clone()));
+		io
+	}
+
+	#[test]
+	fn rpc_extensions_are_served_together() {
+		let mut io = rpc::IoHandler::new();
+		RpcExtensions(extension("node_a", "a"), extension("chain_b", "b")).augment(&mut *io);
+
+		let request = |method: &str| io.handle_request_sync(
+			&format!(r#"{{"jsonrpc":"2.0","method":"{}","params":[],"id":1}}"#, method),
+		);
+		assert_eq!(request("node_a"), Some(r#"{"jsonrpc":"2.0","result":"a","id":1}"#.into()));
+		assert_eq!(request("chain_b"), Some(r#"{"jsonrpc":"2.0","result":"b","id":1}"#.into()));
+	}
+}
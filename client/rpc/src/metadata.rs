@@ -19,6 +19,7 @@ use std::sync::Arc;
 
 use jsonrpc_pubsub::{Session, PubSubMetadata};
 use rpc::futures::sync::mpsc;
+use sc_rpc_api::ConnectionSubscriptions;
 
 /// RPC Metadata.
 ///
@@ -28,6 +29,7 @@ use rpc::futures::sync::mpsc;
 #[derive(Default, Clone)]
 pub struct Metadata {
 	session: Option<Arc<Session>>,
+	subscriptions: ConnectionSubscriptions,
 }
 
 impl rpc::Metadata for Metadata {}
@@ -42,9 +44,15 @@ impl Metadata {
 	pub fn new(transport: mpsc::Sender<String>) -> Self {
 		Metadata {
 			session: Some(Arc::new(Session::new(transport))),
+			subscriptions: Default::default(),
 		}
 	}
 
+	/// Active subscriptions of the connection.
+	pub fn subscriptions(&self) -> &ConnectionSubscriptions {
+		&self.subscriptions
+	}
+
 	/// Create new `Metadata` for tests.
 	#[cfg(test)]
 	pub fn new_test() -> (mpsc::Receiver<String>, Self) {
@@ -37,7 +37,8 @@ use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
 use self::error::{Error, FutureResult};
 
 pub use sc_rpc_api::state::*;
-use sc_client_api::{ExecutorProvider, StorageProvider, BlockchainEvents, Backend};
+pub use sc_rpc_api::child_state::*;
+use sc_client_api::{ExecutorProvider, StorageProvider, BlockchainEvents, Backend, ProofProvider};
 use sp_blockchain::{HeaderMetadata, HeaderBackend};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
@@ -163,6 +164,20 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 		keys: Vec<StorageKey>,
 	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>>;
 
+	/// Query storage entries (by key) at a block.
+	fn query_storage_at(
+		&self,
+		keys: Vec<StorageKey>,
+		at: Option<Block::Hash>
+	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>>;
+
+	/// Returns proof of storage entries at a specific block's state.
+	fn read_proof(
+		&self,
+		block: Option<Block::Hash>,
+		keys: Vec<StorageKey>,
+	) -> FutureResult<ReadProof<Block::Hash>>;
+
 	/// New runtime version subscription
 	fn subscribe_runtime_version(
 		&self,
@@ -191,6 +206,24 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 		_meta: Option<crate::metadata::Metadata>,
 		id: SubscriptionId,
 	) -> RpcResult<bool>;
+
+	/// New child storage subscription
+	fn subscribe_child_storage(
+		&self,
+		_meta: crate::metadata::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		child_storage_key: StorageKey,
+		child_info: StorageKey,
+		child_type: u32,
+		keys: Option<Vec<StorageKey>>,
+	);
+
+	/// Unsubscribe from child storage subscription
+	fn unsubscribe_child_storage(
+		&self,
+		_meta: Option<crate::metadata::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
 }
 
 /// Create new state API that works on full node.
@@ -203,12 +236,12 @@ pub fn new_full<BE, Block: BlockT, Client>(
 		BE: Backend<Block> + 'static,
 		Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + HeaderBackend<Block>
 			+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
-			+ CallApiAt<Block, Error = sp_blockchain::Error>
+			+ CallApiAt<Block, Error = sp_blockchain::Error> + ProofProvider<Block>
 			+ ProvideRuntimeApi<Block> + Send + Sync + 'static,
 		Client::Api: Metadata<Block, Error = sp_blockchain::Error>,
 {
 	State {
-		backend: Box::new(self::state_full::FullState::new(client, subscriptions)),
+		backend: Arc::new(self::state_full::FullState::new(client, subscriptions)),
 	}
 }
 
@@ -229,7 +262,7 @@ pub fn new_light<BE, Block: BlockT, Client, F: Fetcher<Block>>(
 		F: Send + Sync + 'static,
 {
 	State {
-		backend: Box::new(self::state_light::LightState::new(
+		backend: Arc::new(self::state_light::LightState::new(
 			client,
 			subscriptions,
 			remote_blockchain,
@@ -240,7 +273,16 @@ pub fn new_light<BE, Block: BlockT, Client, F: Fetcher<Block>>(
 
 /// State API with subscriptions support.
 pub struct State<Block, Client> {
-	backend: Box<dyn StateBackend<Block, Client>>,
+	backend: Arc<dyn StateBackend<Block, Client>>,
+}
+
+impl<Block, Client> State<Block, Client> {
+	/// Child state API sharing the backend of this state API.
+	pub fn child_state(&self) -> ChildState<Block, Client> {
+		ChildState {
+			backend: self.backend.clone(),
+		}
+	}
 }
 
 impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
@@ -357,6 +399,18 @@ impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
 		self.backend.query_storage(from, to, keys)
 	}
 
+	fn query_storage_at(
+		&self,
+		keys: Vec<StorageKey>,
+		at: Option<Block::Hash>
+	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
+		self.backend.query_storage_at(keys, at)
+	}
+
+	fn read_proof(&self, keys: Vec<StorageKey>, block: Option<Block::Hash>) -> FutureResult<ReadProof<Block::Hash>> {
+		self.backend.read_proof(block, keys)
+	}
+
 	fn subscribe_storage(
 		&self,
 		meta: Self::Metadata,
@@ -387,6 +441,86 @@ impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
 	}
 }
 
+/// Child state API with subscriptions support.
+pub struct ChildState<Block, Client> {
+	backend: Arc<dyn StateBackend<Block, Client>>,
+}
+
+impl<Block, Client> ChildStateApi<Block::Hash> for ChildState<Block, Client>
+	where
+		Block: BlockT + 'static,
+		Client: Send + Sync + 'static,
+{
+	type Metadata = crate::metadata::Metadata;
+
+	fn storage_keys(
+		&self,
+		child_storage_key: StorageKey,
+		child_info: StorageKey,
+		child_type: u32,
+		key_prefix: StorageKey,
+		block: Option<Block::Hash>
+	) -> FutureResult<Vec<StorageKey>> {
+		self.backend.child_storage_keys(block, child_storage_key, child_info, child_type, key_prefix)
+	}
+
+	fn storage(
+		&self,
+		child_storage_key: StorageKey,
+		child_info: StorageKey,
+		child_type: u32,
+		key: StorageKey,
+		block: Option<Block::Hash>
+	) -> FutureResult<Option<StorageData>> {
+		self.backend.child_storage(block, child_storage_key, child_info, child_type, key)
+	}
+
+	fn storage_hash(
+		&self,
+		child_storage_key: StorageKey,
+		child_info: StorageKey,
+		child_type: u32,
+		key: StorageKey,
+		block: Option<Block::Hash>
+	) -> FutureResult<Option<Block::Hash>> {
+		self.backend.child_storage_hash(block, child_storage_key, child_info, child_type, key)
+	}
+
+	fn storage_size(
+		&self,
+		child_storage_key: StorageKey,
+		child_info: StorageKey,
+		child_type: u32,
+		key: StorageKey,
+		block: Option<Block::Hash>
+	) -> FutureResult<Option<u64>> {
+		self.backend.child_storage_size(block, child_storage_key, child_info, child_type, key)
+	}
+
+	fn subscribe_storage(
+		&self,
+		meta: Self::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		child_storage_key: StorageKey,
+		child_info: StorageKey,
+		child_type: u32,
+		keys: Option<Vec<StorageKey>>
+	) {
+		self.backend.subscribe_child_storage(
+			meta,
+			subscriber,
+			child_storage_key,
+			child_info,
+			child_type,
+			keys,
+		);
+	}
+
+	fn unsubscribe_storage(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
+		self.backend.unsubscribe_child_storage(meta, id)
+	}
+}
+
 fn client_err(err: sp_blockchain::Error) -> Error {
 	Error::Client(Box::new(err))
 }
@@ -38,9 +38,11 @@ use sp_runtime::{
 
 use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
 
-use super::{StateBackend, error::{FutureResult, Error, Result}, client_err, child_resolution_error};
+use super::{
+	StateBackend, ReadProof, error::{FutureResult, Error, Result}, client_err, child_resolution_error,
+};
 use std::marker::PhantomData;
-use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider};
+use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider, ProofProvider};
 
 /// Ranges to query in state_queryStorage.
 struct QueryStorageRange<Block: BlockT> {
@@ -218,7 +220,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
 		+ CallApiAt<Block, Error = sp_blockchain::Error> + ProvideRuntimeApi<Block>
-		+ Send + Sync + 'static,
+		+ ProofProvider<Block> + Send + Sync + 'static,
 	Client::Api: Metadata<Block, Error = sp_blockchain::Error>,
 {
 	fn call(
@@ -398,9 +400,46 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 		Box::new(result(call_fn()))
 	}
 
+	fn query_storage_at(
+		&self,
+		keys: Vec<StorageKey>,
+		at: Option<Block::Hash>
+	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
+		let call_fn = move || {
+			let block = self.block_or_best(at).map_err(client_err)?;
+			let id = BlockId::hash(block);
+			let changes = keys.into_iter()
+				.map(|key| self.client.storage(&id, &key).map(|data| (key, data)))
+				.collect::<ClientResult<Vec<_>>>()
+				.map_err(client_err)?;
+			Ok(vec![StorageChangeSet { block, changes }])
+		};
+		Box::new(result(call_fn()))
+	}
+
+	fn read_proof(
+		&self,
+		block: Option<Block::Hash>,
+		keys: Vec<StorageKey>,
+	) -> FutureResult<ReadProof<Block::Hash>> {
+		Box::new(result(
+			self.block_or_best(block)
+				.and_then(|block| {
+					self.client
+						.read_proof(
+							&BlockId::Hash(block),
+							&mut keys.iter().map(|key| key.0.as_ref()),
+						)
+						.map(|proof| proof.iter_nodes().map(|node| node.into()).collect())
+						.map(|proof| ReadProof { at: block, proof })
+				})
+				.map_err(client_err),
+		))
+	}
+
 	fn subscribe_runtime_version(
 		&self,
-		_meta: crate::metadata::Metadata,
+		meta: crate::metadata::Metadata,
 		subscriber: Subscriber<RuntimeVersion>,
 	) {
 		let stream = match self.client.storage_changes_notification_stream(
@@ -414,7 +453,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 			}
 		};
 
-		self.subscriptions.add(subscriber, |sink| {
+		self.subscriptions.add(meta.subscriptions(), subscriber, |sink| {
 			let version = self.runtime_version(None.into())
 				.map_err(Into::into)
 				.wait();
@@ -459,7 +498,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 
 	fn subscribe_storage(
 		&self,
-		_meta: crate::metadata::Metadata,
+		meta: crate::metadata::Metadata,
 		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
 		keys: Option<Vec<StorageKey>>,
 	) {
@@ -490,7 +529,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 				vec![Ok(Ok(StorageChangeSet { block, changes }))]
 			}).unwrap_or_default());
 
-		self.subscriptions.add(subscriber, |sink| {
+		self.subscriptions.add(meta.subscriptions(), subscriber, |sink| {
 			let stream = stream
 				.map(|(block, changes)| Ok::<_, ()>(Ok(StorageChangeSet {
 					block,
@@ -516,6 +555,80 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 	) -> RpcResult<bool> {
 		Ok(self.subscriptions.cancel(id))
 	}
+
+	fn subscribe_child_storage(
+		&self,
+		meta: crate::metadata::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		child_storage_key: StorageKey,
+		child_info: StorageKey,
+		child_type: u32,
+		keys: Option<Vec<StorageKey>>,
+	) {
+		if ChildInfo::resolve_child_info(child_type, &child_info.0[..]).is_none() {
+			let _ = subscriber.reject(client_err(child_resolution_error()).into());
+			return;
+		}
+
+		// no top-level keys, only the keys of the child trie (all of them if `keys` is `None`)
+		let stream = match self.client.storage_changes_notification_stream(
+			Some(&[][..]),
+			Some(&[(child_storage_key.clone(), keys.clone())][..]),
+		) {
+			Ok(stream) => stream,
+			Err(err) => {
+				let _ = subscriber.reject(client_err(err).into());
+				return;
+			},
+		};
+
+		// initial values
+		let initial = stream::iter_result(keys
+			.map(|keys| {
+				let block = self.client.info().best_hash;
+				let changes = keys
+					.into_iter()
+					.map(|key| self.child_storage(
+						Some(block.clone()).into(),
+						child_storage_key.clone(),
+						child_info.clone(),
+						child_type,
+						key.clone(),
+					)
+						.map(|val| (key.clone(), val))
+						.wait()
+						.unwrap_or_else(|_| (key, None))
+					)
+					.collect();
+				vec![Ok(Ok(StorageChangeSet { block, changes }))]
+			}).unwrap_or_default());
+
+		self.subscriptions.add(meta.subscriptions(), subscriber, |sink| {
+			let stream = stream
+				.map(move |(block, changes)| Ok::<_, ()>(Ok(StorageChangeSet {
+					block,
+					changes: changes.iter()
+						.filter_map(|(o_sk, k, v)| if o_sk == Some(&child_storage_key) {
+							Some((k.clone(),v.cloned()))
+						} else { None }).collect(),
+				})))
+				.compat();
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(initial.chain(stream))
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_child_storage(
+		&self,
+		_meta: Option<crate::metadata::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
 }
 
 /// Splits passed range into two subranges where:
@@ -53,7 +53,7 @@ use sp_core::{
 use sp_version::RuntimeVersion;
 use sp_runtime::{generic::BlockId, traits::{Block as BlockT, HashFor}};
 
-use super::{StateBackend, error::{FutureResult, Error}, client_err};
+use super::{StateBackend, ReadProof, error::{FutureResult, Error}, client_err};
 
 /// Storage data map of storage keys => (optional) storage value.
 type StorageMap = HashMap<StorageKey, Option<StorageData>>;
@@ -331,9 +331,39 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
 	}
 
+	fn query_storage_at(
+		&self,
+		keys: Vec<StorageKey>,
+		at: Option<Block::Hash>
+	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
+		let block = self.block_or_best(at);
+		Box::new(storage(
+			&*self.remote_blockchain,
+			self.fetcher.clone(),
+			block,
+			keys.iter().map(|key| key.0.clone()).collect(),
+		).boxed().compat().map(move |values| vec![StorageChangeSet {
+			block,
+			changes: keys.into_iter()
+				.map(|key| {
+					let value = values.get(&key).cloned().unwrap_or(None);
+					(key, value)
+				})
+				.collect(),
+		}]))
+	}
+
+	fn read_proof(
+		&self,
+		_block: Option<Block::Hash>,
+		_keys: Vec<StorageKey>,
+	) -> FutureResult<ReadProof<Block::Hash>> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
 	fn subscribe_storage(
 		&self,
-		_meta: crate::metadata::Metadata,
+		meta: crate::metadata::Metadata,
 		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
 		keys: Option<Vec<StorageKey>>
 	) {
@@ -347,7 +377,7 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 
 		let keys = keys.iter().cloned().collect::<HashSet<_>>();
 		let keys_to_check = keys.iter().map(|k| k.0.clone()).collect::<HashSet<_>>();
-		let subscription_id = self.subscriptions.add(subscriber, move |sink| {
+		let subscription_id = self.subscriptions.add(meta.subscriptions(), subscriber, move |sink| {
 			let fetcher = self.fetcher.clone();
 			let remote_blockchain = self.remote_blockchain.clone();
 			let storage_subscriptions = self.storage_subscriptions.clone();
@@ -454,12 +484,32 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 		Ok(true)
 	}
 
-	fn subscribe_runtime_version(
+	fn subscribe_child_storage(
 		&self,
 		_meta: crate::metadata::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		_child_storage_key: StorageKey,
+		_child_info: StorageKey,
+		_child_type: u32,
+		_keys: Option<Vec<StorageKey>>,
+	) {
+		let _ = subscriber.reject(client_err(ClientError::NotAvailableOnLightClient).into());
+	}
+
+	fn unsubscribe_child_storage(
+		&self,
+		_meta: Option<crate::metadata::Metadata>,
+		_id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(false)
+	}
+
+	fn subscribe_runtime_version(
+		&self,
+		meta: crate::metadata::Metadata,
 		subscriber: Subscriber<RuntimeVersion>,
 	) {
-		self.subscriptions.add(subscriber, move |sink| {
+		self.subscriptions.add(meta.subscriptions(), subscriber, move |sink| {
 			let fetcher = self.fetcher.clone();
 			let remote_blockchain = self.remote_blockchain.clone();
 			let version_subscriptions = self.version_subscriptions.clone();
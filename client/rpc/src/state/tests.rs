@@ -20,6 +20,8 @@ use self::error::Error;
 
 use std::sync::Arc;
 use assert_matches::assert_matches;
+use codec::Decode;
+use sp_runtime::generic::BlockId;
 use futures01::stream::Stream;
 use sp_core::{storage::{well_known_keys, ChildInfo}, ChangesTrieConfiguration};
 use sp_core::hash::H256;
@@ -76,6 +78,34 @@ fn should_return_storage() {
 
 }
 
+#[test]
+fn should_query_storage_at_and_return_read_proof() {
+	const KEY: &[u8] = b":mock";
+	const VALUE: &[u8] = b"hello world";
+
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let client = TestClientBuilder::new()
+		.add_extra_storage(KEY.to_vec(), VALUE.to_vec())
+		.build();
+	let genesis_hash = client.genesis_hash();
+	let api = new_full(Arc::new(client), Subscriptions::new(Arc::new(core.executor())));
+	let key = StorageKey(KEY.to_vec());
+	let missing_key = StorageKey(b":missing".to_vec());
+
+	assert_eq!(
+		api.query_storage_at(vec![key.clone(), missing_key.clone()], Some(genesis_hash)).wait().unwrap(),
+		vec![StorageChangeSet {
+			block: genesis_hash,
+			changes: vec![(key.clone(), Some(StorageData(VALUE.to_vec()))), (missing_key, None)],
+		}],
+	);
+
+	let read_proof = api.read_proof(vec![key], None).wait().unwrap();
+	assert_eq!(read_proof.at, genesis_hash);
+	assert!(!read_proof.proof.is_empty());
+	assert!(read_proof.proof.iter().any(|node| node.0.windows(VALUE.len()).any(|w| w == VALUE)));
+}
+
 #[test]
 fn should_return_child_storage() {
 	let (child_info, child_type) = CHILD_INFO.info();
@@ -124,6 +154,54 @@ fn should_return_child_storage() {
 	);
 }
 
+#[test]
+fn should_serve_child_state_api() {
+	let (child_info, child_type) = CHILD_INFO.info();
+	let child_info = StorageKey(child_info.to_vec());
+	let mut core = tokio::runtime::Runtime::new().unwrap();
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+	let child_key = StorageKey(
+		well_known_keys::CHILD_STORAGE_KEY_PREFIX.iter().chain(b"test").cloned().collect()
+	);
+	let key = StorageKey(b"key".to_vec());
+
+	{
+		let client = Arc::new(substrate_test_runtime_client::TestClientBuilder::new()
+			.add_child_storage("test", "key", CHILD_INFO, vec![42_u8])
+			.build());
+		let api = new_full(client, Subscriptions::new(Arc::new(core.executor()))).child_state();
+
+		assert_matches!(
+			api.storage(child_key.clone(), child_info.clone(), child_type, key.clone(), None).wait(),
+			Ok(Some(StorageData(ref d))) if d[0] == 42 && d.len() == 1
+		);
+		assert_eq!(
+			api.storage_keys(child_key.clone(), child_info.clone(), child_type, StorageKey(vec![]), None)
+				.wait()
+				.unwrap(),
+			vec![key.clone()],
+		);
+
+		api.subscribe_storage(
+			Default::default(),
+			subscriber,
+			child_key,
+			child_info,
+			child_type,
+			Some(vec![key]),
+		);
+
+		// assert id assigned
+		assert_eq!(core.block_on(id), Ok(Ok(SubscriptionId::Number(1))));
+	}
+
+	// assert initial values sent to transport
+	let (notification, next) = core.block_on(transport.into_future()).unwrap();
+	assert!(notification.is_some());
+	// no more notifications on this channel
+	assert_eq!(core.block_on(next.into_future()).unwrap().0, None);
+}
+
 #[test]
 fn should_call_contract() {
 	let core = tokio::runtime::Runtime::new().unwrap();
@@ -137,6 +215,20 @@ fn should_call_contract() {
 	)
 }
 
+#[test]
+fn should_call_runtime_api() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let genesis_hash = client.genesis_hash();
+	let api = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+
+	let result = api.call("Core_version".into(), Bytes(vec![]), Some(genesis_hash).into())
+		.wait()
+		.unwrap();
+	let version = sp_version::RuntimeVersion::decode(&mut &result.0[..]).unwrap();
+	assert_eq!(version, client.runtime_version_at(&BlockId::Hash(genesis_hash)).unwrap());
+}
+
 #[test]
 fn should_notify_about_storage_changes() {
 	let mut core = tokio::runtime::Runtime::new().unwrap();
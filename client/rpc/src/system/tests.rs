@@ -55,6 +55,15 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 						should_have_peers,
 					});
 				},
+				Request::SyncState(sender) => {
+					let _ = sender.send(SyncState {
+						starting_block: 1,
+						current_block: 2,
+						highest_block: if status.is_syncing { Some(10) } else { None },
+						peers: status.peers,
+						is_major_syncing: status.is_syncing,
+					});
+				},
 				Request::Peers(sender) => {
 					let mut peers = vec![];
 					for _peer in 0..status.peers {
@@ -92,6 +101,25 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 						Err(s) => sender.send(Err(error::Error::MalformattedPeerArg(s.to_string()))),
 					};
 				}
+				Request::NetworkReservedPeers(sender) => {
+					let _ = sender.send(vec![status.peer_id.to_base58()]);
+				}
+				Request::NetworkSetReservedOnly(_, sender) => {
+					let _ = sender.send(());
+				}
+				Request::NetworkPeerReputations(sender) => {
+					let _ = sender.send(vec![PeerReputation {
+						peer_id: status.peer_id.to_base58(),
+						reputation: -100,
+						banned: false,
+					}]);
+				}
+				Request::NetworkBanPeer(peer, sender) | Request::NetworkUnbanPeer(peer, sender) => {
+					let _ = match peer.parse::<PeerId>() {
+						Ok(_) => sender.send(Ok(())),
+						Err(s) => sender.send(Err(error::Error::MalformattedPeerArg(s.to_string()))),
+					};
+				}
 				Request::NodeRoles(sender) => {
 					let _ = sender.send(vec![NodeRole::Authority]);
 				}
@@ -237,6 +265,24 @@ fn system_network_state() {
 	);
 }
 
+#[test]
+fn system_sync_state_works() {
+	assert_eq!(
+		wait_receiver(api(Status {
+			peers: 5,
+			is_syncing: true,
+			..Default::default()
+		}).system_sync_state()),
+		SyncState {
+			starting_block: 1,
+			current_block: 2,
+			highest_block: Some(10),
+			peers: 5,
+			is_major_syncing: true,
+		}
+	);
+}
+
 #[test]
 fn system_node_roles() {
 	assert_eq!(
@@ -268,3 +314,42 @@ fn system_network_remove_reserved() {
 	assert_eq!(runtime.block_on(good_fut), Ok(()));
 	assert!(runtime.block_on(bad_fut).is_err());
 }
+
+#[test]
+fn system_network_reserved_peers() {
+	let peer_id = PeerId::random();
+	assert_eq!(
+		wait_receiver(api(Status { peer_id: peer_id.clone(), ..Default::default() }).system_reserved_peers()),
+		vec![peer_id.to_base58()]
+	);
+}
+
+#[test]
+fn system_network_set_reserved_only() {
+	wait_receiver(api(None).system_set_reserved_only(true));
+}
+
+#[test]
+fn system_network_peer_reputations() {
+	let peer_id = PeerId::random();
+	assert_eq!(
+		wait_receiver(api(Status { peer_id: peer_id.clone(), ..Default::default() }).system_peer_reputations()),
+		vec![PeerReputation {
+			peer_id: peer_id.to_base58(),
+			reputation: -100,
+			banned: false,
+		}]
+	);
+}
+
+#[test]
+fn system_network_ban_and_unban_peer() {
+	let good_peer_id = "QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV";
+	let bad_peer_id = "/ip4/198.51.100.19/tcp/30333/p2p/QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV";
+	let mut runtime = tokio::runtime::current_thread::Runtime::new().unwrap();
+
+	assert_eq!(runtime.block_on(api(None).system_ban_peer(good_peer_id.into())), Ok(()));
+	assert!(runtime.block_on(api(None).system_ban_peer(bad_peer_id.into())).is_err());
+	assert_eq!(runtime.block_on(api(None).system_unban_peer(good_peer_id.into())), Ok(()));
+	assert!(runtime.block_on(api(None).system_unban_peer(bad_peer_id.into())).is_err());
+}
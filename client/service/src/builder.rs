@@ -16,7 +16,10 @@
 
 use crate::{Service, NetworkStatus, NetworkState, error::Error, DEFAULT_PROTOCOL_ID, MallocSizeOfWasm};
 use crate::{TaskManagerBuilder, start_rpc_servers, build_network_future, TransactionPoolAdapter};
+use crate::health::HealthProbe;
+use crate::self_check;
 use crate::status_sinks;
+use crate::chain_ops::ImportBenchmark;
 use crate::config::{Configuration, DatabaseConfig, KeystoreConfig, PrometheusConfig};
 use sc_client_api::{
 	self,
@@ -26,8 +29,7 @@ use sc_client_api::{
 	ExecutorProvider, CallExecutor
 };
 use sc_client::Client;
-use sc_chain_spec::get_extension;
-use sp_consensus::import_queue::ImportQueue;
+use sp_consensus::{block_validation::BlockAnnounceValidator, import_queue::ImportQueue};
 use futures::{
 	Future, FutureExt, StreamExt,
 	channel::mpsc,
@@ -39,12 +41,14 @@ use sc_network::config::{FinalityProofProvider, OnDemand, BoxFinalityProofReques
 use sc_network::{NetworkService, NetworkStateInfo};
 use parking_lot::{Mutex, RwLock};
 use sp_runtime::generic::BlockId;
+use sp_core::storage::Storage;
 use sp_runtime::traits::{
 	Block as BlockT, NumberFor, SaturatedConversion, HashFor, UniqueSaturatedInto,
 };
 use sp_api::ProvideRuntimeApi;
 use sc_executor::{NativeExecutor, NativeExecutionDispatch};
 use std::{
+	convert::TryFrom,
 	io::{Read, Write, Seek},
 	marker::PhantomData, sync::Arc, pin::Pin
 };
@@ -113,11 +117,17 @@ pub type BackgroundTask = Pin<Box<dyn Future<Output=()> + Send>>;
 /// - [`with_import_queue`](ServiceBuilder::with_import_queue)
 /// - [`with_finality_proof_provider`](ServiceBuilder::with_finality_proof_provider)
 /// - [`with_transaction_pool`](ServiceBuilder::with_transaction_pool)
+/// - [`with_rpc_extensions`](ServiceBuilder::with_rpc_extensions)
+/// - [`with_block_announce_validator`](ServiceBuilder::with_block_announce_validator)
 ///
 /// After this is done, call [`build`](ServiceBuilder::build) to construct the service.
 ///
 /// The order in which the `with_*` methods are called doesn't matter, as the correct binding of
-/// generics is done when you call `build`.
+/// generics is done when you call `build`. The only exception are the RPC extensions, which are
+/// built from the components already present in the builder.
+///
+/// Calling a `with_*` method again replaces the component set before, so a chain can start from
+/// the builder set up by another node and swap only the components it customizes.
 ///
 pub struct ServiceBuilder<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp,
 	TExPool, TRpc, Backend>
@@ -135,6 +145,7 @@ pub struct ServiceBuilder<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp,
 	transaction_pool: Arc<TExPool>,
 	rpc_extensions: TRpc,
 	remote_backend: Option<Arc<dyn RemoteBlockchain<TBl>>>,
+	block_announce_validator: Option<Box<dyn BlockAnnounceValidator<TBl> + Send>>,
 	marker: PhantomData<(TBl, TRtApi)>,
 	background_tasks: Vec<(&'static str, BackgroundTask)>,
 }
@@ -202,6 +213,16 @@ pub fn new_full_client<TBl, TRtApi, TExecDisp>(
 	new_full_parts(config).map(|parts| parts.0)
 }
 
+/// Creates a new full client for the given config, together with its backend.
+pub fn new_full_client_and_backend<TBl, TRtApi, TExecDisp>(
+	config: &Configuration,
+) -> Result<(TFullClient<TBl, TRtApi, TExecDisp>, Arc<TFullBackend<TBl>>), Error> where
+	TBl: BlockT,
+	TExecDisp: NativeExecutionDispatch + 'static,
+{
+	new_full_parts(config).map(|parts| (parts.0, parts.1))
+}
+
 fn new_full_parts<TBl, TRtApi, TExecDisp>(
 	config: &Configuration,
 ) -> Result<TFullParts<TBl, TRtApi, TExecDisp>,	Error> where
@@ -226,11 +247,11 @@ fn new_full_parts<TBl, TRtApi, TExecDisp>(
 	);
 
 	let chain_spec = config.expect_chain_spec();
-	let fork_blocks = get_extension::<sc_client::ForkBlocks<TBl>>(chain_spec.extensions())
+	let fork_blocks = chain_spec.extension::<sc_client::ForkBlocks<TBl>>()
 		.cloned()
 		.unwrap_or_default();
 
-	let bad_blocks = get_extension::<sc_client::BadBlocks<TBl>>(chain_spec.extensions())
+	let bad_blocks = chain_spec.extension::<sc_client::BadBlocks<TBl>>()
 		.cloned()
 		.unwrap_or_default();
 
@@ -289,6 +310,7 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 		TFullBackend<TBl>,
 	>, Error> {
 		let (client, backend, keystore, tasks_builder) = new_full_parts(&config)?;
+		self_check::run::<TBl, _>(&config, &*backend)?;
 
 		let client = Arc::new(client);
 
@@ -306,6 +328,7 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			transaction_pool: Arc::new(()),
 			rpc_extensions: Default::default(),
 			remote_backend: None,
+			block_announce_validator: None,
 			background_tasks: Default::default(),
 			marker: PhantomData,
 		})
@@ -380,6 +403,7 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			Box::new(tasks_builder.spawn_handle()),
 			config.prometheus_config.as_ref().map(|config| config.registry.clone()),
 		)?);
+		self_check::run::<TBl, _>(&config, &*backend)?;
 
 		Ok(ServiceBuilder {
 			config,
@@ -395,6 +419,7 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			transaction_pool: Arc::new(()),
 			rpc_extensions: Default::default(),
 			remote_backend: Some(remote_blockchain),
+			block_announce_validator: None,
 			background_tasks: Default::default(),
 			marker: PhantomData,
 		})
@@ -444,6 +469,15 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 		self.remote_backend.clone()
 	}
 
+	/// Returns a new manager of RPC subscriptions, running on the tasks of the service and
+	/// following the configured limits. Meant for RPC extensions providing subscriptions.
+	pub fn rpc_subscriptions(&self) -> sc_rpc::Subscriptions {
+		sc_rpc::Subscriptions::with_limits(
+			Arc::new(self.tasks_builder.spawn_handle()),
+			self.config.rpc_subscription_limits,
+		)
+	}
+
 	/// Defines which head-of-chain strategy to use.
 	pub fn with_opt_select_chain<USc>(
 		self,
@@ -468,6 +502,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			transaction_pool: self.transaction_pool,
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
+			block_announce_validator: self.block_announce_validator,
 			background_tasks: self.background_tasks,
 			marker: self.marker,
 		})
@@ -511,6 +546,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			transaction_pool: self.transaction_pool,
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
+			block_announce_validator: self.block_announce_validator,
 			background_tasks: self.background_tasks,
 			marker: self.marker,
 		})
@@ -549,6 +585,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			transaction_pool: self.transaction_pool,
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
+			block_announce_validator: self.block_announce_validator,
 			background_tasks: self.background_tasks,
 			marker: self.marker,
 		})
@@ -611,6 +648,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			transaction_pool: self.transaction_pool,
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
+			block_announce_validator: self.block_announce_validator,
 			background_tasks: self.background_tasks,
 			marker: self.marker,
 		})
@@ -671,6 +709,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			transaction_pool: Arc::new(transaction_pool),
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
+			block_announce_validator: self.block_announce_validator,
 			background_tasks: self.background_tasks,
 			marker: self.marker,
 		})
@@ -699,10 +738,39 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			transaction_pool: self.transaction_pool,
 			rpc_extensions,
 			remote_backend: self.remote_backend,
+			block_announce_validator: self.block_announce_validator,
 			background_tasks: self.background_tasks,
 			marker: self.marker,
 		})
 	}
+
+	/// Defines how incoming block announcements are validated.
+	///
+	/// The validator sees the header and the data attached to each announcement, and can
+	/// reject it before the block is requested. Without it, all announcements are accepted.
+	pub fn with_block_announce_validator(
+		mut self,
+		builder: impl FnOnce(Arc<TCl>) -> Result<Box<dyn BlockAnnounceValidator<TBl> + Send>, Error>,
+	) -> Result<Self, Error> {
+		self.block_announce_validator = Some(builder(self.client.clone())?);
+		Ok(self)
+	}
+
+	/// Adds RPC extensions to the ones already defined.
+	///
+	/// Unlike [`with_rpc_extensions`](ServiceBuilder::with_rpc_extensions), the extensions
+	/// defined before are kept, so a chain can serve its own RPC modules next to the ones of
+	/// the node it builds on.
+	pub fn with_extra_rpc_extensions<URpc>(
+		self,
+		rpc_ext_builder: impl FnOnce(&Self) -> Result<URpc, Error>,
+	) -> Result<ServiceBuilder<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp,
+		TExPool, sc_rpc::RpcExtensions<TRpc, URpc>, Backend>, Error>
+	where TSc: Clone, TFchr: Clone, TRpc: Clone {
+		let extra_rpc_extensions = rpc_ext_builder(&self)?;
+		let rpc_extensions = self.rpc_extensions.clone();
+		self.with_rpc_extensions(|_| Ok(sc_rpc::RpcExtensions(rpc_extensions, extra_rpc_extensions)))
+	}
 }
 
 /// Implemented on `ServiceBuilder`. Allows running block commands, such as import/export/validate
@@ -713,12 +781,23 @@ pub trait ServiceBuilderCommand {
 	/// Native execution dispatch required by some commands.
 	type NativeDispatch: NativeExecutionDispatch + 'static;
 	/// Starts the process of importing blocks.
+	///
+	/// The blocks are read in the format written by `export_blocks` with the same `binary`.
 	fn import_blocks(
 		self,
 		input: impl Read + Seek + Send + 'static,
 		force: bool,
+		binary: bool,
 	) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>;
 
+	/// Import the blocks of `input`, read like in `import_blocks`, into an empty database and
+	/// measure the import.
+	fn benchmark_import(
+		self,
+		input: impl Read + Seek + Send + 'static,
+		binary: bool,
+	) -> Pin<Box<dyn Future<Output = Result<ImportBenchmark, Error>> + Send>>;
+
 	/// Performs the blocks export.
 	fn export_blocks(
 		self,
@@ -734,6 +813,12 @@ pub trait ServiceBuilderCommand {
 		blocks: NumberFor<Self::Block>
 	) -> Result<(), Error>;
 
+	/// Export the raw state at the given `block`, or at the best block if `None`.
+	fn export_raw_state(
+		&self,
+		block: Option<BlockId<Self::Block>>,
+	) -> Result<Storage, Error>;
+
 	/// Re-validate known block.
 	fn check_block(
 		self,
@@ -810,6 +895,7 @@ ServiceBuilder<
 			transaction_pool,
 			rpc_extensions,
 			remote_backend,
+			block_announce_validator,
 			background_tasks,
 		} = self;
 
@@ -859,8 +945,9 @@ ServiceBuilder<
 			sc_network::config::ProtocolId::from(protocol_id_full)
 		};
 
-		let block_announce_validator =
-			Box::new(sp_consensus::block_validation::DefaultBlockAnnounceValidator::new(client.clone()));
+		let block_announce_validator = block_announce_validator.unwrap_or_else(|| Box::new(
+			sp_consensus::block_validation::DefaultBlockAnnounceValidator::new(client.clone())
+		));
 
 		let network_params = sc_network::config::Params {
 			roles: config.roles,
@@ -890,7 +977,11 @@ ServiceBuilder<
 		let offchain_storage = backend.offchain_storage();
 		let offchain_workers = match (config.offchain_worker, offchain_storage.clone()) {
 			(true, Some(db)) => {
-				Some(Arc::new(sc_offchain::OffchainWorkers::new(client.clone(), db)))
+				Some(Arc::new(match config.offchain_worker_threads {
+					Some(threads) =>
+						sc_offchain::OffchainWorkers::new_with_threads(client.clone(), db, threads),
+					None => sc_offchain::OffchainWorkers::new(client.clone(), db),
+				}))
 			},
 			(true, None) => {
 				warn!("Offchain workers disabled, due to lack of offchain storage support in backend.");
@@ -1019,6 +1110,20 @@ ServiceBuilder<
 			None
 		};
 
+		// Health and readiness probes.
+		let health = config.health_config.as_ref().map(|health_config| {
+			let health = HealthProbe::new(health_config, has_bootnodes);
+			let probe = health.clone();
+			spawn_handle.spawn(
+				"health-endpoint",
+				prometheus_endpoint::init_probes(health_config.listen_address, move |path| probe.probe(path))
+					.map(|result| if let Err(err) = result {
+						warn!("Health endpoint failed: {}", err);
+					}),
+			);
+			health
+		});
+
 		// Periodically notify the telemetry.
 		let transaction_pool_ = transaction_pool.clone();
 		let client_ = client.clone();
@@ -1073,6 +1178,14 @@ ServiceBuilder<
 					.map(|usage| usage.io.bytes_written)
 					.unwrap_or(0),
 			);
+			if let Some(health) = health.as_ref() {
+				health.update(
+					net_status.sync_state == sc_network::SyncState::Downloading,
+					num_peers,
+					best_number,
+					finalized_number,
+				);
+			}
 			if let Some(metrics) = metrics.as_ref() {
 				metrics.memory_usage_bytes.set(memory);
 				metrics.cpu_usage_percentage.set(f64::from(cpu_usage));
@@ -1124,9 +1237,21 @@ ServiceBuilder<
 			tel_task_2,
 		);
 
+		let chain_properties = chain_spec.chain_properties()?;
+		if let Some(ss58_format) = chain_properties.ss58_format {
+			let format = sp_core::crypto::Ss58AddressFormat::try_from(ss58_format)
+				.map_err(|_| Error::Other(format!("Unknown SS58 address format {}", ss58_format)))?;
+			sp_core::crypto::set_default_ss58_version(format);
+		}
+
 		// RPC
 		let (system_rpc_tx, system_rpc_rx) = mpsc::unbounded();
-		let gen_handler = || {
+		let rpc_metrics = match config.prometheus_config.as_ref() {
+			Some(prometheus_config) => Some(sc_rpc_server::RpcMetrics::new(&prometheus_config.registry)?),
+			None => None,
+		};
+		let rpc_middleware = sc_rpc_server::RpcMiddleware::new(rpc_metrics, config.rpc_slow_call_threshold);
+		let gen_handler = |deny_unsafe: bool| {
 			use sc_rpc::{chain, state, author, system, offchain};
 
 			let system_info = sc_rpc::system::SystemInfo {
@@ -1136,9 +1261,12 @@ ServiceBuilder<
 				properties: chain_spec.properties().clone(),
 			};
 
-			let subscriptions = sc_rpc::Subscriptions::new(Arc::new(tasks_builder.spawn_handle()));
+			let subscriptions = sc_rpc::Subscriptions::with_limits(
+				Arc::new(tasks_builder.spawn_handle()),
+				config.rpc_subscription_limits,
+			);
 
-			let (chain, state) = if let (Some(remote_backend), Some(on_demand)) =
+			let (chain, state, child_state) = if let (Some(remote_backend), Some(on_demand)) =
 				(remote_backend.as_ref(), on_demand.as_ref()) {
 				// Light clients
 				let chain = sc_rpc::chain::new_light(
@@ -1153,13 +1281,15 @@ ServiceBuilder<
 					remote_backend.clone(),
 					on_demand.clone()
 				);
-				(chain, state)
+				let child_state = state.child_state();
+				(chain, state, child_state)
 
 			} else {
 				// Full nodes
 				let chain = sc_rpc::chain::new_full(client.clone(), subscriptions.clone());
 				let state = sc_rpc::state::new_full(client.clone(), subscriptions.clone());
-				(chain, state)
+				let child_state = state.child_state();
+				(chain, state, child_state)
 			};
 
 			let author = sc_rpc::author::Author::new(
@@ -1169,29 +1299,32 @@ ServiceBuilder<
 				keystore.clone(),
 			);
 			let system = system::System::new(system_info, system_rpc_tx.clone());
+			let denied_methods: &[&str] = if deny_unsafe { sc_rpc::UNSAFE_METHODS } else { &[] };
 
 			match offchain_storage.clone() {
 				Some(storage) => {
 					let offchain = sc_rpc::offchain::Offchain::new(storage);
 					sc_rpc_server::rpc_handler((
 						state::StateApi::to_delegate(state),
+						state::ChildStateApi::to_delegate(child_state),
 						chain::ChainApi::to_delegate(chain),
 						offchain::OffchainApi::to_delegate(offchain),
 						author::AuthorApi::to_delegate(author),
 						system::SystemApi::to_delegate(system),
 						rpc_extensions.clone(),
-					))
+					), denied_methods, rpc_middleware.clone())
 				},
 				None => sc_rpc_server::rpc_handler((
 					state::StateApi::to_delegate(state),
+					state::ChildStateApi::to_delegate(child_state),
 					chain::ChainApi::to_delegate(chain),
 					author::AuthorApi::to_delegate(author),
 					system::SystemApi::to_delegate(system),
 					rpc_extensions.clone(),
-				))
+				), denied_methods, rpc_middleware.clone())
 			}
 		};
-		let rpc_handlers = gen_handler();
+		let rpc_handlers = gen_handler(false);
 		let rpc = start_rpc_servers(&config, gen_handler)?;
 
 		spawn_handle.spawn(
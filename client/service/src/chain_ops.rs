@@ -19,6 +19,7 @@
 use crate::error;
 use crate::builder::{ServiceBuilderCommand, ServiceBuilder};
 use crate::error::Error;
+use crate::config::{Configuration, DatabaseConfig};
 use sc_chain_spec::ChainSpec;
 use log::{warn, info};
 use futures::{future, prelude::*};
@@ -27,6 +28,7 @@ use sp_runtime::traits::{
 };
 use sp_runtime::generic::{BlockId, SignedBlock};
 use codec::{Decode, Encode, IoReader};
+use serde::de::DeserializeOwned;
 use sc_client::{Client, LocalCallExecutor};
 use sp_consensus::{
 	BlockOrigin,
@@ -34,14 +36,155 @@ use sp_consensus::{
 };
 use sc_executor::{NativeExecutor, NativeExecutionDispatch};
 
-use std::{io::{Read, Write, Seek}, pin::Pin};
-use sc_client_api::BlockBackend;
+use std::{io::{Read, Write, Seek}, pin::Pin, time::{Duration, Instant}};
+use sc_client_api::{BlockBackend, StorageProvider};
+use sp_core::{
+	hexdisplay::HexDisplay,
+	storage::{OwnedChildInfo, Storage, StorageChild, StorageKey, well_known_keys},
+};
 
 /// Build a chain spec json
 pub fn build_spec(spec: &dyn ChainSpec, raw: bool) -> error::Result<String> {
 	Ok(spec.as_json(raw)?)
 }
 
+/// Read the full state at `block`, with the content of its child tries.
+///
+/// Child tries are stored under their storage key, without their root in the top trie.
+fn read_raw_state<TBl, TBackend, TCl>(
+	client: &TCl,
+	block: &BlockId<TBl>,
+) -> Result<Storage, Error> where
+	TBl: BlockT,
+	TBackend: sc_client_api::backend::Backend<TBl>,
+	TCl: StorageProvider<TBl, TBackend>,
+{
+	let empty_prefix = StorageKey(Vec::new());
+	let mut storage = Storage::default();
+	for (key, value) in client.storage_pairs(block, &empty_prefix)? {
+		if !well_known_keys::is_child_storage_key(&key.0) {
+			storage.top.insert(key.0, value.0);
+			continue;
+		}
+
+		// The child info of a default child trie is the rest of its storage key.
+		let child_info = match key.0.get(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..) {
+			Some(unique_id) if key.0.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) =>
+				OwnedChildInfo::new_default(unique_id.to_vec()),
+			_ => return Err(
+				format!("Unsupported child trie 0x{}", HexDisplay::from(&key.0)).into()
+			),
+		};
+		let mut child = StorageChild { data: Default::default(), child_info };
+		let child_keys = client.child_storage_keys(block, &key, child.child_info.as_ref(), &empty_prefix)?;
+		for child_key in child_keys {
+			if let Some(value) = client.child_storage(block, &key, child.child_info.as_ref(), &child_key)? {
+				child.data.insert(child_key.0, value.0);
+			}
+		}
+		storage.children.insert(key.0, child);
+	}
+
+	Ok(storage)
+}
+
+/// Size of each column of the existing database of a full client, with the names of the columns.
+///
+/// The database must not be open already.
+pub fn database_column_sizes<TBl: BlockT>(
+	config: &Configuration,
+) -> error::Result<Vec<(&'static str, u64)>> {
+	Ok(sc_client_db::column_sizes::<TBl>(&database_settings(config))?)
+}
+
+/// Genesis, best and finalized blocks of the existing database of a full client.
+///
+/// Unlike building a client, this fails if there is no chain in the database rather than
+/// initializing its genesis. The database must not be open already.
+pub fn database_chain_info<TBl: BlockT>(
+	config: &Configuration,
+) -> error::Result<sp_blockchain::Info<TBl>> {
+	Ok(sc_client_db::chain_info::<TBl>(&database_settings(config))?)
+}
+
+fn database_settings(config: &Configuration) -> sc_client_db::DatabaseSettings {
+	sc_client_db::DatabaseSettings {
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio: config.state_cache_child_ratio.map(|v| (v, 100)),
+		pruning: config.pruning.clone(),
+		source: match config.expect_database() {
+			DatabaseConfig::Path { path, cache_size } =>
+				sc_client_db::DatabaseSettingsSrc::Path {
+					path: path.clone(),
+					cache_size: cache_size.clone().map(|u| u as usize),
+				},
+			DatabaseConfig::Custom(db) =>
+				sc_client_db::DatabaseSettingsSrc::Custom(db.clone()),
+		},
+	}
+}
+
+/// Measurements of a block import, see `ServiceBuilderCommand::benchmark_import`.
+#[derive(Debug, Clone)]
+pub struct ImportBenchmark {
+	/// Number of blocks imported.
+	pub blocks: u64,
+	/// Time spent importing the blocks.
+	pub elapsed: Duration,
+	/// Bytes written to the database, if the database reports it.
+	pub bytes_written: Option<u64>,
+}
+
+/// Blocks read from a file written by `export_blocks`.
+enum ExportedBlocks<R: Read, B> {
+	/// The number of blocks followed by the SCALE encoded blocks.
+	Binary {
+		reader: IoReader<R>,
+		count: Option<u64>,
+		read: u64,
+	},
+	/// The JSON encoded blocks, one after the other.
+	Json(serde_json::StreamDeserializer<'static, serde_json::de::IoRead<R>, SignedBlock<B>>),
+}
+
+impl<R: Read, B: BlockT + DeserializeOwned> ExportedBlocks<R, B> {
+	fn new(input: R, binary: bool) -> Self {
+		if binary {
+			ExportedBlocks::Binary { reader: IoReader(input), count: None, read: 0 }
+		} else {
+			ExportedBlocks::Json(serde_json::Deserializer::from_reader(input).into_iter())
+		}
+	}
+}
+
+impl<R: Read, B: BlockT + DeserializeOwned> Iterator for ExportedBlocks<R, B> {
+	type Item = Result<SignedBlock<B>, String>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			ExportedBlocks::Binary { reader, count, read } => {
+				let total = match *count {
+					Some(total) => total,
+					None => match u64::decode(reader) {
+						Ok(total) => {
+							info!("Importing {} blocks", total);
+							*count = Some(total);
+							total
+						},
+						Err(err) => return Some(Err(format!("Error reading file: {}", err))),
+					},
+				};
+				if *read >= total {
+					return None
+				}
+				*read += 1;
+				Some(SignedBlock::decode(reader).map_err(|e| e.to_string()))
+			},
+			ExportedBlocks::Json(blocks) => blocks.next().map(|block| block.map_err(|e| e.to_string())),
+		}
+	}
+}
+
 impl<
 	TBl, TRtApi, TBackend,
 	TExecDisp, TFchr, TSc, TImpQu, TFprb, TFpp,
@@ -51,7 +194,7 @@ impl<
 	Client<TBackend, LocalCallExecutor<TBackend, NativeExecutor<TExecDisp>>, TBl, TRtApi>,
 	TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend
 > where
-	TBl: BlockT,
+	TBl: BlockT + DeserializeOwned,
 	TBackend: 'static + sc_client_api::backend::Backend<TBl> + Send,
 	TExecDisp: 'static + NativeExecutionDispatch,
 	TImpQu: 'static + ImportQueue<TBl>,
@@ -64,6 +207,7 @@ impl<
 		self,
 		input: impl Read + Seek + Send + 'static,
 		force: bool,
+		binary: bool,
 	) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>> {
 		struct WaitLink {
 			imported_blocks: u64,
@@ -101,8 +245,8 @@ impl<
 		let client = self.client;
 		let mut queue = self.import_queue;
 
-		let mut io_reader_input = IoReader(input);
-		let mut count = None::<u64>;
+		let mut blocks = ExportedBlocks::<_, Self::Block>::new(input, binary);
+		let mut reading = true;
 		let mut read_block_count = 0;
 		let mut link = WaitLink::new();
 
@@ -114,27 +258,10 @@ impl<
 		// This makes it possible either to interleave other operations in-between the block imports,
 		// or to stop the operation completely.
 		let import = future::poll_fn(move |cx| {
-			// Start by reading the number of blocks if not done so already.
-			let count = match count {
-				Some(c) => c,
-				None => {
-					let c: u64 = match Decode::decode(&mut io_reader_input) {
-						Ok(c) => c,
-						Err(err) => {
-							let err = format!("Error reading file: {}", err);
-							return std::task::Poll::Ready(Err(From::from(err)));
-						},
-					};
-					info!("Importing {} blocks", c);
-					count = Some(c);
-					c
-				}
-			};
-
 			// Read blocks from the input.
-			if read_block_count < count {
-				match SignedBlock::<Self::Block>::decode(&mut io_reader_input) {
-					Ok(signed) => {
+			if reading {
+				match blocks.next() {
+					Some(Ok(signed)) => {
 						let (header, extrinsics) = signed.block.deconstruct();
 						let hash = header.hash();
 						// import queue handles verification and importing it into the client
@@ -147,13 +274,22 @@ impl<
 								origin: None,
 								allow_missing_state: false,
 								import_existing: force,
+								imported_state: None,
 							}
 						]);
 					}
-					Err(e) => {
+					Some(Err(e)) if read_block_count == 0 => {
+						return std::task::Poll::Ready(Err(From::from(e)));
+					}
+					Some(Err(e)) => {
 						warn!("Error reading block data at {}: {}", read_block_count, e);
 						return std::task::Poll::Ready(Ok(()));
 					}
+					None => {
+						reading = false;
+						cx.waker().wake_by_ref();
+						return std::task::Poll::Pending;
+					}
 				}
 
 				read_block_count += 1;
@@ -180,11 +316,11 @@ impl<
 				info!(
 					"#{} blocks were imported (#{} left)",
 					link.imported_blocks,
-					count - link.imported_blocks
+					read_block_count - link.imported_blocks
 				);
 			}
 
-			if link.imported_blocks >= count {
+			if link.imported_blocks >= read_block_count {
 				info!("Imported {} blocks. Best: #{}", read_block_count, client.chain_info().best_number);
 				return std::task::Poll::Ready(Ok(()));
 
@@ -196,6 +332,31 @@ impl<
 		Box::pin(import)
 	}
 
+	fn benchmark_import(
+		self,
+		input: impl Read + Seek + Send + 'static,
+		binary: bool,
+	) -> Pin<Box<dyn Future<Output = Result<ImportBenchmark, Error>> + Send>> {
+		let client = self.client.clone();
+		if !client.chain_info().best_number.is_zero() {
+			return Box::pin(future::err::<ImportBenchmark, Error>(
+				"The blocks must be imported into an empty database".into()
+			))
+		}
+
+		let start = Instant::now();
+		Box::pin(self.import_blocks(input, true, binary).map(move |result| -> Result<_, Error> {
+			result?;
+			let elapsed = start.elapsed();
+			let info = client.usage_info();
+			Ok(ImportBenchmark {
+				blocks: info.chain.best_number.saturated_into(),
+				elapsed,
+				bytes_written: info.usage.map(|usage| usage.io.bytes_written),
+			})
+		}))
+	}
+
 	fn export_blocks(
 		self,
 		mut output: impl Write + 'static,
@@ -277,9 +438,24 @@ impl<
 		} else {
 			info!("Reverted {} blocks. Best: #{} ({})", reverted, info.best_number, info.best_hash);
 		}
+		if reverted < blocks {
+			info!(
+				"Finalized blocks are never reverted, stopped at the last finalized block #{} ({})",
+				info.finalized_number,
+				info.finalized_hash,
+			);
+		}
 		Ok(())
 	}
 
+	fn export_raw_state(
+		&self,
+		block: Option<BlockId<TBl>>,
+	) -> Result<Storage, Error> {
+		let block = block.unwrap_or_else(|| BlockId::Hash(self.client.chain_info().best_hash));
+		read_raw_state(&*self.client, &block)
+	}
+
 	fn check_block(
 		self,
 		block_id: BlockId<TBl>
@@ -290,10 +466,51 @@ impl<
 				1u64.encode_to(&mut buf);
 				block.encode_to(&mut buf);
 				let reader = std::io::Cursor::new(buf);
-				self.import_blocks(reader, true)
+				self.import_blocks(reader, true, true)
 			}
 			Ok(None) => Box::pin(future::err("Unknown block".into())),
 			Err(e) => Box::pin(future::err(format!("Error reading block: {:?}", e).into())),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::storage::ChildInfo;
+	use sp_runtime::{BuildStorage, traits::{BlakeTwo256, Hash}};
+	use substrate_test_runtime_client::prelude::*;
+
+	type RawSpec = sc_chain_spec::GenericChainSpec<(), sc_chain_spec::NoExtension>;
+
+	#[test]
+	fn exported_state_keeps_child_tries() {
+		let storage_key = b":child_storage:default:export".to_vec();
+		let client = TestClientBuilder::new()
+			.add_extra_child_storage(
+				storage_key.clone(),
+				ChildInfo::new_default(b"export"),
+				b"key".to_vec(),
+				b"value".to_vec(),
+			)
+			.build();
+		let genesis = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		let state = read_raw_state(&client, &BlockId::Number(0)).unwrap();
+		assert!(!state.top.contains_key(&storage_key));
+		assert_eq!(state.children[&storage_key].data.get(&b"key"[..]), Some(&b"value".to_vec()));
+
+		// seed a chain spec with the exported state and read it back.
+		let mut spec = RawSpec::from_genesis("test", "test", || (), Vec::new(), None, None, None, None);
+		spec.set_storage(state);
+		let json = spec.as_json(true).unwrap();
+		let storage = RawSpec::from_json_bytes(json.into_bytes()).unwrap().build_storage().unwrap();
+
+		let child_roots = storage.children.iter().map(|(key, child)| {
+			let root = BlakeTwo256::trie_root(child.data.clone().into_iter().collect());
+			(key.clone(), root.encode())
+		});
+		let state_root = BlakeTwo256::trie_root(storage.top.into_iter().chain(child_roots).collect());
+		assert_eq!(&state_root, genesis.state_root());
+	}
+}
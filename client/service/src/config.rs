@@ -20,8 +20,11 @@ pub use sc_client::ExecutionStrategies;
 pub use sc_client_db::{kvdb::KeyValueDB, PruningMode};
 pub use sc_network::config::{ExtTransport, NetworkConfiguration, Roles};
 pub use sc_executor::WasmExecutionMethod;
+pub use sc_rpc::{NotificationDropPolicy, SubscriptionLimits};
+pub use crate::health::HealthConfig;
+pub use crate::self_check::SelfCheckConfig;
 
-use std::{future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc};
+use std::{future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc, time::Duration};
 pub use sc_transaction_pool::txpool::Options as TransactionPoolOptions;
 use sc_chain_spec::ChainSpec;
 use sp_core::crypto::Protected;
@@ -90,12 +93,27 @@ pub struct Configuration {
 	pub rpc_http: Option<SocketAddr>,
 	/// RPC over Websockets binding address. `None` if disabled.
 	pub rpc_ws: Option<SocketAddr>,
+	/// RPC over IPC socket path. `None` if disabled.
+	pub rpc_ipc: Option<String>,
 	/// Maximum number of connections for WebSockets RPC server. `None` if default.
 	pub rpc_ws_max_connections: Option<usize>,
 	/// CORS settings for HTTP & WS servers. `None` if all origins are allowed.
 	pub rpc_cors: Option<Vec<String>>,
+	/// Hosts allowed in the `Host` header of HTTP & WS requests, besides the listening address.
+	/// `None` if all hosts are allowed.
+	pub rpc_allowed_hosts: Option<Vec<String>>,
+	/// RPC methods to expose over HTTP & WS.
+	pub rpc_methods: RpcMethods,
+	/// Limits on the subscriptions of each RPC connection.
+	pub rpc_subscription_limits: SubscriptionLimits,
+	/// RPC calls taking longer than this are logged. `None` if disabled.
+	pub rpc_slow_call_threshold: Option<Duration>,
 	/// Prometheus endpoint configuration. `None` if disabled.
 	pub prometheus_config: Option<PrometheusConfig>,
+	/// Health endpoint configuration. `None` if disabled.
+	pub health_config: Option<HealthConfig>,
+	/// Checks to run at startup, before starting the network. `None` if disabled.
+	pub self_check: Option<SelfCheckConfig>,
 	/// Telemetry service URL. `None` if disabled.
 	pub telemetry_endpoints: Option<TelemetryEndpoints>,
 	/// External WASM transport for the telemetry. If `Some`, when connection to a telemetry
@@ -105,6 +123,8 @@ pub struct Configuration {
 	pub default_heap_pages: Option<u64>,
 	/// Should offchain workers be executed.
 	pub offchain_worker: bool,
+	/// Number of threads dedicated to the offchain workers. `None` for one per CPU.
+	pub offchain_worker_threads: Option<usize>,
 	/// Sentry mode is enabled, the node's role is AUTHORITY but it should not
 	/// actively participate in consensus (i.e. no keystores should be passed to
 	/// consensus modules).
@@ -170,6 +190,42 @@ pub enum DatabaseConfig {
 	Custom(Arc<dyn KeyValueDB>),
 }
 
+/// RPC methods to expose over HTTP & WS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RpcMethods {
+	/// Expose the unsafe methods only on the servers listening on a loopback address.
+	Auto,
+	/// Only expose the safe methods.
+	Safe,
+	/// Expose all the methods.
+	Unsafe,
+}
+
+impl Default for RpcMethods {
+	fn default() -> Self {
+		RpcMethods::Auto
+	}
+}
+
+impl RpcMethods {
+	/// Whether the unsafe methods are denied on the IPC server, only reachable from this machine.
+	pub fn deny_unsafe_ipc(&self) -> bool {
+		match self {
+			RpcMethods::Safe => true,
+			RpcMethods::Auto | RpcMethods::Unsafe => false,
+		}
+	}
+
+	/// Whether the unsafe methods are denied on a server listening on `address`.
+	pub fn deny_unsafe(&self, address: &SocketAddr) -> bool {
+		match self {
+			RpcMethods::Auto => !address.ip().is_loopback(),
+			RpcMethods::Safe => true,
+			RpcMethods::Unsafe => false,
+		}
+	}
+}
+
 /// Configuration of the Prometheus endpoint.
 #[derive(Clone)]
 pub struct PrometheusConfig {
@@ -215,13 +271,21 @@ impl Default for Configuration {
 			execution_strategies: Default::default(),
 			rpc_http: None,
 			rpc_ws: None,
+			rpc_ipc: None,
 			rpc_ws_max_connections: None,
 			rpc_cors: Some(vec![]),
+			rpc_allowed_hosts: Some(vec![]),
+			rpc_methods: Default::default(),
+			rpc_subscription_limits: Default::default(),
+			rpc_slow_call_threshold: None,
 			prometheus_config: None,
+			health_config: None,
+			self_check: None,
 			telemetry_endpoints: None,
 			telemetry_external_transport: None,
 			default_heap_pages: None,
 			offchain_worker: Default::default(),
+			offchain_worker_threads: None,
 			sentry_mode: false,
 			force_authoring: false,
 			disable_grandpa: false,
@@ -304,6 +368,63 @@ impl Configuration {
 			_ => Err("Keystore config specified when it should not be!".into()),
 		}
 	}
+
+	/// The configuration of a light client embedded in the process of the full node configured
+	/// by `self`, serving RPC on `rpc_http`.
+	///
+	/// The light client keeps its data in the `light` directory under the configuration
+	/// directory of the full node, apart from the database of the full node, and only connects
+	/// to the reserved nodes, where the full node is expected to be added. It uses the
+	/// pre-shared key of the full node, if it runs in a private network.
+	pub fn embedded_light_client(&self, rpc_http: Option<SocketAddr>) -> Result<Configuration, String> {
+		let config_dir = self.config_dir.as_ref().map(|dir| dir.join("light"));
+		let database = match (&self.database, &config_dir) {
+			(Some(DatabaseConfig::Path { cache_size, .. }), Some(dir)) => Some(DatabaseConfig::Path {
+				path: dir.join("db"),
+				cache_size: *cache_size,
+			}),
+			(Some(DatabaseConfig::Path { .. }), None) =>
+				return Err("An embedded light client needs a configuration directory".into()),
+			(Some(DatabaseConfig::Custom(_)), _) =>
+				return Err("An embedded light client needs a database path".into()),
+			(None, _) => None,
+		};
+
+		let mut network = NetworkConfiguration::new_local();
+		network.config_path = config_dir.as_ref().map(|dir| dir.join("network"));
+		network.net_config_path = network.config_path.clone();
+		network.non_reserved_mode = sc_network::config::NonReservedPeerMode::Deny;
+		network.client_version = self.network.client_version.clone();
+		network.node_name = format!("{}-light", self.network.node_name);
+		network.transport = self.network.transport.clone();
+		// the full node is the only peer, it rejects connections without its pre-shared key.
+		network.pre_shared_key = self.network.pre_shared_key;
+
+		Ok(Configuration {
+			impl_name: self.impl_name,
+			impl_version: self.impl_version,
+			impl_commit: self.impl_commit,
+			roles: Roles::LIGHT,
+			task_executor: self.task_executor.clone(),
+			network,
+			config_dir,
+			keystore: KeystoreConfig::InMemory,
+			database,
+			chain_spec: self.chain_spec.as_ref().map(|spec| spec.cloned_box()),
+			name: format!("{}-light", self.name),
+			wasm_method: self.wasm_method,
+			execution_strategies: self.execution_strategies.clone(),
+			rpc_http,
+			rpc_cors: self.rpc_cors.clone(),
+			rpc_allowed_hosts: self.rpc_allowed_hosts.clone(),
+			rpc_methods: self.rpc_methods,
+			rpc_subscription_limits: self.rpc_subscription_limits,
+			rpc_slow_call_threshold: self.rpc_slow_call_threshold,
+			default_heap_pages: self.default_heap_pages,
+			max_runtime_instances: self.max_runtime_instances,
+			..Default::default()
+		})
+	}
 }
 
 /// Returns platform info
@@ -318,3 +439,33 @@ pub fn full_version_from_strs(impl_version: &str, impl_commit: &str) -> String {
 	let commit_dash = if impl_commit.is_empty() { "" } else { "-" };
 	format!("{}{}{}-{}", impl_version, commit_dash, impl_commit, platform())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn auto_rpc_methods_deny_unsafe_off_loopback() {
+		let public: SocketAddr = "0.0.0.0:9933".parse().unwrap();
+		let local: SocketAddr = "127.0.0.1:9933".parse().unwrap();
+		let local_v6: SocketAddr = "[::1]:9933".parse().unwrap();
+
+		assert!(RpcMethods::Auto.deny_unsafe(&public));
+		assert!(!RpcMethods::Auto.deny_unsafe(&local));
+		assert!(!RpcMethods::Auto.deny_unsafe(&local_v6));
+		assert!(!RpcMethods::Auto.deny_unsafe_ipc());
+	}
+
+	#[test]
+	fn safe_and_unsafe_rpc_methods_ignore_the_address() {
+		let public: SocketAddr = "0.0.0.0:9933".parse().unwrap();
+		let local: SocketAddr = "127.0.0.1:9933".parse().unwrap();
+
+		assert!(RpcMethods::Safe.deny_unsafe(&public));
+		assert!(RpcMethods::Safe.deny_unsafe(&local));
+		assert!(RpcMethods::Safe.deny_unsafe_ipc());
+		assert!(!RpcMethods::Unsafe.deny_unsafe(&public));
+		assert!(!RpcMethods::Unsafe.deny_unsafe(&local));
+		assert!(!RpcMethods::Unsafe.deny_unsafe_ipc());
+	}
+}
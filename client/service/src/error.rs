@@ -43,6 +43,10 @@ pub enum Error {
 	/// Tasks executor is missing.
 	#[display(fmt="Tasks executor hasn't been provided.")]
 	TaskExecutorRequired,
+	/// A startup self-check failed.
+	#[display(fmt="Startup self-check failed: {}", _0)]
+	#[from(ignore)]
+	SelfCheck(String),
 	/// Other error.
 	Other(String),
 }
@@ -0,0 +1,177 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Health and readiness probes of the service, served over HTTP for orchestrators and load
+//! balancers.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use parking_lot::Mutex;
+use wasm_timer::Instant;
+
+/// The status is refreshed every few seconds, a status older than this means the service is stuck.
+const MAX_STATUS_AGE: Duration = Duration::from_secs(30);
+
+/// Configuration of the health endpoint.
+#[derive(Clone, Debug)]
+pub struct HealthConfig {
+	/// Address to serve `/health` and `/ready` on.
+	pub listen_address: SocketAddr,
+	/// Number of connected peers below which the node is not ready.
+	///
+	/// Ignored on chains without bootnodes, where the node may legitimately have no peers.
+	pub min_peers: usize,
+	/// Number of blocks the finalized block may lag behind the best block while ready.
+	pub max_finality_lag: u64,
+}
+
+impl HealthConfig {
+	/// Create a new config with the default thresholds.
+	pub fn new(listen_address: SocketAddr) -> Self {
+		Self {
+			listen_address,
+			min_peers: 1,
+			max_finality_lag: 50,
+		}
+	}
+}
+
+/// The latest status of the node.
+#[derive(Clone, Debug)]
+struct NodeStatus {
+	is_syncing: bool,
+	peers: usize,
+	best_number: u64,
+	finalized_number: u64,
+	updated: Instant,
+}
+
+/// Answers the `/health` and `/ready` probes from the latest status of the node.
+#[derive(Clone)]
+pub(crate) struct HealthProbe {
+	min_peers: usize,
+	max_finality_lag: u64,
+	should_have_peers: bool,
+	status: Arc<Mutex<Option<NodeStatus>>>,
+}
+
+impl HealthProbe {
+	pub(crate) fn new(config: &HealthConfig, should_have_peers: bool) -> Self {
+		HealthProbe {
+			min_peers: config.min_peers,
+			max_finality_lag: config.max_finality_lag,
+			should_have_peers,
+			status: Default::default(),
+		}
+	}
+
+	/// Record the latest status of the node.
+	pub(crate) fn update(&self, is_syncing: bool, peers: usize, best_number: u64, finalized_number: u64) {
+		*self.status.lock() = Some(NodeStatus {
+			is_syncing,
+			peers,
+			best_number,
+			finalized_number,
+			updated: Instant::now(),
+		});
+	}
+
+	/// Answer the probe at `path`, `None` if there is none.
+	pub(crate) fn probe(&self, path: &str) -> Option<Result<String, String>> {
+		match path {
+			"/health" => Some(self.health().map(|_| "ok".into())),
+			"/ready" => Some(self.ready()),
+			_ => None,
+		}
+	}
+
+	/// The node is healthy as long as its status keeps being refreshed.
+	fn health(&self) -> Result<NodeStatus, String> {
+		match self.status.lock().clone() {
+			None => Err("starting".into()),
+			Some(ref status) if status.updated.elapsed() > MAX_STATUS_AGE => Err(format!(
+				"status not updated for {} seconds",
+				status.updated.elapsed().as_secs(),
+			)),
+			Some(status) => Ok(status),
+		}
+	}
+
+	/// The node is ready when it is healthy, synced, connected and finalizing.
+	fn ready(&self) -> Result<String, String> {
+		let status = self.health()?;
+		if status.is_syncing {
+			return Err(format!("syncing, at block #{}", status.best_number))
+		}
+		if self.should_have_peers && status.peers < self.min_peers {
+			return Err(format!("{} peers, {} required", status.peers, self.min_peers))
+		}
+		let finality_lag = status.best_number.saturating_sub(status.finalized_number);
+		if finality_lag > self.max_finality_lag {
+			return Err(format!(
+				"finalized block #{} lags {} blocks behind best block #{}",
+				status.finalized_number,
+				finality_lag,
+				status.best_number,
+			))
+		}
+		Ok(format!("ready, at block #{}", status.best_number))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn probe(should_have_peers: bool) -> HealthProbe {
+		HealthProbe::new(&HealthConfig::new(([127, 0, 0, 1], 9616).into()), should_have_peers)
+	}
+
+	#[test]
+	fn probes_fail_before_the_first_status() {
+		let probe = probe(true);
+		assert_eq!(probe.probe("/health"), Some(Err("starting".into())));
+		assert_eq!(probe.probe("/ready"), Some(Err("starting".into())));
+		assert_eq!(probe.probe("/metrics"), None);
+	}
+
+	#[test]
+	fn ready_requires_sync_peers_and_finality() {
+		let probe = probe(true);
+
+		probe.update(true, 3, 10, 8);
+		assert_eq!(probe.probe("/health"), Some(Ok("ok".into())));
+		assert_eq!(probe.probe("/ready"), Some(Err("syncing, at block #10".into())));
+
+		probe.update(false, 0, 10, 8);
+		assert_eq!(probe.probe("/ready"), Some(Err("0 peers, 1 required".into())));
+
+		probe.update(false, 3, 100, 8);
+		assert_eq!(
+			probe.probe("/ready"),
+			Some(Err("finalized block #8 lags 92 blocks behind best block #100".into())),
+		);
+
+		probe.update(false, 3, 100, 98);
+		assert_eq!(probe.probe("/ready"), Some(Ok("ready, at block #100".into())));
+	}
+
+	#[test]
+	fn peers_are_not_required_without_bootnodes() {
+		let probe = probe(false);
+		probe.update(false, 0, 10, 10);
+		assert_eq!(probe.probe("/ready"), Some(Ok("ready, at block #10".into())));
+	}
+}
@@ -25,6 +25,8 @@ pub mod chain_ops;
 pub mod error;
 
 mod builder;
+mod health;
+mod self_check;
 mod status_sinks;
 mod task_manager;
 
@@ -38,11 +40,11 @@ use std::task::{Poll, Context};
 use parking_lot::Mutex;
 
 use sc_client::Client;
+use sp_blockchain::HeaderBackend;
 use futures::{
 	Future, FutureExt, Stream, StreamExt,
 	channel::mpsc,
 	compat::*,
-	sink::SinkExt,
 	task::{Spawn, FutureObj, SpawnError},
 };
 use sc_network::{NetworkService, network_state::NetworkState, PeerId, ReportHandle};
@@ -54,11 +56,11 @@ use parity_util_mem::MallocSizeOf;
 
 pub use self::error::Error;
 pub use self::builder::{
-	new_full_client,
+	new_full_client, new_full_client_and_backend,
 	ServiceBuilder, ServiceBuilderCommand, TFullClient, TLightClient, TFullBackend, TLightBackend,
 	TFullCallExecutor, TLightCallExecutor,
 };
-pub use config::{Configuration, Roles, PruningMode};
+pub use config::{Configuration, Roles, PruningMode, RpcMethods};
 pub use sc_chain_spec::{
 	ChainSpec, GenericChainSpec, Properties, RuntimeGenesis, Extension as ChainSpecExtension
 };
@@ -71,7 +73,7 @@ pub use sc_executor::NativeExecutionDispatch;
 pub use std::{ops::Deref, result::Result, sync::Arc};
 #[doc(hidden)]
 pub use sc_network::config::{FinalityProofProvider, OnDemand, BoxFinalityProofRequestBuilder};
-pub use task_manager::{TaskManagerBuilder, SpawnTaskHandle};
+pub use task_manager::{TaskManagerBuilder, SpawnTaskHandle, RunningTasks};
 use task_manager::TaskManager;
 
 const DEFAULT_PROTOCOL_ID: &str = "sup";
@@ -98,9 +100,9 @@ pub struct Service<TBl, TCl, TSc, TNetStatus, TNet, TTxPool, TOc> {
 	transaction_pool: Arc<TTxPool>,
 	/// Send a signal when a spawned essential task has concluded. The next time
 	/// the service future is polled it should complete with an error.
-	essential_failed_tx: mpsc::UnboundedSender<()>,
+	essential_failed_tx: mpsc::UnboundedSender<Cow<'static, str>>,
 	/// A receiver for spawned essential-tasks concluding.
-	essential_failed_rx: mpsc::UnboundedReceiver<()>,
+	essential_failed_rx: mpsc::UnboundedReceiver<Cow<'static, str>>,
 	rpc_handlers: sc_rpc_server::RpcHandler<sc_rpc::Metadata>,
 	_rpc: Box<dyn std::any::Any + Send + Sync>,
 	_telemetry: Option<sc_telemetry::Telemetry>,
@@ -139,13 +141,16 @@ pub trait AbstractService: 'static + Future<Output = Result<(), Error>> +
 	fn spawn_task(&self, name: impl Into<Cow<'static, str>>, task: impl Future<Output = ()> + Send + 'static);
 
 	/// Spawns a task in the background that runs the future passed as
-	/// parameter. The given task is considered essential, i.e. if it errors we
-	/// trigger a service exit.
+	/// parameter. The given task is considered essential, i.e. if it panics or
+	/// concludes before the service exits we trigger a service exit.
 	fn spawn_essential_task(&self, name: impl Into<Cow<'static, str>>, task: impl Future<Output = ()> + Send + 'static);
 
 	/// Returns a handle for spawning tasks.
 	fn spawn_task_handle(&self) -> SpawnTaskHandle;
 
+	/// Get the names of the background tasks which have not finished yet.
+	fn running_tasks(&self) -> RunningTasks;
+
 	/// Returns the keystore that stores keys.
 	fn keystore(&self) -> sc_keystore::KeyStorePtr;
 
@@ -222,12 +227,20 @@ where
 	}
 
 	fn spawn_essential_task(&self, name: impl Into<Cow<'static, str>>, task: impl Future<Output = ()> + Send + 'static) {
-		let mut essential_failed = self.essential_failed_tx.clone();
+		let name = name.into();
+		let essential_failed = self.essential_failed_tx.clone();
+		let running = self.task_manager.running_tasks();
+		let task_name = name.clone();
 		let essential_task = std::panic::AssertUnwindSafe(task)
 			.catch_unwind()
-			.map(move |_| {
-				error!("Essential task failed. Shutting down service.");
-				let _ = essential_failed.send(());
+			.map(move |result| {
+				if result.is_err() {
+					error!("Essential task `{}` panicked. Shutting down service.", task_name);
+					running.record_panic(task_name.clone());
+				} else {
+					error!("Essential task `{}` concluded. Shutting down service.", task_name);
+				}
+				let _ = essential_failed.unbounded_send(task_name);
 			});
 
 		let _ = self.spawn_task(name, essential_task);
@@ -237,6 +250,10 @@ where
 		self.task_manager.spawn_handle()
 	}
 
+	fn running_tasks(&self) -> RunningTasks {
+		self.task_manager.running_tasks()
+	}
+
 	fn rpc_query(&self, mem: &RpcSession, request: &str) -> Pin<Box<dyn Future<Output = Option<String>> + Send>> {
 		Box::pin(
 			self.rpc_handlers.handle_request(request, mem.metadata.clone())
@@ -288,10 +305,13 @@ impl<TBl, TCl, TSc, TNetStatus, TNet, TTxPool, TOc> Future for
 
 		match Pin::new(&mut this.essential_failed_rx).poll_next(cx) {
 			Poll::Pending => {},
-			Poll::Ready(_) => {
+			Poll::Ready(name) => {
 				// Ready(None) should not be possible since we hold a live
 				// sender.
-				return Poll::Ready(Err(Error::Other("Essential task failed.".into())));
+				return Poll::Ready(Err(Error::Other(format!(
+					"Essential task `{}` failed.",
+					name.unwrap_or_else(|| "unknown".into()),
+				))));
 			}
 		}
 
@@ -319,7 +339,7 @@ impl<TBl, TCl, TSc, TNetStatus, TNet, TTxPool, TOc> Spawn for
 /// The `status_sink` contain a list of senders to send a periodic network status to.
 fn build_network_future<
 	B: BlockT,
-	C: sc_client::BlockchainEvents<B>,
+	C: sc_client::BlockchainEvents<B> + HeaderBackend<B>,
 	H: sc_network::ExHashT
 > (
 	roles: Roles,
@@ -331,6 +351,7 @@ fn build_network_future<
 ) -> impl Future<Output = ()> {
 	let mut imported_blocks_stream = client.import_notification_stream().fuse();
 	let mut finality_notification_stream = client.finality_notification_stream().fuse();
+	let starting_block = client.info().best_number;
 
 	futures::future::poll_fn(move |cx| {
 		let before_polling = Instant::now();
@@ -359,6 +380,15 @@ fn build_network_future<
 						should_have_peers,
 					});
 				},
+				sc_rpc::system::Request::SyncState(sender) => {
+					let _ = sender.send(sc_rpc::system::SyncState {
+						starting_block,
+						current_block: client.info().best_number,
+						highest_block: network.best_seen_block(),
+						peers: network.num_connected_peers(),
+						is_major_syncing: network.service().is_major_syncing(),
+					});
+				},
 				sc_rpc::system::Request::Peers(sender) => {
 					let _ = sender.send(network.peers_debug_info().into_iter().map(|(peer_id, p)|
 						sc_rpc::system::PeerInfo {
@@ -391,6 +421,49 @@ fn build_network_future<
 						))),
 					};
 				}
+				sc_rpc::system::Request::NetworkReservedPeers(sender) => {
+					let _ = sender.send(
+						network.reserved_peers().iter().map(PeerId::to_base58).collect()
+					);
+				}
+				sc_rpc::system::Request::NetworkSetReservedOnly(reserved_only, sender) => {
+					if reserved_only {
+						network.service().deny_unreserved_peers();
+					} else {
+						network.service().accept_unreserved_peers();
+					}
+					let _ = sender.send(());
+				}
+				sc_rpc::system::Request::NetworkPeerReputations(sender) => {
+					let _ = sender.send(network.reputations().into_iter()
+						.map(|peer| sc_rpc::system::PeerReputation {
+							peer_id: peer.peer_id.to_base58(),
+							reputation: peer.reputation,
+							banned: peer.banned,
+						}).collect());
+				}
+				sc_rpc::system::Request::NetworkBanPeer(peer_id, sender) => {
+					let _ = match peer_id.parse::<PeerId>() {
+						Ok(peer_id) => {
+							network.service().ban_peer(peer_id);
+							sender.send(Ok(()))
+						}
+						Err(e) => sender.send(Err(sc_rpc::system::error::Error::MalformattedPeerArg(
+							e.to_string(),
+						))),
+					};
+				}
+				sc_rpc::system::Request::NetworkUnbanPeer(peer_id, sender) => {
+					let _ = match peer_id.parse::<PeerId>() {
+						Ok(peer_id) => {
+							network.service().unban_peer(peer_id);
+							sender.send(Ok(()))
+						}
+						Err(e) => sender.send(Err(sc_rpc::system::error::Error::MalformattedPeerArg(
+							e.to_string(),
+						))),
+					};
+				}
 				sc_rpc::system::Request::NodeRoles(sender) => {
 					use sc_rpc::system::NodeRole;
 
@@ -464,7 +537,7 @@ pub struct NetworkStatus<B: BlockT> {
 }
 
 #[cfg(not(target_os = "unknown"))]
-// Wrapper for HTTP and WS servers that makes sure they are properly shut down.
+// Wrapper for HTTP, WS and IPC servers that makes sure they are properly shut down.
 mod waiting {
 	pub struct HttpServer(pub Option<sc_rpc_server::HttpServer>);
 	impl Drop for HttpServer {
@@ -485,11 +558,21 @@ mod waiting {
 			}
 		}
 	}
+
+	pub struct IpcServer(pub Option<sc_rpc_server::IpcServer>);
+	impl Drop for IpcServer {
+		fn drop(&mut self) {
+			if let Some(server) = self.0.take() {
+				server.close_handle().close();
+				server.wait();
+			}
+		}
+	}
 }
 
 /// Starts RPC servers that run in their own thread, and returns an opaque object that keeps them alive.
 #[cfg(not(target_os = "unknown"))]
-fn start_rpc_servers<H: FnMut() -> sc_rpc_server::RpcHandler<sc_rpc::Metadata>>(
+fn start_rpc_servers<H: FnMut(bool) -> sc_rpc_server::RpcHandler<sc_rpc::Metadata>>(
 	config: &Configuration,
 	mut gen_handler: H
 ) -> Result<Box<dyn std::any::Any + Send + Sync>, error::Error> {
@@ -514,7 +597,12 @@ fn start_rpc_servers<H: FnMut() -> sc_rpc_server::RpcHandler<sc_rpc::Metadata>>(
 	Ok(Box::new((
 		maybe_start_server(
 			config.rpc_http,
-			|address| sc_rpc_server::start_http(address, config.rpc_cors.as_ref(), gen_handler()),
+			|address| sc_rpc_server::start_http(
+				address,
+				config.rpc_cors.as_ref(),
+				config.rpc_allowed_hosts.as_ref(),
+				gen_handler(config.rpc_methods.deny_unsafe(address)),
+			),
 		)?.map(|s| waiting::HttpServer(Some(s))),
 		maybe_start_server(
 			config.rpc_ws,
@@ -522,15 +610,21 @@ fn start_rpc_servers<H: FnMut() -> sc_rpc_server::RpcHandler<sc_rpc::Metadata>>(
 				address,
 				config.rpc_ws_max_connections,
 				config.rpc_cors.as_ref(),
-				gen_handler(),
+				config.rpc_allowed_hosts.as_ref(),
+				gen_handler(config.rpc_methods.deny_unsafe(address)),
 			),
 		)?.map(|s| waiting::WsServer(Some(s))).map(Mutex::new),
+		config.rpc_ipc.as_ref()
+			.map(|path| sc_rpc_server::start_ipc(path, gen_handler(config.rpc_methods.deny_unsafe_ipc())))
+			.transpose()?
+			.map(|s| waiting::IpcServer(Some(s)))
+			.map(Mutex::new),
 	)))
 }
 
 /// Starts RPC servers that run in their own thread, and returns an opaque object that keeps them alive.
 #[cfg(target_os = "unknown")]
-fn start_rpc_servers<H: FnMut() -> sc_rpc_server::RpcHandler<sc_rpc::Metadata>>(
+fn start_rpc_servers<H: FnMut(bool) -> sc_rpc_server::RpcHandler<sc_rpc::Metadata>>(
 	_: &Configuration,
 	_: H
 ) -> Result<Box<dyn std::any::Any + Send + Sync>, error::Error> {
@@ -690,4 +784,44 @@ mod tests {
 		// this should not panic
 		let _ = transactions[0].1.transfer();
 	}
+
+	#[test]
+	fn embedded_light_client_uses_its_own_directories() {
+		let mut config = Configuration::default();
+		config.name = "node".into();
+		config.config_dir = Some("/base".into());
+		config.database = Some(config::DatabaseConfig::Path { path: "/base/db".into(), cache_size: Some(128) });
+
+		let light = config.embedded_light_client(Some(([127, 0, 0, 1], 9934).into())).unwrap();
+		assert_eq!(light.roles, Roles::LIGHT);
+		assert_eq!(light.name, "node-light");
+		assert_eq!(light.config_dir, Some("/base/light".into()));
+		match light.database {
+			Some(config::DatabaseConfig::Path { path, cache_size }) => {
+				assert_eq!(path, std::path::PathBuf::from("/base/light/db"));
+				assert_eq!(cache_size, Some(128));
+			},
+			_ => panic!("the light client has a database path"),
+		}
+		assert_eq!(light.network.non_reserved_mode, sc_network::config::NonReservedPeerMode::Deny);
+		assert_eq!(light.rpc_http, Some(([127, 0, 0, 1], 9934).into()));
+	}
+
+	#[test]
+	fn embedded_light_client_joins_the_private_network_of_the_full_node() {
+		let psk = sc_network::config::PreSharedKey::new([0xab; 32]);
+		let mut config = Configuration::default();
+		config.network.pre_shared_key = Some(psk);
+
+		let light = config.embedded_light_client(None).unwrap();
+		assert_eq!(light.network.pre_shared_key, Some(psk));
+	}
+
+	#[test]
+	fn embedded_light_client_needs_a_configuration_directory() {
+		let mut config = Configuration::default();
+		config.database = Some(config::DatabaseConfig::Path { path: "/base/db".into(), cache_size: None });
+
+		assert!(config.embedded_light_client(None).is_err());
+	}
 }
@@ -0,0 +1,241 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checks run once at startup, before the network and the other tasks of the service are started.
+//!
+//! The version of the database is already checked when it is opened.
+
+use std::{
+	io, net::UdpSocket, path::Path, time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use log::{debug, warn};
+use sc_client_api::backend::{Backend, BlockImportOperation};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{BuildStorage, generic::BlockId, traits::{Block as BlockT, Header as HeaderT, Zero}};
+use sysinfo::{DiskExt, System, SystemExt};
+use crate::{config::{Configuration, DatabaseConfig}, error::Error};
+
+/// Seconds between the NTP epoch (1900) and the UNIX epoch (1970).
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// Configuration of the startup self-check.
+#[derive(Clone, Debug)]
+pub struct SelfCheckConfig {
+	/// Minimum free space, in bytes, on the disk holding the database.
+	pub min_free_disk_space: u64,
+	/// SNTP server, as `host:port`, to compare the local clock against. The clock isn't checked
+	/// if `None`.
+	pub ntp_server: Option<String>,
+	/// Maximum difference allowed between the local clock and the one of `ntp_server`.
+	pub max_clock_skew: Duration,
+}
+
+impl Default for SelfCheckConfig {
+	fn default() -> Self {
+		Self {
+			min_free_disk_space: 512 * 1024 * 1024,
+			ntp_server: None,
+			max_clock_skew: Duration::from_secs(2),
+		}
+	}
+}
+
+/// Run the checks enabled in the configuration against the freshly opened `backend`.
+pub(crate) fn run<Block, B>(config: &Configuration, backend: &B) -> Result<(), Error> where
+	Block: BlockT,
+	B: Backend<Block>,
+{
+	let self_check = match &config.self_check {
+		Some(self_check) => self_check,
+		None => return Ok(()),
+	};
+
+	if let Some(DatabaseConfig::Path { path, .. }) = &config.database {
+		check_disk_space(path, self_check.min_free_disk_space)?;
+	}
+
+	if let Some(ntp_server) = &self_check.ntp_server {
+		check_clock(ntp_server, self_check.max_clock_skew)?;
+	}
+
+	check_genesis::<Block, _>(backend, config.expect_chain_spec().as_storage_builder())
+}
+
+/// Check that the disk holding `path` has at least `min_free` bytes available.
+fn check_disk_space(path: &Path, min_free: u64) -> Result<(), Error> {
+	// The database directory doesn't exist before the first run.
+	let path = match path.ancestors().find_map(|path| path.canonicalize().ok()) {
+		Some(path) => path,
+		None => return Ok(()),
+	};
+
+	let mut system = System::new();
+	system.refresh_disks_list();
+	system.refresh_disks();
+
+	let disk = system.get_disks().iter()
+		.filter(|disk| path.starts_with(disk.get_mount_point()))
+		.max_by_key(|disk| disk.get_mount_point().as_os_str().len());
+
+	match disk {
+		Some(disk) if disk.get_available_space() < min_free => Err(Error::SelfCheck(format!(
+			"Only {} MiB are available on {}, at least {} MiB are required.",
+			disk.get_available_space() / 1024 / 1024,
+			disk.get_mount_point().display(),
+			min_free / 1024 / 1024,
+		))),
+		Some(_) => Ok(()),
+		None => {
+			debug!("Skipping disk space check, no disk found for {}", path.display());
+			Ok(())
+		},
+	}
+}
+
+/// Check that the local clock is within `max_skew` of the clock of `ntp_server`.
+///
+/// An unreachable server is only reported, as it says nothing about the local clock.
+fn check_clock(ntp_server: &str, max_skew: Duration) -> Result<(), Error> {
+	let offset = match query_clock_offset(ntp_server) {
+		Ok(offset) => offset,
+		Err(err) => {
+			warn!("Skipping clock check, failed to query {}: {}", ntp_server, err);
+			return Ok(())
+		},
+	};
+
+	if offset.abs() > max_skew.as_secs_f64() {
+		return Err(Error::SelfCheck(format!(
+			"The local clock is {:.3}s {} the one of {}, at most {:.3}s are allowed. \
+			Synchronize the clock of this machine.",
+			offset.abs(),
+			if offset > 0.0 { "behind" } else { "ahead of" },
+			ntp_server,
+			max_skew.as_secs_f64(),
+		)))
+	}
+
+	Ok(())
+}
+
+/// Check that the genesis block in the database is the one of the chain specification.
+fn check_genesis<Block, B>(backend: &B, genesis: &dyn BuildStorage) -> Result<(), Error> where
+	Block: BlockT,
+	B: Backend<Block>,
+{
+	let db_genesis = match backend.blockchain().header(BlockId::Number(Zero::zero()))? {
+		Some(header) => header.hash(),
+		None => return Ok(()),
+	};
+
+	// Compute the genesis state root the same way the client does when initializing the
+	// database, without committing the operation.
+	let mut op = backend.begin_operation()?;
+	backend.begin_state_operation(&mut op, BlockId::Hash(Default::default()))?;
+	let state_root = op.reset_storage(genesis.build_storage()?)?;
+	let spec_genesis = sc_client::genesis::construct_genesis_block::<Block>(state_root)
+		.header()
+		.hash();
+
+	if db_genesis != spec_genesis {
+		return Err(Error::SelfCheck(format!(
+			"The database was created for a chain with genesis {}, but the chain specification \
+			has genesis {}. Use another base path, or purge the chain.",
+			db_genesis,
+			spec_genesis,
+		)))
+	}
+
+	Ok(())
+}
+
+/// Query the clock of an SNTP server and return how many seconds it is ahead of the local clock.
+fn query_clock_offset(ntp_server: &str) -> io::Result<f64> {
+	let socket = UdpSocket::bind("0.0.0.0:0")?;
+	socket.set_read_timeout(Some(Duration::from_secs(5)))?;
+	socket.connect(ntp_server)?;
+
+	let mut packet = [0u8; 48];
+	// Leap indicator 0, version 3, client mode.
+	packet[0] = 0x1b;
+	let sent = unix_now();
+	socket.send(&packet)?;
+	let len = socket.recv(&mut packet)?;
+	let received = unix_now();
+
+	let server_time = parse_transmit_timestamp(&packet[..len])
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid SNTP response"))?;
+
+	Ok(server_time - (sent + received) / 2.0)
+}
+
+/// Seconds since the UNIX epoch.
+fn unix_now() -> f64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0)
+}
+
+/// Extract the transmit timestamp of an SNTP response, in seconds since the UNIX epoch.
+fn parse_transmit_timestamp(packet: &[u8]) -> Option<f64> {
+	if packet.len() < 48 {
+		return None
+	}
+
+	let mut seconds = [0u8; 4];
+	let mut fraction = [0u8; 4];
+	seconds.copy_from_slice(&packet[40..44]);
+	fraction.copy_from_slice(&packet[44..48]);
+	let seconds = u32::from_be_bytes(seconds) as u64;
+	let fraction = u32::from_be_bytes(fraction) as f64 / (1u64 << 32) as f64;
+
+	if seconds < NTP_UNIX_OFFSET {
+		return None
+	}
+
+	Some((seconds - NTP_UNIX_OFFSET) as f64 + fraction)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::storage::Storage;
+	use substrate_test_runtime_client::{
+		GenesisInit, TestClientBuilderExt, DefaultTestClientBuilderExt, TestClientBuilder,
+	};
+
+	#[test]
+	fn parses_transmit_timestamp() {
+		let mut packet = [0u8; 48];
+		packet[40..44].copy_from_slice(&((NTP_UNIX_OFFSET + 1_000) as u32).to_be_bytes());
+		packet[44..48].copy_from_slice(&(1u32 << 31).to_be_bytes());
+
+		assert_eq!(parse_transmit_timestamp(&packet), Some(1_000.5));
+		assert_eq!(parse_transmit_timestamp(&packet[..47]), None);
+		assert_eq!(parse_transmit_timestamp(&[0u8; 48]), None);
+	}
+
+	#[test]
+	fn detects_genesis_mismatch() {
+		let mut builder = TestClientBuilder::new();
+		let genesis = builder.genesis_init_mut().genesis_storage();
+		let (_client, backend) = builder.build_with_backend();
+
+		assert!(check_genesis(&*backend, &genesis).is_ok());
+
+		let mut other: Storage = genesis.clone();
+		other.top.insert(b"other_chain".to_vec(), vec![1]);
+		assert!(check_genesis(&*backend, &other).is_err());
+	}
+}
@@ -17,9 +17,11 @@ use std::{
 	result::Result, sync::Arc,
 	task::{Poll, Context},
 	borrow::Cow, pin::Pin,
+	collections::BTreeMap,
 };
 use exit_future::Signal;
 use log::{debug, error};
+use parking_lot::Mutex;
 use futures::{
 	Future, FutureExt, Stream,
 	future::select, channel::mpsc,
@@ -34,6 +36,57 @@ pub type ServiceTaskExecutor = Arc<dyn Fn(Pin<Box<dyn Future<Output = ()> + Send
 /// Type alias for the task scheduler.
 pub type TaskScheduler = mpsc::UnboundedSender<(Pin<Box<dyn Future<Output = ()> + Send>>, Cow<'static, str>)>;
 
+/// The names of the background tasks of a service which have not finished yet.
+///
+/// A task is counted until its future is dropped, either because it has completed or because
+/// the executor running it has shut down. The tasks which panicked are recorded as well.
+#[derive(Clone, Default)]
+pub struct RunningTasks {
+	running: Arc<Mutex<BTreeMap<Cow<'static, str>, usize>>>,
+	panicked: Arc<Mutex<BTreeMap<Cow<'static, str>, usize>>>,
+}
+
+impl RunningTasks {
+	/// The names of the running tasks, with the number of running tasks of each name.
+	pub fn names(&self) -> Vec<(String, usize)> {
+		self.running.lock().iter().map(|(name, count)| (name.to_string(), *count)).collect()
+	}
+
+	/// The names of the tasks which panicked, with the number of panics of each name.
+	pub fn panicked(&self) -> Vec<(String, usize)> {
+		self.panicked.lock().iter().map(|(name, count)| (name.to_string(), *count)).collect()
+	}
+
+	/// Count a running task named `name` until the returned guard is dropped.
+	fn start(&self, name: Cow<'static, str>) -> RunningTaskGuard {
+		*self.running.lock().entry(name.clone()).or_insert(0) += 1;
+		RunningTaskGuard { tasks: self.clone(), name }
+	}
+
+	/// Record that the task named `name` panicked.
+	pub(crate) fn record_panic(&self, name: Cow<'static, str>) {
+		*self.panicked.lock().entry(name).or_insert(0) += 1;
+	}
+}
+
+struct RunningTaskGuard {
+	tasks: RunningTasks,
+	name: Cow<'static, str>,
+}
+
+impl Drop for RunningTaskGuard {
+	fn drop(&mut self) {
+		let mut tasks = self.tasks.running.lock();
+		let remaining = tasks.get_mut(&self.name).map(|count| {
+			*count -= 1;
+			*count
+		});
+		if remaining == Some(0) {
+			tasks.remove(&self.name);
+		}
+	}
+}
+
 /// Helper struct to setup background tasks execution for service.
 pub struct TaskManagerBuilder {
 	/// A future that resolves when the service has exited, this is useful to
@@ -85,6 +138,7 @@ impl TaskManagerBuilder {
 			to_spawn_tx,
 			to_spawn_rx,
 			executor,
+			running: Default::default(),
 		}
 	}
 }
@@ -147,6 +201,8 @@ pub struct TaskManager {
 	to_spawn_rx: mpsc::UnboundedReceiver<(Pin<Box<dyn Future<Output = ()> + Send>>, Cow<'static, str>)>,
 	/// How to spawn background tasks.
 	executor: ServiceTaskExecutor,
+	/// The tasks spawned which have not finished yet.
+	running: RunningTasks,
 }
 
 impl TaskManager {
@@ -177,10 +233,27 @@ impl TaskManager {
 	/// Process background task receiver.
 	pub(super) fn process_receiver(&mut self, cx: &mut Context) {
 		while let Poll::Ready(Some((task_to_spawn, name))) = Pin::new(&mut self.to_spawn_rx).poll_next(cx) {
-			(self.executor)(Box::pin(futures_diagnose::diagnose(name, task_to_spawn)));
+			let guard = self.running.start(name.clone());
+			let running = self.running.clone();
+			let task_name = name.clone();
+			let task = std::panic::AssertUnwindSafe(task_to_spawn)
+				.catch_unwind()
+				.map(move |result| {
+					if result.is_err() {
+						error!("Task `{}` panicked.", task_name);
+						running.record_panic(task_name);
+					}
+					drop(guard)
+				});
+			(self.executor)(Box::pin(futures_diagnose::diagnose(name, task)));
 		}
 	}
 
+	/// The tasks spawned which have not finished yet.
+	pub(super) fn running_tasks(&self) -> RunningTasks {
+		self.running.clone()
+	}
+
 	/// Clone on exit signal.
 	pub(super) fn on_exit(&self) -> exit_future::Exit {
 		self.on_exit.clone()
@@ -195,3 +268,37 @@ impl Drop for TaskManager {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn running_tasks_are_counted_until_dropped() {
+		let tasks = RunningTasks::default();
+		let first = tasks.start("import".into());
+		let second = tasks.start("import".into());
+		let other = tasks.start("network".into());
+		assert_eq!(tasks.names(), vec![("import".to_string(), 2), ("network".to_string(), 1)]);
+
+		drop(first);
+		drop(other);
+		assert_eq!(tasks.names(), vec![("import".to_string(), 1)]);
+
+		drop(second);
+		assert!(tasks.names().is_empty());
+	}
+
+	#[test]
+	fn panicking_tasks_are_recorded() {
+		let mut task_manager = TaskManagerBuilder::new()
+			.into_task_manager(Arc::new(|task| futures::executor::block_on(task)));
+		task_manager.spawn("panicking", async { panic!("task panicked on purpose") });
+		task_manager.spawn("completing", async {});
+		task_manager.process_receiver(&mut Context::from_waker(futures::task::noop_waker_ref()));
+
+		let tasks = task_manager.running_tasks();
+		assert_eq!(tasks.panicked(), vec![("panicking".to_string(), 1)]);
+		assert!(tasks.names().is_empty());
+	}
+}
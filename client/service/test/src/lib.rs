@@ -157,11 +157,14 @@ fn node_config<G: RuntimeGenesis + 'static, E: ChainSpecExtension + Clone + 'sta
 		public_addresses: vec![],
 		boot_nodes: vec![],
 		node_key: NodeKeyConfig::Ed25519(Secret::New),
+		pre_shared_key: None,
 		in_peers: 50,
 		out_peers: 450,
 		reserved_nodes: vec![],
 		non_reserved_mode: NonReservedPeerMode::Accept,
+		reputation: Default::default(),
 		sentry_nodes: vec![],
+		sentry_validators: vec![],
 		client_version: "network/test/0.1".to_owned(),
 		node_name: "unknown".to_owned(),
 		transport: TransportConfig::Normal {
@@ -169,8 +172,12 @@ fn node_config<G: RuntimeGenesis + 'static, E: ChainSpecExtension + Clone + 'sta
 			allow_private_ipv4: true,
 			wasm_external_transport: None,
 			use_yamux_flow_control: true,
+			enable_port_mapping: false,
 		},
+		bandwidth_limits: Default::default(),
 		max_parallel_downloads: NetworkConfiguration::default().max_parallel_downloads,
+		max_blocks_per_request: NetworkConfiguration::default().max_blocks_per_request,
+		sync_mode: NetworkConfiguration::default().sync_mode,
 	};
 
 	Configuration {
@@ -199,13 +206,21 @@ fn node_config<G: RuntimeGenesis + 'static, E: ChainSpecExtension + Clone + 'sta
 		execution_strategies: Default::default(),
 		rpc_http: None,
 		rpc_ws: None,
+		rpc_ipc: None,
 		rpc_ws_max_connections: None,
 		rpc_cors: None,
+		rpc_allowed_hosts: None,
+		rpc_methods: Default::default(),
+		rpc_subscription_limits: Default::default(),
+		rpc_slow_call_threshold: None,
 		prometheus_config: None,
+		health_config: None,
+		self_check: None,
 		telemetry_endpoints: None,
 		telemetry_external_transport: None,
 		default_heap_pages: None,
 		offchain_worker: false,
+		offchain_worker_threads: None,
 		sentry_mode: false,
 		force_authoring: false,
 		disable_grandpa: false,
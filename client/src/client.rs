@@ -27,7 +27,7 @@ use codec::{Encode, Decode};
 use hash_db::Prefix;
 use sp_core::{
 	ChangesTrieConfiguration, convert_hash, traits::CodeExecutor,
-	NativeOrEncoded, storage::{StorageKey, StorageData, well_known_keys, ChildInfo},
+	NativeOrEncoded, storage::{Storage, StorageKey, StorageData, well_known_keys, ChildInfo},
 };
 use sc_telemetry::{telemetry, SUBSTRATE_INFO};
 use sp_runtime::{
@@ -40,7 +40,7 @@ use sp_runtime::{
 };
 use sp_state_machine::{
 	DBValue, Backend as StateBackend, ChangesTrieAnchorBlockId,
-	prove_read, prove_child_read, ChangesTrieRootsStorage, ChangesTrieStorage,
+	prove_read, prove_child_read, prove_state_chunk, ChangesTrieRootsStorage, ChangesTrieStorage,
 	ChangesTrieConfigurationRange, key_changes, key_changes_proof,
 };
 use sc_executor::{RuntimeVersion, RuntimeInfo};
@@ -570,6 +570,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			fork_choice,
 			intermediates,
 			import_existing,
+			imported_state,
 			..
 		} = import_block;
 
@@ -609,6 +610,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			auxiliary,
 			fork_choice,
 			import_existing,
+			imported_state,
 		);
 
 		if let Ok(ImportResult::Imported(ref aux)) = result {
@@ -638,6 +640,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		aux: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 		fork_choice: ForkChoiceStrategy,
 		import_existing: bool,
+		imported_state: Option<Storage>,
 	) -> sp_blockchain::Result<ImportResult> where
 		Self: ProvideRuntimeApi<Block>,
 		<Self as ProvideRuntimeApi<Block>>::Api: CoreApi<Block, Error = Error> +
@@ -669,6 +672,14 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			BlockOrigin::Genesis | BlockOrigin::NetworkInitialSync | BlockOrigin::File => false,
 		};
 
+		// the state of the block was downloaded, check that it matches the header.
+		if let Some(state) = imported_state {
+			let state_root = operation.op.reset_storage(state)?;
+			if &state_root != import_headers.post().state_root() {
+				return Err(Error::InvalidStateRoot);
+			}
+		}
+
 		let storage_changes = match storage_changes {
 			Some(storage_changes) => {
 				self.backend.begin_state_operation(&mut operation.op, BlockId::Hash(parent_hash))?;
@@ -784,6 +795,12 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			BlockStatus::KnownBad => return Ok(Some(ImportResult::KnownBad)),
 		};
 
+		// The complete state of the block is provided, there is nothing to execute.
+		if import_block.imported_state.is_some() {
+			import_block.storage_changes = None;
+			return Ok(None);
+		}
+
 		match (enact_state, &mut import_block.storage_changes, &mut import_block.body) {
 			// We have storage changes and should enact the state, so we don't need to do anything
 			// here
@@ -1160,6 +1177,17 @@ impl<B, E, Block, RA> ProofProvider<Block> for Client<B, E, Block, RA> where
 			cht::size(),
 		)
 	}
+
+	fn state_chunk_proof(
+		&self,
+		id: &BlockId<Block>,
+		start: &[Vec<u8>],
+		size_limit: usize,
+	) -> sp_blockchain::Result<StorageProof> {
+		self.state_at(id)
+			.and_then(|state| prove_state_chunk(state, start, size_limit)
+				.map_err(Into::into))
+	}
 }
 
 
@@ -3010,6 +3038,44 @@ pub(crate) mod tests {
 		assert_eq!(980, current_balance(&client));
 	}
 
+	#[test]
+	fn revert_stops_at_the_last_finalized_block() {
+		let tmp = tempfile::tempdir().unwrap();
+
+		let backend = Arc::new(Backend::new(
+			DatabaseSettings {
+				state_cache_size: 1 << 20,
+				state_cache_child_ratio: None,
+				pruning: PruningMode::ArchiveAll,
+				source: DatabaseSettingsSrc::Path {
+					path: tmp.path().into(),
+					cache_size: None,
+				}
+			},
+			u64::max_value(),
+		).unwrap());
+
+		let mut client = TestClientBuilder::with_backend(backend).build();
+
+		// G -> A1 -> A2 -> A3
+		let mut parent = client.chain_info().genesis_hash;
+		let mut hashes = Vec::new();
+		for _ in 0..3 {
+			let block = client.new_block_at(&BlockId::Hash(parent), Default::default(), false)
+				.unwrap().build().unwrap().block;
+			parent = block.hash();
+			hashes.push(parent);
+			client.import(BlockOrigin::Own, block).unwrap();
+		}
+
+		ClientExt::finalize_block(&client, BlockId::Hash(hashes[0]), None).unwrap();
+
+		assert_eq!(client.revert(10).unwrap(), 2);
+		assert_eq!(client.chain_info().best_number, 1);
+		assert_eq!(client.chain_info().best_hash, hashes[0]);
+		assert_eq!(client.revert(1).unwrap(), 0);
+	}
+
 	#[test]
 	fn doesnt_import_blocks_that_revert_finality() {
 		let _ = env_logger::try_init();
@@ -85,8 +85,13 @@ pub enum Error<E: fmt::Debug> {
 	InvalidBlockNumber,
 	/// Trying to insert block with unknown parent.
 	InvalidParent,
-	/// Invalid pruning mode specified. Contains expected mode.
-	InvalidPruningMode(String),
+	/// Invalid pruning mode specified.
+	InvalidPruningMode {
+		/// The mode the database was created with.
+		stored: String,
+		/// The mode requested.
+		requested: String,
+	},
 }
 
 /// Pinning error type.
@@ -109,7 +114,12 @@ impl<E: fmt::Debug> fmt::Debug for Error<E> {
 			Error::InvalidBlock => write!(f, "Trying to canonicalize invalid block"),
 			Error::InvalidBlockNumber => write!(f, "Trying to insert block with invalid number"),
 			Error::InvalidParent => write!(f, "Trying to insert block with unknown parent"),
-			Error::InvalidPruningMode(e) => write!(f, "Expected pruning mode: {}", e),
+			Error::InvalidPruningMode { stored, requested } => write!(
+				f,
+				"Pruning mode `{}` does not match the pruning mode `{}` the database was created with",
+				requested,
+				stored,
+			),
 		}
 	}
 }
@@ -234,7 +244,10 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		);
 		match &db_mode {
 			Some(v) if v.as_slice() == mode.id() => Ok(()),
-			Some(v) => Err(Error::InvalidPruningMode(String::from_utf8_lossy(v).into())),
+			Some(v) => Err(Error::InvalidPruningMode {
+				stored: String::from_utf8_lossy(v).into(),
+				requested: String::from_utf8_lossy(mode.id()).into(),
+			}),
 			None => Ok(()),
 		}
 	}
@@ -271,6 +284,28 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		}
 	}
 
+	fn import_state<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		mut changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		changeset.deleted.clear();
+		let mut commit = CommitSet {
+			data: changeset,
+			meta: ChangeSet::default(),
+		};
+		if self.mode == PruningMode::ArchiveAll {
+			return Ok(commit)
+		}
+		self.non_canonical.import_canonical(hash, number, &mut commit)?;
+		if let Some(ref mut pruning) = self.pruning {
+			pruning.note_canonical(&hash, &mut commit);
+		}
+		self.prune(&mut commit);
+		Ok(commit)
+	}
+
 	fn canonicalize_block<E: fmt::Debug>(
 		&mut self,
 		hash: &BlockHash,
@@ -456,6 +491,17 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 		self.db.write().insert_block(hash, number, parent_hash, changeset)
 	}
 
+	/// Add a canonical block with a complete state that was obtained without executing
+	/// its ancestors. Fails if there are any non-canonical blocks.
+	pub fn import_state<E: fmt::Debug>(
+		&self,
+		hash: &BlockHash,
+		number: u64,
+		changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		self.db.write().import_state(hash, number, changeset)
+	}
+
 	/// Finalize a previously inserted block.
 	pub fn canonicalize_block<E: fmt::Debug>(
 		&self,
@@ -518,7 +564,7 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 mod tests {
 	use std::io;
 	use sp_core::H256;
-	use crate::{StateDb, PruningMode, Constraints};
+	use crate::{StateDb, PruningMode, Constraints, Error};
 	use crate::test::{make_db, make_changeset, TestDb};
 
 	fn make_test_db(settings: PruningMode) -> (TestDb, StateDb<H256, H256>) {
@@ -651,6 +697,69 @@ mod tests {
 		);
 		let new_mode = PruningMode::Constrained(Constraints { max_blocks: Some(2), max_mem: None });
 		let state_db: Result<StateDb<H256, H256>, _> = StateDb::new(new_mode, &db);
-		assert!(state_db.is_err());
+		match state_db {
+			Err(Error::InvalidPruningMode { stored, requested }) => {
+				assert_eq!(stored, "archive");
+				assert_eq!(requested, "constrained");
+			},
+			_ => panic!("Opening the database with a different pruning mode should fail"),
+		}
+	}
+	#[test]
+	fn import_state_after_genesis() {
+		let mut db = make_db(&[]);
+		let state_db = StateDb::new(PruningMode::keep_blocks(2), &db).unwrap();
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(0),
+					0,
+					&H256::from_low_u64_be(0),
+					make_changeset(&[90], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(0)).unwrap());
+		state_db.apply_pending();
+
+		// there is no parent state to delete anything from.
+		db.commit(
+			&state_db
+				.import_state::<io::Error>(
+					&H256::from_low_u64_be(10),
+					10,
+					make_changeset(&[10, 11], &[90]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(10));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(10), 10));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(5), 5));
+		assert!(db.data_eq(&make_db(&[10, 11, 90])));
+
+		// blocks are imported on top of the imported state as usual.
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(20),
+					11,
+					&H256::from_low_u64_be(10),
+					make_changeset(&[20], &[11]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+
+		// not possible while there are non-canonical blocks.
+		assert!(state_db
+			.import_state::<io::Error>(&H256::from_low_u64_be(30), 30, make_changeset(&[30], &[]))
+			.is_err());
+
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(20)).unwrap());
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(11));
+		assert!(db.data_eq(&make_db(&[10, 11, 20, 90])));
 	}
 }
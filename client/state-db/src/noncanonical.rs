@@ -37,6 +37,8 @@ pub struct NonCanonicalOverlay<BlockHash: Hash, Key: Hash> {
 	parents: HashMap<BlockHash, BlockHash>,
 	pending_canonicalizations: Vec<BlockHash>,
 	pending_insertions: Vec<BlockHash>,
+	// last canonicalized block before the pending `import_canonical` call, if any.
+	pending_import: Option<Option<(BlockHash, u64)>>,
 	values: HashMap<Key, (u32, DBValue)>, //ref counted
 	//would be deleted but kept around because block is pinned, ref counted.
 	pinned: HashMap<BlockHash, u32>,
@@ -181,6 +183,7 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 			parents,
 			pending_canonicalizations: Default::default(),
 			pending_insertions: Default::default(),
+			pending_import: None,
 			pinned: Default::default(),
 			pinned_insertions: Default::default(),
 			values: values,
@@ -247,6 +250,33 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		Ok(commit)
 	}
 
+	/// Make a block that was not inserted into the overlay the last canonicalized one.
+	/// Used when the state of the block was obtained without executing its ancestors.
+	/// Only possible when there are no non-canonical blocks and no pending changes.
+	pub fn import_canonical<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		commit: &mut CommitSet<Key>,
+	) -> Result<(), Error<E>> {
+		if !self.levels.is_empty()
+			|| !self.pending_canonicalizations.is_empty()
+			|| !self.pending_insertions.is_empty()
+			|| self.pending_import.is_some()
+		{
+			trace!(target: "state-db", "Failed to import canonical block {}, overlay is not empty", number);
+			return Err(Error::InvalidBlockNumber);
+		}
+		if self.last_canonicalized.as_ref().map_or(false, |&(_, n)| number <= n) {
+			return Err(Error::InvalidBlockNumber);
+		}
+		let last_canonicalized = (hash.clone(), number);
+		commit.meta.inserted.push((to_meta_key(LAST_CANONICAL, &()), last_canonicalized.encode()));
+		trace!(target: "state-db", "Imported canonical block {} ({:?})", number, hash);
+		self.pending_import = Some(self.last_canonicalized.replace(last_canonicalized));
+		Ok(())
+	}
+
 	fn discard_journals(
 		&self,
 		level_index: usize,
@@ -421,12 +451,16 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	pub fn apply_pending(&mut self) {
 		self.apply_canonicalizations();
 		self.pending_insertions.clear();
+		self.pending_import = None;
 	}
 
 	/// Revert all pending changes
 	pub fn revert_pending(&mut self) {
 		self.pending_canonicalizations.clear();
 		self.revert_insertions();
+		if let Some(last_canonicalized) = self.pending_import.take() {
+			self.last_canonicalized = last_canonicalized;
+		}
 	}
 
 	/// Pin state values in memory
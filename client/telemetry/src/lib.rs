@@ -272,7 +272,7 @@ impl slog::Drain for TelemetryDrain {
 }
 
 /// Parses a WebSocket URL into a libp2p `Multiaddr`.
-fn url_to_multiaddr(url: &str) -> Result<Multiaddr, libp2p::multiaddr::Error> {
+pub fn url_to_multiaddr(url: &str) -> Result<Multiaddr, libp2p::multiaddr::Error> {
 	// First, assume that we have a `Multiaddr`.
 	let parse_error = match url.parse() {
 		Ok(ma) => return Ok(ma),
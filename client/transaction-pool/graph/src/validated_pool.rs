@@ -549,6 +549,13 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.pool.read().ready()
 	}
 
+	/// Get the transactions in the future queue, waiting for the tags they require.
+	///
+	/// The future queue doesn't share its transactions, so they are duplicated.
+	pub fn futures(&self) -> Vec<TransactionFor<B>> {
+		self.pool.read().futures().map(|tx| Arc::new(tx.duplicate())).collect()
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> PoolStatus {
 		self.pool.read().status()
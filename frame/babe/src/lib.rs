@@ -29,7 +29,7 @@ use sp_runtime::{generic::DigestItem, ConsensusEngineId, Perbill, PerThing};
 use sp_runtime::traits::{IsMember, SaturatedConversion, Saturating, Hash};
 use sp_staking::{
 	SessionIndex,
-	offence::{Offence, Kind},
+	offence::{Offence, Kind, kind, SessionLength},
 };
 
 use codec::{Encode, Decode};
@@ -260,7 +260,7 @@ struct BabeEquivocationOffence<FullIdentification> {
 }
 
 impl<FullIdentification: Clone> Offence<FullIdentification> for BabeEquivocationOffence<FullIdentification> {
-	const ID: Kind = *b"babe:equivocatio";
+	const ID: Kind = kind::BABE_EQUIVOCATION;
 	type TimeSlot = u64;
 
 	fn offenders(&self) -> Vec<FullIdentification> {
@@ -282,6 +282,7 @@ impl<FullIdentification: Clone> Offence<FullIdentification> for BabeEquivocation
 	fn slash_fraction(
 		offenders_count: u32,
 		validator_set_count: u32,
+		_session_length: Option<SessionLength>,
 	) -> Perbill {
 		// the formula is min((3k / n)^2, 1)
 		let x = Perbill::from_rational_approximation(3 * offenders_count, validator_set_count);
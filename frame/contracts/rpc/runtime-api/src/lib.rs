@@ -40,18 +40,77 @@ pub enum ContractExecResult {
 		///
 		/// Can be empty.
 		data: Vec<u8>,
+		/// Amount of gas consumed by the call.
+		gas_consumed: u64,
+		/// Debug messages printed by the contracts during the call, one per line.
+		///
+		/// Empty unless printing is enabled in the schedule.
+		debug_message: Vec<u8>,
+	},
+	/// The contract execution either trapped or returned an error.
+	Error {
+		/// Amount of gas consumed by the call.
+		gas_consumed: u64,
+		/// Debug messages printed by the contracts during the call, one per line.
+		///
+		/// Empty unless printing is enabled in the schedule.
+		debug_message: Vec<u8>,
+	},
+}
+
+/// A result of execution of a contract, as returned by version 1 of `ContractsApi`.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum ContractExecResultV1 {
+	/// The contract returned successfully.
+	Success {
+		/// Status code returned by the contract.
+		status: u8,
+		/// Output data returned by the contract.
+		data: Vec<u8>,
 	},
 	/// The contract execution either trapped or returned an error.
 	Error,
 }
 
+/// Version 1 runtimes don't report the gas consumed nor the debug messages, they are left zero
+/// and empty.
+impl From<ContractExecResultV1> for ContractExecResult {
+	fn from(result: ContractExecResultV1) -> Self {
+		match result {
+			ContractExecResultV1::Success { status, data } => ContractExecResult::Success {
+				status,
+				data,
+				gas_consumed: 0,
+				debug_message: Vec::new(),
+			},
+			ContractExecResultV1::Error => ContractExecResult::Error {
+				gas_consumed: 0,
+				debug_message: Vec::new(),
+			},
+		}
+	}
+}
+
 sp_api::decl_runtime_apis! {
 	/// The API to interact with contracts without using executive.
+	#[api_version(2)]
 	pub trait ContractsApi<AccountId, Balance, BlockNumber> where
 		AccountId: Codec,
 		Balance: Codec,
 		BlockNumber: Codec,
 	{
+		/// Perform a call from a specified account to a given contract.
+		///
+		/// See the contracts' `call` dispatchable function for more details.
+		#[changed_in(2)]
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractExecResultV1;
+
 		/// Perform a call from a specified account to a given contract.
 		///
 		/// See the contracts' `call` dispatchable function for more details.
@@ -23,7 +23,7 @@ use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use pallet_contracts_primitives::RentProjection;
 use serde::{Deserialize, Serialize};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::{Bytes, H256};
 use sp_rpc::number;
@@ -93,19 +93,39 @@ pub enum RpcContractExecResult {
 		status: u8,
 		/// Output data
 		data: Bytes,
+		/// Gas consumed
+		#[serde(rename = "gasConsumed")]
+		gas_consumed: u64,
+		/// Debug messages printed by the contracts
+		#[serde(rename = "debugMessage")]
+		debug_message: Bytes,
 	},
 	/// Error execution
-	Error(()),
+	Error {
+		/// Gas consumed
+		#[serde(rename = "gasConsumed")]
+		gas_consumed: u64,
+		/// Debug messages printed by the contracts
+		#[serde(rename = "debugMessage")]
+		debug_message: Bytes,
+	},
 }
 
 impl From<ContractExecResult> for RpcContractExecResult {
 	fn from(r: ContractExecResult) -> Self {
 		match r {
-			ContractExecResult::Success { status, data } => RpcContractExecResult::Success {
-				status,
-				data: data.into(),
-			},
-			ContractExecResult::Error => RpcContractExecResult::Error(()),
+			ContractExecResult::Success { status, data, gas_consumed, debug_message } =>
+				RpcContractExecResult::Success {
+					status,
+					data: data.into(),
+					gas_consumed,
+					debug_message: debug_message.into(),
+				},
+			ContractExecResult::Error { gas_consumed, debug_message } =>
+				RpcContractExecResult::Error {
+					gas_consumed,
+					debug_message: debug_message.into(),
+				},
 		}
 	}
 }
@@ -118,6 +138,10 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 	/// This call is performed locally without submitting any transactions. Thus executing this
 	/// won't change any state. Nonetheless, the calling state-changing contracts is still possible.
 	///
+	/// Along with the output of the contract, the gas consumed by the call and the messages the
+	/// contracts printed are returned, which makes it suitable for estimating the gas limit of a
+	/// transaction and for debugging.
+	///
 	/// This method is useful for calling getter-like methods on contracts.
 	#[rpc(name = "contracts_call")]
 	fn call(
@@ -219,9 +243,22 @@ where
 			});
 		}
 
-		let exec_result = api
-			.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
+		let has_api_v2 = api
+			.has_api_with::<dyn ContractsRuntimeApi<
+				Block,
+				AccountId,
+				Balance,
+				<<Block as BlockT>::Header as HeaderT>::Number,
+				Error = (),
+			>, _>(&at, |v| v >= 2)
 			.map_err(|e| runtime_error_into_rpc_err(e))?;
+		let exec_result = if has_api_v2 {
+			api.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
+		} else {
+			#[allow(deprecated)]
+			api.call_before_version_2(&at, origin, dest, value, gas_limit, input_data.to_vec())
+				.map(Into::into)
+		}.map_err(|e| runtime_error_into_rpc_err(e))?;
 
 		Ok(exec_result.into())
 	}
@@ -289,7 +326,7 @@ mod tests {
 			assert_eq!(actual, expected);
 		}
 
-		test(r#"{"success":{"status":5,"data":"0x1234"}}"#);
-		test(r#"{"error":null}"#);
+		test(r#"{"success":{"status":5,"data":"0x1234","gasConsumed":5000,"debugMessage":"0x"}}"#);
+		test(r#"{"error":{"gasConsumed":10,"debugMessage":"0x48656c6c6f"}}"#);
 	}
 }
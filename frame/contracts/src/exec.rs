@@ -20,7 +20,7 @@ use crate::account_db::{AccountDb, DirectAccountDb, OverlayAccountDb};
 use crate::gas::{Gas, GasMeter, Token, approx_gas_for_balance};
 use crate::rent;
 
-use sp_std::prelude::*;
+use sp_std::{prelude::*, cell::RefCell};
 use sp_runtime::traits::{Bounded, CheckedAdd, CheckedSub, Zero};
 use frame_support::{
 	storage::unhashed, dispatch::DispatchError,
@@ -203,6 +203,12 @@ pub trait Ext {
 	///
 	/// Returns `None` if the value doesn't exist.
 	fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>>;
+
+	/// Append a message to the debug buffer of the current call, if there is one, followed by a
+	/// newline.
+	///
+	/// Debug messages are only collected for calls made through a dry-run, e.g. an RPC.
+	fn append_debug_message(&mut self, message: &str);
 }
 
 /// Loader is a companion of the `Vm` trait. It loads an appropriate abstract
@@ -300,6 +306,7 @@ pub struct ExecutionContext<'a, T: Trait + 'a, V, L> {
 	pub loader: &'a L,
 	pub timestamp: MomentOf<T>,
 	pub block_number: T::BlockNumber,
+	pub debug_message: Option<&'a RefCell<Vec<u8>>>,
 }
 
 impl<'a, T, E, V, L> ExecutionContext<'a, T, V, L>
@@ -325,6 +332,7 @@ where
 			loader: &loader,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Module<T>>::block_number(),
+			debug_message: None,
 		}
 	}
 
@@ -343,6 +351,7 @@ where
 			loader: self.loader,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
+			debug_message: self.debug_message,
 		}
 	}
 
@@ -868,6 +877,14 @@ where
 	fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
 		unhashed::get_raw(&key)
 	}
+
+	fn append_debug_message(&mut self, message: &str) {
+		if let Some(buffer) = self.ctx.debug_message {
+			let mut buffer = buffer.borrow_mut();
+			buffer.extend_from_slice(message.as_bytes());
+			buffer.push(b'\n');
+		}
+	}
 }
 
 /// These tests exercise the executive layer.
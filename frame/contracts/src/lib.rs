@@ -109,7 +109,7 @@ pub use crate::exec::{ExecResult, ExecReturnValue, ExecError, StatusCode};
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
 use sp_core::crypto::UncheckedFrom;
-use sp_std::{prelude::*, marker::PhantomData, fmt::Debug};
+use sp_std::{prelude::*, marker::PhantomData, fmt::Debug, cell::RefCell};
 use codec::{Codec, Encode, Decode};
 use sp_io::hashing::blake2_256;
 use sp_runtime::{
@@ -623,10 +623,11 @@ decl_module! {
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 
-			Self::execute_wasm(origin, gas_limit, |ctx, gas_meter| {
+			Self::execute_wasm(origin, gas_limit, None, |ctx, gas_meter| {
 				ctx.instantiate(endowment, gas_meter, &code_hash, data)
 					.map(|(_address, output)| output)
 			})
+			.0
 			.map(|_| ())
 			.map_err(|e| e.reason.into())
 		}
@@ -669,6 +670,18 @@ decl_module! {
 	}
 }
 
+/// The result of a contract call performed by `Module::dry_run_call`.
+pub struct DryRunResult {
+	/// The result of the call.
+	pub exec_result: ExecResult,
+	/// The amount of gas consumed by the call.
+	pub gas_consumed: Gas,
+	/// The debug messages printed by the contracts during the call, one per line.
+	///
+	/// Messages are only printed when `Schedule::enable_println` is set.
+	pub debug_message: Vec<u8>,
+}
+
 /// Public APIs provided by the contracts module.
 impl<T: Trait> Module<T> {
 	/// Perform a call to a specified contract.
@@ -682,9 +695,33 @@ impl<T: Trait> Module<T> {
 		gas_limit: Gas,
 		input_data: Vec<u8>,
 	) -> ExecResult {
-		Self::execute_wasm(origin, gas_limit, |ctx, gas_meter| {
+		Self::execute_wasm(origin, gas_limit, None, |ctx, gas_meter| {
 			ctx.call(dest, value, gas_meter, input_data)
-		})
+		}).0
+	}
+
+	/// Perform a call to a specified contract, collecting the gas consumed and the debug messages
+	/// emitted by the contracts along the way.
+	///
+	/// This function is meant to be used for executing calls off-chain, e.g. from an RPC, and
+	/// behaves exactly like `Self::bare_call` otherwise.
+	pub fn dry_run_call(
+		origin: T::AccountId,
+		dest: T::AccountId,
+		value: BalanceOf<T>,
+		gas_limit: Gas,
+		input_data: Vec<u8>,
+	) -> DryRunResult {
+		let debug_message = RefCell::new(Vec::new());
+		let (exec_result, gas_consumed) =
+			Self::execute_wasm(origin, gas_limit, Some(&debug_message), |ctx, gas_meter| {
+				ctx.call(dest, value, gas_meter, input_data)
+			});
+		DryRunResult {
+			exec_result,
+			gas_consumed,
+			debug_message: debug_message.into_inner(),
+		}
 	}
 
 	/// Query storage of a specified contract under a specified key.
@@ -714,28 +751,34 @@ impl<T: Trait> Module<T> {
 }
 
 impl<T: Trait> Module<T> {
+	/// Execute `func` in a new top level execution context, returning its result along with the
+	/// gas it consumed.
+	///
+	/// Debug messages emitted by the contracts are appended to `debug_message` if it is set.
 	fn execute_wasm(
 		origin: T::AccountId,
 		gas_limit: Gas,
+		debug_message: Option<&RefCell<Vec<u8>>>,
 		func: impl FnOnce(&mut ExecutionContext<T, WasmVm, WasmLoader>, &mut GasMeter<T>) -> ExecResult
-	) -> ExecResult {
+	) -> (ExecResult, Gas) {
 		// Pay for the gas upfront.
 		//
 		// NOTE: it is very important to avoid any state changes before
 		// paying for the gas.
-		let (mut gas_meter, imbalance) =
-			try_or_exec_error!(
-				gas::buy_gas::<T>(&origin, gas_limit),
-				// We don't have a spare buffer here in the first place, so create a new empty one.
-				Vec::new()
-			);
+		let (mut gas_meter, imbalance) = match gas::buy_gas::<T>(&origin, gas_limit) {
+			Ok(bought) => bought,
+			// We don't have a spare buffer here in the first place, so create a new empty one.
+			Err(reason) => return (Err(ExecError { reason: reason.into(), buffer: Vec::new() }), 0),
+		};
 
 		let cfg = Config::preload();
 		let vm = WasmVm::new(&cfg.schedule);
 		let loader = WasmLoader::new(&cfg.schedule);
 		let mut ctx = ExecutionContext::top_level(origin.clone(), &cfg, &vm, &loader);
+		ctx.debug_message = debug_message;
 
 		let result = func(&mut ctx, &mut gas_meter);
+		let gas_consumed = gas_limit.saturating_sub(gas_meter.gas_left());
 
 		if result.as_ref().map(|output| output.is_success()).unwrap_or(false) {
 			// Commit all changes that made it thus far into the persistent storage.
@@ -783,7 +826,7 @@ impl<T: Trait> Module<T> {
 			}
 		});
 
-		result
+		(result, gas_consumed)
 	}
 
 	fn restore_to(
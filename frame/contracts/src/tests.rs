@@ -2736,3 +2736,46 @@ fn get_runtime_storage() {
 		));
 	});
 }
+
+const CODE_PRINTLN: &str = r#"
+(module
+	(import "env" "ext_println" (func $ext_println (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $ext_println
+			(i32.const 0)	;; Pointer to the message
+			(i32.const 6)	;; Length of the message
+		)
+		(call $ext_println
+			(i32.const 6)	;; Pointer to the message
+			(i32.const 5)	;; Length of the message
+		)
+	)
+
+	(data (i32.const 0) "Hello World")
+)
+"#;
+
+#[test]
+fn dry_run_call_returns_gas_consumed_and_debug_message() {
+	let (wasm, code_hash) = compile_module::<Test>(CODE_PRINTLN).unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::put_code(Origin::signed(ALICE), 100_000, wasm));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			100,
+			100_000,
+			code_hash.into(),
+			vec![],
+		));
+
+		let result = Contracts::dry_run_call(ALICE, BOB, 0, 100_000, vec![]);
+		assert!(result.exec_result.unwrap().is_success());
+		assert!(result.gas_consumed > 0 && result.gas_consumed < 100_000);
+		assert_eq!(result.debug_message, b"Hello \nWorld\n".to_vec());
+	});
+}
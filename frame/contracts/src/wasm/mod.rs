@@ -207,6 +207,7 @@ mod tests {
 		restores: Vec<RestoreEntry>,
 		// (topics, data)
 		events: Vec<(Vec<H256>, Vec<u8>)>,
+		debug_message: Vec<u8>,
 		next_account_id: u64,
 
 		/// Runtime storage keys works the following way.
@@ -373,6 +374,10 @@ mod tests {
 				)
 			)
 		}
+
+		fn append_debug_message(&mut self, message: &str) {
+			self.debug_message.extend_from_slice(message.as_bytes());
+		}
 	}
 
 	impl Ext for &mut MockExt {
@@ -478,6 +483,9 @@ mod tests {
 		fn get_runtime_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
 			(**self).get_runtime_storage(key)
 		}
+		fn append_debug_message(&mut self, message: &str) {
+			(**self).append_debug_message(message)
+		}
 	}
 
 	fn execute<E: Ext>(
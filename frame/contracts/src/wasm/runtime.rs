@@ -970,13 +970,15 @@ define_env!(Env, <E: Ext>,
 		Ok(())
 	},
 
-	// Prints utf8 encoded string from the data buffer.
+	// Prints utf8 encoded string from the data buffer and appends it to the debug message of the
+	// call, which is returned by dry-runs.
 	// Only available on `--dev` chains.
 	// This function may be removed at any time, superseded by a more general contract debugging feature.
 	ext_println(ctx, str_ptr: u32, str_len: u32) => {
 		let data = read_sandbox_memory(ctx, str_ptr, str_len)?;
 		if let Ok(utf8) = core::str::from_utf8(&data) {
 			sp_runtime::print(utf8);
+			ctx.ext.append_debug_message(utf8);
 		}
 		Ok(())
 	},
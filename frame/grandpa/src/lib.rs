@@ -38,7 +38,7 @@ use sp_runtime::{
 };
 use sp_staking::{
 	SessionIndex,
-	offence::{Offence, Kind},
+	offence::{Offence, Kind, kind, SessionLength},
 };
 use fg_primitives::{
 	GRANDPA_AUTHORITIES_KEY, GRANDPA_ENGINE_ID, ScheduledChange, ConsensusLog, SetId, RoundNumber,
@@ -475,7 +475,7 @@ struct GrandpaEquivocationOffence<FullIdentification> {
 }
 
 impl<FullIdentification: Clone> Offence<FullIdentification> for GrandpaEquivocationOffence<FullIdentification> {
-	const ID: Kind = *b"grandpa:equivoca";
+	const ID: Kind = kind::GRANDPA_EQUIVOCATION;
 	type TimeSlot = GrandpaTimeSlot;
 
 	fn offenders(&self) -> Vec<FullIdentification> {
@@ -497,6 +497,7 @@ impl<FullIdentification: Clone> Offence<FullIdentification> for GrandpaEquivocat
 	fn slash_fraction(
 		offenders_count: u32,
 		validator_set_count: u32,
+		_session_length: Option<SessionLength>,
 	) -> Perbill {
 		// the formula is min((3k / n)^2, 1)
 		let x = Perbill::from_rational_approximation(3 * offenders_count, validator_set_count);
@@ -79,19 +79,19 @@ use pallet_session::historical::IdentificationTuple;
 use sp_runtime::{
 	offchain::storage::StorageValueRef,
 	RuntimeDebug,
-	traits::{Convert, Member, Saturating, AtLeast32Bit}, Perbill, PerThing,
+	traits::{Convert, Member, Saturating, AtLeast32Bit, UniqueSaturatedInto}, Perbill, PerThing,
 	transaction_validity::{
 		TransactionValidity, ValidTransaction, InvalidTransaction,
 		TransactionPriority,
 	},
 };
 use sp_staking::{
-	SessionIndex,
-	offence::{ReportOffence, Offence, Kind},
+	SessionIndex, ChillValidator,
+	offence::{ReportOffence, Offence, Kind, kind, normalize_to_session_length, SessionLength},
 };
 use frame_support::{
 	decl_module, decl_event, decl_storage, Parameter, debug, decl_error,
-	traits::Get,
+	storage::IterableStorageMap, traits::Get,
 };
 use frame_system::{self as system, ensure_none};
 use frame_system::offchain::SubmitUnsignedTransaction;
@@ -238,6 +238,26 @@ pub trait Trait: frame_system::Trait + pallet_session::historical::Trait {
 	/// there is a chance the authority will produce a block and they won't be necessary.
 	type SessionDuration: Get<Self::BlockNumber>;
 
+	/// The session length, in blocks, for which the unresponsiveness slash fraction is
+	/// calibrated.
+	///
+	/// Unresponsiveness in shorter sessions is slashed proportionally less.
+	type ReferenceSessionLength: Get<u64>;
+
+	/// The minimum number of validators unresponsive in the same session for any of them to
+	/// be slashed.
+	///
+	/// The slash fraction of unresponsiveness is tuned for large validator sets. On small sets
+	/// this prevents a single flaky validator from being slashed session after session.
+	type UnresponsivenessQuorum: Get<u32>;
+
+	/// The number of consecutive sessions after which an offline validator is chilled, even if
+	/// its unresponsiveness is not slashed. Zero disables chilling.
+	type MaxOfflineSessions: Get<SessionIndex>;
+
+	/// A type that gives us the ability to chill persistently offline validators.
+	type ChillOffline: ChillValidator<Self::ValidatorId>;
+
 	/// A type that gives us the ability to submit unresponsiveness offence reports.
 	type ReportUnresponsiveness:
 		ReportOffence<
@@ -285,6 +305,11 @@ decl_storage! {
 		AuthoredBlocks get(fn authored_blocks):
 			double_map hasher(twox_64_concat) SessionIndex, hasher(twox_64_concat) T::ValidatorId
 			=> u32;
+
+		/// The number of consecutive sessions each validator of the current set has been
+		/// offline for, only validators offline in the last session are kept.
+		OfflineSessions get(fn offline_sessions):
+			map hasher(twox_64_concat) T::ValidatorId => SessionIndex;
 	}
 	add_extra_genesis {
 		config(keys): Vec<T::AuthorityId>;
@@ -413,6 +438,30 @@ impl<T: Trait> Module<T> {
 		<ReceivedHeartbeats>::contains_key(&current_session, &authority_index)
 	}
 
+	/// Count the consecutive sessions the validators have been offline for, given the
+	/// validators offline in the ending session, and chill those offline for too long.
+	fn note_offline_sessions(offline: &[T::ValidatorId]) {
+		// the validators back online or out of the set are not consecutively offline anymore.
+		let stale = <OfflineSessions<T>>::iter()
+			.map(|(id, _)| id)
+			.filter(|id| !offline.contains(id))
+			.collect::<Vec<_>>();
+		for id in stale {
+			<OfflineSessions<T>>::remove(id);
+		}
+
+		let max_offline_sessions = T::MaxOfflineSessions::get();
+		for id in offline {
+			let sessions = <OfflineSessions<T>>::mutate(id, |sessions| {
+				*sessions = sessions.saturating_add(1);
+				*sessions
+			});
+			if max_offline_sessions != 0 && sessions >= max_offline_sessions {
+				T::ChillOffline::chill_validator(id);
+			}
+		}
+	}
+
 	/// Note that the given authority has authored a block in the current session.
 	fn note_authorship(author: T::ValidatorId) {
 		let current_session = <pallet_session::Module<T>>::current_index();
@@ -589,10 +638,15 @@ impl<T: Trait> pallet_session::OneSessionHandler<T::AccountId> for Module<T> {
 		let keys = Keys::<T>::get();
 		let current_validators = <pallet_session::Module<T>>::validators();
 
-		let offenders = current_validators.into_iter().enumerate()
+		let offline = current_validators.into_iter().enumerate()
 			.filter(|(index, id)|
 				!Self::is_online_aux(*index as u32, id)
-			).filter_map(|(_, id)|
+			).map(|(_, id)| id)
+			.collect::<Vec<_>>();
+		Self::note_offline_sessions(&offline);
+
+		let offenders = offline.into_iter()
+			.filter_map(|id|
 				T::FullIdentificationOf::convert(id.clone()).map(|full_id| (id, full_id))
 			).collect::<Vec<IdentificationTuple<T>>>();
 
@@ -608,7 +662,17 @@ impl<T: Trait> pallet_session::OneSessionHandler<T::AccountId> for Module<T> {
 			Self::deposit_event(RawEvent::SomeOffline(offenders.clone()));
 
 			let validator_set_count = keys.len() as u32;
-			let offence = UnresponsivenessOffence { session_index, validator_set_count, offenders };
+			let session_length = SessionLength {
+				length: T::SessionDuration::get().unique_saturated_into(),
+				reference: T::ReferenceSessionLength::get(),
+			};
+			let offence = UnresponsivenessOffence {
+				session_index,
+				validator_set_count,
+				session_length,
+				min_offenders: T::UnresponsivenessQuorum::get(),
+				offenders,
+			};
 			if let Err(e) = T::ReportUnresponsiveness::report_offence(vec![], offence) {
 				sp_runtime::print(e);
 			}
@@ -676,12 +740,16 @@ pub struct UnresponsivenessOffence<Offender> {
 	session_index: SessionIndex,
 	/// The size of the validator set in current session/era.
 	validator_set_count: u32,
+	/// The length of the current session and the reference session length, in blocks.
+	session_length: SessionLength,
+	/// The minimum number of offenders for the offence to be slashed.
+	min_offenders: u32,
 	/// Authorities that were unresponsive during the current era.
 	offenders: Vec<Offender>,
 }
 
 impl<Offender: Clone> Offence<Offender> for UnresponsivenessOffence<Offender> {
-	const ID: Kind = *b"im-online:offlin";
+	const ID: Kind = kind::IM_ONLINE_UNRESPONSIVENESS;
 	type TimeSlot = SessionIndex;
 
 	fn offenders(&self) -> Vec<Offender> {
@@ -700,15 +768,35 @@ impl<Offender: Clone> Offence<Offender> for UnresponsivenessOffence<Offender> {
 		self.session_index
 	}
 
-	fn slash_fraction(offenders: u32, validator_set_count: u32) -> Perbill {
+	fn min_offenders(&self) -> u32 {
+		self.min_offenders
+	}
+
+	fn session_length(&self) -> Option<SessionLength> {
+		Some(self.session_length)
+	}
+
+	fn slash_fraction(
+		offenders: u32,
+		validator_set_count: u32,
+		session_length: Option<SessionLength>,
+	) -> Perbill {
 		// the formula is min((3 * (k - (n / 10 + 1))) / n, 1) * 0.07
 		// basically, 10% can be offline with no slash, but after that, it linearly climbs up to 7%
 		// when 13/30 are offline (around 5% when 1/3 are offline).
-		if let Some(threshold) = offenders.checked_sub(validator_set_count / 10 + 1) {
+		let fraction = if let Some(threshold) = offenders.checked_sub(validator_set_count / 10 + 1) {
 			let x = Perbill::from_rational_approximation(3 * threshold, validator_set_count);
 			x.saturating_mul(Perbill::from_percent(7))
 		} else {
 			Perbill::default()
+		};
+
+		// being unresponsive for a session shorter than the one the formula is calibrated for
+		// is less severe.
+		match session_length {
+			Some(SessionLength { length, reference }) =>
+				normalize_to_session_length(fraction, length, reference),
+			None => fraction,
 		}
 	}
 }
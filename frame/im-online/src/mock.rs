@@ -22,7 +22,7 @@ use std::cell::RefCell;
 
 use crate::{Module, Trait};
 use sp_runtime::Perbill;
-use sp_staking::{SessionIndex, offence::{ReportOffence, OffenceError}};
+use sp_staking::{SessionIndex, ChillValidator, offence::{ReportOffence, OffenceError}};
 use sp_runtime::testing::{Header, UintAuthorityId, TestXt};
 use sp_runtime::traits::{IdentityLookup, BlakeTwo256, ConvertInto};
 use sp_core::H256;
@@ -85,6 +85,18 @@ impl ReportOffence<u64, IdentificationTuple, Offence> for OffenceHandler {
 	}
 }
 
+thread_local! {
+	pub static CHILLED: RefCell<Vec<u64>> = RefCell::new(vec![]);
+}
+
+/// A mock chilling handler.
+pub struct ChillHandler;
+impl ChillValidator<u64> for ChillHandler {
+	fn chill_validator(validator: &u64) {
+		CHILLED.with(|l| l.borrow_mut().push(*validator));
+	}
+}
+
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
 	t.into()
@@ -125,7 +137,10 @@ impl frame_system::Trait for Runtime {
 
 parameter_types! {
 	pub const Period: u64 = 1;
+	pub const ReferenceSessionLength: u64 = 4;
 	pub const Offset: u64 = 0;
+	pub const UnresponsivenessQuorum: u32 = 3;
+	pub const MaxOfflineSessions: SessionIndex = 2;
 }
 
 parameter_types! {
@@ -166,6 +181,10 @@ impl Trait for Runtime {
 	type SubmitTransaction = SubmitTransaction;
 	type ReportUnresponsiveness = OffenceHandler;
 	type SessionDuration = Period;
+	type ReferenceSessionLength = ReferenceSessionLength;
+	type UnresponsivenessQuorum = UnresponsivenessQuorum;
+	type MaxOfflineSessions = MaxOfflineSessions;
+	type ChillOffline = ChillHandler;
 }
 
 /// Im Online module.
@@ -27,33 +27,97 @@ use sp_core::offchain::{
 	testing::{TestOffchainExt, TestTransactionPoolExt},
 };
 use frame_support::{dispatch, assert_noop};
+use sp_staking::offence::{slash_fraction_of, SessionLength};
 use sp_runtime::testing::UintAuthorityId;
 
 #[test]
 fn test_unresponsiveness_slash_fraction() {
 	// A single case of unresponsiveness is not slashed.
 	assert_eq!(
-		UnresponsivenessOffence::<()>::slash_fraction(1, 50),
+		UnresponsivenessOffence::<()>::slash_fraction(1, 50, None),
 		Perbill::zero(),
 	);
 
 	assert_eq!(
-		UnresponsivenessOffence::<()>::slash_fraction(5, 50),
+		UnresponsivenessOffence::<()>::slash_fraction(5, 50, None),
 		Perbill::zero(), // 0%
 	);
 
 	assert_eq!(
-		UnresponsivenessOffence::<()>::slash_fraction(7, 50),
+		UnresponsivenessOffence::<()>::slash_fraction(7, 50, None),
 		Perbill::from_parts(4200000), // 0.42%
 	);
 
 	// One third offline should be punished around 5%.
 	assert_eq!(
-		UnresponsivenessOffence::<()>::slash_fraction(17, 50),
+		UnresponsivenessOffence::<()>::slash_fraction(17, 50, None),
 		Perbill::from_parts(46200000), // 4.62%
 	);
 }
 
+#[test]
+fn test_unresponsiveness_slash_fraction_is_normalized_to_session_length() {
+	let full = UnresponsivenessOffence::<()>::slash_fraction(17, 50, None);
+	let session = |length, reference| Some(SessionLength { length, reference });
+
+	// sessions as long as the reference, or longer, are not scaled.
+	assert_eq!(UnresponsivenessOffence::<()>::slash_fraction(17, 50, session(100, 100)), full);
+	assert_eq!(UnresponsivenessOffence::<()>::slash_fraction(17, 50, session(200, 100)), full);
+
+	// shorter sessions are scaled down proportionally.
+	assert_eq!(
+		UnresponsivenessOffence::<()>::slash_fraction(17, 50, session(25, 100)),
+		Perbill::from_parts(11550000), // 1.155%
+	);
+	assert_eq!(
+		UnresponsivenessOffence::<()>::slash_fraction(17, 50, session(50, 14_400)),
+		Perbill::from_parts(160416), // ~0.016%
+	);
+
+	// no slash stays no slash.
+	assert_eq!(
+		UnresponsivenessOffence::<()>::slash_fraction(5, 50, session(50, 14_400)),
+		Perbill::zero(),
+	);
+}
+
+#[test]
+fn unresponsiveness_quorum_on_small_validator_sets() {
+	let offence = |validator_set_count, min_offenders| UnresponsivenessOffence::<()> {
+		session_index: 0,
+		validator_set_count,
+		session_length: SessionLength { length: 1, reference: 1 },
+		min_offenders,
+		offenders: vec![],
+	};
+
+	for validator_set_count in 1..=10 {
+		for offenders in 1..=validator_set_count {
+			let unguarded = slash_fraction_of(&offence(validator_set_count, 1), offenders);
+			let guarded = slash_fraction_of(&offence(validator_set_count, 3), offenders);
+
+			assert_eq!(
+				unguarded,
+				UnresponsivenessOffence::<()>::slash_fraction(offenders, validator_set_count, None),
+			);
+			if offenders < 3 {
+				assert_eq!(guarded, Perbill::zero(), "{} of {}", offenders, validator_set_count);
+			} else {
+				assert_eq!(guarded, unguarded, "{} of {}", offenders, validator_set_count);
+			}
+		}
+	}
+
+	// without a quorum, two validators out of two are slashed the maximum,
+	assert_eq!(slash_fraction_of(&offence(2, 1), 2), Perbill::from_percent(7));
+	// and not with a quorum of three.
+	assert_eq!(slash_fraction_of(&offence(2, 3), 2), Perbill::zero());
+	// three out of three reach the quorum.
+	assert_eq!(slash_fraction_of(&offence(3, 3), 3), Perbill::from_percent(7));
+	// as do three out of ten.
+	assert_eq!(slash_fraction_of(&offence(10, 3), 3), Perbill::from_parts(21000000)); // 2.1%
+}
+
 #[test]
 fn should_report_offline_validators() {
 	new_test_ext().execute_with(|| {
@@ -77,6 +141,8 @@ fn should_report_offline_validators() {
 			(vec![], UnresponsivenessOffence {
 				session_index: 2,
 				validator_set_count: 3,
+				session_length: SessionLength { length: 1, reference: 4 },
+				min_offenders: 3,
 				offenders: vec![
 					(1, 1),
 					(2, 2),
@@ -85,6 +151,13 @@ fn should_report_offline_validators() {
 			})
 		]);
 
+		// the mock sessions are a quarter of the reference session length.
+		let offence = &offences[0].1;
+		assert_eq!(
+			slash_fraction_of(offence, 3),
+			Perbill::from_parts(17500000), // a quarter of 7%
+		);
+
 		// should not report when heartbeat is sent
 		for (idx, v) in validators.into_iter().take(4).enumerate() {
 			let _ = heartbeat(block, 3, idx as u32, v.into()).unwrap();
@@ -97,6 +170,8 @@ fn should_report_offline_validators() {
 			(vec![], UnresponsivenessOffence {
 				session_index: 3,
 				validator_set_count: 6,
+				session_length: SessionLength { length: 1, reference: 4 },
+				min_offenders: 3,
 				offenders: vec![
 					(5, 5),
 					(6, 6),
@@ -342,3 +417,40 @@ fn should_not_send_a_report_if_already_online() {
 		});
 	});
 }
+
+#[test]
+fn should_chill_persistently_offline_validators() {
+	use pallet_authorship::EventHandler;
+
+	new_test_ext().execute_with(|| {
+		advance_session();
+		advance_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+
+		// 1 is online during the first session and 2 during the second one, 3 never is.
+		ImOnline::note_author(1);
+		advance_session();
+		assert_eq!(ImOnline::offline_sessions(1), 0);
+		assert_eq!(ImOnline::offline_sessions(2), 1);
+		assert_eq!(ImOnline::offline_sessions(3), 1);
+		assert!(CHILLED.with(|l| l.borrow().is_empty()));
+
+		ImOnline::note_author(2);
+		advance_session();
+		assert_eq!(ImOnline::offline_sessions(1), 1);
+		assert_eq!(ImOnline::offline_sessions(2), 0);
+		assert_eq!(ImOnline::offline_sessions(3), 2);
+		assert_eq!(CHILLED.with(|l| l.replace(vec![])), vec![3]);
+
+		// the validators are chilled at every session until they are back online.
+		advance_session();
+		assert_eq!(CHILLED.with(|l| l.replace(vec![])), vec![1, 3]);
+
+		ImOnline::note_author(1);
+		ImOnline::note_author(3);
+		advance_session();
+		assert_eq!(ImOnline::offline_sessions(1), 0);
+		assert_eq!(ImOnline::offline_sessions(3), 0);
+		assert_eq!(CHILLED.with(|l| l.replace(vec![])), vec![2]);
+	});
+}
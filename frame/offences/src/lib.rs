@@ -30,7 +30,10 @@ use frame_support::{
 };
 use sp_runtime::traits::Hash;
 use sp_staking::{
-	offence::{Offence, ReportOffence, Kind, OnOffenceHandler, OffenceDetails, OffenceError},
+	offence::{
+		Offence, ReportOffence, Kind, OnOffenceHandler, OffenceDetails, OffenceError,
+		slash_fraction_of,
+	},
 };
 use codec::{Encode, Decode};
 use frame_system as system;
@@ -102,7 +105,6 @@ where
 	fn report_offence(reporters: Vec<T::AccountId>, offence: O) -> Result<(), OffenceError> {
 		let offenders = offence.offenders();
 		let time_slot = offence.time_slot();
-		let validator_set_count = offence.validator_set_count();
 
 		// Go through all offenders in the offence report and find all offenders that was spotted
 		// in unique reports.
@@ -122,7 +124,7 @@ where
 		let offenders_count = concurrent_offenders.len() as u32;
 
 		// The amount new offenders are slashed
-		let new_fraction = O::slash_fraction(offenders_count, validator_set_count);
+		let new_fraction = slash_fraction_of(&offence, offenders_count);
 
 		let slash_perbill: Vec<_> = (0..concurrent_offenders.len())
 			.map(|_| new_fraction.clone()).collect();
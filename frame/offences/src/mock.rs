@@ -24,7 +24,7 @@ use codec::Encode;
 use sp_runtime::Perbill;
 use sp_staking::{
 	SessionIndex,
-	offence::{self, Kind, OffenceDetails},
+	offence::{self, Kind, OffenceDetails, SessionLength},
 };
 use sp_runtime::testing::Header;
 use sp_runtime::traits::{IdentityLookup, BlakeTwo256};
@@ -163,6 +163,7 @@ impl<T: Clone> offence::Offence<T> for Offence<T> {
 	fn slash_fraction(
 		offenders_count: u32,
 		validator_set_count: u32,
+		_session_length: Option<SessionLength>,
 	) -> Perbill {
 		Perbill::from_percent(5 + offenders_count * 100 / validator_set_count)
 	}
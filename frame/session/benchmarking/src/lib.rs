@@ -24,14 +24,12 @@ use sp_std::vec;
 
 use frame_system::RawOrigin;
 use frame_benchmarking::benchmarks;
+use frame_support::traits::Get;
 
 use pallet_session::*;
 use pallet_session::Module as Session;
 
-use pallet_staking::{
-	MAX_NOMINATIONS,
-	benchmarking::create_validator_with_nominators,
-};
+use pallet_staking::benchmarking::create_validator_with_nominators;
 
 pub struct Module<T: Trait>(pallet_session::Module<T>);
 
@@ -41,15 +39,15 @@ benchmarks! {
 	_ {	}
 
 	set_keys {
-		let n in 1 .. MAX_NOMINATIONS as u32;
-		let validator = create_validator_with_nominators::<T>(n, MAX_NOMINATIONS as u32)?;
+		let n in 1 .. T::MaxNominations::get();
+		let validator = create_validator_with_nominators::<T>(n, T::MaxNominations::get())?;
 		let keys = T::Keys::default();
 		let proof: Vec<u8> = vec![0,1,2,3];
 	}: _(RawOrigin::Signed(validator), keys, proof)
 
 	purge_keys {
-		let n in 1 .. MAX_NOMINATIONS as u32;
-		let validator = create_validator_with_nominators::<T>(n, MAX_NOMINATIONS as u32)?;
+		let n in 1 .. T::MaxNominations::get();
+		let validator = create_validator_with_nominators::<T>(n, T::MaxNominations::get())?;
 		let keys = T::Keys::default();
 		let proof: Vec<u8> = vec![0,1,2,3];
 		Session::<T>::set_keys(RawOrigin::Signed(validator.clone()).into(), keys, proof)?;
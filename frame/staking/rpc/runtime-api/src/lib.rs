@@ -0,0 +1,54 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for the staking module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Codec, HasCompact};
+pub use pallet_staking::{EraIndex, Exposure, StakingLedger, UnappliedSlash, UnlockChunk};
+
+sp_api::decl_runtime_apis! {
+	pub trait StakingApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec + HasCompact,
+	{
+		/// The stashes of the validators of the current session.
+		fn validators() -> Vec<AccountId>;
+
+		/// The exposure of the validator stash `who` in the active era.
+		///
+		/// The exposure is empty if `who` was not elected for the active era.
+		fn exposure_of(who: AccountId) -> Exposure<AccountId, Balance>;
+
+		/// The ledger of the controller `controller`, if it is bonded.
+		fn ledger(controller: AccountId) -> Option<StakingLedger<AccountId, Balance>>;
+
+		/// The total balance that can be slashed from the stash `who` as of right now.
+		fn slashable_balance(who: AccountId) -> Balance;
+
+		/// The funds of the controller `controller` being unbonded, with the era at which each
+		/// chunk becomes withdrawable.
+		///
+		/// Empty if `controller` is not bonded.
+		fn unlocking(controller: AccountId) -> Vec<UnlockChunk<Balance>>;
+
+		/// The slashes deferred by the slash defer duration and not applied yet, by era in which
+		/// they were reported, oldest first.
+		fn pending_slashes() -> Vec<(EraIndex, Vec<UnappliedSlash<AccountId, Balance>>)>;
+	}
+}
@@ -0,0 +1,184 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface for the staking module.
+
+use std::sync::Arc;
+use codec::{Codec, HasCompact};
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+use pallet_staking_rpc_runtime_api::{
+	EraIndex, Exposure, StakingLedger, UnappliedSlash, UnlockChunk,
+};
+pub use pallet_staking_rpc_runtime_api::StakingApi as StakingRuntimeApi;
+pub use self::gen_client::Client as StakingClient;
+
+/// Staking RPC methods.
+#[rpc]
+pub trait StakingApi<BlockHash, AccountId, Balance> where
+	Balance: HasCompact,
+{
+	/// The stashes of the validators of the current session.
+	#[rpc(name = "staking_validators")]
+	fn validators(&self, at: Option<BlockHash>) -> Result<Vec<AccountId>>;
+
+	/// The exposure of the validator stash `who` in the active era.
+	#[rpc(name = "staking_exposureOf")]
+	fn exposure_of(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Exposure<AccountId, Balance>>;
+
+	/// The ledger of the controller `controller`, if it is bonded.
+	#[rpc(name = "staking_ledger")]
+	fn ledger(
+		&self,
+		controller: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Option<StakingLedger<AccountId, Balance>>>;
+
+	/// The total balance that can be slashed from the stash `who` as of right now.
+	#[rpc(name = "staking_slashableBalance")]
+	fn slashable_balance(&self, who: AccountId, at: Option<BlockHash>) -> Result<Balance>;
+
+	/// The funds of the controller `controller` being unbonded, with the era at which each
+	/// chunk becomes withdrawable.
+	#[rpc(name = "staking_unlocking")]
+	fn unlocking(
+		&self,
+		controller: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<UnlockChunk<Balance>>>;
+
+	/// The slashes deferred and not applied yet, by era in which they were reported, oldest
+	/// first.
+	#[rpc(name = "staking_pendingSlashes")]
+	fn pending_slashes(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<Vec<(EraIndex, Vec<UnappliedSlash<AccountId, Balance>>)>>;
+}
+
+/// A struct that implements the [`StakingApi`].
+pub struct Staking<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Staking<C, B> {
+	/// Create new `Staking` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Staking { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query staking state.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, Balance> StakingApi<<Block as BlockT>::Hash, AccountId, Balance>
+	for Staking<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: StakingRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + HasCompact + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+	fn validators(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<AccountId>> {
+		let api = self.client.runtime_api();
+		api.validators(&self.at(at)).map_err(runtime_error)
+	}
+
+	fn exposure_of(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Exposure<AccountId, Balance>> {
+		let api = self.client.runtime_api();
+		api.exposure_of(&self.at(at), who).map_err(runtime_error)
+	}
+
+	fn ledger(
+		&self,
+		controller: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<StakingLedger<AccountId, Balance>>> {
+		let api = self.client.runtime_api();
+		api.ledger(&self.at(at), controller).map_err(runtime_error)
+	}
+
+	fn slashable_balance(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		api.slashable_balance(&self.at(at), who).map_err(runtime_error)
+	}
+
+	fn unlocking(
+		&self,
+		controller: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<UnlockChunk<Balance>>> {
+		let api = self.client.runtime_api();
+		api.unlocking(&self.at(at), controller).map_err(runtime_error)
+	}
+
+	fn pending_slashes(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<(EraIndex, Vec<UnappliedSlash<AccountId, Balance>>)>> {
+		let api = self.client.runtime_api();
+		api.pending_slashes(&self.at(at)).map_err(runtime_error)
+	}
+}
+
+impl<C, Block> Staking<C, Block> where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	fn at(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block> {
+		BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		))
+	}
+}
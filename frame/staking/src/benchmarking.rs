@@ -62,7 +62,7 @@ fn create_validators<T: Trait>(max: u32) -> Result<Vec<<T::Lookup as StaticLooku
 	Ok(validators)
 }
 
-// This function generates v validators and n nominators who are randomly nominating up to MAX_NOMINATIONS.
+// This function generates v validators and n nominators who are randomly nominating up to `MaxNominations`.
 pub fn create_validators_with_nominators_for_era<T: Trait>(v: u32, n: u32) -> Result<(), &'static str> {
 	let mut validators: Vec<<T::Lookup as StaticLookup>::Source> = Vec::with_capacity(v as usize);
 	let mut rng = ChaChaRng::from_seed(SEED.using_encoded(blake2_256));
@@ -84,8 +84,9 @@ pub fn create_validators_with_nominators_for_era<T: Trait>(v: u32, n: u32) -> Re
 
 		// Have them randomly validate
 		let mut available_validators = validators.clone();
-		let mut selected_validators: Vec<<T::Lookup as StaticLookup>::Source> = Vec::with_capacity(MAX_NOMINATIONS);
-		for _ in 0 .. v.min(MAX_NOMINATIONS as u32) {
+		let mut selected_validators: Vec<<T::Lookup as StaticLookup>::Source> =
+			Vec::with_capacity(T::MaxNominations::get() as usize);
+		for _ in 0 .. v.min(T::MaxNominations::get()) {
 			let selected = rng.next_u32() as usize % available_validators.len();
 			let validator = available_validators.remove(selected);
 			selected_validators.push(validator);
@@ -246,9 +247,9 @@ benchmarks! {
 		let prefs = ValidatorPrefs::default();
 	}: _(RawOrigin::Signed(controller), prefs)
 
-	// Worst case scenario, MAX_NOMINATIONS
+	// Worst case scenario, `MaxNominations`
 	nominate {
-		let n in 1 .. MAX_NOMINATIONS as u32;
+		let n in 1 .. T::MaxNominations::get();
 		let (_, controller) = create_stash_controller::<T>(n + 1)?;
 		let validators = create_validators::<T>(n)?;
 	}: _(RawOrigin::Signed(controller), validators)
@@ -307,13 +308,13 @@ benchmarks! {
 	}: _(RawOrigin::Root, era, slash_indices)
 
 	payout_validator {
-		let n in 1 .. MAX_NOMINATIONS as u32;
-		let validator = create_validator_with_nominators::<T>(n, MAX_NOMINATIONS as u32)?;
+		let n in 1 .. T::MaxNominations::get();
+		let validator = create_validator_with_nominators::<T>(n, T::MaxNominations::get())?;
 		let current_era = CurrentEra::get().unwrap();
 	}: _(RawOrigin::Signed(validator), current_era)
 
 	payout_nominator {
-		let v in 0 .. MAX_NOMINATIONS as u32;
+		let v in 0 .. T::MaxNominations::get();
 		let (nominator, validators) = create_nominator_with_validators::<T>(v)?;
 		let current_era = CurrentEra::get().unwrap();
 		let find_nominator = validators.into_iter().map(|x| (x, 0)).collect();
@@ -423,7 +424,7 @@ mod tests {
 
 			let validator = create_validator_with_nominators::<Test>(
 				n,
-				MAX_NOMINATIONS as u32,
+				MaxNominations::get(),
 			).unwrap();
 
 			let current_era = CurrentEra::get().unwrap();
@@ -115,6 +115,13 @@
 //!
 //! Slashing logic is further described in the documentation of the `slashing` module.
 //!
+//! A chain can run slashing in record-only mode, see [`SlashingMode`], for instance during a
+//! monitoring period before enabling penalties: slashes are computed and kept in
+//! `RecordedSlashes`, and a `SlashRecorded` event is emitted, but no funds are deducted and
+//! offenders are not chilled or disabled. Recorded slashes are not part of the slashing history
+//! used to compute enforced slashes, so offences reported again once slashes are enforced are
+//! slashed in full.
+//!
 //! Similar to slashing, rewards are also shared among a validator and its associated nominators.
 //! Yet, the reward funds are not always transferred to the stash account and can be configured.
 //! See [Reward Calculation](#reward-calculation) for more details.
@@ -235,6 +242,14 @@
 //! values until the total difference among votes of a particular nominator are less than a
 //! threshold.
 //!
+//! The election does not have to run on-chain. During the last session of an era, a snapshot of
+//! the validators and nominators is kept and anyone may submit a pre-computed solution through
+//! the unsigned [`submit_election_solution`](./enum.Call.html#variant.submit_election_solution)
+//! call. Solutions are verified against the snapshot when they are submitted, and only one scoring
+//! better than the queued solution is accepted. The queued solution is used when the next era is
+//! planned. If none was submitted, or if the stakers changed such that the queued one is no
+//! longer valid, the election runs on-chain as usual.
+//!
 //! ## GenesisConfig
 //!
 //! The Staking module depends on the [`GenesisConfig`](./struct.GenesisConfig.html).
@@ -254,15 +269,20 @@ mod mock;
 #[cfg(test)]
 mod tests;
 mod slashing;
+mod migration;
 #[cfg(any(feature = "runtime-benchmarks", test))]
 pub mod benchmarking;
 
 pub mod inflation;
 
-use sp_std::{prelude::*, result, collections::btree_map::BTreeMap};
+use sp_std::{
+	prelude::*, result,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+};
 use codec::{HasCompact, Encode, Decode};
 use frame_support::{
-	decl_module, decl_event, decl_storage, ensure, decl_error, weights::SimpleDispatchInfo,
+	decl_module, decl_event, decl_storage, ensure, decl_error,
+	weights::{SimpleDispatchInfo, FunctionOf, DispatchClass, Weight},
 	dispatch::DispatchResult, storage::IterableStorageMap, traits::{
 		Currency, LockIdentifier, LockableCurrency, WithdrawReasons, OnUnbalanced, Imbalance, Get,
 		Time
@@ -270,25 +290,28 @@ use frame_support::{
 };
 use pallet_session::historical::SessionManager;
 use sp_runtime::{
-	Perbill, PerThing, RuntimeDebug,
+	Perbill, Percent, PerThing, RuntimeDebug,
 	curve::PiecewiseLinear,
 	traits::{
 		Convert, Zero, StaticLookup, CheckedSub, Saturating, SaturatedConversion,
 		AtLeast32Bit, EnsureOrigin,
-	}
+	},
+	transaction_validity::{
+		TransactionValidity, ValidTransaction, InvalidTransaction, TransactionLongevity,
+	},
 };
 use sp_staking::{
-	SessionIndex,
+	SessionIndex, ChillValidator,
 	offence::{OnOffenceHandler, OffenceDetails, Offence, ReportOffence, OffenceError},
 };
+pub use sp_staking::slashing::UnappliedSlash;
 #[cfg(feature = "std")]
 use sp_runtime::{Serialize, Deserialize};
-use frame_system::{self as system, ensure_signed, ensure_root};
+use frame_system::{self as system, ensure_signed, ensure_root, ensure_none};
 
-use sp_phragmen::ExtendedBalance;
+use sp_phragmen::{ExtendedBalance, PhragmenAssignment, PhragmenScore, SupportMap};
 
 const DEFAULT_MINIMUM_VALIDATOR_COUNT: u32 = 4;
-pub const MAX_NOMINATIONS: usize = 16;
 const MAX_UNLOCKING_CHUNKS: usize = 32;
 const STAKING_ID: LockIdentifier = *b"staking ";
 
@@ -302,12 +325,12 @@ pub type RewardPoint = u32;
 #[derive(Encode, Decode, RuntimeDebug)]
 pub struct ActiveEraInfo<Moment> {
 	/// Index of era.
-	index: EraIndex,
+	pub index: EraIndex,
 	/// Moment of start
 	///
 	/// Start can be none if start hasn't been set for the era yet,
 	/// Start is set on the first on_finalize of the era to guarantee usage of `Time`.
-	start: Option<Moment>,
+	pub start: Option<Moment>,
 }
 
 /// Reward points of an era. Used to split era total payout between validators.
@@ -369,6 +392,7 @@ impl Default for ValidatorPrefs {
 
 /// Just a Balance/BlockNumber tuple to encode when a chunk of funds will be unlocked.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct UnlockChunk<Balance: HasCompact> {
 	/// Amount of funds to be unlocked.
 	#[codec(compact)]
@@ -380,6 +404,7 @@ pub struct UnlockChunk<Balance: HasCompact> {
 
 /// The ledger of a (bonded) stash.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct StakingLedger<AccountId, Balance: HasCompact> {
 	/// The stash account whose balance is actually locked and at stake.
 	pub stash: AccountId,
@@ -510,6 +535,10 @@ impl<AccountId, Balance> StakingLedger<AccountId, Balance> where
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 pub struct Nominations<AccountId> {
 	/// The targets of nomination.
+	///
+	/// At most `MaxNominations` of them. `nominate` rejects more, and nominations stored before
+	/// the limit was introduced or lowered are truncated to it, a few per block, after the
+	/// runtime upgrade. Until then, only the first `MaxNominations` are used.
 	pub targets: Vec<AccountId>,
 	/// The era the nominations were submitted.
 	///
@@ -521,6 +550,7 @@ pub struct Nominations<AccountId> {
 
 /// The amount of exposure (to slashing) than an individual nominator has.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct IndividualExposure<AccountId, Balance: HasCompact> {
 	/// The stash account of the nominator in question.
 	who: AccountId,
@@ -531,6 +561,7 @@ pub struct IndividualExposure<AccountId, Balance: HasCompact> {
 
 /// A snapshot of the stake backing a single validator in the system.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Exposure<AccountId, Balance: HasCompact> {
 	/// The total balance backing this validator.
 	#[codec(compact)]
@@ -542,20 +573,71 @@ pub struct Exposure<AccountId, Balance: HasCompact> {
 	pub others: Vec<IndividualExposure<AccountId, Balance>>,
 }
 
-/// A pending slash record. The value of the slash has been computed but not applied yet,
-/// rather deferred for several eras.
-#[derive(Encode, Decode, Default, RuntimeDebug)]
-pub struct UnappliedSlash<AccountId, Balance: HasCompact> {
-	/// The stash ID of the offending validator.
-	validator: AccountId,
-	/// The validator's own slash.
-	own: Balance,
-	/// All other slashed stakers and amounts.
-	others: Vec<(AccountId, Balance)>,
-	/// Reporters of the offence; bounty payout recipients.
-	reporters: Vec<AccountId>,
-	/// The amount of payout.
-	payout: Balance,
+/// Index of a validator in the election snapshot.
+pub type ValidatorIndex = u16;
+
+/// Index of a voter (validator self-vote or nominator) in the election snapshot.
+pub type NominatorIndex = u32;
+
+/// A single edge of a compact election solution: a share of a voter's stake given to a winner.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct CompactEdge {
+	/// Index of the supported validator in `SnapshotValidators`.
+	#[codec(compact)]
+	pub validator: ValidatorIndex,
+	/// The proportion of the voter's stake given to this validator.
+	#[codec(compact)]
+	pub ratio: Perbill,
+}
+
+/// The distribution of a single voter's stake in a compact election solution.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct CompactVote {
+	/// Index of the voter in `SnapshotNominators`.
+	#[codec(compact)]
+	pub nominator: NominatorIndex,
+	/// The edges of this voter.
+	pub distribution: Vec<CompactEdge>,
+}
+
+/// An election solution encoded with indices into the election snapshot rather than accounts.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+pub struct CompactAssignments {
+	/// The votes of every voter of the solution.
+	pub votes: Vec<CompactVote>,
+}
+
+/// Indicates whether an off-chain election solution may currently be submitted.
+#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug)]
+pub enum ElectionStatus {
+	/// Nothing may be submitted, there is no snapshot.
+	Closed,
+	/// A snapshot has been taken and solutions are accepted until the next era is planned.
+	Open,
+}
+
+impl Default for ElectionStatus {
+	fn default() -> Self {
+		ElectionStatus::Closed
+	}
+}
+
+/// The way in which the validators of an era were elected.
+#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug)]
+pub enum ElectionCompute {
+	/// The election was run on-chain at the end of the previous era.
+	OnChain,
+	/// The election result was submitted by an unsigned transaction.
+	Unsigned,
+}
+
+/// A verified election solution, queued to be used at the end of the current era.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+pub struct ElectionResult<AccountId> {
+	/// The elected stashes.
+	pub winners: Vec<AccountId>,
+	/// The distribution of each voter's stake among the winners.
+	pub assignments: Vec<(AccountId, Vec<PhragmenAssignment<AccountId, Perbill>>)>,
 }
 
 pub type BalanceOf<T> =
@@ -659,6 +741,11 @@ pub trait Trait: frame_system::Trait {
 	/// For each validator only the `$MaxNominatorRewardedPerValidator` biggest stakers can claim
 	/// their reward. This used to limit the i/o cost for the nominator payout.
 	type MaxNominatorRewardedPerValidator: Get<u32>;
+
+	/// The maximum number of validators a nominator can nominate.
+	///
+	/// This bounds the number of edges a single nominator adds to the election.
+	type MaxNominations: Get<u32>;
 }
 
 /// Mode of era-forcing.
@@ -679,6 +766,21 @@ impl Default for Forcing {
 	fn default() -> Self { Forcing::NotForcing }
 }
 
+/// Whether the slashes computed for reported offences are applied.
+#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum SlashingMode {
+	/// Slashes are applied, possibly after `SlashDeferDuration` eras.
+	Enforcing,
+	/// Slashes are computed and kept in `RecordedSlashes`, but never applied, and offenders
+	/// are not chilled or disabled.
+	RecordOnly,
+}
+
+impl Default for SlashingMode {
+	fn default() -> Self { SlashingMode::Enforcing }
+}
+
 // A value placed in storage that represents the current version of the Staking storage.
 // This value is used by the `on_runtime_upgrade` logic to determine whether we run
 // storage migration logic. This should match directly with the semantic versions of the Rust crate.
@@ -686,6 +788,9 @@ impl Default for Forcing {
 enum Releases {
 	V1_0_0Ancient,
 	V2_0_0,
+	/// Era information older than `HistoryDepth` has been pruned, or is being pruned if
+	/// `NextEraToPrune` is set.
+	V3_0_0,
 }
 
 impl Default for Releases {
@@ -712,6 +817,12 @@ decl_storage! {
 		pub MinimumValidatorCount get(fn minimum_validator_count) config():
 			u32 = DEFAULT_MINIMUM_VALIDATOR_COUNT;
 
+		/// The minimum active bond of a stash to declare the desire to validate.
+		pub MinValidatorBond get(fn min_validator_bond) config(): BalanceOf<T>;
+
+		/// The minimum active bond of a stash to declare the desire to nominate.
+		pub MinNominatorBond get(fn min_nominator_bond) config(): BalanceOf<T>;
+
 		/// Any validators that may never be slashed or forcibly kicked. It's a Vec since they're
 		/// easy to initialize and the performance hit is minimal (we expect no more than four
 		/// invulnerables) and restricted to testnets.
@@ -815,10 +926,20 @@ decl_storage! {
 		/// canceled by extraordinary circumstances (e.g. governance).
 		pub CanceledSlashPayout get(fn canceled_payout) config(): BalanceOf<T>;
 
+		/// Whether the slashes of reported offences are applied or only recorded.
+		pub SlashMode get(fn slash_mode) config(): SlashingMode;
+
 		/// All unapplied slashes that are queued for later.
 		pub UnappliedSlashes:
 			map hasher(twox_64_concat) EraIndex => Vec<UnappliedSlash<T::AccountId, BalanceOf<T>>>;
 
+		/// The slashes computed in record-only mode, by era of the offence.
+		///
+		/// They are kept apart from the slashing metadata below, which only tracks the slashes
+		/// actually enforced.
+		pub RecordedSlashes get(fn recorded_slashes):
+			map hasher(twox_64_concat) EraIndex => Vec<UnappliedSlash<T::AccountId, BalanceOf<T>>>;
+
 		/// A mapping from still-bonded eras to the first session index of that era.
 		///
 		/// Must contains information for eras for the range:
@@ -848,10 +969,45 @@ decl_storage! {
 		/// The earliest era for which we have a pending, unapplied slash.
 		EarliestUnappliedSlash: Option<EraIndex>;
 
+		/// Whether an off-chain election solution for the next era may be submitted.
+		pub EraElectionStatus get(fn era_election_status): ElectionStatus;
+
+		/// Snapshot of the validators taken when the election window opened.
+		pub SnapshotValidators get(fn snapshot_validators): Option<Vec<T::AccountId>>;
+
+		/// Snapshot of the voters taken when the election window opened: all validators,
+		/// voting for themselves, followed by all nominators.
+		pub SnapshotNominators get(fn snapshot_nominators): Option<Vec<T::AccountId>>;
+
+		/// The best submitted election solution, used at the end of the era instead of the
+		/// on-chain election.
+		pub QueuedElected get(fn queued_elected): Option<ElectionResult<T::AccountId>>;
+
+		/// The score of `QueuedElected`.
+		pub QueuedScore get(fn queued_score): Option<PhragmenScore>;
+
+		/// The oldest era still to prune of the history kept from before eras were pruned on
+		/// era change.
+		///
+		/// Set by the migration to v3.0.0, and removed once the whole history older than
+		/// `HistoryDepth` is pruned.
+		NextEraToPrune: Option<EraIndex>;
+
+		/// The `MaxNominations` all the stored nominations are bounded by.
+		///
+		/// Unset on chains which stored nominations before `MaxNominations` existed. When the
+		/// runtime is upgraded with a lower `MaxNominations`, this is only updated once all the
+		/// stored nominations are truncated to it.
+		NominationsBound build(|_: &GenesisConfig<T>| Some(T::MaxNominations::get())): Option<u32>;
+
+		/// Raw storage key of the last nomination checked against `MaxNominations`, while the
+		/// stored nominations are being truncated to it.
+		NominationsToBound: Option<Vec<u8>>;
+
 		/// Storage version of the pallet.
 		///
-		/// This is set to v2.0.0 for new networks.
-		StorageVersion build(|_: &GenesisConfig<T>| Releases::V2_0_0): Releases;
+		/// This is set to v3.0.0 for new networks.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V3_0_0): Releases;
 	}
 	add_extra_genesis {
 		config(stakers):
@@ -889,7 +1045,7 @@ decl_storage! {
 
 decl_event!(
 	pub enum Event<T> where Balance = BalanceOf<T>, <T as frame_system::Trait>::AccountId {
-		/// The staker has been rewarded by this amount. AccountId is controller account.
+		/// The staker has been rewarded by this amount. AccountId is the stash account.
 		Reward(AccountId, Balance),
 		/// One validator (and its nominators) has been slashed by the given amount.
 		Slash(AccountId, Balance),
@@ -906,6 +1062,19 @@ decl_event!(
 		/// An account has called `withdraw_unbonded` and removed unbonding chunks worth `Balance`
 		/// from the unlocking queue.
 		Withdrawn(AccountId, Balance),
+		/// A new set of validators was elected with the given computation method.
+		StakingElection(ElectionCompute),
+		/// A new election solution has been queued.
+		SolutionStored(ElectionCompute),
+		/// One validator (and its nominators) would have been slashed by the given amount, but
+		/// the slash was only recorded as slashing is in record-only mode.
+		SlashRecorded(AccountId, Balance),
+		/// A validator has been chilled without being slashed, for instance for being
+		/// persistently offline.
+		Chilled(AccountId),
+		/// The total payout of an era has been set, it can now be claimed by the validators and
+		/// nominators of the era.
+		EraPayout(EraIndex, Balance),
 	}
 );
 
@@ -940,6 +1109,30 @@ decl_error! {
 		InvalidNumberOfNominations,
 		/// Items are not sorted and unique.
 		NotSortedAndUnique,
+		/// Can not validate or nominate with an active bond less than the minimum for the role.
+		InsufficientBond,
+		/// The stash is not a validator or nominator with an active bond below the minimum.
+		NotBelowMinimum,
+		/// The submitted election solution was received while the election window is closed.
+		PhragmenEarlySubmission,
+		/// The submitted election solution is for a different era.
+		PhragmenWrongEra,
+		/// The submitted election solution is not better than the one already queued.
+		PhragmenWeakSubmission,
+		/// The submitted election solution elects the wrong number of validators.
+		PhragmenBogusWinnerCount,
+		/// One of the winners of the submitted election solution is not a validator.
+		PhragmenBogusWinner,
+		/// One of the voters of the submitted election solution is unknown or duplicated.
+		PhragmenBogusNominator,
+		/// A voter of the submitted election solution backs a winner it did not nominate.
+		PhragmenBogusNomination,
+		/// A voter of the submitted election solution distributes more than its stake.
+		PhragmenBogusEdge,
+		/// The claimed score does not match the submitted election solution.
+		PhragmenBogusScore,
+		/// More targets than `MaxNominations` were given.
+		TooManyTargets,
 	}
 }
 
@@ -955,6 +1148,15 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() {
+			migration::on_runtime_upgrade::<T>();
+		}
+
+		fn on_initialize() {
+			migration::prune_era_history_step::<T>();
+			migration::bound_nominations_step::<T>();
+		}
+
 		fn on_finalize() {
 			// Set the start of the first era.
 			if let Some(mut active_era) = Self::active_era() {
@@ -1005,6 +1207,15 @@ decl_module! {
 				Err(Error::<T>::InsufficientValue)?
 			}
 
+			// only the free balance of the stash is bonded.
+			let stash_balance = T::Currency::free_balance(&stash);
+			let value = value.min(stash_balance);
+
+			// reject a bond which could neither validate nor nominate.
+			if value < Self::min_validator_bond().min(Self::min_nominator_bond()) {
+				Err(Error::<T>::InsufficientBond)?
+			}
+
 			// You're auto-bonded forever, here. We might improve this by only bonding when
 			// you actually validate/nominate and remove once you unbond __everything__.
 			<Bonded<T>>::insert(&stash, &controller);
@@ -1012,8 +1223,6 @@ decl_module! {
 
 			system::Module::<T>::inc_ref(&stash);
 
-			let stash_balance = T::Currency::free_balance(&stash);
-			let value = value.min(stash_balance);
 			Self::deposit_event(RawEvent::Bonded(stash.clone(), value));
 			let item = StakingLedger {
 				stash,
@@ -1105,6 +1314,11 @@ decl_module! {
 					ledger.active = Zero::zero();
 				}
 
+				// Validators and nominators must chill before going below their minimum bond.
+				if let Some(min_bond) = Self::min_bond_of(&ledger.stash) {
+					ensure!(ledger.active >= min_bond, Error::<T>::InsufficientBond);
+				}
+
 				// Note: in case there is no current era it is fine to bond one era more.
 				let era = Self::current_era().unwrap_or(0) + T::BondingDuration::get();
 				ledger.unlocking.push(UnlockChunk { value, era });
@@ -1176,6 +1390,7 @@ decl_module! {
 		fn validate(origin, prefs: ValidatorPrefs) {
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			ensure!(ledger.active >= Self::min_validator_bond(), Error::<T>::InsufficientBond);
 			let stash = &ledger.stash;
 			<Nominators<T>>::remove(stash);
 			<Validators<T>>::insert(stash, prefs);
@@ -1189,17 +1404,24 @@ decl_module! {
 		///
 		/// # <weight>
 		/// - The transaction's complexity is proportional to the size of `targets`,
-		/// which is capped at `MAX_NOMINATIONS`.
+		/// which is capped at `MaxNominations`, and so is its weight.
 		/// - Both the reads and writes follow a similar pattern.
 		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(750_000)]
+		#[weight = FunctionOf(
+			|(targets,): (&Vec<<T::Lookup as StaticLookup>::Source>,)| {
+				(targets.len() as Weight).saturating_mul(50_000).saturating_add(500_000)
+			},
+			DispatchClass::Normal,
+			true
+		)]
 		fn nominate(origin, targets: Vec<<T::Lookup as StaticLookup>::Source>) {
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			ensure!(ledger.active >= Self::min_nominator_bond(), Error::<T>::InsufficientBond);
 			let stash = &ledger.stash;
 			ensure!(!targets.is_empty(), Error::<T>::EmptyTargets);
+			ensure!(targets.len() <= T::MaxNominations::get() as usize, Error::<T>::TooManyTargets);
 			let targets = targets.into_iter()
-				.take(MAX_NOMINATIONS)
 				.map(|t| T::Lookup::lookup(t))
 				.collect::<result::Result<Vec<T::AccountId>, _>>()?;
 
@@ -1232,6 +1454,24 @@ decl_module! {
 			Self::chill_stash(&ledger.stash);
 		}
 
+		/// Chill a validator or nominator whose active bond is below the current minimum for
+		/// their role, e.g. after the minimum was raised.
+		///
+		/// The dispatch origin for this call must be _Signed_, by any account.
+		///
+		/// # <weight>
+		/// - Independent of the arguments. Insignificant complexity.
+		/// - Contains a limited number of reads.
+		/// - Writes are limited to the `stash` account key.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(500_000)]
+		fn kick_below_minimum(origin, stash: T::AccountId) {
+			ensure_signed(origin)?;
+			let min_bond = Self::min_bond_of(&stash).ok_or(Error::<T>::NotBelowMinimum)?;
+			ensure!(Self::slashable_balance_of(&stash) < min_bond, Error::<T>::NotBelowMinimum);
+			Self::chill_stash(&stash);
+		}
+
 		/// (Re-)set the payment target for a controller.
 		///
 		/// Effects will be felt at the beginning of the next era.
@@ -1287,6 +1527,35 @@ decl_module! {
 			ValidatorCount::put(new);
 		}
 
+		/// Increase the ideal number of validators by `additional`.
+		#[weight = SimpleDispatchInfo::FixedNormal(5_000)]
+		fn increase_validator_count(origin, #[compact] additional: u32) {
+			ensure_root(origin)?;
+			ValidatorCount::mutate(|n| *n = n.saturating_add(additional));
+		}
+
+		/// Scale up the ideal number of validators by `factor`.
+		#[weight = SimpleDispatchInfo::FixedNormal(5_000)]
+		fn scale_validator_count(origin, factor: Percent) {
+			ensure_root(origin)?;
+			ValidatorCount::mutate(|n| *n = n.saturating_add(factor * *n));
+		}
+
+		/// Set the minimum active bond to validate and to nominate.
+		///
+		/// Existing validators and nominators below the new minimum are not chilled by this call,
+		/// see [`Call::kick_below_minimum`].
+		#[weight = SimpleDispatchInfo::FixedNormal(5_000)]
+		fn set_min_bonds(
+			origin,
+			#[compact] min_validator_bond: BalanceOf<T>,
+			#[compact] min_nominator_bond: BalanceOf<T>,
+		) {
+			ensure_root(origin)?;
+			<MinValidatorBond<T>>::put(min_validator_bond);
+			<MinNominatorBond<T>>::put(min_nominator_bond);
+		}
+
 		/// Force there to be no new eras indefinitely.
 		///
 		/// # <weight>
@@ -1340,6 +1609,20 @@ decl_module! {
 			ForceEra::put(Forcing::ForceAlways);
 		}
 
+		/// Set whether the slashes of offences reported from now on are applied or only recorded.
+		///
+		/// Slashes deferred before switching to `RecordOnly` are still applied, unless canceled
+		/// with `cancel_deferred_slash`.
+		///
+		/// # <weight>
+		/// - One storage write.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(5_000)]
+		fn set_slash_mode(origin, mode: SlashingMode) {
+			ensure_root(origin)?;
+			SlashMode::put(mode);
+		}
+
 		/// Cancel enactment of a deferred slash. Can be called by either the root origin or
 		/// the `T::SlashCancelOrigin`.
 		/// passing the era and indices of the slashes for that era to kill.
@@ -1378,7 +1661,7 @@ decl_module! {
 		///   I.e. each element is a tuple of
 		///   `(validator, index of `who` in clipped exposure of validator)`.
 		///   If it is incomplete, then less than the full reward will be paid out.
-		///   It must not exceed `MAX_NOMINATIONS`.
+		///   It must not exceed `MaxNominations`.
 		///
 		/// WARNING: once an era is payed for a validator such validator can't claim the payout of
 		/// previous era.
@@ -1387,10 +1670,10 @@ decl_module! {
 		///
 		/// # <weight>
 		/// - Number of storage read of `O(validators)`; `validators` is the argument of the call,
-		///   and is bounded by `MAX_NOMINATIONS`.
+		///   and is bounded by `MaxNominations`.
 		/// - Each storage read is `O(N)` size and decode complexity; `N` is the  maximum
 		///   nominations that can be given to a single validator.
-		/// - Computation complexity: `O(MAX_NOMINATIONS * logN)`; `MAX_NOMINATIONS` is the
+		/// - Computation complexity: `O(MaxNominations * logN)`; `MaxNominations` is the
 		///   maximum number of validators that may be nominated by a single nominator, it is
 		///   bounded only economically (all nominators are required to place a minimum stake).
 		/// # </weight>
@@ -1472,6 +1755,79 @@ decl_module! {
 			Self::kill_stash(&stash)?;
 			T::Currency::remove_lock(STAKING_ID, &stash);
 		}
+
+		/// Submit an election solution computed off-chain for the election at the end of `era`.
+		///
+		/// The solution refers to validators and voters by their index in `SnapshotValidators`
+		/// and `SnapshotNominators`. It is verified and, if its `score` is better than the one
+		/// currently queued, used instead of the on-chain election when the next era is planned.
+		///
+		/// The dispatch origin for this call must be _None_. The transaction pool only accepts
+		/// it while the election window of `era` is open and if its `score` is valid and better
+		/// than the queued one.
+		///
+		/// # <weight>
+		/// - Linear in the number of winners, voters and edges of the solution. There are at most
+		///   as many voters as in `SnapshotNominators`, each with at most `MaxNominations` edges.
+		/// - Reads the ledger and nominations of every voter of the solution.
+		/// - Writes `QueuedElected` and `QueuedScore`.
+		/// # </weight>
+		#[weight = FunctionOf(
+			|(winners, compact, _, _): (
+				&Vec<ValidatorIndex>, &CompactAssignments, &PhragmenScore, &EraIndex,
+			)| {
+				let edges = compact.votes.iter().map(|vote| vote.distribution.len()).sum::<usize>();
+				let items = winners.len().saturating_add(compact.votes.len()).saturating_add(edges);
+				(items as Weight).saturating_mul(100_000).saturating_add(10_000_000)
+			},
+			DispatchClass::Operational,
+			true
+		)]
+		fn submit_election_solution(
+			origin,
+			winners: Vec<ValidatorIndex>,
+			compact: CompactAssignments,
+			score: PhragmenScore,
+			era: EraIndex,
+		) {
+			ensure_none(origin)?;
+			let result = Self::check_election_solution(winners, compact, score, era)?;
+
+			<QueuedElected<T>>::put(result);
+			QueuedScore::put(score);
+			Self::deposit_event(RawEvent::SolutionStored(ElectionCompute::Unsigned));
+		}
+	}
+}
+
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		if let Call::submit_election_solution(winners, compact, score, era) = call {
+			// checks the election window, the era and that the score is valid and better than
+			// the queued one.
+			if let Err(e) = Self::check_election_solution(
+				winners.clone(),
+				compact.clone(),
+				*score,
+				*era,
+			) {
+				return InvalidTransaction::Custom(e.as_u8()).into();
+			}
+
+			Ok(ValidTransaction {
+				// better solutions replace worse ones in the pool.
+				priority: score[0].saturated_into(),
+				requires: vec![],
+				provides: vec![(b"staking-election", era).encode()],
+				// stale solutions are invalidated on re-validation once the window closes.
+				longevity: TransactionLongevity::max_value(),
+				propagate: true,
+			})
+		} else {
+			InvalidTransaction::Call.into()
+		}
 	}
 }
 
@@ -1483,14 +1839,42 @@ impl<T: Trait> Module<T> {
 		Self::bonded(stash).and_then(Self::ledger).map(|l| l.active).unwrap_or_default()
 	}
 
+	/// The slashes deferred by `SlashDeferDuration` and not applied yet, oldest first.
+	///
+	/// Each entry holds the era in which the slashes were reported; they are applied at the start
+	/// of era `era + SlashDeferDuration + 1`, unless canceled in the meantime.
+	pub fn pending_slashes() -> Vec<(EraIndex, Vec<UnappliedSlash<T::AccountId, BalanceOf<T>>>)> {
+		let earliest = match <Self as Store>::EarliestUnappliedSlash::get() {
+			Some(earliest) => earliest,
+			None => return Vec::new(),
+		};
+		let active_era = Self::active_era().map(|e| e.index).unwrap_or(earliest);
+
+		(earliest..=active_era)
+			.map(|era| (era, <Self as Store>::UnappliedSlashes::get(&era)))
+			.filter(|(_, slashes)| !slashes.is_empty())
+			.collect()
+	}
+
+	/// The minimum active bond required by the current role of a stash, if it has any.
+	pub fn min_bond_of(stash: &T::AccountId) -> Option<BalanceOf<T>> {
+		if <Validators<T>>::contains_key(stash) {
+			Some(Self::min_validator_bond())
+		} else if <Nominators<T>>::contains_key(stash) {
+			Some(Self::min_nominator_bond())
+		} else {
+			None
+		}
+	}
+
 	// MUTABLES (DANGEROUS)
 
 	fn do_payout_nominator(who: T::AccountId, era: EraIndex, validators: Vec<(T::AccountId, u32)>)
 		-> DispatchResult
 	{
-		// validators len must not exceed `MAX_NOMINATIONS` to avoid querying more validator
+		// validators len must not exceed `MaxNominations` to avoid querying more validator
 		// exposure than necessary.
-		if validators.len() > MAX_NOMINATIONS {
+		if validators.len() > T::MaxNominations::get() as usize {
 			return Err(Error::<T>::InvalidNumberOfNominations.into());
 		}
 
@@ -1542,7 +1926,7 @@ impl<T: Trait> Module<T> {
 		}
 
 		if let Some(imbalance) = Self::make_payout(&nominator_ledger.stash, reward * era_payout) {
-			Self::deposit_event(RawEvent::Reward(who, imbalance.peek()));
+			Self::deposit_event(RawEvent::Reward(nominator_ledger.stash, imbalance.peek()));
 		}
 
 		Ok(())
@@ -1584,7 +1968,7 @@ impl<T: Trait> Module<T> {
 		);
 
 		if let Some(imbalance) = Self::make_payout(&ledger.stash, reward * era_payout) {
-			Self::deposit_event(RawEvent::Reward(who, imbalance.peek()));
+			Self::deposit_event(RawEvent::Reward(ledger.stash, imbalance.peek()));
 		}
 
 		Ok(())
@@ -1652,6 +2036,14 @@ impl<T: Trait> Module<T> {
 				Forcing::ForceNew => ForceEra::kill(),
 				Forcing::ForceAlways => (),
 				Forcing::NotForcing if era_length >= T::SessionsPerEra::get() => (),
+				Forcing::NotForcing => {
+					// The next planned session ends the era: give off-chain election solutions
+					// until then to be submitted.
+					if era_length + 1 >= T::SessionsPerEra::get() {
+						Self::open_election_window();
+					}
+					return None
+				},
 				_ => return None,
 			}
 
@@ -1750,6 +2142,7 @@ impl<T: Trait> Module<T> {
 
 			// Set ending era reward.
 			<ErasValidatorReward<T>>::insert(&active_era.index, total_payout);
+			Self::deposit_event(RawEvent::EraPayout(active_era.index, total_payout));
 		}
 	}
 
@@ -1803,6 +2196,9 @@ impl<T: Trait> Module<T> {
 	/// Select a new validator set from the assembled stakers and their role preferences, and store
 	/// staking information for the new current era.
 	///
+	/// The queued off-chain election solution is used if there is one and it is still valid,
+	/// otherwise the election is run on-chain. This closes the election window.
+	///
 	/// Fill the storages `ErasStakers`, `ErasStakersClipped`, `ErasValidatorPrefs` and
 	/// `ErasTotalStake` for current era.
 	///
@@ -1810,55 +2206,26 @@ impl<T: Trait> Module<T> {
 	///
 	/// Assumes storage is coherent with the declaration.
 	fn select_validators(current_era: EraIndex) -> Option<Vec<T::AccountId>> {
-		let mut all_nominators: Vec<(T::AccountId, Vec<T::AccountId>)> = Vec::new();
-		let mut all_validators_and_prefs = BTreeMap::new();
-		let mut all_validators = Vec::new();
-		for (validator, preference) in <Validators<T>>::iter() {
-			let self_vote = (validator.clone(), vec![validator.clone()]);
-			all_nominators.push(self_vote);
-			all_validators_and_prefs.insert(validator.clone(), preference);
-			all_validators.push(validator);
-		}
-
-		let nominator_votes = <Nominators<T>>::iter().map(|(nominator, nominations)| {
-			let Nominations { submitted_in, mut targets, suppressed: _ } = nominations;
-
-			// Filter out nomination targets which were nominated before the most recent
-			// non-zero slash.
-			targets.retain(|stash| {
-				<Self as Store>::SlashingSpans::get(&stash).map_or(
-					true,
-					|spans| submitted_in >= spans.last_nonzero_slash(),
-				)
-			});
-
-			(nominator, targets)
-		});
-		all_nominators.extend(nominator_votes);
-
-		let maybe_phragmen_result = sp_phragmen::elect::<_, _, _, T::CurrencyToVote, Perbill>(
-			Self::validator_count() as usize,
-			Self::minimum_validator_count().max(1) as usize,
-			all_validators,
-			all_nominators,
-			Self::slashable_balance_of,
-		);
-
-		if let Some(phragmen_result) = maybe_phragmen_result {
-			let elected_stashes = phragmen_result.winners.into_iter()
-				.map(|(s, _)| s)
-				.collect::<Vec<T::AccountId>>();
-			let assignments = phragmen_result.assignments;
+		let queued = <QueuedElected<T>>::take();
+		let candidate_count = Self::snapshot_validators().map_or(0, |v| v.len());
+		Self::close_election_window();
+
+		// the score was checked against the snapshot on submission, but stakers may have changed
+		// their intentions since.
+		let maybe_election = queued
+			.and_then(|result| {
+				Self::check_election_result(&result, candidate_count)
+					.ok()
+					.map(|supports| (result.winners, supports, ElectionCompute::Unsigned))
+			})
+			.or_else(|| Self::elect_on_chain()
+				.map(|(winners, supports)| (winners, supports, ElectionCompute::OnChain))
+			);
 
+		if let Some((elected_stashes, supports, compute)) = maybe_election {
 			let to_balance = |e: ExtendedBalance|
 				<T::CurrencyToVote as Convert<ExtendedBalance, BalanceOf<T>>>::convert(e);
 
-			let supports = sp_phragmen::build_support_map::<_, _, _, T::CurrencyToVote, Perbill>(
-				&elected_stashes,
-				&assignments,
-				Self::slashable_balance_of,
-			);
-
 			// Populate stakers information and figure out the total stake.
 			let mut total_staked = BalanceOf::<T>::zero();
 			for (c, s) in supports.into_iter() {
@@ -1902,13 +2269,12 @@ impl<T: Trait> Module<T> {
 
 			// Insert current era staking informations
 			<ErasTotalStake<T>>::insert(&current_era, total_staked);
-			let default_pref = ValidatorPrefs::default();
 			for stash in &elected_stashes {
-				let pref = all_validators_and_prefs.get(stash)
-					.unwrap_or(&default_pref); // Must never happen, but better to be safe.
-				<ErasValidatorPrefs<T>>::insert(&current_era, stash, pref);
+				<ErasValidatorPrefs<T>>::insert(&current_era, stash, Self::validators(stash));
 			}
 
+			Self::deposit_event(RawEvent::StakingElection(compute));
+
 			// In order to keep the property required by `n_session_ending`
 			// that we must return the new validator set even if it's the same as the old,
 			// as long as any underlying economic conditions have changed, we don't attempt
@@ -1925,6 +2291,212 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
+	/// Run the Phragmén election on-chain over all current validators and nominators.
+	///
+	/// Returns the winners and their support, or `None` if there are not enough candidates.
+	fn elect_on_chain() -> Option<(Vec<T::AccountId>, SupportMap<T::AccountId>)> {
+		let mut all_nominators: Vec<(T::AccountId, Vec<T::AccountId>)> = Vec::new();
+		let mut all_validators = Vec::new();
+		for (validator, _preference) in <Validators<T>>::iter() {
+			let self_vote = (validator.clone(), vec![validator.clone()]);
+			all_nominators.push(self_vote);
+			all_validators.push(validator);
+		}
+
+		let nominator_votes = <Nominators<T>>::iter().map(|(nominator, nominations)| {
+			(nominator, Self::eligible_targets(nominations))
+		});
+		all_nominators.extend(nominator_votes);
+
+		let phragmen_result = sp_phragmen::elect::<_, _, _, T::CurrencyToVote, Perbill>(
+			Self::validator_count() as usize,
+			Self::minimum_validator_count().max(1) as usize,
+			all_validators,
+			all_nominators,
+			Self::slashable_balance_of,
+		)?;
+
+		let elected_stashes = phragmen_result.winners.into_iter()
+			.map(|(s, _)| s)
+			.collect::<Vec<T::AccountId>>();
+
+		let supports = sp_phragmen::build_support_map::<_, _, _, T::CurrencyToVote, Perbill>(
+			&elected_stashes,
+			&phragmen_result.assignments,
+			Self::slashable_balance_of,
+		);
+
+		Some((elected_stashes, supports))
+	}
+
+	/// The targets of `nominations` which may still be backed in an election.
+	///
+	/// Only the first `MaxNominations` targets are considered, as nominations stored before it was
+	/// lowered may not be truncated yet. Of those, the targets which were nominated before the
+	/// most recent non-zero slash are filtered out.
+	fn eligible_targets(nominations: Nominations<T::AccountId>) -> Vec<T::AccountId> {
+		let Nominations { submitted_in, mut targets, suppressed: _ } = nominations;
+
+		targets.truncate(T::MaxNominations::get() as usize);
+
+		targets.retain(|stash| {
+			<Self as Store>::SlashingSpans::get(&stash).map_or(
+				true,
+				|spans| submitted_in >= spans.last_nonzero_slash(),
+			)
+		});
+
+		targets
+	}
+
+	/// Take a snapshot of all validators and voters and start accepting off-chain election
+	/// solutions for the election at the end of the current era.
+	fn open_election_window() {
+		if Self::era_election_status() == ElectionStatus::Open {
+			return
+		}
+
+		let validators = <Validators<T>>::iter().map(|(v, _)| v).collect::<Vec<_>>();
+		let mut nominators = validators.clone();
+		nominators.extend(<Nominators<T>>::iter().map(|(n, _)| n));
+
+		<SnapshotValidators<T>>::put(validators);
+		<SnapshotNominators<T>>::put(nominators);
+		EraElectionStatus::put(ElectionStatus::Open);
+	}
+
+	/// Stop accepting off-chain election solutions and remove the snapshot.
+	fn close_election_window() {
+		<SnapshotValidators<T>>::kill();
+		<SnapshotNominators<T>>::kill();
+		<QueuedElected<T>>::kill();
+		QueuedScore::kill();
+		EraElectionStatus::put(ElectionStatus::Closed);
+	}
+
+	/// Verify a compact election solution against the snapshot and the current staking state.
+	///
+	/// Returns the solution in terms of accounts, ready to be queued.
+	fn check_election_solution(
+		winners: Vec<ValidatorIndex>,
+		compact: CompactAssignments,
+		score: PhragmenScore,
+		era: EraIndex,
+	) -> result::Result<ElectionResult<T::AccountId>, Error<T>> {
+		ensure!(
+			Self::era_election_status() == ElectionStatus::Open,
+			Error::<T>::PhragmenEarlySubmission,
+		);
+		ensure!(era == Self::current_era().unwrap_or(0), Error::<T>::PhragmenWrongEra);
+		if let Some(queued_score) = Self::queued_score() {
+			ensure!(
+				sp_phragmen::is_score_better(score, queued_score),
+				Error::<T>::PhragmenWeakSubmission,
+			);
+		}
+
+		let snapshot_validators = Self::snapshot_validators()
+			.ok_or(Error::<T>::PhragmenEarlySubmission)?;
+		let snapshot_nominators = Self::snapshot_nominators()
+			.ok_or(Error::<T>::PhragmenEarlySubmission)?;
+
+		// bound the work done below before looking at any of the votes.
+		ensure!(
+			winners.len() <= snapshot_validators.len(),
+			Error::<T>::PhragmenBogusWinnerCount,
+		);
+		ensure!(
+			compact.votes.len() <= snapshot_nominators.len(),
+			Error::<T>::PhragmenBogusNominator,
+		);
+		ensure!(
+			compact.votes.iter().all(|vote| {
+				vote.distribution.len() <= T::MaxNominations::get() as usize
+			}),
+			Error::<T>::PhragmenBogusNomination,
+		);
+
+		let validator_at = |index: ValidatorIndex| snapshot_validators.get(index as usize)
+			.cloned()
+			.ok_or(Error::<T>::PhragmenBogusWinner);
+
+		let winners = winners.into_iter()
+			.map(&validator_at)
+			.collect::<result::Result<Vec<_>, _>>()?;
+		let assignments = compact.votes.into_iter()
+			.map(|vote| {
+				let who = snapshot_nominators.get(vote.nominator as usize)
+					.cloned()
+					.ok_or(Error::<T>::PhragmenBogusNominator)?;
+				let distribution = vote.distribution.into_iter()
+					.map(|edge| validator_at(edge.validator).map(|target| (target, edge.ratio)))
+					.collect::<result::Result<Vec<_>, _>>()?;
+				Ok((who, distribution))
+			})
+			.collect::<result::Result<Vec<_>, Error<T>>>()?;
+
+		let result = ElectionResult { winners, assignments };
+		let supports = Self::check_election_result(&result, snapshot_validators.len())?;
+		ensure!(sp_phragmen::evaluate_support(&supports) == score, Error::<T>::PhragmenBogusScore);
+
+		Ok(result)
+	}
+
+	/// Check that an election result only uses edges which are valid at this point and elects
+	/// as many validators as the on-chain election would from `candidate_count` candidates.
+	///
+	/// Returns the support of each winner given the current stake of the voters.
+	fn check_election_result(
+		result: &ElectionResult<T::AccountId>,
+		candidate_count: usize,
+	) -> result::Result<SupportMap<T::AccountId>, Error<T>> {
+		let ElectionResult { winners, assignments } = result;
+
+		let expected_winners = (Self::validator_count() as usize).min(candidate_count);
+		ensure!(
+			winners.len() == expected_winners &&
+				winners.len() >= Self::minimum_validator_count().max(1) as usize,
+			Error::<T>::PhragmenBogusWinnerCount,
+		);
+
+		let mut unique_winners = BTreeSet::new();
+		for winner in winners {
+			ensure!(
+				<Validators<T>>::contains_key(winner) && unique_winners.insert(winner.clone()),
+				Error::<T>::PhragmenBogusWinner,
+			);
+		}
+
+		let mut unique_voters = BTreeSet::new();
+		for (who, distribution) in assignments {
+			ensure!(unique_voters.insert(who.clone()), Error::<T>::PhragmenBogusNominator);
+
+			let targets = if <Validators<T>>::contains_key(who) {
+				vec![who.clone()]
+			} else {
+				Self::nominators(who)
+					.map(Self::eligible_targets)
+					.ok_or(Error::<T>::PhragmenBogusNominator)?
+			};
+
+			let mut total_parts: u64 = 0;
+			for (target, ratio) in distribution {
+				ensure!(
+					unique_winners.contains(target) && targets.contains(target),
+					Error::<T>::PhragmenBogusNomination,
+				);
+				total_parts = total_parts.saturating_add(ratio.deconstruct().into());
+			}
+			ensure!(total_parts <= Perbill::ACCURACY.into(), Error::<T>::PhragmenBogusEdge);
+		}
+
+		Ok(sp_phragmen::build_support_map::<_, _, _, T::CurrencyToVote, Perbill>(
+			winners,
+			assignments,
+			Self::slashable_balance_of,
+		))
+	}
+
 	/// Remove all associated data of a stash account from the staking system.
 	///
 	/// Assumes storage is upgraded before calling.
@@ -2071,6 +2643,17 @@ impl<T: Trait> Convert<T::AccountId, Option<Exposure<T::AccountId, BalanceOf<T>>
 	}
 }
 
+/// Chill validators on behalf of other modules, such as the persistently offline validators
+/// reported by the im-online module.
+impl<T: Trait> ChillValidator<T::AccountId> for Module<T> {
+	fn chill_validator(stash: &T::AccountId) {
+		if <Validators<T>>::contains_key(stash) {
+			<Validators<T>>::remove(stash);
+			Self::deposit_event(RawEvent::Chilled(stash.clone()));
+		}
+	}
+}
+
 /// This is intended to be used with `FilterHistoricalOffences`.
 impl <T: Trait> OnOffenceHandler<T::AccountId, pallet_session::historical::IdentificationTuple<T>> for Module<T> where
 	T: pallet_session::Trait<ValidatorId = <T as frame_system::Trait>::AccountId>,
@@ -2125,6 +2708,7 @@ impl <T: Trait> OnOffenceHandler<T::AccountId, pallet_session::historical::Ident
 		});
 
 		let slash_defer_duration = T::SlashDeferDuration::get();
+		let slash_mode = Self::slash_mode();
 
 		for (details, slash_fraction) in offenders.iter().zip(slash_fraction) {
 			let stash = &details.offender.0;
@@ -2143,11 +2727,20 @@ impl <T: Trait> OnOffenceHandler<T::AccountId, pallet_session::historical::Ident
 				window_start,
 				now: active_era,
 				reward_proportion,
+				record_only: slash_mode == SlashingMode::RecordOnly,
 			});
 
 			if let Some(mut unapplied) = unapplied {
 				unapplied.reporters = details.reporters.clone();
-				if slash_defer_duration == 0 {
+				if slash_mode == SlashingMode::RecordOnly {
+					let total = unapplied.others.iter()
+						.fold(unapplied.own, |total, (_, slash)| total.saturating_add(*slash));
+					Self::deposit_event(RawEvent::SlashRecorded(stash.clone(), total));
+					<Self as Store>::RecordedSlashes::mutate(
+						slash_era,
+						move |recorded| recorded.push(unapplied),
+					);
+				} else if slash_defer_duration == 0 {
 					// apply right away.
 					slashing::apply_slash::<T>(unapplied);
 				} else {
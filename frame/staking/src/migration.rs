@@ -0,0 +1,142 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations for the staking module.
+
+use super::{
+	Trait, Module, Releases, StorageVersion, CurrentEra, HistoryDepth, EraIndex,
+	NextEraToPrune, Nominators, Nominations, NominationsBound, NominationsToBound,
+};
+use frame_support::{storage::{StorageValue, generator::StorageMap, unhashed}, traits::Get};
+
+/// Maximum number of eras of old history pruned in a single block, so that chains with a long
+/// history don't have to prune it all in the block of the runtime upgrade.
+const ERAS_PRUNED_PER_BLOCK: EraIndex = 4;
+
+/// Maximum number of stored nominations checked against `MaxNominations` in a single block.
+const NOMINATIONS_BOUNDED_PER_BLOCK: u32 = 64;
+
+/// Bring the staking storage up to date with the current `Releases` version.
+///
+/// Each step migrates the storage from one version to the next, so that chains several
+/// releases behind go through all of them in order.
+///
+/// If the upgrade lowered `MaxNominations`, the stored nominations are then scheduled to be
+/// truncated to it.
+pub(crate) fn on_runtime_upgrade<T: Trait>() {
+	loop {
+		match StorageVersion::get() {
+			Releases::V2_0_0 => {
+				start_pruning_era_history();
+				StorageVersion::put(Releases::V3_0_0);
+			},
+			// the current version.
+			Releases::V3_0_0 => break,
+			// predates on-chain migrations, it can't be upgraded.
+			Releases::V1_0_0Ancient => break,
+		}
+	}
+
+	start_bounding_nominations::<T>();
+}
+
+/// Schedule the truncation of the stored nominations to `MaxNominations`, unless they are
+/// already bounded by it or a lower value.
+///
+/// The nominations are truncated a few per block by `bound_nominations_step`.
+fn start_bounding_nominations<T: Trait>() {
+	let max = T::MaxNominations::get();
+	match NominationsBound::get() {
+		Some(bound) if bound <= max => if NominationsToBound::get().is_none() {
+			NominationsBound::put(max);
+		},
+		// unset if nominations were stored before `MaxNominations` existed.
+		_ => NominationsToBound::put(<Nominators<T>>::prefix_hash()),
+	}
+}
+
+/// Truncate up to `NOMINATIONS_BOUNDED_PER_BLOCK` of the stored nominations scheduled to be
+/// bounded, if any. Only the nominations over `MaxNominations` are written.
+///
+/// Called at the beginning of every block. This is a single read once all of them are bounded.
+pub(crate) fn bound_nominations_step<T: Trait>() {
+	let mut previous_key = match NominationsToBound::get() {
+		Some(key) => key,
+		None => return,
+	};
+	let prefix = <Nominators<T>>::prefix_hash();
+	let max = T::MaxNominations::get();
+
+	for _ in 0..NOMINATIONS_BOUNDED_PER_BLOCK {
+		let next_key = sp_io::storage::next_key(&previous_key)
+			.filter(|key| key.starts_with(&prefix));
+		previous_key = match next_key {
+			Some(key) => key,
+			None => {
+				NominationsToBound::kill();
+				NominationsBound::put(max);
+				return;
+			},
+		};
+		if let Some(mut nominations) = unhashed::get::<Nominations<T::AccountId>>(&previous_key) {
+			if nominations.targets.len() > max as usize {
+				nominations.targets.truncate(max as usize);
+				unhashed::put(&previous_key, &nominations);
+			}
+		}
+	}
+	NominationsToBound::put(previous_key);
+}
+
+/// Schedule the pruning of all per-era staking information that is older than `HistoryDepth`.
+///
+/// Chains which started before era information was pruned on era change still hold it for
+/// every era since genesis, while `new_era` only ever clears the single era leaving the window.
+/// The history is pruned from the first era on, a few eras per block, by
+/// `prune_era_history_step`.
+fn start_pruning_era_history() {
+	if CurrentEra::get().is_some() {
+		NextEraToPrune::put(0);
+	}
+}
+
+/// Prune up to `ERAS_PRUNED_PER_BLOCK` eras of the history scheduled for pruning, if any.
+///
+/// Called at the beginning of every block. This is a single read once pruning is over.
+pub(crate) fn prune_era_history_step<T: Trait>() {
+	let next_era = match NextEraToPrune::get() {
+		Some(era) => era,
+		None => return,
+	};
+	let last_era = CurrentEra::get()
+		.and_then(|current_era| current_era.checked_sub(HistoryDepth::get() + 1));
+
+	match last_era {
+		Some(last_era) if next_era <= last_era => {
+			let end = next_era.saturating_add(ERAS_PRUNED_PER_BLOCK - 1).min(last_era);
+			for era_index in next_era..=end {
+				<Module<T>>::clear_era_information(era_index);
+			}
+			if end == last_era {
+				NextEraToPrune::kill();
+			} else {
+				NextEraToPrune::put(end + 1);
+			}
+		},
+		// everything older than `HistoryDepth` is pruned.
+		_ => NextEraToPrune::kill(),
+	}
+}
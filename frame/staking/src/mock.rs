@@ -24,13 +24,17 @@ use sp_runtime::testing::{Header, UintAuthorityId};
 use sp_staking::{SessionIndex, offence::{OffenceDetails, OnOffenceHandler}};
 use sp_core::{H256, crypto::key_types};
 use sp_io;
+use frame_system as system;
+use pallet_balances as balances;
+use pallet_session as session;
 use frame_support::{
-	assert_ok, impl_outer_origin, parameter_types, StorageValue, StorageMap,
+	assert_ok, impl_outer_origin, impl_outer_event, parameter_types, StorageValue, StorageMap,
 	StorageDoubleMap, IterableStorageMap, traits::{Currency, Get, FindAuthor}, weights::Weight,
 };
 use crate::{
-	EraIndex, GenesisConfig, Module, Trait, StakerStatus, ValidatorPrefs, RewardDestination,
-	Nominators, inflation, SessionInterface, Exposure, ErasStakers, ErasRewardPoints
+	self as staking, EraIndex, GenesisConfig, Module, Trait, StakerStatus, ValidatorPrefs,
+	RewardDestination, Nominators, inflation, SessionInterface, Exposure, ErasStakers,
+	ErasRewardPoints, ValidatorIndex, NominatorIndex, CompactAssignments, CompactVote, CompactEdge,
 };
 
 /// The AccountId alias in this test module.
@@ -101,6 +105,15 @@ impl_outer_origin!{
 	pub enum Origin for Test  where system = frame_system {}
 }
 
+impl_outer_event! {
+	pub enum MetaEvent for Test {
+		system<T>,
+		balances<T>,
+		session,
+		staking<T>,
+	}
+}
+
 /// Author of block is always 11
 pub struct Author11;
 impl FindAuthor<u64> for Author11 {
@@ -130,7 +143,7 @@ impl frame_system::Trait for Test {
 	type AccountId = AccountId;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = MetaEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
 	type AvailableBlockRatio = AvailableBlockRatio;
@@ -144,7 +157,7 @@ impl frame_system::Trait for Test {
 impl pallet_balances::Trait for Test {
 	type Balance = Balance;
 	type DustRemoval = ();
-	type Event = ();
+	type Event = MetaEvent;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 }
@@ -155,7 +168,7 @@ parameter_types! {
 	pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(25);
 }
 impl pallet_session::Trait for Test {
-	type Event = ();
+	type Event = MetaEvent;
 	type ValidatorId = AccountId;
 	type ValidatorIdOf = crate::StashOf<Test>;
 	type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
@@ -198,13 +211,14 @@ parameter_types! {
 	pub const BondingDuration: EraIndex = 3;
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &I_NPOS;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxNominations: u32 = 16;
 }
 impl Trait for Test {
 	type Currency = pallet_balances::Module<Self>;
 	type Time = pallet_timestamp::Module<Self>;
 	type CurrencyToVote = CurrencyToVoteHandler;
 	type RewardRemainder = ();
-	type Event = ();
+	type Event = MetaEvent;
 	type Slash = ();
 	type Reward = ();
 	type SessionsPerEra = SessionsPerEra;
@@ -214,6 +228,7 @@ impl Trait for Test {
 	type SessionInterface = Self;
 	type RewardCurve = RewardCurve;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type MaxNominations = MaxNominations;
 }
 
 pub struct ExtBuilder {
@@ -512,6 +527,14 @@ pub fn on_offence_now(
 	on_offence_in_era(offenders, slash_fraction, now)
 }
 
+/// The events deposited by the staking module, in order.
+pub fn staking_events() -> Vec<staking::Event<Test>> {
+	System::events().into_iter().filter_map(|record| match record.event {
+		MetaEvent::staking(event) => Some(event),
+		_ => None,
+	}).collect()
+}
+
 /// Make all validator and nominator request their payment
 pub fn make_all_reward_payment(era: EraIndex) {
 	let validators_with_reward = ErasRewardPoints::<Test>::get(era).individual.keys()
@@ -543,3 +566,29 @@ pub fn make_all_reward_payment(era: EraIndex) {
 		assert_ok!(Staking::payout_validator(Origin::signed(validator_controller), era));
 	}
 }
+
+/// Encode an election solution given in accounts against the current election snapshot.
+pub fn compact_solution(
+	winners: &[AccountId],
+	votes: &[(AccountId, Vec<(AccountId, Perbill)>)],
+) -> (Vec<ValidatorIndex>, CompactAssignments) {
+	let snapshot_validators = Staking::snapshot_validators().unwrap();
+	let snapshot_nominators = Staking::snapshot_nominators().unwrap();
+	let validator_index = |who: &AccountId| snapshot_validators.iter()
+		.position(|v| v == who)
+		.unwrap() as ValidatorIndex;
+	let nominator_index = |who: &AccountId| snapshot_nominators.iter()
+		.position(|n| n == who)
+		.unwrap() as NominatorIndex;
+
+	let compact = CompactAssignments {
+		votes: votes.iter().map(|(who, distribution)| CompactVote {
+			nominator: nominator_index(who),
+			distribution: distribution.iter()
+				.map(|(target, ratio)| CompactEdge { validator: validator_index(target), ratio: *ratio })
+				.collect(),
+		}).collect(),
+	};
+
+	(winners.iter().map(validator_index).collect(), compact)
+}
@@ -60,13 +60,12 @@ use frame_support::{
 use sp_std::vec::Vec;
 use codec::{Encode, Decode};
 
+pub(crate) use sp_staking::slashing::SpanIndex;
+
 /// The proportion of the slashing reward to be paid out on the first slashing detection.
 /// This is f_1 in the paper.
 const REWARD_F1: Perbill = Perbill::from_percent(50);
 
-/// The index of a slashing span - unique to each stash.
-pub(crate) type SpanIndex = u32;
-
 // A range of start..end eras for a slashing span.
 #[derive(Encode, Decode)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
@@ -205,11 +204,15 @@ pub(crate) struct SlashParams<'a, T: 'a + Trait> {
 	/// The maximum percentage of a slash that ever gets paid out.
 	/// This is f_inf in the paper.
 	pub(crate) reward_proportion: Perbill,
+	/// Whether the slash is only recorded. The slashing metadata is then read but left
+	/// untouched, and the offender is not chilled or disabled.
+	pub(crate) record_only: bool,
 }
 
 /// Computes a slash of a validator and nominators. It returns an unapplied
 /// record to be applied at some later point. Slashing metadata is updated in storage,
-/// since unapplied records are only rarely intended to be dropped.
+/// since unapplied records are only rarely intended to be dropped, unless the slash is
+/// only recorded.
 ///
 /// The pending slash record returned does not have initialized reporters. Those have
 /// to be set at a higher level, if any.
@@ -224,6 +227,7 @@ pub(crate) fn compute_slash<T: Trait>(params: SlashParams<T>)
 		window_start,
 		now,
 		reward_proportion,
+		record_only,
 	} = params.clone();
 
 	let mut reward_payout = Zero::zero();
@@ -246,11 +250,13 @@ pub(crate) fn compute_slash<T: Trait>(params: SlashParams<T>)
 	// compare slash proportions rather than slash values to avoid issues due to rounding
 	// error.
 	if slash.deconstruct() > prior_slash_p.deconstruct() {
-		<Module<T> as Store>::ValidatorSlashInEra::insert(
-			&slash_era,
-			stash,
-			&(slash, own_slash),
-		);
+		if !record_only {
+			<Module<T> as Store>::ValidatorSlashInEra::insert(
+				&slash_era,
+				stash,
+				&(slash, own_slash),
+			);
+		}
 	} else {
 		// we slash based on the max in era - this new event is not the max,
 		// so neither the validator or any nominators will need an update.
@@ -270,6 +276,7 @@ pub(crate) fn compute_slash<T: Trait>(params: SlashParams<T>)
 			&mut reward_payout,
 			&mut val_slashed,
 			reward_proportion,
+			record_only,
 		);
 
 		let target_span = spans.compare_and_update_span_slash(
@@ -284,12 +291,8 @@ pub(crate) fn compute_slash<T: Trait>(params: SlashParams<T>)
 			// chill the validator - it misbehaved in the current span and should
 			// not continue in the next election. also end the slashing span.
 			spans.end_span(now);
-			<Module<T>>::chill_stash(stash);
-
-			// make sure to disable validator till the end of this session
-			if T::SessionInterface::disable_validator(stash).unwrap_or(false) {
-				// force a new era, to select a new validator set
-				<Module<T>>::ensure_new_era()
+			if !record_only {
+				disable_offender_now::<T>(stash);
 			}
 		}
 	}
@@ -311,6 +314,11 @@ pub(crate) fn compute_slash<T: Trait>(params: SlashParams<T>)
 fn kick_out_if_recent<T: Trait>(
 	params: SlashParams<T>,
 ) {
+	// a recorded slash neither chills nor ends any span.
+	if params.record_only {
+		return
+	}
+
 	// these are not updated by era-span or end-span.
 	let mut reward_payout = Zero::zero();
 	let mut val_slashed = Zero::zero();
@@ -320,17 +328,23 @@ fn kick_out_if_recent<T: Trait>(
 		&mut reward_payout,
 		&mut val_slashed,
 		params.reward_proportion,
+		false,
 	);
 
 	if spans.era_span(params.slash_era).map(|s| s.index) == Some(spans.span_index()) {
 		spans.end_span(params.now);
-		<Module<T>>::chill_stash(params.stash);
+		disable_offender_now::<T>(params.stash);
+	}
+}
 
-		// make sure to disable validator till the end of this session
-		if T::SessionInterface::disable_validator(params.stash).unwrap_or(false) {
-			// force a new era, to select a new validator set
-			<Module<T>>::ensure_new_era()
-		}
+// chills the validator and disables it till the end of the session.
+fn disable_offender_now<T: Trait>(stash: &T::AccountId) {
+	<Module<T>>::chill_stash(stash);
+
+	// make sure to disable validator till the end of this session
+	if T::SessionInterface::disable_validator(stash).unwrap_or(false) {
+		// force a new era, to select a new validator set
+		<Module<T>>::ensure_new_era()
 	}
 }
 
@@ -350,6 +364,7 @@ fn slash_nominators<T: Trait>(
 		window_start,
 		now,
 		reward_proportion,
+		record_only,
 	} = params;
 
 	let mut reward_payout = Zero::zero();
@@ -373,11 +388,13 @@ fn slash_nominators<T: Trait>(
 
 			era_slash += own_slash_difference;
 
-			<Module<T> as Store>::NominatorSlashInEra::insert(
-				&slash_era,
-				stash,
-				&era_slash,
-			);
+			if !record_only {
+				<Module<T> as Store>::NominatorSlashInEra::insert(
+					&slash_era,
+					stash,
+					&era_slash,
+				);
+			}
 
 			era_slash
 		};
@@ -390,6 +407,7 @@ fn slash_nominators<T: Trait>(
 				&mut reward_payout,
 				&mut nom_slashed,
 				reward_proportion,
+				record_only,
 			);
 
 			let target_span = spans.compare_and_update_span_slash(
@@ -411,7 +429,8 @@ fn slash_nominators<T: Trait>(
 }
 
 // helper struct for managing a set of spans we are currently inspecting.
-// writes alterations to disk on drop, but only if a slash has been carried out.
+// writes alterations to disk on drop, but only if a slash has been carried out and
+// is not only recorded.
 //
 // NOTE: alterations to slashing metadata should not be done after this is dropped.
 // dropping this struct applies any necessary slashes, which can lead to free balance
@@ -425,20 +444,26 @@ struct InspectingSpans<'a, T: Trait + 'a> {
 	paid_out: &'a mut BalanceOf<T>,
 	slash_of: &'a mut BalanceOf<T>,
 	reward_proportion: Perbill,
+	record_only: bool,
 	_marker: sp_std::marker::PhantomData<T>,
 }
 
 // fetches the slashing spans record for a stash account, initializing it if necessary.
+//
+// if `record_only`, nothing is written to storage, neither now nor on drop.
 fn fetch_spans<'a, T: Trait + 'a>(
 	stash: &'a T::AccountId,
 	window_start: EraIndex,
 	paid_out: &'a mut BalanceOf<T>,
 	slash_of: &'a mut BalanceOf<T>,
 	reward_proportion: Perbill,
+	record_only: bool,
 ) -> InspectingSpans<'a, T> {
 	let spans = <Module<T> as Store>::SlashingSpans::get(stash).unwrap_or_else(|| {
 		let spans = SlashingSpans::new(window_start);
-		<Module<T> as Store>::SlashingSpans::insert(stash, &spans);
+		if !record_only {
+			<Module<T> as Store>::SlashingSpans::insert(stash, &spans);
+		}
 		spans
 	});
 
@@ -450,6 +475,7 @@ fn fetch_spans<'a, T: Trait + 'a>(
 		slash_of,
 		paid_out,
 		reward_proportion,
+		record_only,
 		_marker: sp_std::marker::PhantomData,
 	}
 }
@@ -516,7 +542,7 @@ impl<'a, T: 'a + Trait> InspectingSpans<'a, T> {
 			*self.paid_out += reward;
 		}
 
-		if changed {
+		if changed && !self.record_only {
 			self.dirty = true;
 			<Module<T> as Store>::SpanSlash::insert(&span_slash_key, &span_record);
 		}
@@ -528,7 +554,7 @@ impl<'a, T: 'a + Trait> InspectingSpans<'a, T> {
 impl<'a, T: 'a + Trait> Drop for InspectingSpans<'a, T> {
 	fn drop(&mut self) {
 		// only update on disk if we slashed this account.
-		if !self.dirty { return }
+		if !self.dirty || self.record_only { return }
 
 		if let Some((start, end)) = self.spans.prune(self.window_start) {
 			for span_index in start..end {
@@ -544,6 +570,7 @@ impl<'a, T: 'a + Trait> Drop for InspectingSpans<'a, T> {
 pub(crate) fn clear_era_metadata<T: Trait>(obsolete_era: EraIndex) {
 	<Module<T> as Store>::ValidatorSlashInEra::remove_prefix(&obsolete_era);
 	<Module<T> as Store>::NominatorSlashInEra::remove_prefix(&obsolete_era);
+	<Module<T> as Store>::RecordedSlashes::remove(&obsolete_era);
 }
 
 /// Clear slashing metadata for a dead account.
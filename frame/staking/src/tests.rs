@@ -18,7 +18,7 @@
 
 use super::*;
 use mock::*;
-use sp_runtime::{assert_eq_error_rate, traits::{OnInitialize, BadOrigin}};
+use sp_runtime::{assert_eq_error_rate, Percent, traits::{OnInitialize, BadOrigin}};
 use sp_staking::offence::OffenceDetails;
 use frame_support::{
 	assert_ok, assert_noop,
@@ -149,6 +149,26 @@ fn change_controller_works() {
 	})
 }
 
+#[test]
+fn set_payee_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// Stash 11 defaults to having its rewards staked.
+		assert_eq!(Staking::payee(&11), RewardDestination::Staked);
+
+		// Only the controller may change the payee, not the stash.
+		assert_noop!(
+			Staking::set_payee(Origin::signed(11), RewardDestination::Stash),
+			Error::<Test>::NotController,
+		);
+
+		// The payee is keyed by the stash even though the controller sets it.
+		assert_ok!(Staking::set_payee(Origin::signed(10), RewardDestination::Stash));
+		assert_eq!(Staking::payee(&11), RewardDestination::Stash);
+		assert_ok!(Staking::set_payee(Origin::signed(10), RewardDestination::Controller));
+		assert_eq!(Staking::payee(&11), RewardDestination::Controller);
+	})
+}
+
 #[test]
 fn rewards_should_work() {
 	// should check that:
@@ -239,6 +259,36 @@ fn rewards_should_work() {
 	});
 }
 
+#[test]
+fn era_payout_and_rewards_deposit_events() {
+	ExtBuilder::default().nominate(true).build().execute_with(|| {
+		// the rewards are paid to the controllers, but the events name the stashes.
+		Payee::<Test>::insert(11, RewardDestination::Controller);
+		Payee::<Test>::insert(101, RewardDestination::Controller);
+		<Module<Test>>::reward_by_ids(vec![(11, 1)]);
+
+		let total_payout_0 = current_total_payout_for_duration(3 * 1000);
+		assert!(total_payout_0 > 10); // Test is meaningful if reward something
+
+		start_era(1);
+		assert!(staking_events().contains(&RawEvent::EraPayout(0, total_payout_0)));
+
+		let balance_10 = Balances::total_balance(&10);
+		let balance_100 = Balances::total_balance(&100);
+		let events_before_payout = staking_events().len();
+		mock::make_all_reward_payment(0);
+
+		let reward_10 = Balances::total_balance(&10) - balance_10;
+		let reward_100 = Balances::total_balance(&100) - balance_100;
+		assert!(reward_10 > 0);
+		assert!(reward_100 > 0);
+		assert_eq_uvec!(
+			staking_events()[events_before_payout..].to_vec(),
+			vec![RawEvent::Reward(11, reward_10), RawEvent::Reward(101, reward_100)]
+		);
+	});
+}
+
 #[test]
 fn staking_should_work() {
 	// should test:
@@ -751,6 +801,156 @@ fn forcing_new_era_works() {
 	});
 }
 
+#[test]
+fn forcing_dispatchables_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		// only root can control era transitions.
+		assert_noop!(Staking::force_no_eras(Origin::signed(10)), BadOrigin);
+		assert_noop!(Staking::force_new_era(Origin::signed(10)), BadOrigin);
+		assert_noop!(Staking::force_new_era_always(Origin::signed(10)), BadOrigin);
+		assert_eq!(Staking::force_era(), Forcing::NotForcing);
+
+		start_session(3);
+		assert_eq!(Staking::active_era().unwrap().index, 1);
+
+		// no era change.
+		assert_ok!(Staking::force_no_eras(Origin::ROOT));
+		assert_eq!(Staking::force_era(), Forcing::ForceNone);
+		start_session(9);
+		assert_eq!(Staking::active_era().unwrap().index, 1);
+
+		// a single new era, one session later, then back to normal.
+		assert_ok!(Staking::force_new_era(Origin::ROOT));
+		assert_eq!(Staking::force_era(), Forcing::ForceNew);
+		start_session(10);
+		assert_eq!(Staking::active_era().unwrap().index, 1);
+		start_session(11);
+		assert_eq!(Staking::active_era().unwrap().index, 2);
+		assert_eq!(Staking::force_era(), Forcing::NotForcing);
+
+		// a new era at every session.
+		assert_ok!(Staking::force_new_era_always(Origin::ROOT));
+		assert_eq!(Staking::force_era(), Forcing::ForceAlways);
+		start_session(12);
+		assert_eq!(Staking::active_era().unwrap().index, 2);
+		start_session(13);
+		assert_eq!(Staking::active_era().unwrap().index, 3);
+		start_session(14);
+		assert_eq!(Staking::active_era().unwrap().index, 4);
+		assert_eq!(Staking::force_era(), Forcing::ForceAlways);
+	});
+}
+
+#[test]
+fn validator_count_dispatchables_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(Staking::set_validator_count(Origin::signed(10), 10), BadOrigin);
+		assert_noop!(Staking::increase_validator_count(Origin::signed(10), 1), BadOrigin);
+		assert_noop!(
+			Staking::scale_validator_count(Origin::signed(10), Percent::from_percent(10)),
+			BadOrigin,
+		);
+		assert_eq!(Staking::validator_count(), 2);
+
+		assert_ok!(Staking::set_validator_count(Origin::ROOT, 10));
+		assert_eq!(Staking::validator_count(), 10);
+
+		assert_ok!(Staking::increase_validator_count(Origin::ROOT, 5));
+		assert_eq!(Staking::validator_count(), 15);
+
+		// 15 + 20% of 15.
+		assert_ok!(Staking::scale_validator_count(Origin::ROOT, Percent::from_percent(20)));
+		assert_eq!(Staking::validator_count(), 18);
+
+		// the count saturates instead of overflowing.
+		assert_ok!(Staking::set_validator_count(Origin::ROOT, u32::max_value() - 1));
+		assert_ok!(Staking::increase_validator_count(Origin::ROOT, 5));
+		assert_eq!(Staking::validator_count(), u32::max_value());
+		assert_ok!(Staking::scale_validator_count(Origin::ROOT, Percent::from_percent(50)));
+		assert_eq!(Staking::validator_count(), u32::max_value());
+	});
+}
+
+#[test]
+fn nominate_rejects_more_targets_than_max_nominations() {
+	ExtBuilder::default().build().execute_with(|| {
+		let max = MaxNominations::get() as u64;
+		let targets = (1..=max + 1).collect::<Vec<_>>();
+		assert_noop!(
+			Staking::nominate(Origin::signed(100), targets.clone()),
+			Error::<Test>::TooManyTargets,
+		);
+
+		assert_ok!(Staking::nominate(Origin::signed(100), targets[..max as usize].to_vec()));
+		assert_eq!(Staking::nominators(101).unwrap().targets, targets[..max as usize].to_vec());
+	});
+}
+
+#[test]
+fn nominate_is_weighted_by_targets() {
+	use frame_support::weights::GetDispatchInfo;
+
+	let weight = |targets: u64| {
+		Call::<Test>::nominate((1..=targets).collect()).get_dispatch_info().weight
+	};
+	assert_eq!(weight(1), 550_000);
+	assert_eq!(weight(MaxNominations::get() as u64), 1_300_000);
+}
+
+#[test]
+fn runtime_upgrade_bounds_stored_nominations() {
+	ExtBuilder::default().build().execute_with(|| {
+		let max = MaxNominations::get() as u64;
+		assert_eq!(NominationsBound::get(), Some(max as u32));
+
+		// nominations stored before `MaxNominations` was lowered from 20.
+		NominationsBound::put(20);
+		let targets = (1..=max + 4).collect::<Vec<_>>();
+		let stashes = 1000..1100;
+		for stash in stashes.clone() {
+			<Nominators<Test>>::insert(stash, Nominations {
+				targets: targets.clone(),
+				submitted_in: 0,
+				suppressed: false,
+			});
+		}
+		let oversized = || stashes.clone()
+			.filter(|stash| Staking::nominators(stash).unwrap().targets.len() > max as usize)
+			.count();
+
+		<Staking as sp_runtime::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(oversized(), 100);
+
+		// the nominations are truncated at most 64 per block from the next block on.
+		Staking::on_initialize(1);
+		assert!(oversized() >= 100 - 64);
+		assert_eq!(NominationsBound::get(), Some(20));
+		Staking::on_initialize(2);
+		Staking::on_initialize(3);
+		assert_eq!(oversized(), 0);
+		assert_eq!(Staking::nominators(1000).unwrap().targets, targets[..max as usize].to_vec());
+		assert_eq!(NominationsBound::get(), Some(max as u32));
+		assert_eq!(NominationsToBound::get(), None);
+
+		// nothing is scheduled while `MaxNominations` doesn't change.
+		<Staking as sp_runtime::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(NominationsToBound::get(), None);
+	});
+}
+
+#[test]
+fn elections_only_use_max_nominations_targets() {
+	ExtBuilder::default().build().execute_with(|| {
+		// an oversized nomination which isn't truncated yet.
+		let max = MaxNominations::get() as usize;
+		let mut targets = vec![11; max];
+		targets.push(21);
+		let nominations = Nominations { targets, submitted_in: 0, suppressed: false };
+
+		assert_eq!(Staking::eligible_targets(nominations), vec![11; max]);
+	});
+}
+
 #[test]
 fn cannot_transfer_staked_balance() {
 	// Tests that a stash account cannot transfer funds
@@ -1374,6 +1574,69 @@ fn rebond_is_fifo() {
 		})
 }
 
+#[test]
+fn rebond_more_than_unlocking_rebonds_everything() {
+	// Rebonding more than is scheduled to unlock only rebonds what is unlocking, and rebonded
+	// funds are no longer withdrawn once the bonding duration has passed.
+	ExtBuilder::default()
+		.nominate(false)
+		.build()
+		.execute_with(|| {
+			assert_ok!(Staking::set_payee(
+				Origin::signed(10),
+				RewardDestination::Controller
+			));
+			let _ = Balances::make_free_balance_be(&11, 1000000);
+
+			start_era(2);
+
+			Staking::unbond(Origin::signed(10), 400).unwrap();
+			start_era(3);
+			Staking::unbond(Origin::signed(10), 300).unwrap();
+
+			// Re-bond part of the latest chunk and none of the oldest.
+			Staking::rebond(Origin::signed(10), 100).unwrap();
+			assert_eq!(
+				Staking::ledger(&10),
+				Some(StakingLedger {
+					stash: 11,
+					total: 1000,
+					active: 400,
+					unlocking: vec![
+						UnlockChunk { value: 400, era: 2 + 3 },
+						UnlockChunk { value: 200, era: 3 + 3 },
+					],
+					last_reward: None,
+				})
+			);
+
+			// Re-bond more than what is left to unlock.
+			Staking::rebond(Origin::signed(10), 5000).unwrap();
+			assert_eq!(
+				Staking::ledger(&10),
+				Some(StakingLedger {
+					stash: 11,
+					total: 1000,
+					active: 1000,
+					unlocking: vec![],
+					last_reward: None,
+				})
+			);
+
+			// Nothing is left to rebond.
+			assert_noop!(
+				Staking::rebond(Origin::signed(10), 1),
+				Error::<Test>::NoUnlockChunk,
+			);
+
+			// Nothing is withdrawn once the chunks would have unlocked.
+			start_era(6);
+			assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+			assert_eq!(Staking::ledger(&10).unwrap().total, 1000);
+			assert_eq!(Staking::ledger(&10).unwrap().active, 1000);
+		})
+}
+
 #[test]
 fn reward_to_stake_works() {
 	ExtBuilder::default().nominate(false).fair(false).build().execute_with(|| {
@@ -2031,6 +2294,129 @@ fn slashing_performed_according_exposure() {
 	});
 }
 
+#[test]
+fn slashing_the_largest_balances_is_exact() {
+	ExtBuilder::default().build().execute_with(|| {
+		// slashes are computed on the balance type itself, the conversion to votes of
+		// `CurrencyToVote` is only used by the election. The mock balance is `u64`, so this is the
+		// largest stake it can hold: multiplying it by the parts of the slash fraction overflows.
+		let stake: Balance = u64::max_value();
+		assert!(stake.checked_mul(Perbill::from_percent(10).deconstruct() as Balance).is_none());
+		let _ = Balances::make_free_balance_be(&1000, stake);
+		assert_ok!(Staking::bond(Origin::signed(1000), 1001, stake, RewardDestination::Staked));
+
+		on_offence_now(
+			&[OffenceDetails {
+				offender: (1000, Exposure { total: stake, own: stake, others: vec![] }),
+				reporters: vec![],
+			}],
+			&[Perbill::from_percent(10)],
+		);
+
+		// 10% of 18_446_744_073_709_551_615, rounded down.
+		let slash = 1_844_674_407_370_955_161;
+		assert_eq!(Staking::ledger(&1001).unwrap().active, stake - slash);
+		assert_eq!(Balances::free_balance(1000), stake - slash);
+	});
+}
+
+#[test]
+fn chill_validator_removes_validators_from_election() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(<Validators<Test>>::contains_key(11));
+		assert!(Session::validators().contains(&11));
+
+		<Staking as ChillValidator<_>>::chill_validator(&11);
+		assert!(!<Validators<Test>>::contains_key(11));
+
+		// nominators are left alone.
+		<Staking as ChillValidator<_>>::chill_validator(&101);
+		assert!(<Nominators<Test>>::contains_key(101));
+
+		start_era(1);
+		assert!(!Session::validators().contains(&11));
+	});
+}
+
+#[test]
+fn record_only_slashing_mode_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(Staking::slash_mode(), SlashingMode::Enforcing);
+		assert_noop!(Staking::set_slash_mode(Origin::signed(10), SlashingMode::RecordOnly), BadOrigin);
+		assert_ok!(Staking::set_slash_mode(Origin::ROOT, SlashingMode::RecordOnly));
+
+		let exposure = Staking::eras_stakers(Staking::active_era().unwrap().index, 11);
+		let nominated = exposure.others.iter().find(|o| o.who == 101).unwrap().value;
+		let nominator_balance = Balances::free_balance(101);
+
+		on_offence_now(
+			&[OffenceDetails {
+				offender: (11, exposure.clone()),
+				reporters: vec![1],
+			}],
+			&[Perbill::from_percent(10)],
+		);
+
+		// nothing is deducted, nobody is rewarded.
+		assert_eq!(Balances::free_balance(11), 1000);
+		assert_eq!(Balances::free_balance(101), nominator_balance);
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_eq!(Staking::ledger(&10).unwrap().active, 1000);
+
+		// the validator keeps validating.
+		assert!(<Validators<Test>>::contains_key(11));
+		assert!(!is_disabled(10));
+		assert_eq!(Staking::force_era(), Forcing::NotForcing);
+		assert!(<Staking as crate::Store>::UnappliedSlashes::get(0).is_empty());
+
+		// the slash is recorded apart from the slashing history.
+		let recorded = Staking::recorded_slashes(0);
+		assert_eq!(recorded.len(), 1);
+		assert_eq!(recorded[0].validator, 11);
+		assert_eq!(recorded[0].own, 100);
+		assert_eq!(recorded[0].others, vec![(101, Perbill::from_percent(10) * nominated)]);
+		assert_eq!(recorded[0].reporters, vec![1]);
+		assert_eq!(<Staking as crate::Store>::ValidatorSlashInEra::get(0, 11), None);
+		assert_eq!(<Staking as crate::Store>::NominatorSlashInEra::get(0, 101), None);
+		assert!(<Staking as crate::Store>::SlashingSpans::get(&11).is_none());
+		assert!(<Staking as crate::Store>::SlashingSpans::get(&101).is_none());
+		assert_eq!(<Staking as crate::Store>::SpanSlash::get(&(11, 0)).amount_slashed(), &0);
+
+		// switching back to enforcing needs no migration. the recorded offence was not paid, so
+		// reporting it again slashes the full amount.
+		assert_ok!(Staking::set_slash_mode(Origin::ROOT, SlashingMode::Enforcing));
+		on_offence_now(
+			&[OffenceDetails {
+				offender: (11, exposure.clone()),
+				reporters: vec![],
+			}],
+			&[Perbill::from_percent(10)],
+		);
+		assert_eq!(Balances::free_balance(11), 1000 - 100);
+		assert_eq!(
+			Balances::free_balance(101),
+			nominator_balance - Perbill::from_percent(10) * nominated,
+		);
+		assert!(!<Validators<Test>>::contains_key(11));
+		assert!(is_disabled(10));
+
+		// a bigger slash in the same era deducts the difference with the enforced one.
+		on_offence_now(
+			&[OffenceDetails {
+				offender: (11, exposure),
+				reporters: vec![],
+			}],
+			&[Perbill::from_percent(20)],
+		);
+		assert_eq!(Balances::free_balance(11), 1000 - 200);
+		assert_eq!(
+			Balances::free_balance(101),
+			nominator_balance - Perbill::from_percent(20) * nominated,
+		);
+		assert_eq!(Staking::recorded_slashes(0).len(), 1);
+	});
+}
+
 #[test]
 fn slash_in_old_span_does_not_deselect() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -2294,6 +2680,44 @@ fn only_slash_for_max_in_era() {
 	})
 }
 
+#[test]
+fn slashing_spans_are_cleared_when_all_funds_are_withdrawn() {
+	ExtBuilder::default().build().execute_with(|| {
+		on_offence_now(
+			&[
+				OffenceDetails {
+					offender: (11, Staking::eras_stakers(Staking::active_era().unwrap().index, 11)),
+					reporters: vec![],
+				},
+			],
+			&[Perbill::from_percent(10)],
+		);
+
+		assert_eq!(Staking::ledger(&10).unwrap().active, 900);
+		assert!(<Staking as crate::Store>::SlashingSpans::get(&11).is_some());
+		assert_eq!(<Staking as crate::Store>::SpanSlash::get(&(11, 0)).amount_slashed(), &100);
+
+		assert_ok!(Staking::chill(Origin::signed(10)));
+		assert_ok!(Staking::unbond(Origin::signed(10), 400));
+		start_era(1);
+		assert_ok!(Staking::unbond(Origin::signed(10), 500));
+
+		// a partial withdrawal keeps the spans, the stash may still be slashed for them.
+		start_era(3);
+		assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+		assert_eq!(Staking::ledger(&10).unwrap().total, 500);
+		assert!(<Staking as crate::Store>::SlashingSpans::get(&11).is_some());
+		assert_eq!(<Staking as crate::Store>::SpanSlash::get(&(11, 0)).amount_slashed(), &100);
+
+		// withdrawing everything removes the stash along with its spans.
+		start_era(4);
+		assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+		assert!(Staking::ledger(&10).is_none());
+		assert!(<Staking as crate::Store>::SlashingSpans::get(&11).is_none());
+		assert_eq!(<Staking as crate::Store>::SpanSlash::get(&(11, 0)).amount_slashed(), &0);
+	})
+}
+
 #[test]
 fn garbage_collection_after_slashing() {
 	ExtBuilder::default().existential_deposit(2).build().execute_with(|| {
@@ -2579,6 +3003,38 @@ fn deferred_slashes_are_deferred() {
 	})
 }
 
+#[test]
+fn pending_slashes_lists_deferred_slashes() {
+	ExtBuilder::default().slash_defer_duration(2).build().execute_with(|| {
+		start_era(1);
+		assert!(Staking::pending_slashes().is_empty());
+
+		on_offence_now(
+			&[
+				OffenceDetails {
+					offender: (11, Staking::eras_stakers(Staking::active_era().unwrap().index, 11)),
+					reporters: vec![],
+				},
+			],
+			&[Perbill::from_percent(10)],
+		);
+
+		start_era(2);
+
+		let pending = Staking::pending_slashes();
+		assert_eq!(pending.len(), 1);
+		assert_eq!(pending[0].0, 1);
+		assert_eq!(pending[0].1.len(), 1);
+		assert_eq!(pending[0].1[0].validator, 11);
+		assert_eq!(pending[0].1[0].own, 100);
+
+		// slashes from era 1 are applied at the start of era 4.
+		start_era(4);
+
+		assert!(Staking::pending_slashes().is_empty());
+	})
+}
+
 #[test]
 fn remove_deferred() {
 	ExtBuilder::default().slash_defer_duration(2).build().execute_with(|| {
@@ -3039,3 +3495,401 @@ fn set_history_depth_works() {
 		assert!(!<Staking as Store>::ErasTotalStake::contains_key(10 - 5));
 	});
 }
+
+#[test]
+fn runtime_upgrade_prunes_era_history() {
+	ExtBuilder::default().build().execute_with(|| {
+		start_era(10);
+		// Simulate a chain which kept era information from before pruning was introduced.
+		for era in 0..10 {
+			<Staking as Store>::ErasTotalStake::insert(era, 1);
+		}
+		HistoryDepth::put(4);
+		StorageVersion::put(Releases::V2_0_0);
+
+		<Staking as sp_runtime::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get(), Releases::V3_0_0);
+
+		// The history is pruned at most 4 eras per block from the next block on.
+		let pruned = || (0..=10)
+			.filter(|era| !<Staking as Store>::ErasTotalStake::contains_key(era))
+			.collect::<Vec<_>>();
+		assert_eq!(pruned(), Vec::<EraIndex>::new());
+		Staking::on_initialize(1);
+		assert_eq!(pruned(), vec![0, 1, 2, 3]);
+		assert_eq!(NextEraToPrune::get(), Some(4));
+		Staking::on_initialize(2);
+		assert_eq!(pruned(), vec![0, 1, 2, 3, 4, 5]);
+		assert_eq!(NextEraToPrune::get(), None);
+
+		// The migration only runs once.
+		<Staking as Store>::ErasTotalStake::insert(0, 1);
+		<Staking as sp_runtime::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+		Staking::on_initialize(3);
+		assert!(<Staking as Store>::ErasTotalStake::contains_key(0));
+	});
+}
+
+#[test]
+fn runtime_upgrade_is_a_noop_on_current_storage() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(StorageVersion::get(), Releases::V3_0_0);
+		let ledger = Staking::ledger(&10);
+
+		<Staking as sp_runtime::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get(), Releases::V3_0_0);
+		assert_eq!(Staking::ledger(&10), ledger);
+	});
+}
+
+/// Golden vectors for the amounts slashed by an offence.
+///
+/// Every row pins the exact amount taken from the validator `11`, and the nominators `101`
+/// (bonded 500) and `21` (bonded 1000) for a given slash fraction and exposure. `Perbill`
+/// multiplication rounds to the nearest unit with ties rounded down, and a ledger left with no
+/// more than the existential deposit is slashed entirely; both are covered here.
+///
+/// This test is expected to fail on any change of the slashing math. Such a change alters the
+/// economics of every chain using this module and must come with an update of this table.
+#[test]
+fn slash_amounts_golden_vectors() {
+	// (slash parts per billion, own, nominated by 101, nominated by 21,
+	//  slashed from 11, slashed from 101, slashed from 21)
+	const VECTORS: &[(u32, Balance, Balance, Balance, Balance, Balance, Balance)] = &[
+		(0, 1000, 500, 0, 0, 0, 0),
+		(1, 1000, 500, 0, 0, 0, 0),
+		(1, 999, 499, 0, 0, 0, 0),
+		(500_000_000, 1, 1, 0, 0, 0, 0),
+		(500_000_000, 3, 3, 0, 1, 1, 0),
+		(500_000_000, 999, 333, 667, 499, 166, 333),
+		(333_333_333, 1000, 500, 0, 333, 167, 0),
+		(333_333_333, 1000, 250, 250, 333, 83, 83),
+		(333_333_333, 1, 1, 499, 0, 0, 166),
+		(666_666_667, 1000, 333, 667, 667, 222, 445),
+		(100_000_000, 10, 5, 15, 1, 0, 1),
+		(999_999_999, 1000, 500, 1000, 1000, 500, 1000),
+		(999_999_999, 999, 499, 999, 1000, 500, 1000),
+		(1_000_000_000, 1000, 500, 1000, 1000, 500, 1000),
+		(1_000_000_000, 998, 498, 998, 998, 498, 998),
+		(1_000_000_000, 0, 500, 0, 0, 500, 0),
+	];
+
+	for &(parts, own, by_101, by_21, slashed_11, slashed_101, slashed_21) in VECTORS {
+		ExtBuilder::default().build().execute_with(|| {
+			let others = vec![(101, by_101), (21, by_21)]
+				.into_iter()
+				.filter(|&(_, value)| value != 0)
+				.map(|(who, value)| IndividualExposure { who, value })
+				.collect::<Vec<_>>();
+
+			on_offence_now(
+				&[OffenceDetails {
+					offender: (11, Exposure { total: own + by_101 + by_21, own, others }),
+					reporters: vec![],
+				}],
+				&[Perbill::from_parts(parts)],
+			);
+
+			let vector = (parts, own, by_101, by_21);
+			assert_eq!(Balances::free_balance(11), 1000 - slashed_11, "{:?}", vector);
+			assert_eq!(Balances::free_balance(101), 2000 - slashed_101, "{:?}", vector);
+			assert_eq!(Balances::free_balance(21), 2000 - slashed_21, "{:?}", vector);
+		});
+	}
+}
+
+#[test]
+fn election_window_is_open_during_last_planned_session() {
+	ExtBuilder::default().build().execute_with(|| {
+		start_era(1);
+		assert_eq!(Staking::era_election_status(), ElectionStatus::Closed);
+		assert!(Staking::snapshot_validators().is_none());
+
+		// era 2 is planned when session 5 starts, so solutions are accepted during session 4.
+		start_session(4);
+		assert_eq!(Staking::era_election_status(), ElectionStatus::Open);
+		assert_eq_uvec!(Staking::snapshot_validators().unwrap(), vec![11, 21, 31]);
+		assert_eq_uvec!(Staking::snapshot_nominators().unwrap(), vec![11, 21, 31, 101]);
+
+		start_session(5);
+		assert_eq!(Staking::current_era(), Some(2));
+		assert_eq!(Staking::era_election_status(), ElectionStatus::Closed);
+		assert!(Staking::snapshot_validators().is_none());
+		assert!(Staking::snapshot_nominators().is_none());
+	});
+}
+
+#[test]
+fn submitted_election_solution_is_used() {
+	ExtBuilder::default().build().execute_with(|| {
+		start_session(4);
+
+		// 101 is split evenly between 11 and 21, which is better than the on-chain election.
+		let (winners, compact) = compact_solution(&[11, 21], &[
+			(11, vec![(11, Perbill::one())]),
+			(21, vec![(21, Perbill::one())]),
+			(101, vec![(11, Perbill::from_percent(50)), (21, Perbill::from_percent(50))]),
+		]);
+		let score = [1250, 2500, 1250 * 1250 * 2];
+		assert_ok!(Staking::submit_election_solution(Origin::NONE, winners, compact, score, 1));
+		assert_eq!(Staking::queued_score(), Some(score));
+
+		start_session(5);
+		assert_eq!(Staking::eras_stakers(2, 11).total, 1250);
+		assert_eq!(Staking::eras_stakers(2, 21).total, 1250);
+		assert!(Staking::queued_elected().is_none());
+		assert!(Staking::queued_score().is_none());
+	});
+}
+
+#[test]
+fn queued_election_solution_is_used_without_on_chain_election() {
+	ExtBuilder::default().build().execute_with(|| {
+		start_session(4);
+
+		// a feasible solution which elects 31, with a stake of 1, instead of 21.
+		let (winners, compact) = compact_solution(&[11, 31], &[
+			(11, vec![(11, Perbill::one())]),
+			(31, vec![(31, Perbill::one())]),
+			(101, vec![(11, Perbill::one())]),
+		]);
+		let score = [1, 1501, 1500 * 1500 + 1];
+		assert_ok!(Staking::submit_election_solution(Origin::NONE, winners, compact, score, 1));
+		assert_eq!(Staking::queued_score(), Some(score));
+
+		// the election does not run on-chain, even though it would score better.
+		start_session(5);
+		assert_eq!(Staking::eras_stakers(2, 31).total, 1);
+		assert_eq!(Staking::eras_stakers(2, 11).total, 1500);
+		assert_eq!(Staking::eras_stakers(2, 21).total, 0);
+		assert!(staking_events().contains(&RawEvent::StakingElection(ElectionCompute::Unsigned)));
+		assert!(!staking_events().contains(&RawEvent::StakingElection(ElectionCompute::OnChain)));
+	});
+}
+
+#[test]
+fn election_solutions_are_validated_before_entering_the_pool() {
+	use frame_support::unsigned::ValidateUnsigned;
+	use sp_runtime::transaction_validity::{InvalidTransaction, TransactionValidityError};
+
+	ExtBuilder::default().build().execute_with(|| {
+		let (winners, compact) = compact_solution(&[11, 21], &[
+			(11, vec![(11, Perbill::one())]),
+			(21, vec![(21, Perbill::one())]),
+			(101, vec![(11, Perbill::from_percent(50)), (21, Perbill::from_percent(50))]),
+		]);
+		let score = [1250, 2500, 1250 * 1250 * 2];
+		let call = |score, era| Call::submit_election_solution(
+			winners.clone(),
+			compact.clone(),
+			score,
+			era,
+		);
+		let invalid = |error: Error<Test>| Err(TransactionValidityError::Invalid(
+			InvalidTransaction::Custom(error.as_u8()),
+		));
+
+		// the election window is closed.
+		assert_eq!(
+			<Staking as ValidateUnsigned>::validate_unsigned(&call(score, 1)),
+			invalid(Error::<Test>::PhragmenEarlySubmission),
+		);
+
+		start_session(4);
+		assert_eq!(
+			<Staking as ValidateUnsigned>::validate_unsigned(&call(score, 2)),
+			invalid(Error::<Test>::PhragmenWrongEra),
+		);
+		assert_eq!(
+			<Staking as ValidateUnsigned>::validate_unsigned(&call([1251, 2500, 0], 1)),
+			invalid(Error::<Test>::PhragmenBogusScore),
+		);
+		let valid = <Staking as ValidateUnsigned>::validate_unsigned(&call(score, 1)).unwrap();
+		assert_eq!(valid.priority, 1250);
+
+		// once queued, the same score is no longer an improvement.
+		assert_ok!(Staking::submit_election_solution(
+			Origin::NONE, winners.clone(), compact.clone(), score, 1,
+		));
+		assert_eq!(
+			<Staking as ValidateUnsigned>::validate_unsigned(&call(score, 1)),
+			invalid(Error::<Test>::PhragmenWeakSubmission),
+		);
+	});
+}
+
+#[test]
+fn invalidated_election_solution_falls_back_to_on_chain() {
+	ExtBuilder::default().build().execute_with(|| {
+		start_session(4);
+
+		let (winners, compact) = compact_solution(&[11, 21], &[
+			(11, vec![(11, Perbill::one())]),
+			(21, vec![(21, Perbill::one())]),
+			(101, vec![(11, Perbill::from_percent(50)), (21, Perbill::from_percent(50))]),
+		]);
+		let score = [1250, 2500, 1250 * 1250 * 2];
+		assert_ok!(Staking::submit_election_solution(Origin::NONE, winners, compact, score, 1));
+
+		// 21 stops validating before the era ends, so the queued solution is no longer valid.
+		assert_ok!(Staking::chill(Origin::signed(20)));
+
+		start_session(5);
+		assert_eq!(Staking::eras_stakers(2, 21).total, 0);
+		assert!(Staking::eras_stakers(2, 11).total > 0);
+		assert!(Staking::eras_stakers(2, 31).total > 0);
+	});
+}
+
+#[test]
+fn bogus_election_solutions_are_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		let solution = || compact_solution(&[11, 21], &[
+			(11, vec![(11, Perbill::one())]),
+			(21, vec![(21, Perbill::one())]),
+			(101, vec![(11, Perbill::from_percent(50)), (21, Perbill::from_percent(50))]),
+		]);
+		let score = [1250, 2500, 1250 * 1250 * 2];
+		let submit = |origin, (winners, compact), score| {
+			Staking::submit_election_solution(origin, winners, compact, score, 1)
+		};
+
+		start_session(3);
+		assert_noop!(
+			Staking::submit_election_solution(Origin::NONE, vec![], Default::default(), score, 1),
+			Error::<Test>::PhragmenEarlySubmission,
+		);
+
+		start_session(4);
+		assert_noop!(submit(Origin::signed(10), solution(), score), BadOrigin);
+
+		let (winners, compact) = solution();
+		assert_noop!(
+			Staking::submit_election_solution(Origin::NONE, winners, compact, score, 2),
+			Error::<Test>::PhragmenWrongEra,
+		);
+
+		assert_noop!(
+			submit(Origin::NONE, solution(), [1251, 2500, 0]),
+			Error::<Test>::PhragmenBogusScore,
+		);
+
+		assert_noop!(
+			submit(
+				Origin::NONE,
+				compact_solution(&[11], &[(11, vec![(11, Perbill::one())])]),
+				score,
+			),
+			Error::<Test>::PhragmenBogusWinnerCount,
+		);
+
+		// more votes than voters in the snapshot.
+		let (winners, mut compact) = solution();
+		let vote = compact.votes[0].clone();
+		compact.votes.extend(vec![vote; 2]);
+		assert_noop!(
+			submit(Origin::NONE, (winners, compact), score),
+			Error::<Test>::PhragmenBogusNominator,
+		);
+
+		// 101 does not nominate 31.
+		assert_noop!(
+			submit(
+				Origin::NONE,
+				compact_solution(&[11, 31], &[(101, vec![(31, Perbill::one())])]),
+				score,
+			),
+			Error::<Test>::PhragmenBogusNomination,
+		);
+
+		// 101 distributes more than its stake.
+		assert_noop!(
+			submit(
+				Origin::NONE,
+				compact_solution(&[11, 21], &[
+					(101, vec![(11, Perbill::from_percent(60)), (21, Perbill::from_percent(60))]),
+				]),
+				score,
+			),
+			Error::<Test>::PhragmenBogusEdge,
+		);
+
+		assert_ok!(submit(Origin::NONE, solution(), score));
+
+		// the same solution again is not an improvement.
+		assert_noop!(
+			submit(Origin::NONE, solution(), score),
+			Error::<Test>::PhragmenWeakSubmission,
+		);
+	});
+}
+
+#[test]
+fn min_bonds_are_enforced() {
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = Balances::make_free_balance_be(&5, 2000);
+		assert_ok!(Staking::set_min_bonds(Origin::ROOT, 1000, 500));
+
+		// a bond too small for either role is rejected.
+		assert_noop!(
+			Staking::bond(Origin::signed(5), 6, 499, RewardDestination::Controller),
+			Error::<Test>::InsufficientBond,
+		);
+		// the check applies to the free balance actually bonded.
+		let _ = Balances::make_free_balance_be(&7, 499);
+		assert_noop!(
+			Staking::bond(Origin::signed(7), 8, 2000, RewardDestination::Controller),
+			Error::<Test>::InsufficientBond,
+		);
+		assert_ok!(Staking::bond(Origin::signed(5), 6, 500, RewardDestination::Controller));
+
+		// enough to nominate, but not to validate.
+		assert_noop!(
+			Staking::validate(Origin::signed(6), ValidatorPrefs::default()),
+			Error::<Test>::InsufficientBond,
+		);
+		assert_ok!(Staking::nominate(Origin::signed(6), vec![11]));
+
+		// a nominator cannot unbond below the minimum without chilling first.
+		assert_noop!(Staking::unbond(Origin::signed(6), 1), Error::<Test>::InsufficientBond);
+		assert_ok!(Staking::chill(Origin::signed(6)));
+		assert_ok!(Staking::unbond(Origin::signed(6), 1));
+
+		// below the nominator minimum now.
+		assert_noop!(Staking::nominate(Origin::signed(6), vec![11]), Error::<Test>::InsufficientBond);
+	});
+}
+
+#[test]
+fn kick_below_minimum_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// 11 validates with 1000, 101 nominates with 500.
+		assert_noop!(
+			Staking::kick_below_minimum(Origin::signed(1), 11),
+			Error::<Test>::NotBelowMinimum,
+		);
+		assert_noop!(
+			Staking::kick_below_minimum(Origin::signed(1), 101),
+			Error::<Test>::NotBelowMinimum,
+		);
+
+		assert_ok!(Staking::set_min_bonds(Origin::ROOT, 1001, 500));
+		assert_noop!(
+			Staking::kick_below_minimum(Origin::signed(1), 101),
+			Error::<Test>::NotBelowMinimum,
+		);
+		assert_ok!(Staking::kick_below_minimum(Origin::signed(1), 11));
+		assert!(!<Validators<Test>>::contains_key(11));
+
+		assert_ok!(Staking::set_min_bonds(Origin::ROOT, 1001, 501));
+		assert_ok!(Staking::kick_below_minimum(Origin::signed(1), 101));
+		assert!(Staking::nominators(101).is_none());
+
+		// chilled stashes cannot be kicked again.
+		assert_noop!(
+			Staking::kick_below_minimum(Origin::signed(1), 11),
+			Error::<Test>::NotBelowMinimum,
+		);
+	});
+}
@@ -22,6 +22,43 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+/// A filter selecting the events of a block.
+///
+/// Events are identified by the index of the module emitting them in the outer event enum of the
+/// runtime and by their index within the event enum of that module, i.e. by the first two bytes of
+/// the encoded event.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase", deny_unknown_fields))]
+pub struct EventFilter<Hash> {
+	/// The accepted `(module, variant)` pairs; a `None` variant accepts all the events of the
+	/// module. Events of any module are accepted if empty.
+	#[cfg_attr(feature = "std", serde(default))]
+	pub events: Vec<(u8, Option<u8>)>,
+	/// The event must have at least one of these topics. Events are accepted regardless of their
+	/// topics if empty.
+	#[cfg_attr(feature = "std", serde(default))]
+	pub topics: Vec<Hash>,
+}
+
+impl<Hash: PartialEq> EventFilter<Hash> {
+	/// Whether the encoded event `event`, deposited with the topics `topics`, passes the filter.
+	pub fn matches(&self, event: &[u8], topics: &[Hash]) -> bool {
+		let event_matches = self.events.is_empty() || self.events.iter().any(|(module, variant)|
+			event.get(0) == Some(module) && variant.map_or(true, |v| event.get(1) == Some(&v))
+		);
+		let topic_matches = self.topics.is_empty()
+			|| topics.iter().any(|topic| self.topics.contains(topic));
+
+		event_matches && topic_matches
+	}
+}
+
 sp_api::decl_runtime_apis! {
 	/// The API to query account nonce (aka transaction index).
 	pub trait AccountNonceApi<AccountId, Index> where
@@ -31,4 +68,43 @@ sp_api::decl_runtime_apis! {
 		/// Get current account nonce of given `AccountId`.
 		fn account_nonce(account: AccountId) -> Index;
 	}
+
+	/// The API to query the events of a block.
+	pub trait EventsApi<Hash> where
+		Hash: codec::Codec,
+	{
+		/// Get the encoded records of the events deposited in the block that pass `filter`.
+		///
+		/// The next block must not be initialized for this call, since doing so clears the
+		/// events of the block.
+		#[skip_initialize_block]
+		fn events(filter: EventFilter<Hash>) -> Vec<Vec<u8>>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn filter_matches_events_by_module_variant_and_topic() {
+		let event = [3u8, 1, 42];
+		let filter = |events: Vec<(u8, Option<u8>)>, topics: Vec<u64>| EventFilter { events, topics };
+
+		assert!(filter(vec![], vec![]).matches(&event, &[]));
+		assert!(filter(vec![(3, None)], vec![]).matches(&event, &[]));
+		assert!(filter(vec![(0, None), (3, Some(1))], vec![]).matches(&event, &[]));
+		assert!(!filter(vec![(3, Some(0))], vec![]).matches(&event, &[]));
+		assert!(!filter(vec![(1, None)], vec![]).matches(&event, &[]));
+
+		assert!(filter(vec![(3, None)], vec![7, 8]).matches(&event, &[8]));
+		assert!(!filter(vec![(3, None)], vec![7, 8]).matches(&event, &[9]));
+		assert!(!filter(vec![], vec![7]).matches(&event, &[]));
+	}
+
+	#[test]
+	fn filter_deserializes_with_defaults() {
+		let filter: EventFilter<u64> = serde_json::from_str(r#"{"events":[[3,null],[4,1]]}"#).unwrap();
+		assert_eq!(filter, EventFilter { events: vec![(3, None), (4, Some(1))], topics: vec![] });
+	}
 }
@@ -28,8 +28,15 @@ use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
 pub use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi as TransactionPaymentRuntimeApi;
 pub use self::gen_client::Client as TransactionPaymentClient;
 
+/// Transaction payment RPC API.
 #[rpc]
 pub trait TransactionPaymentApi<BlockHash, ResponseType> {
+	/// Query the weight, the dispatch class and the partial fee of the SCALE encoded extrinsic
+	/// `encoded_xt`, at the state of block `at` (the best block if `None`).
+	///
+	/// The partial fee doesn't include the tip, which is up to the sender, and doesn't depend on
+	/// the signature: it can be queried before the extrinsic is signed, as long as the encoded
+	/// length matches the signed one.
 	#[rpc(name = "payment_queryInfo")]
 	fn query_info(
 		&self,
@@ -18,6 +18,7 @@
 
 use sp_runtime::traits::{Block as BlockT, DigestItemFor, Header as HeaderT, NumberFor, HashFor};
 use sp_runtime::Justification;
+use sp_core::storage::Storage;
 use serde::{Serialize, Deserialize};
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -163,6 +164,9 @@ pub struct BlockImportParams<Block: BlockT, Transaction> {
 	pub allow_missing_state: bool,
 	/// Re-validate existing block.
 	pub import_existing: bool,
+	/// Complete state of the block, downloaded from the network. If this is `Some(_)`, the block
+	/// is imported with this state instead of being executed on top of its parent.
+	pub imported_state: Option<Storage>,
 	/// Cached full header hash (with post-digests applied).
 	pub post_hash: Option<Block::Hash>,
 }
@@ -185,6 +189,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			fork_choice: None,
 			allow_missing_state: false,
 			import_existing: false,
+			imported_state: None,
 			post_hash: None,
 		}
 	}
@@ -225,6 +230,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			allow_missing_state: self.allow_missing_state,
 			fork_choice: self.fork_choice,
 			import_existing: self.import_existing,
+			imported_state: self.imported_state,
 			post_hash: self.post_hash,
 		}
 	}
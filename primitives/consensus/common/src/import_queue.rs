@@ -27,6 +27,7 @@
 
 use std::collections::HashMap;
 use sp_runtime::{Justification, traits::{Block as BlockT, Header as _, NumberFor}};
+use sp_core::storage::Storage;
 use crate::error::Error as ConsensusError;
 use crate::block_import::{
 	BlockImport, BlockOrigin, BlockImportParams, ImportedAux, JustificationImport, ImportResult,
@@ -71,6 +72,8 @@ pub struct IncomingBlock<B: BlockT> {
 	pub allow_missing_state: bool,
 	/// Re-validate existing block.
 	pub import_existing: bool,
+	/// Complete state of the block, if it was downloaded instead of being obtained by execution.
+	pub imported_state: Option<Storage>,
 }
 
 /// Type of keys in the blockchain cache that consensus module could use for its needs.
@@ -88,6 +91,12 @@ pub trait Verifier<B: BlockT>: Send + Sync {
 		justification: Option<Justification>,
 		body: Option<Vec<B::Extrinsic>>,
 	) -> Result<(BlockImportParams<B, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String>;
+
+	/// Whether blocks can be verified from their header and justification alone, without their
+	/// body nor the state of their parent, as they are imported by fast sync.
+	fn verifies_headers_only(&self) -> bool {
+		true
+	}
 }
 
 /// Blocks import queue API.
@@ -120,6 +129,13 @@ pub trait ImportQueue<B: BlockT>: Send {
 	/// task and notify later when more actions are ready to be polled. To continue the comparison,
 	/// it is as if this method always returned `Poll::Pending`.
 	fn poll_actions(&mut self, cx: &mut futures::task::Context, link: &mut dyn Link<B>);
+
+	/// Whether blocks can be imported from their header and justification alone.
+	///
+	/// See `Verifier::verifies_headers_only`.
+	fn imports_headers_only(&self) -> bool {
+		true
+	}
 }
 
 /// Hooks that the verification queue can use to influence the synchronization
@@ -257,6 +273,7 @@ pub fn import_single_block<B: BlockT, V: Verifier<B>, Transaction>(
 		cache.extend(keys.into_iter());
 	}
 	import_block.allow_missing_state = block.allow_missing_state;
+	import_block.imported_state = block.imported_state;
 
 	import_error(import_handle.import_block(import_block.convert_transaction(), cache))
 }
@@ -42,6 +42,8 @@ pub struct BasicQueue<B: BlockT, Transaction> {
 	/// A thread pool where the background worker is being run.
 	pool: Option<futures::executor::ThreadPool>,
 	pool_guard: Arc<(Mutex<usize>, Condvar)>,
+	/// Whether the verifier accepts blocks without body nor parent state.
+	verifies_headers_only: bool,
 	_phantom: PhantomData<Transaction>,
 }
 
@@ -74,6 +76,7 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 		justification_import: Option<BoxJustificationImport<B>>,
 		finality_proof_import: Option<BoxFinalityProofImport<B>>,
 	) -> Self {
+		let verifies_headers_only = verifier.verifies_headers_only();
 		let (result_sender, result_port) = buffered_link::buffered_link();
 		let (future, worker_sender) = BlockImportWorker::new(
 			result_sender,
@@ -116,6 +119,7 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 			manual_poll,
 			pool,
 			pool_guard: guard,
+			verifies_headers_only,
 			_phantom: PhantomData,
 		}
 	}
@@ -170,6 +174,10 @@ impl<B: BlockT, Transaction: Send> ImportQueue<B> for BasicQueue<B, Transaction>
 
 		self.result_port.poll_actions(cx, link);
 	}
+
+	fn imports_headers_only(&self) -> bool {
+		self.verifies_headers_only
+	}
 }
 
 /// Message destinated to the background worker.
@@ -385,6 +385,46 @@ pub fn build_support_map<Balance, AccountId, FS, C, R>(
 	supports
 }
 
+/// The score of an election result, used to compare different solutions of the same election.
+///
+/// The elements are, in order: the minimum support of any winner, the sum of all supports and
+/// the sum of all supports squared.
+pub type PhragmenScore = [ExtendedBalance; 3];
+
+/// Compute the [`PhragmenScore`] of the given support map.
+pub fn evaluate_support<AccountId>(support: &SupportMap<AccountId>) -> PhragmenScore {
+	let mut min_support = ExtendedBalance::max_value();
+	let mut sum: ExtendedBalance = Zero::zero();
+	let mut sum_squared: ExtendedBalance = Zero::zero();
+	for (_, support) in support.iter() {
+		sum = sum.saturating_add(support.total);
+		sum_squared = sum_squared.saturating_add(support.total.saturating_mul(support.total));
+		if support.total < min_support {
+			min_support = support.total;
+		}
+	}
+	if support.is_empty() {
+		min_support = Zero::zero();
+	}
+	[min_support, sum, sum_squared]
+}
+
+/// Returns `true` if `this` score is strictly better than `that`.
+///
+/// Scores are compared lexicographically: a greater minimum support wins, then a greater sum of
+/// supports, then a smaller sum of supports squared.
+pub fn is_score_better(this: PhragmenScore, that: PhragmenScore) -> bool {
+	match this[0].cmp(&that[0]) {
+		sp_std::cmp::Ordering::Greater => true,
+		sp_std::cmp::Ordering::Less => false,
+		sp_std::cmp::Ordering::Equal => match this[1].cmp(&that[1]) {
+			sp_std::cmp::Ordering::Greater => true,
+			sp_std::cmp::Ordering::Less => false,
+			sp_std::cmp::Ordering::Equal => this[2] < that[2],
+		},
+	}
+}
+
 /// Performs equalize post-processing to the output of the election algorithm. This happens in
 /// rounds. The number of rounds and the maximum diff-per-round tolerance can be tuned through input
 /// parameters.
@@ -19,9 +19,12 @@
 #![cfg(test)]
 
 use crate::mock::*;
-use crate::{elect, PhragmenResult, PhragmenStakedAssignment, build_support_map, Support, equalize};
+use crate::{
+	elect, PhragmenResult, PhragmenStakedAssignment, build_support_map, Support, equalize,
+	evaluate_support, is_score_better, SupportMap, ExtendedBalance,
+};
 use substrate_test_utils::assert_eq_uvec;
-use sp_runtime::Perbill;
+use sp_runtime::{Perbill, Percent, Permill, Perquintill, PerThing};
 
 type Output = Perbill;
 
@@ -495,3 +498,147 @@ fn self_votes_should_be_kept() {
 		&Support { total: 20u128, voters: vec![(20u64, 20u128)] },
 	);
 }
+
+#[test]
+fn score_is_evaluated_from_supports() {
+	let mut supports = SupportMap::<AccountId>::new();
+	assert_eq!(evaluate_support(&supports), [0, 0, 0]);
+
+	supports.insert(1, Support { total: 10, voters: vec![(10, 10)] });
+	supports.insert(2, Support { total: 30, voters: vec![(20, 20), (30, 10)] });
+	assert_eq!(evaluate_support(&supports), [10, 40, 1000]);
+}
+
+#[test]
+fn score_comparison_is_lexicographic() {
+	// greater minimum support wins regardless of the rest.
+	assert!(is_score_better([11, 0, 1000], [10, 100, 0]));
+	assert!(!is_score_better([10, 100, 0], [11, 0, 1000]));
+	// then a greater sum of supports.
+	assert!(is_score_better([10, 101, 1000], [10, 100, 0]));
+	// then a smaller sum of supports squared.
+	assert!(is_score_better([10, 100, 99], [10, 100, 100]));
+	// equal scores are not better.
+	assert!(!is_score_better([10, 100, 100], [10, 100, 100]));
+}
+
+/// Check golden vectors for the stake a nominator puts behind each winner, and for the slash of
+/// that stake, with the election rounding to the accuracy `R`.
+///
+/// `elect` splits the stake of a nominator in ratios of type `R`, which sets how the split is
+/// rounded. The nominator `10` votes for `1`, `2` and `3`, and `20`, with the same stake, for `3`
+/// alone, so `10` is split about 40%, 40% and 20%. Each vector is the stake of `10`, a slash
+/// fraction in parts per billion, and the exact stake of `10` behind `1`, `2` and `3` together
+/// with the amount slashed from it.
+///
+/// These tests are expected to fail on any change of the election or slashing rounding. Such a
+/// change alters the economics of every chain using them and must come with an update of the
+/// vectors.
+fn assert_golden_vectors<R>(vectors: &[(Balance, u32, [(Balance, Balance); 3])]) where
+	R: PerThing + sp_std::ops::Mul<ExtendedBalance, Output=ExtendedBalance>,
+{
+	for &(stake, slash, expected) in vectors {
+		let stake_of = create_stake_of(&[(10, stake), (20, stake)]);
+		let PhragmenResult { winners, assignments } = elect::<_, _, _, TestCurrencyToVote, R>(
+			3,
+			3,
+			vec![1, 2, 3],
+			vec![(10, vec![1, 2, 3]), (20, vec![3])],
+			&stake_of,
+		).unwrap();
+		let supports = build_support_map::<Balance, AccountId, _, TestCurrencyToVote, R>(
+			&winners.into_iter().map(|(who, _)| who).collect(),
+			&assignments,
+			&stake_of,
+		);
+
+		let split = [1 as AccountId, 2, 3].iter().map(|winner| {
+			let backing = supports[winner].voters.iter()
+				.find(|(who, _)| *who == 10)
+				.map(|(_, backing)| *backing)
+				.unwrap();
+			(backing, Perbill::from_parts(slash) * backing)
+		}).collect::<Vec<_>>();
+		assert_eq!(split, expected.to_vec(), "{:?}", (stake, slash));
+	}
+}
+
+#[test]
+fn golden_vectors_percent() {
+	assert_golden_vectors::<Percent>(&[
+		(1_000, 100_000_000, [(410, 41), (400, 40), (190, 19)]),
+		(1_000, 333_333_333, [(410, 137), (400, 133), (190, 63)]),
+		(1_000_003, 100_000_000, [(400_001, 40_000), (400_001, 40_000), (200_001, 20_000)]),
+		(1_000_003, 333_333_333, [(400_001, 133_334), (400_001, 133_334), (200_001, 66_667)]),
+		(999_999_999_999_999, 100_000_000, [
+			(410_000_000_000_000, 41_000_000_000_000),
+			(400_000_000_000_000, 40_000_000_000_000),
+			(190_000_000_000_000, 19_000_000_000_000),
+		]),
+		(999_999_999_999_999, 333_333_333, [
+			(410_000_000_000_000, 136_666_666_530_000),
+			(400_000_000_000_000, 133_333_333_200_000),
+			(190_000_000_000_000, 63_333_333_270_000),
+		]),
+	]);
+}
+
+#[test]
+fn golden_vectors_permill() {
+	assert_golden_vectors::<Permill>(&[
+		(1_000, 100_000_000, [(400, 40), (400, 40), (200, 20)]),
+		(1_000, 333_333_333, [(400, 133), (400, 133), (200, 67)]),
+		(1_000_003, 100_000_000, [(400_001, 40_000), (400_001, 40_000), (200_001, 20_000)]),
+		(1_000_003, 333_333_333, [(400_001, 133_334), (400_001, 133_334), (200_001, 66_667)]),
+		(999_999_999_999_999, 100_000_000, [
+			(400_001_000_000_000, 40_000_100_000_000),
+			(400_000_000_000_000, 40_000_000_000_000),
+			(199_999_000_000_000, 19_999_900_000_000),
+		]),
+		(999_999_999_999_999, 333_333_333, [
+			(400_001_000_000_000, 133_333_666_533_333),
+			(400_000_000_000_000, 133_333_333_200_000),
+			(199_999_000_000_000, 66_666_333_266_667),
+		]),
+	]);
+}
+
+#[test]
+fn golden_vectors_perbill() {
+	assert_golden_vectors::<Perbill>(&[
+		(1_000, 100_000_000, [(400, 40), (400, 40), (200, 20)]),
+		(1_000, 333_333_333, [(400, 133), (400, 133), (200, 67)]),
+		(1_000_003, 100_000_000, [(400_001, 40_000), (400_001, 40_000), (200_001, 20_000)]),
+		(1_000_003, 333_333_333, [(400_001, 133_334), (400_001, 133_334), (200_001, 66_667)]),
+		(999_999_999_999_999, 100_000_000, [
+			(400_000_001_000_000, 40_000_000_100_000),
+			(400_000_000_000_000, 40_000_000_000_000),
+			(199_999_999_000_000, 19_999_999_900_000),
+		]),
+		(999_999_999_999_999, 333_333_333, [
+			(400_000_001_000_000, 133_333_333_533_333),
+			(400_000_000_000_000, 133_333_333_200_000),
+			(199_999_999_000_000, 66_666_666_266_667),
+		]),
+	]);
+}
+
+#[test]
+fn golden_vectors_perquintill() {
+	assert_golden_vectors::<Perquintill>(&[
+		(1_000, 100_000_000, [(400, 40), (400, 40), (200, 20)]),
+		(1_000, 333_333_333, [(400, 133), (400, 133), (200, 67)]),
+		(1_000_003, 100_000_000, [(400_001, 40_000), (400_001, 40_000), (200_001, 20_000)]),
+		(1_000_003, 333_333_333, [(400_001, 133_334), (400_001, 133_334), (200_001, 66_667)]),
+		(999_999_999_999_999, 100_000_000, [
+			(400_000_000_000_000, 40_000_000_000_000),
+			(400_000_000_000_000, 40_000_000_000_000),
+			(200_000_000_000_000, 20_000_000_000_000),
+		]),
+		(999_999_999_999_999, 333_333_333, [
+			(400_000_000_000_000, 133_333_333_200_000),
+			(400_000_000_000_000, 133_333_333_200_000),
+			(200_000_000_000_000, 66_666_666_600_000),
+		]),
+	]);
+}
@@ -18,6 +18,17 @@
 //! approaches in general. Definitions related to sessions, slashing, etc go here.
 
 pub mod offence;
+pub mod slashing;
 
 /// Simple index type with which we can count sessions.
 pub type SessionIndex = u32;
+
+/// Something that can remove a validator from the next elections, without slashing it.
+pub trait ChillValidator<ValidatorId> {
+	/// Chill `validator`. It is only elected again after declaring its intention to validate anew.
+	fn chill_validator(validator: &ValidatorId);
+}
+
+impl<ValidatorId> ChillValidator<ValidatorId> for () {
+	fn chill_validator(_: &ValidatorId) {}
+}
@@ -20,7 +20,7 @@
 use sp_std::vec::Vec;
 
 use codec::{Encode, Decode};
-use sp_runtime::Perbill;
+use sp_runtime::{Perbill, traits::Saturating};
 
 use crate::SessionIndex;
 
@@ -30,6 +30,31 @@ use crate::SessionIndex;
 // flexible? as you see in examples, they get cut off with long names.
 pub type Kind = [u8; 16];
 
+/// Kind identifiers of the offences reported by the modules shipped with Substrate.
+///
+/// These are kept here so that off-chain consumers can match on reported offences without
+/// depending on the modules that report them.
+pub mod kind {
+	use super::Kind;
+
+	/// An authority was unresponsive for a whole session (`pallet-im-online`).
+	pub const IM_ONLINE_UNRESPONSIVENESS: Kind = *b"im-online:offlin";
+	/// An authority produced two blocks in the same slot (`pallet-babe`).
+	pub const BABE_EQUIVOCATION: Kind = *b"babe:equivocatio";
+	/// An authority cast conflicting votes in the same round (`pallet-grandpa`).
+	pub const GRANDPA_EQUIVOCATION: Kind = *b"grandpa:equivoca";
+}
+
+/// The length of the session in which an offence happened, and the session length its slash
+/// fraction is calibrated for, both in blocks.
+#[derive(Clone, Copy, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub struct SessionLength {
+	/// The length of the session in which the offence happened.
+	pub length: u64,
+	/// The session length the slash fraction of the offence is calibrated for.
+	pub reference: u64,
+}
+
 /// Number of times the offence of this authority was already reported in the past.
 ///
 /// Note that we don't buffer offence reporting, so every time we see a new offence
@@ -80,17 +105,68 @@ pub trait Offence<Offender> {
 	/// number. Note that for GRANDPA the round number is reset each epoch.
 	fn time_slot(&self) -> Self::TimeSlot;
 
+	/// The minimum number of concurrent offenders for this offence to be slashed at all.
+	///
+	/// Collective faults, such as unresponsiveness, can require several offenders so that a
+	/// single faulty authority of a small validator set is not slashed.
+	fn min_offenders(&self) -> u32 {
+		1
+	}
+
+	/// The length of the session in which this offence happened, if known.
+	///
+	/// Passed to `slash_fraction` so that offences measured over a whole session can be
+	/// normalized to the session length of the chain.
+	fn session_length(&self) -> Option<SessionLength> {
+		None
+	}
+
 	/// A slash fraction of the total exposure that should be slashed for this
 	/// particular offence kind for the given parameters that happened at a singular `TimeSlot`.
 	///
 	/// `offenders_count` - the count of unique offending authorities. It is >0.
 	/// `validator_set_count` - the cardinality of the validator set at the time of offence.
+	/// `session_length` - the length of the session of the offence, see `session_length`.
 	fn slash_fraction(
 		offenders_count: u32,
 		validator_set_count: u32,
+		session_length: Option<SessionLength>,
 	) -> Perbill;
 }
 
+/// The slash fraction of `offence` when `offenders_count` authorities committed it at the
+/// same time.
+///
+/// This is zero if there are less offenders than `offence.min_offenders()`, and
+/// `O::slash_fraction` otherwise.
+pub fn slash_fraction_of<Offender, O: Offence<Offender>>(
+	offence: &O,
+	offenders_count: u32,
+) -> Perbill {
+	if offenders_count < offence.min_offenders() {
+		return Perbill::zero()
+	}
+
+	O::slash_fraction(offenders_count, offence.validator_set_count(), offence.session_length())
+}
+
+/// Scale `fraction`, calibrated for sessions of `reference_length` blocks, to a session of
+/// `session_length` blocks.
+///
+/// Shorter sessions are scaled down proportionally, longer sessions are not scaled up: the
+/// result never exceeds `fraction`.
+pub fn normalize_to_session_length(
+	fraction: Perbill,
+	session_length: u64,
+	reference_length: u64,
+) -> Perbill {
+	if session_length >= reference_length {
+		return fraction
+	}
+
+	fraction.saturating_mul(Perbill::from_rational_approximation(session_length, reference_length))
+}
+
 /// Errors that may happen on offence reports.
 #[derive(PartialEq, sp_runtime::RuntimeDebug)]
 pub enum OffenceError {
@@ -0,0 +1,44 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types describing slashes, shared between the runtime and off-chain consumers such as
+//! indexers and RPC clients that need to decode slashing storage without depending on FRAME.
+
+use sp_std::vec::Vec;
+
+use codec::{Encode, Decode, HasCompact};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+/// The index of a slashing span - unique to each stash.
+pub type SpanIndex = u32;
+
+/// A pending slash record. The value of the slash has been computed but not applied yet,
+/// rather deferred for several eras.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct UnappliedSlash<AccountId, Balance: HasCompact> {
+	/// The stash ID of the offending validator.
+	pub validator: AccountId,
+	/// The validator's own slash.
+	pub own: Balance,
+	/// All other slashed stakers and amounts.
+	pub others: Vec<(AccountId, Balance)>,
+	/// Reporters of the offence; bounty payout recipients.
+	pub reporters: Vec<AccountId>,
+	/// The amount of payout.
+	pub payout: Balance,
+}
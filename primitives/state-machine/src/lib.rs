@@ -23,7 +23,8 @@ use log::{warn, trace};
 use hash_db::Hasher;
 use codec::{Decode, Encode, Codec};
 use sp_core::{
-	storage::ChildInfo, NativeOrEncoded, NeverNativeValue, hexdisplay::HexDisplay,
+	storage::{ChildInfo, well_known_keys}, NativeOrEncoded, NeverNativeValue,
+	hexdisplay::HexDisplay,
 	traits::{CodeExecutor, CallInWasmExt, RuntimeCode},
 };
 use overlayed_changes::OverlayedChangeSet;
@@ -730,6 +731,171 @@ where
 		.map_err(|e| Box::new(e) as Box<dyn Error>)
 }
 
+/// A chunk of the state, as read by `read_state_chunk`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StateChunk {
+	/// Key-value pairs of the top trie, ordered by key.
+	pub top: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Key-value pairs of the child tries, by child storage key and ordered by key.
+	///
+	/// The first child trie may continue the one the chunk started in, and the last one may be
+	/// continued by the next chunk.
+	pub children: Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>,
+	/// Where the next chunk starts, or `None` if there are no keys left.
+	pub next: Option<Vec<Vec<u8>>>,
+}
+
+/// Read a chunk of the state, starting right after `start`.
+///
+/// `start` is empty to start with the first key, holds a top trie key to start after it, or holds
+/// a child storage key followed by a key of that child trie to continue the child trie after the
+/// latter. Child tries are read right after their child storage key, and only if they use the
+/// default child storage key prefix.
+///
+/// Pairs are read until their total size reaches `size_limit`. At least one pair is read if there
+/// are any left.
+pub fn read_state_chunk<B, H>(
+	backend: &B,
+	start: &[Vec<u8>],
+	size_limit: usize,
+) -> Result<StateChunk, Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+{
+	let mut chunk = StateChunk::default();
+	let mut size = 0;
+	let mut key = match start {
+		[] => Vec::new(),
+		[key] => key.clone(),
+		[storage_key, child_key] => {
+			let last = read_child_chunk(
+				backend,
+				storage_key,
+				child_key.clone(),
+				size_limit,
+				&mut size,
+				&mut chunk,
+			)?;
+			if let Some(last) = last {
+				chunk.next = Some(vec![storage_key.clone(), last]);
+				return Ok(chunk)
+			}
+			storage_key.clone()
+		},
+		_ => return Err(Box::new("Invalid start of a state chunk")),
+	};
+
+	loop {
+		if size >= size_limit && size > 0 {
+			chunk.next = Some(vec![key]);
+			return Ok(chunk)
+		}
+		key = match backend.next_storage_key(&key).map_err(|e| Box::new(e) as Box<dyn Error>)? {
+			Some(next) => next,
+			None => return Ok(chunk),
+		};
+		let value = backend.storage(&key)
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?
+			.unwrap_or_default();
+		size += key.len() + value.len();
+		chunk.top.push((key.clone(), value));
+
+		if key.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			let last = read_child_chunk(
+				backend,
+				&key,
+				Vec::new(),
+				size_limit,
+				&mut size,
+				&mut chunk,
+			)?;
+			if let Some(last) = last {
+				chunk.next = Some(vec![key, last]);
+				return Ok(chunk)
+			}
+		}
+	}
+}
+
+/// Read the pairs of the child trie at `storage_key` after `key` into `chunk`.
+///
+/// Returns the last key read if `size` reached `size_limit` before the end of the child trie.
+fn read_child_chunk<B, H>(
+	backend: &B,
+	storage_key: &[u8],
+	mut key: Vec<u8>,
+	size_limit: usize,
+	size: &mut usize,
+	chunk: &mut StateChunk,
+) -> Result<Option<Vec<u8>>, Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+{
+	let unique_id = storage_key.get(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..)
+		.ok_or_else(|| Box::new("Invalid child storage key") as Box<dyn Error>)?;
+	let child_info = ChildInfo::new_default(unique_id);
+	let mut pairs = Vec::new();
+	let last = loop {
+		if *size >= size_limit && *size > 0 {
+			break Some(key)
+		}
+		key = match backend.next_child_storage_key(storage_key, child_info, &key)
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?
+		{
+			Some(next) => next,
+			None => break None,
+		};
+		let value = backend.child_storage(storage_key, child_info, &key)
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?
+			.unwrap_or_default();
+		*size += key.len() + value.len();
+		pairs.push((key.clone(), value));
+	};
+
+	if !pairs.is_empty() {
+		chunk.children.push((storage_key.to_vec(), pairs));
+	}
+	Ok(last)
+}
+
+/// Generate a proof of the chunk of the state read by `read_state_chunk`.
+pub fn prove_state_chunk<B, H>(
+	mut backend: B,
+	start: &[Vec<u8>],
+	size_limit: usize,
+) -> Result<StorageProof, Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let trie_backend = backend.as_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+	let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+	read_state_chunk(&proving_backend, start, size_limit)?;
+	Ok(proving_backend.extract_proof())
+}
+
+/// Check a proof generated by `prove_state_chunk` against the state `root`.
+///
+/// Returns the chunk of the state, which is read again from the proof with the same `start` and
+/// `size_limit`. This fails if the proof misses any of the nodes needed to read it.
+pub fn state_chunk_proof_check<H>(
+	root: H::Out,
+	proof: StorageProof,
+	start: &[Vec<u8>],
+	size_limit: usize,
+) -> Result<StateChunk, Box<dyn Error>>
+where
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+	read_state_chunk(&proving_backend, start, size_limit)
+}
+
 #[cfg(test)]
 mod tests {
 	use std::collections::BTreeMap;
@@ -1087,6 +1253,61 @@ mod tests {
 		);
 	}
 
+	fn state_chunk_test_backend() -> InMemoryBackend<BlakeTwo256> {
+		use sp_core::storage::{Storage, StorageChild, OwnedChildInfo};
+
+		let child = StorageChild {
+			data: (0u8..10).map(|i| (vec![i], vec![i; 2])).collect(),
+			child_info: OwnedChildInfo::new_default(b"sub1".to_vec()),
+		};
+		Storage {
+			top: (0u8..20).map(|i| (vec![i], vec![i; 3])).collect(),
+			children: vec![(b":child_storage:default:sub1".to_vec(), child)].into_iter().collect(),
+		}.into()
+	}
+
+	#[test]
+	fn prove_state_chunk_and_proof_check_works() {
+		let mut remote_backend = state_chunk_test_backend();
+		let remote_trie = remote_backend.as_trie_backend().unwrap();
+		let remote_root = *remote_trie.root();
+
+		let mut top = Vec::new();
+		let mut children: Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)> = Vec::new();
+		let mut start = Vec::new();
+		let mut chunks = 0;
+		loop {
+			// the child trie is split across chunks as well.
+			let proof = prove_state_chunk(state_chunk_test_backend(), &start, 8).unwrap();
+			let chunk = state_chunk_proof_check::<BlakeTwo256>(remote_root, proof, &start, 8)
+				.unwrap();
+			assert!(!chunk.top.is_empty() || !chunk.children.is_empty());
+			top.extend(chunk.top);
+			for (storage_key, pairs) in chunk.children {
+				match children.last_mut() {
+					Some((last, last_pairs)) if *last == storage_key => last_pairs.extend(pairs),
+					_ => children.push((storage_key, pairs)),
+				}
+			}
+			chunks += 1;
+			match chunk.next {
+				Some(next) => start = next,
+				None => break,
+			}
+		}
+
+		assert!(chunks > 2);
+		assert_eq!(top, remote_trie.pairs());
+		assert_eq!(children, vec![(
+			b":child_storage:default:sub1".to_vec(),
+			(0u8..10).map(|i| (vec![i], vec![i; 2])).collect::<Vec<_>>(),
+		)]);
+
+		// the proof of a chunk does not prove the following ones.
+		let proof = prove_state_chunk(state_chunk_test_backend(), &[], 8).unwrap();
+		assert!(state_chunk_proof_check::<BlakeTwo256>(remote_root, proof, &[], 1024).is_err());
+	}
+
 	#[test]
 	fn child_storage_uuid() {
 		const CHILD_INFO_1: ChildInfo<'static> = ChildInfo::new_default(b"unique_id_1");
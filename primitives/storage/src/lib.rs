@@ -57,7 +57,7 @@ pub struct StorageChild {
 }
 
 #[cfg(feature = "std")]
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
 /// Struct containing data needed for a storage.
 pub struct Storage {
 	/// Top trie storage data.
@@ -98,6 +98,11 @@ pub mod well_known_keys {
 	/// Prefix of child storage keys.
 	pub const CHILD_STORAGE_KEY_PREFIX: &'static [u8] = b":child_storage:";
 
+	/// Prefix of child storage keys using the default child trie layout.
+	///
+	/// The rest of the key after this prefix is used as the unique id of the child trie.
+	pub const DEFAULT_CHILD_STORAGE_KEY_PREFIX: &'static [u8] = b":child_storage:default:";
+
 	/// Whether a key is a child storage key.
 	///
 	/// This is convenience function which basically checks if the given `key` starts
@@ -113,7 +118,7 @@ pub mod well_known_keys {
 	///
 	/// `child_trie_root` and `child_delta_trie_root` can panic if invalid value is provided to them.
 	pub fn is_child_trie_key_valid(storage_key: &[u8]) -> bool {
-		let has_right_prefix = storage_key.starts_with(b":child_storage:default:");
+		let has_right_prefix = storage_key.starts_with(DEFAULT_CHILD_STORAGE_KEY_PREFIX);
 		if has_right_prefix {
 			// This is an attempt to catch a change of `is_child_storage_key`, which
 			// just checks if the key has prefix `:child_storage:` at the moment of writing.
@@ -220,6 +220,10 @@ pub trait TransactionPool: Send + Sync {
 	/// Get an iterator for ready transactions ordered by priority.
 	fn ready(&self) -> Box<dyn Iterator<Item=Arc<Self::InPoolTransaction>> + Send>;
 
+	// *** RPC
+	/// Get the transactions waiting for the transactions they depend on, e.g. with a nonce gap.
+	fn futures(&self) -> Vec<Arc<Self::InPoolTransaction>>;
+
 	// *** Block production
 	/// Remove transactions identified by given hashes (and dependent transactions) from the pool.
 	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>>;
@@ -52,6 +52,7 @@ where
 		allow_private_ipv4: true,
 		enable_mdns: false,
 		use_yamux_flow_control: true,
+		enable_port_mapping: false,
 	};
 	config.task_executor = Some(Arc::new(move |fut| {
 		wasm_bindgen_futures::spawn_local(fut)
@@ -0,0 +1,140 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Subscription to the events of the new best blocks, filtered by the runtime.
+
+use std::sync::Arc;
+
+use futures::{future, StreamExt as _, TryStreamExt as _};
+use jsonrpc_core::{Result as RpcResult, futures::{Future, Sink}};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use log::warn;
+use serde::{Serialize, Deserialize};
+use sc_client::BlockchainEvents;
+use sc_rpc::{Metadata, Subscriptions};
+use sp_api::ProvideRuntimeApi;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits};
+
+pub use frame_system_rpc_runtime_api::{EventFilter, EventsApi as EventsRuntimeApi};
+pub use self::gen_client::Client as EventsClient;
+
+/// The events of a block passing the filter of a subscription.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockEvents<Hash> {
+	/// Hash of the block.
+	pub block: Hash,
+	/// SCALE-encoded records of the events, in the order they were deposited.
+	pub events: Vec<Bytes>,
+}
+
+/// Events RPC methods.
+#[rpc]
+pub trait EventsApi<Hash> {
+	/// RPC metadata
+	type Metadata;
+
+	/// Subscribe to the events of the new best blocks passing `filter`.
+	///
+	/// Blocks without any such event are not notified.
+	#[pubsub(subscription = "system_events", subscribe, name = "system_subscribeEvents")]
+	fn subscribe_events(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<BlockEvents<Hash>>,
+		filter: Option<EventFilter<Hash>>,
+	);
+
+	/// Unsubscribe from events subscription.
+	#[pubsub(subscription = "system_events", unsubscribe, name = "system_unsubscribeEvents")]
+	fn unsubscribe_events(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+}
+
+/// An implementation of the events subscription on full client.
+pub struct FullEvents<C, B> {
+	client: Arc<C>,
+	subscriptions: Subscriptions,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> FullEvents<C, B> {
+	/// Create new `FullEvents` given client and subscriptions manager.
+	pub fn new(client: Arc<C>, subscriptions: Subscriptions) -> Self {
+		FullEvents {
+			client,
+			subscriptions,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block> EventsApi<Block::Hash> for FullEvents<C, Block>
+where
+	Block: traits::Block,
+	C: ProvideRuntimeApi<Block> + BlockchainEvents<Block>,
+	C: Send + Sync + 'static,
+	C::Api: EventsRuntimeApi<Block, Block::Hash>,
+	<C::Api as sp_api::ApiErrorExt>::Error: std::fmt::Debug,
+{
+	type Metadata = Metadata;
+
+	fn subscribe_events(
+		&self,
+		meta: Metadata,
+		subscriber: Subscriber<BlockEvents<Block::Hash>>,
+		filter: Option<EventFilter<Block::Hash>>,
+	) {
+		let client = self.client.clone();
+		let filter = filter.unwrap_or_default();
+
+		self.subscriptions.add(meta.subscriptions(), subscriber, |sink| {
+			let stream = client.import_notification_stream()
+				.filter(|notification| future::ready(notification.is_new_best))
+				.filter_map(move |notification| {
+					let block = notification.hash;
+					let events = client.runtime_api().events(&BlockId::hash(block), filter.clone());
+					future::ready(match events {
+						Ok(ref events) if events.is_empty() => None,
+						Ok(events) => Some(Ok::<_, ()>(Ok(BlockEvents {
+							block,
+							events: events.into_iter().map(Into::into).collect(),
+						}))),
+						Err(err) => {
+							warn!("Unable to query the events of block {}: {:?}", block, err);
+							None
+						},
+					})
+				})
+				.compat();
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(stream)
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_events(&self, _meta: Option<Metadata>, id: SubscriptionId) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+}
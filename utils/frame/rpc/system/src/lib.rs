@@ -42,6 +42,11 @@ use sp_transaction_pool::{TransactionPool, InPoolTransaction};
 
 pub use frame_system_rpc_runtime_api::AccountNonceApi;
 pub use self::gen_client::Client as SystemClient;
+pub use self::events::{
+	BlockEvents, EventFilter, EventsApi, EventsClient, EventsRuntimeApi, FullEvents,
+};
+
+mod events;
 
 /// Future that resolves to account nonce.
 pub type FutureResult<T> = Box<dyn Future<Item = T, Error = Error> + Send>;
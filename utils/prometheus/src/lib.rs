@@ -17,6 +17,7 @@
 use futures_util::{FutureExt, future::Future};
 pub use prometheus::{
 	Registry, Error as PrometheusError, Opts,
+	Histogram, HistogramOpts, HistogramVec, exponential_buckets,
 	core::{
 		GenericGauge as Gauge, GenericCounter as Counter,
 		GenericGaugeVec as GaugeVec, GenericCounterVec as CounterVec,
@@ -30,9 +31,9 @@ use std::net::SocketAddr;
 mod networking;
 
 #[cfg(target_os = "unknown")]
-pub use unknown_os::init_prometheus;
+pub use unknown_os::{init_prometheus, init_probes};
 #[cfg(not(target_os = "unknown"))]
-pub use known_os::init_prometheus;
+pub use known_os::{init_prometheus, init_probes};
 
 pub fn register<T: Clone + Collector + 'static>(metric: T, registry: &Registry) -> Result<T, PrometheusError> {
 	registry.register(Box::new(metric.clone()))?;
@@ -49,6 +50,10 @@ mod unknown_os {
 	pub async fn init_prometheus(_: SocketAddr, _registry: Registry) -> Result<(), Error> {
 		Ok(())
 	}
+
+	pub async fn init_probes<P>(_: SocketAddr, _probe: P) -> Result<(), Error> {
+		Ok(())
+	}
 }
 
 #[cfg(not(target_os = "unknown"))]
@@ -65,7 +70,7 @@ mod known_os {
 		Http(hyper::http::Error),
 		/// i/o error.
 		Io(std::io::Error),
-		#[display(fmt = "Prometheus port {} already in use.", _0)]
+		#[display(fmt = "Port {} already in use.", _0)]
 		PortInUse(SocketAddr)
 	}
 
@@ -99,6 +104,21 @@ mod known_os {
 
 	}
 
+	async fn request_probe<P>(req: Request<Body>, probe: P) -> Result<Response<Body>, Error>
+	where
+		P: Fn(&str) -> Option<Result<String, String>>,
+	{
+		let (status, body) = match probe(req.uri().path()) {
+			Some(Ok(body)) => (StatusCode::OK, body),
+			Some(Err(body)) => (StatusCode::SERVICE_UNAVAILABLE, body),
+			None => (StatusCode::NOT_FOUND, "Not found.".into()),
+		};
+
+		Response::builder().status(status)
+			.body(Body::from(body))
+			.map_err(Error::Http)
+	}
+
 	#[derive(Clone)]
 	pub struct Executor;
 
@@ -141,4 +161,37 @@ mod known_os {
 
 		result
 	}
+
+	/// Starts an HTTP server answering probes, such as health checks.
+	///
+	/// `probe` is called with the path of each request and answers `Ok` with a body for a
+	/// successful probe, `Err` with a body for a failed probe, served with a 503, or `None`
+	/// for an unknown path.
+	pub async fn init_probes<P>(probe_addr: SocketAddr, probe: P) -> Result<(), Error>
+	where
+		P: Fn(&str) -> Option<Result<String, String>> + Clone + Send + Sync + 'static,
+	{
+		use networking::Incoming;
+		let listener = async_std::net::TcpListener::bind(&probe_addr)
+			.await
+			.map_err(|_| Error::PortInUse(probe_addr))?;
+
+		log::info!("Probe server started at {}", probe_addr);
+
+		let service = make_service_fn(move |_| {
+			let probe = probe.clone();
+
+			async move {
+				Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
+					request_probe(req, probe.clone())
+				}))
+			}
+		});
+
+		Server::builder(Incoming(listener.incoming()))
+			.executor(Executor)
+			.serve(service)
+			.await
+			.map_err(Into::into)
+	}
 }